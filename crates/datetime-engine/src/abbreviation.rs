@@ -0,0 +1,413 @@
+//! Time zone abbreviations and free-form UTC offset notations in pasted input.
+//!
+//! Abbreviations are not unique — `IST` is India, Ireland or Israel, `CST` is US
+//! Central, China or Cuba — so every match carries all of its candidates, most widely
+//! used first. Offsets such as `UTC+5:30` or `GMT-3` are exact.
+
+use jiff::tz::Offset;
+
+use crate::json_utils::json_escape;
+use crate::zone::{parse_fixed_offset, parse_short_offset};
+
+/// One reading of an abbreviation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Candidate {
+    pub offset_seconds: i32,
+    /// A representative IANA zone using the abbreviation
+    pub zone: &'static str,
+    pub name: &'static str,
+}
+
+impl Candidate {
+    pub fn offset(&self) -> Offset {
+        Offset::from_seconds(self.offset_seconds).unwrap_or(Offset::UTC)
+    }
+}
+
+const fn c(hours: i32, minutes: i32, zone: &'static str, name: &'static str) -> Candidate {
+    Candidate {
+        offset_seconds: hours * 3600 + minutes * 60,
+        zone,
+        name,
+    }
+}
+
+/// Common abbreviations, each with its candidates in order of likelihood.
+const ABBREVIATIONS: &[(&str, &[Candidate])] = &[
+    ("GMT", &[c(0, 0, "Europe/London", "Greenwich Mean Time")]),
+    ("UTC", &[c(0, 0, "UTC", "Coordinated Universal Time")]),
+    ("WET", &[c(0, 0, "Europe/Lisbon", "Western European Time")]),
+    (
+        "WEST",
+        &[c(1, 0, "Europe/Lisbon", "Western European Summer Time")],
+    ),
+    (
+        "BST",
+        &[
+            c(1, 0, "Europe/London", "British Summer Time"),
+            c(6, 0, "Asia/Dhaka", "Bangladesh Standard Time"),
+        ],
+    ),
+    (
+        "IST",
+        &[
+            c(5, 30, "Asia/Kolkata", "India Standard Time"),
+            c(1, 0, "Europe/Dublin", "Irish Standard Time"),
+            c(2, 0, "Asia/Jerusalem", "Israel Standard Time"),
+        ],
+    ),
+    ("CET", &[c(1, 0, "Europe/Paris", "Central European Time")]),
+    (
+        "CEST",
+        &[c(2, 0, "Europe/Paris", "Central European Summer Time")],
+    ),
+    ("EET", &[c(2, 0, "Europe/Athens", "Eastern European Time")]),
+    (
+        "EEST",
+        &[c(3, 0, "Europe/Athens", "Eastern European Summer Time")],
+    ),
+    ("MSK", &[c(3, 0, "Europe/Moscow", "Moscow Time")]),
+    ("WAT", &[c(1, 0, "Africa/Lagos", "West Africa Time")]),
+    ("CAT", &[c(2, 0, "Africa/Maputo", "Central Africa Time")]),
+    (
+        "SAST",
+        &[c(2, 0, "Africa/Johannesburg", "South Africa Standard Time")],
+    ),
+    ("EAT", &[c(3, 0, "Africa/Nairobi", "East Africa Time")]),
+    ("PKT", &[c(5, 0, "Asia/Karachi", "Pakistan Standard Time")]),
+    ("WIB", &[c(7, 0, "Asia/Jakarta", "Western Indonesia Time")]),
+    ("HKT", &[c(8, 0, "Asia/Hong_Kong", "Hong Kong Time")]),
+    ("SGT", &[c(8, 0, "Asia/Singapore", "Singapore Time")]),
+    (
+        "AWST",
+        &[c(
+            8,
+            0,
+            "Australia/Perth",
+            "Australian Western Standard Time",
+        )],
+    ),
+    ("JST", &[c(9, 0, "Asia/Tokyo", "Japan Standard Time")]),
+    ("KST", &[c(9, 0, "Asia/Seoul", "Korea Standard Time")]),
+    (
+        "ACST",
+        &[c(
+            9,
+            30,
+            "Australia/Adelaide",
+            "Australian Central Standard Time",
+        )],
+    ),
+    (
+        "ACDT",
+        &[c(
+            10,
+            30,
+            "Australia/Adelaide",
+            "Australian Central Daylight Time",
+        )],
+    ),
+    (
+        "AEST",
+        &[c(
+            10,
+            0,
+            "Australia/Sydney",
+            "Australian Eastern Standard Time",
+        )],
+    ),
+    (
+        "AEDT",
+        &[c(
+            11,
+            0,
+            "Australia/Sydney",
+            "Australian Eastern Daylight Time",
+        )],
+    ),
+    (
+        "NZST",
+        &[c(12, 0, "Pacific/Auckland", "New Zealand Standard Time")],
+    ),
+    (
+        "NZDT",
+        &[c(13, 0, "Pacific/Auckland", "New Zealand Daylight Time")],
+    ),
+    (
+        "HST",
+        &[c(-10, 0, "Pacific/Honolulu", "Hawaii Standard Time")],
+    ),
+    (
+        "AKST",
+        &[c(-9, 0, "America/Anchorage", "Alaska Standard Time")],
+    ),
+    (
+        "AKDT",
+        &[c(-8, 0, "America/Anchorage", "Alaska Daylight Time")],
+    ),
+    (
+        "PST",
+        &[
+            c(-8, 0, "America/Los_Angeles", "Pacific Standard Time"),
+            c(8, 0, "Asia/Manila", "Philippine Standard Time"),
+        ],
+    ),
+    (
+        "PDT",
+        &[c(-7, 0, "America/Los_Angeles", "Pacific Daylight Time")],
+    ),
+    (
+        "MST",
+        &[c(-7, 0, "America/Denver", "Mountain Standard Time")],
+    ),
+    (
+        "MDT",
+        &[c(-6, 0, "America/Denver", "Mountain Daylight Time")],
+    ),
+    (
+        "CST",
+        &[
+            c(-6, 0, "America/Chicago", "Central Standard Time"),
+            c(8, 0, "Asia/Shanghai", "China Standard Time"),
+            c(-5, 0, "America/Havana", "Cuba Standard Time"),
+        ],
+    ),
+    (
+        "CDT",
+        &[
+            c(-5, 0, "America/Chicago", "Central Daylight Time"),
+            c(-4, 0, "America/Havana", "Cuba Daylight Time"),
+        ],
+    ),
+    (
+        "EST",
+        &[c(-5, 0, "America/New_York", "Eastern Standard Time")],
+    ),
+    (
+        "EDT",
+        &[c(-4, 0, "America/New_York", "Eastern Daylight Time")],
+    ),
+    (
+        "AST",
+        &[
+            c(-4, 0, "America/Halifax", "Atlantic Standard Time"),
+            c(3, 0, "Asia/Riyadh", "Arabia Standard Time"),
+        ],
+    ),
+    (
+        "ADT",
+        &[c(-3, 0, "America/Halifax", "Atlantic Daylight Time")],
+    ),
+    (
+        "NST",
+        &[c(-3, -30, "America/St_Johns", "Newfoundland Standard Time")],
+    ),
+    (
+        "NDT",
+        &[c(-2, -30, "America/St_Johns", "Newfoundland Daylight Time")],
+    ),
+    ("BRT", &[c(-3, 0, "America/Sao_Paulo", "Brasília Time")]),
+    (
+        "ART",
+        &[c(-3, 0, "America/Argentina/Buenos_Aires", "Argentina Time")],
+    ),
+];
+
+/// Candidates for an abbreviation such as `"PST"` (case-insensitive), most likely first.
+pub fn candidates(abbreviation: &str) -> Option<&'static [Candidate]> {
+    ABBREVIATIONS
+        .iter()
+        .find(|(abbr, _)| abbr.eq_ignore_ascii_case(abbreviation.trim()))
+        .map(|(_, candidates)| *candidates)
+}
+
+/// Parse an offset written as `UTC+5:30`, `GMT-3`, `UTC+0530` or plain `+05:30`.
+///
+/// The sign means east of UTC as people write it, not the inverted POSIX sense of
+/// `Etc/GMT-3`. Offsets outside the -12:00 to +14:00 that zones actually use are
+/// rejected.
+pub fn parse_offset_notation(input: &str) -> Option<Offset> {
+    parse_offset_notation_unchecked(input)
+        .filter(|offset| (-12 * 3600..=14 * 3600).contains(&offset.seconds()))
+}
+
+/// `parse_offset_notation` without the range check, for telling an out-of-range
+/// offset from text that is not an offset at all.
+pub(crate) fn parse_offset_notation_unchecked(input: &str) -> Option<Offset> {
+    let trimmed = input.trim();
+    let rest = ["UTC", "GMT"]
+        .iter()
+        .find(|prefix| {
+            trimmed
+                .get(..3)
+                .is_some_and(|head| head.eq_ignore_ascii_case(prefix))
+        })
+        .map_or(trimmed, |_| trimmed[3..].trim_start());
+    if rest.len() == trimmed.len() {
+        return parse_fixed_offset(rest);
+    }
+    if rest.is_empty() {
+        return Some(Offset::UTC);
+    }
+    parse_short_offset(rest)
+}
+
+/// The zone part of an input such as `10:00 PST` or `2024-03-01 14:00 UTC+5:30`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZoneSuffix {
+    /// The suffix as written
+    pub text: String,
+    /// The offset used: the exact one for offset notations, else the first candidate's
+    pub offset: Offset,
+    /// Abbreviation readings; empty for offset notations
+    pub candidates: &'static [Candidate],
+}
+
+impl ZoneSuffix {
+    /// Read `text` as an offset notation or a known abbreviation.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if let Some(offset) = parse_offset_notation(text) {
+            return Some(ZoneSuffix {
+                text: text.to_string(),
+                offset,
+                candidates: &[],
+            });
+        }
+        let candidates = candidates(text)?;
+        Some(ZoneSuffix {
+            text: text.to_string(),
+            offset: candidates[0].offset(),
+            candidates,
+        })
+    }
+
+    pub fn is_ambiguous(&self) -> bool {
+        self.candidates.len() > 1
+    }
+
+    /// A warning for ambiguous abbreviations, naming the reading that was used.
+    pub fn warning(&self) -> Option<String> {
+        if !self.is_ambiguous() {
+            return None;
+        }
+        let readings: Vec<String> = self
+            .candidates
+            .iter()
+            .map(|c| format!("{} ({})", c.name, format_offset(c.offset())))
+            .collect();
+        Some(format!(
+            "'{}' is ambiguous: {}; assumed {}",
+            self.text,
+            readings.join(", "),
+            self.candidates[0].name
+        ))
+    }
+
+    /// Serialize to JSON string for Wasm boundary (manual serialization).
+    pub fn to_json(&self) -> String {
+        format!(
+            r#"{{"input":"{}","offset":"{}","ambiguous":{},"candidates":{}}}"#,
+            json_escape(&self.text),
+            format_offset(self.offset),
+            self.is_ambiguous(),
+            self.candidates_json()
+        )
+    }
+
+    /// JSON array of the candidates, `[{"offset", "zone", "name"}]`.
+    pub fn candidates_json(&self) -> String {
+        let entries: Vec<String> = self
+            .candidates
+            .iter()
+            .map(|c| {
+                format!(
+                    r#"{{"offset":"{}","zone":"{}","name":"{}"}}"#,
+                    format_offset(c.offset()),
+                    c.zone,
+                    json_escape(c.name)
+                )
+            })
+            .collect();
+        format!("[{}]", entries.join(","))
+    }
+}
+
+/// Split a trailing zone suffix off `input`: `("10:00", PST)` for `"10:00 PST"`.
+pub fn split_zone_suffix(input: &str) -> Option<(&str, ZoneSuffix)> {
+    let (head, tail) = input.trim().rsplit_once(' ')?;
+    Some((head.trim_end(), ZoneSuffix::parse(tail)?))
+}
+
+/// `±HH:MM`, always with minutes (jiff's `Display` drops zero minutes), and `±HH:MM:SS`
+/// for offsets with seconds such as local mean time.
+pub fn format_offset(offset: Offset) -> String {
+    let seconds = offset.seconds();
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let (minutes, rest) = (seconds.abs() / 60, seconds.abs() % 60);
+    let hh_mm = format!("{}{:02}:{:02}", sign, minutes / 60, minutes % 60);
+    if rest == 0 {
+        hh_mm
+    } else {
+        format!("{}:{:02}", hh_mm, rest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offset_notations() {
+        let offset = |s: &str| parse_offset_notation(s).map(|o| o.seconds());
+        assert_eq!(offset("UTC+5:30"), Some(19_800));
+        assert_eq!(offset("GMT-3"), Some(-10_800));
+        assert_eq!(offset("utc+0545"), Some(20_700));
+        assert_eq!(offset("-08:00"), Some(-28_800));
+        assert_eq!(offset("UTC"), Some(0));
+        assert_eq!(offset("UTC+"), None);
+        assert_eq!(offset("UTC+14"), Some(50_400));
+        assert_eq!(offset("GMT-12:00"), Some(-43_200));
+        assert_eq!(offset("UTC+15"), None);
+        assert_eq!(offset("UTC+25"), None);
+        assert_eq!(offset("GMT-13"), None);
+        assert_eq!(offset("PST"), None);
+    }
+
+    #[test]
+    fn test_ambiguous_abbreviations() {
+        let ist = ZoneSuffix::parse("IST").unwrap();
+        assert!(ist.is_ambiguous());
+        assert_eq!(ist.offset.seconds(), 19_800);
+        let warning = ist.warning().unwrap();
+        assert!(warning.contains("Irish Standard Time (+01:00)"));
+        assert!(warning.ends_with("assumed India Standard Time"));
+        assert_eq!(ZoneSuffix::parse("jst").unwrap().warning(), None);
+        assert!(ZoneSuffix::parse("XYZ").is_none());
+        assert_eq!(
+            ZoneSuffix::parse("CST").unwrap().to_json(),
+            r#"{"input":"CST","offset":"-06:00","ambiguous":true,"candidates":[{"offset":"-06:00","zone":"America/Chicago","name":"Central Standard Time"},{"offset":"+08:00","zone":"Asia/Shanghai","name":"China Standard Time"},{"offset":"-05:00","zone":"America/Havana","name":"Cuba Standard Time"}]}"#
+        );
+    }
+
+    #[test]
+    fn test_split_suffix() {
+        let (head, suffix) = split_zone_suffix("2024-03-01 10:00 PST").unwrap();
+        assert_eq!(head, "2024-03-01 10:00");
+        assert_eq!(suffix.offset.seconds(), -28_800);
+        assert!(split_zone_suffix("10:00").is_none());
+        assert_eq!(
+            format_offset(Offset::from_seconds(-12_600).unwrap()),
+            "-03:30"
+        );
+    }
+
+    #[test]
+    fn test_table_names_are_unique_and_upper_case() {
+        for (i, (abbr, candidates)) in ABBREVIATIONS.iter().enumerate() {
+            assert_eq!(*abbr, abbr.to_ascii_uppercase());
+            assert!(!candidates.is_empty());
+            assert!(ABBREVIATIONS[..i].iter().all(|(other, _)| other != abbr));
+        }
+    }
+}
@@ -0,0 +1,467 @@
+//! Schedule analysis: how the runs of one or more schedules are spread over a window.
+//!
+//! Answers questions such as "do these two jobs ever run in the same minute?" and
+//! "what is the longest gap between runs over the next year?".
+
+use jiff::civil::Date;
+use jiff::tz::TimeZone;
+use jiff::{Timestamp, ToSpan};
+
+use crate::calc::parse_window;
+use crate::cron::{CronOptions, CronRun, DstPolicy, Schedule};
+use crate::json_utils::{json_escape, parse_string_array};
+use crate::zone::zone_name;
+
+/// Upper bound on the runs collected per schedule; a minutely job over a leap year fits.
+pub const MAX_ANALYSIS_RUNS: usize = 527_040;
+
+/// Upper bound on the collisions listed in a report (`collision_count` stays exact).
+const MAX_LISTED_COLLISIONS: usize = 1000;
+
+/// Run statistics for one schedule over `[start, end)`.
+#[derive(Debug, PartialEq)]
+pub struct ScheduleAnalysis {
+    pub expression: String,
+    pub zone: TimeZone,
+    pub start: Timestamp,
+    /// End of the analyzed window; pulled in when `truncated`
+    pub end: Timestamp,
+    /// Set when the schedule ran more than `MAX_ANALYSIS_RUNS` times in the requested window
+    pub truncated: bool,
+    pub runs: Vec<CronRun>,
+    /// Shortest, longest and mean spacing between consecutive runs, in seconds
+    pub min_interval: Option<i64>,
+    pub max_interval: Option<i64>,
+    pub average_interval: Option<f64>,
+    /// The consecutive runs furthest apart (the first such pair on ties)
+    pub longest_gap: Option<(Timestamp, Timestamp)>,
+    /// Runs per local calendar day in `zone`, including days without runs
+    pub runs_per_day: Vec<(Date, usize)>,
+}
+
+impl ScheduleAnalysis {
+    /// Serialize to JSON string for Wasm boundary (manual serialization).
+    ///
+    /// Statistics that need at least one run (or two, for intervals) are omitted when
+    /// the schedule does not run often enough in the window.
+    pub fn to_json(&self) -> String {
+        let mut json = format!(
+            r#"{{"expression":"{}","zone":"{}","start":"{}","end":"{}","truncated":{},"runCount":{}"#,
+            json_escape(&self.expression),
+            json_escape(&zone_name(&self.zone)),
+            self.start,
+            self.end,
+            self.truncated,
+            self.runs.len()
+        );
+        if let (Some(first), Some(last)) = (self.runs.first(), self.runs.last()) {
+            json.push_str(&format!(
+                r#","firstRun":"{}","lastRun":"{}""#,
+                first.timestamp, last.timestamp
+            ));
+        }
+        if let (Some(min), Some(max), Some(average)) =
+            (self.min_interval, self.max_interval, self.average_interval)
+        {
+            json.push_str(&format!(
+                r#","minIntervalSeconds":{},"maxIntervalSeconds":{},"averageIntervalSeconds":{}"#,
+                min, max, average
+            ));
+        }
+        if let Some((from, to)) = self.longest_gap {
+            json.push_str(&format!(
+                r#","longestGap":{{"from":"{}","to":"{}","seconds":{}}}"#,
+                from,
+                to,
+                to.as_second() - from.as_second()
+            ));
+        }
+        let days: Vec<String> = self
+            .runs_per_day
+            .iter()
+            .map(|(date, runs)| format!(r#"{{"date":"{}","runs":{}}}"#, date, runs))
+            .collect();
+        json.push_str(&format!(r#","runsPerDay":[{}]}}"#, days.join(",")));
+        json
+    }
+}
+
+/// A minute in which two or more schedules run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Collision {
+    /// Start of the shared minute
+    pub minute: Timestamp,
+    /// Indices of the colliding schedules, ascending
+    pub schedules: Vec<usize>,
+}
+
+/// Minutes shared by several schedules over `[start, end)`.
+#[derive(Debug, PartialEq)]
+pub struct CollisionReport {
+    pub expressions: Vec<String>,
+    pub zone: TimeZone,
+    pub start: Timestamp,
+    /// End of the analyzed window; pulled in when `truncated`
+    pub end: Timestamp,
+    /// Set when a schedule ran more than `MAX_ANALYSIS_RUNS` times in the requested window
+    pub truncated: bool,
+    /// Number of colliding minutes, including any beyond the listed ones
+    pub collision_count: usize,
+    /// The first colliding minutes, at most `MAX_LISTED_COLLISIONS`
+    pub collisions: Vec<Collision>,
+}
+
+impl CollisionReport {
+    /// Serialize to JSON string for Wasm boundary (manual serialization).
+    pub fn to_json(&self) -> String {
+        let expressions: Vec<String> = self
+            .expressions
+            .iter()
+            .map(|e| format!(r#""{}""#, json_escape(e)))
+            .collect();
+        let collisions: Vec<String> = self
+            .collisions
+            .iter()
+            .map(|c| {
+                let schedules: Vec<String> = c.schedules.iter().map(|i| i.to_string()).collect();
+                format!(
+                    r#"{{"iso":"{}","unixTimestamp":{},"local":"{}","schedules":[{}]}}"#,
+                    c.minute,
+                    c.minute.as_second(),
+                    json_escape(&c.minute.to_zoned(self.zone.clone()).to_string()),
+                    schedules.join(",")
+                )
+            })
+            .collect();
+        format!(
+            r#"{{"expressions":[{}],"zone":"{}","start":"{}","end":"{}","truncated":{},"collisionCount":{},"collisions":[{}]}}"#,
+            expressions.join(","),
+            json_escape(&zone_name(&self.zone)),
+            self.start,
+            self.end,
+            self.truncated,
+            self.collision_count,
+            collisions.join(",")
+        )
+    }
+}
+
+/// Runs of `schedule` in `[start, end)`, together with the end of the window actually
+/// covered: if the schedule runs more than `MAX_ANALYSIS_RUNS` times, the window stops
+/// at the first run past the limit.
+fn window_runs(
+    schedule: &Schedule,
+    start: Timestamp,
+    end: Timestamp,
+    tz: &TimeZone,
+    policy: DstPolicy,
+) -> (Vec<CronRun>, Timestamp) {
+    let mut runs = schedule.runs_between(start, end, MAX_ANALYSIS_RUNS, tz, policy);
+    if runs.len() > MAX_ANALYSIS_RUNS {
+        let cut = runs[MAX_ANALYSIS_RUNS].timestamp;
+        runs.truncate(MAX_ANALYSIS_RUNS);
+        return (runs, cut);
+    }
+    (runs, end)
+}
+
+/// Interval statistics and a per-day run histogram for `schedule` over `[start, end)`.
+pub fn analyze(
+    schedule: &Schedule,
+    start: Timestamp,
+    end: Timestamp,
+    tz: &TimeZone,
+    policy: DstPolicy,
+) -> ScheduleAnalysis {
+    let (runs, covered) = window_runs(schedule, start, end, tz, policy);
+
+    let mut min_interval = None;
+    let mut max_interval: Option<i64> = None;
+    let mut longest_gap = None;
+    for pair in runs.windows(2) {
+        let seconds = pair[1].timestamp.as_second() - pair[0].timestamp.as_second();
+        min_interval = Some(min_interval.map_or(seconds, |min: i64| min.min(seconds)));
+        if max_interval.is_none_or(|max| seconds > max) {
+            max_interval = Some(seconds);
+            longest_gap = Some((pair[0].timestamp, pair[1].timestamp));
+        }
+    }
+    let average_interval = match (runs.first(), runs.last()) {
+        (Some(first), Some(last)) if runs.len() > 1 => Some(
+            (last.timestamp.as_second() - first.timestamp.as_second()) as f64
+                / (runs.len() - 1) as f64,
+        ),
+        _ => None,
+    };
+
+    ScheduleAnalysis {
+        expression: schedule.expression().to_string(),
+        zone: tz.clone(),
+        start,
+        end: covered,
+        truncated: covered < end,
+        runs_per_day: runs_per_day(&runs, start, covered, tz),
+        runs,
+        min_interval,
+        max_interval,
+        average_interval,
+        longest_gap,
+    }
+}
+
+/// Count runs per local date in `tz`, listing every date the window touches.
+fn runs_per_day(
+    runs: &[CronRun],
+    start: Timestamp,
+    end: Timestamp,
+    tz: &TimeZone,
+) -> Vec<(Date, usize)> {
+    let mut days = Vec::new();
+    if end <= start {
+        return days;
+    }
+    let first = start.to_zoned(tz.clone()).date();
+    let last = end
+        .checked_sub(1.nanosecond())
+        .map_or(first, |ts| ts.to_zoned(tz.clone()).date());
+    let mut runs = runs
+        .iter()
+        .map(|run| run.timestamp.to_zoned(tz.clone()).date())
+        .peekable();
+    let mut date = first;
+    while date <= last {
+        let mut count = 0;
+        while runs.next_if(|&d| d == date).is_some() {
+            count += 1;
+        }
+        days.push((date, count));
+        match date.tomorrow() {
+            Ok(next) => date = next,
+            Err(_) => break,
+        }
+    }
+    days
+}
+
+/// Minutes in `[start, end)` in which at least two of `schedules` run.
+pub fn collisions(
+    schedules: &[Schedule],
+    start: Timestamp,
+    end: Timestamp,
+    tz: &TimeZone,
+    policy: DstPolicy,
+) -> CollisionReport {
+    let mut covered = end;
+    let mut minutes: Vec<(i64, usize)> = Vec::new();
+    for (index, schedule) in schedules.iter().enumerate() {
+        let (runs, cut) = window_runs(schedule, start, end, tz, policy);
+        covered = covered.min(cut);
+        minutes.extend(
+            runs.iter()
+                .map(|run| (run.timestamp.as_second().div_euclid(60), index)),
+        );
+    }
+    // A schedule cut short says nothing about the rest of the window.
+    minutes.retain(|&(minute, _)| minute * 60 < covered.as_second());
+    minutes.sort_unstable();
+    minutes.dedup();
+
+    let mut collision_count = 0;
+    let mut listed = Vec::new();
+    for group in minutes.chunk_by(|a, b| a.0 == b.0) {
+        if group.len() < 2 {
+            continue;
+        }
+        collision_count += 1;
+        if listed.len() < MAX_LISTED_COLLISIONS {
+            if let Ok(minute) = Timestamp::from_second(group[0].0 * 60) {
+                listed.push(Collision {
+                    minute,
+                    schedules: group.iter().map(|&(_, index)| index).collect(),
+                });
+            }
+        }
+    }
+
+    CollisionReport {
+        expressions: schedules
+            .iter()
+            .map(|s| s.expression().to_string())
+            .collect(),
+        zone: tz.clone(),
+        start,
+        end: covered,
+        truncated: covered < end,
+        collision_count,
+        collisions: listed,
+    }
+}
+
+/// Interval statistics and runs-per-day histogram for a cron `expression` over
+/// `[start, end)`.
+///
+/// `options_json` is the same as for `cron_runs`; local days are counted in its `zone`.
+pub fn analyze_schedule(
+    expression: &str,
+    start: &str,
+    end: &str,
+    options_json: &str,
+) -> Result<ScheduleAnalysis, String> {
+    let options = CronOptions::from_json(options_json)?;
+    let schedule = Schedule::parse(expression, options.dialect, &options.seed)?;
+    let (start, end) = parse_window(start, end, &options.zone)?;
+    Ok(analyze(
+        &schedule,
+        start,
+        end,
+        &options.zone,
+        options.dst_policy,
+    ))
+}
+
+/// Minutes in `[start, end)` in which two or more of the cron expressions in
+/// `expressions_json` (a JSON string array) run.
+///
+/// All expressions share the dialect, zone and DST policy from `options_json`.
+pub fn schedule_collisions(
+    expressions_json: &str,
+    start: &str,
+    end: &str,
+    options_json: &str,
+) -> Result<CollisionReport, String> {
+    let options = CronOptions::from_json(options_json)?;
+    let expressions = parse_string_array(expressions_json)?;
+    if expressions.len() < 2 {
+        return Err("At least two expressions are needed to look for collisions".to_string());
+    }
+    let schedules = expressions
+        .iter()
+        .map(|e| Schedule::parse(e, options.dialect, &options.seed))
+        .collect::<Result<Vec<_>, _>>()?;
+    let (start, end) = parse_window(start, end, &options.zone)?;
+    Ok(collisions(
+        &schedules,
+        start,
+        end,
+        &options.zone,
+        options.dst_policy,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cron::CronDialect;
+
+    fn ts(s: &str) -> Timestamp {
+        s.parse().unwrap()
+    }
+
+    fn cron(expression: &str) -> Schedule {
+        Schedule::parse(expression, CronDialect::Standard, "").unwrap()
+    }
+
+    #[test]
+    fn test_interval_stats_for_weekday_job() {
+        // Mon 2024-07-01 .. Mon 2024-07-15: two working weeks
+        let analysis = analyze(
+            &cron("0 9 * * 1-5"),
+            ts("2024-07-01T00:00:00Z"),
+            ts("2024-07-15T00:00:00Z"),
+            &TimeZone::UTC,
+            DstPolicy::Vixie,
+        );
+        assert_eq!(analysis.runs.len(), 10);
+        assert_eq!(analysis.min_interval, Some(86_400));
+        assert_eq!(analysis.max_interval, Some(3 * 86_400));
+        assert_eq!(
+            analysis.longest_gap,
+            Some((ts("2024-07-05T09:00:00Z"), ts("2024-07-08T09:00:00Z")))
+        );
+        assert_eq!(analysis.average_interval, Some(11.0 * 86_400.0 / 9.0));
+        assert!(!analysis.truncated);
+    }
+
+    #[test]
+    fn test_runs_per_day_includes_idle_days() {
+        let analysis = analyze(
+            &cron("0 */6 * * 1-5"),
+            ts("2024-07-05T00:00:00Z"),
+            ts("2024-07-08T00:00:00Z"),
+            &TimeZone::UTC,
+            DstPolicy::Vixie,
+        );
+        let counts: Vec<usize> = analysis.runs_per_day.iter().map(|(_, n)| *n).collect();
+        assert_eq!(counts, vec![4, 0, 0]);
+        assert!(analysis
+            .to_json()
+            .contains(r#"{"date":"2024-07-06","runs":0}"#));
+    }
+
+    #[test]
+    fn test_single_run_has_no_intervals() {
+        let analysis = analyze(
+            &cron("0 0 1 1 *"),
+            ts("2024-01-01T00:00:00Z"),
+            ts("2024-06-01T00:00:00Z"),
+            &TimeZone::UTC,
+            DstPolicy::Vixie,
+        );
+        assert_eq!(analysis.runs.len(), 1);
+        assert_eq!(analysis.max_interval, None);
+        let json = analysis.to_json();
+        assert!(json.contains(r#""firstRun":"2024-01-01T00:00:00Z""#));
+        assert!(!json.contains("longestGap"));
+    }
+
+    #[test]
+    fn test_window_is_cut_at_run_limit() {
+        let start = ts("2024-01-01T00:00:00Z");
+        let analysis = analyze(
+            &cron("* * * * *"),
+            start,
+            ts("2026-01-01T00:00:00Z"),
+            &TimeZone::UTC,
+            DstPolicy::Vixie,
+        );
+        assert!(analysis.truncated);
+        assert_eq!(analysis.runs.len(), MAX_ANALYSIS_RUNS);
+        assert_eq!(
+            analysis.end.as_second() - start.as_second(),
+            MAX_ANALYSIS_RUNS as i64 * 60
+        );
+    }
+
+    #[test]
+    fn test_collisions_by_minute() {
+        let report = collisions(
+            &[
+                cron("*/15 * * * *"),
+                cron("0,20,40 * * * *"),
+                cron("0 */2 * * *"),
+            ],
+            ts("2024-07-01T00:00:00Z"),
+            ts("2024-07-01T03:00:00Z"),
+            &TimeZone::UTC,
+            DstPolicy::Vixie,
+        );
+        assert_eq!(report.collision_count, 3);
+        assert_eq!(report.collisions[0].schedules, vec![0, 1, 2]);
+        assert_eq!(report.collisions[1].minute, ts("2024-07-01T01:00:00Z"));
+        assert_eq!(report.collisions[1].schedules, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_rate_schedule_intervals() {
+        let rate = Schedule::parse("rate(2 hours)", CronDialect::Aws, "").unwrap();
+        let analysis = analyze(
+            &rate,
+            ts("2024-07-01T00:00:00Z"),
+            ts("2024-07-02T00:00:00Z"),
+            &TimeZone::UTC,
+            DstPolicy::Vixie,
+        );
+        assert_eq!(analysis.runs.len(), 11);
+        assert_eq!(analysis.min_interval, analysis.max_interval);
+    }
+}
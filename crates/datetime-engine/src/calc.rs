@@ -1,13 +1,21 @@
 use jiff::{Timestamp, ToSpan};
 
-use crate::format::FormattedResult;
+use crate::abbreviation;
+use crate::format::{self, FormattedResult, NamedFormat};
+use crate::json_utils::{extract_number_field, extract_string_field, parse_string_array};
+use crate::locale::Locale;
+use crate::spreadsheet;
+use crate::windows::{self, Decoded};
+use crate::zone::{self, resolve_zone, Disambiguation};
 
 /// A single arithmetic operation to apply to a datetime.
 #[derive(Debug, Clone)]
 pub struct Operation {
-    /// "add" or "subtract"
+    /// "add", "subtract" or "set"
     pub op_type: String,
-    /// "years", "months", "days", "hours", "minutes", "seconds"
+    /// "years", "months", "days", "hours", "minutes", "seconds"; for "set", the
+    /// field to replace ("days" is the day of the month; setting the year or month
+    /// clamps the day to the end of the new month)
     pub unit: String,
     /// The numeric value for the operation
     pub value: i64,
@@ -67,7 +75,7 @@ fn parse_single_operation(json: &str) -> Result<Operation, String> {
     let value = extract_number_field(json, "value")
         .ok_or_else(|| "Missing 'value' field in operation".to_string())?;
 
-    if op_type != "add" && op_type != "subtract" {
+    if !["add", "subtract", "set"].contains(&op_type.as_str()) {
         return Err(format!("Unknown operation type: '{}'", op_type));
     }
 
@@ -83,49 +91,30 @@ fn parse_single_operation(json: &str) -> Result<Operation, String> {
     })
 }
 
-/// Extract a string value for a given key from a JSON object string.
-fn extract_string_field(json: &str, key: &str) -> Option<String> {
-    let pattern = format!("\"{}\"", key);
-    let key_pos = json.find(&pattern)?;
-    let after_key = &json[key_pos + pattern.len()..];
-    // Skip whitespace and colon
-    let after_colon = after_key.trim_start().strip_prefix(':')?;
-    let after_colon = after_colon.trim_start();
-    // Find the opening quote
-    let after_quote = after_colon.strip_prefix('"')?;
-    // Find the closing quote (simple - no escape handling needed for our values)
-    let end_quote = after_quote.find('"')?;
-    Some(after_quote[..end_quote].to_string())
-}
-
-/// Extract a number value for a given key from a JSON object string.
-fn extract_number_field(json: &str, key: &str) -> Option<i64> {
-    let pattern = format!("\"{}\"", key);
-    let key_pos = json.find(&pattern)?;
-    let after_key = &json[key_pos + pattern.len()..];
-    let after_colon = after_key.trim_start().strip_prefix(':')?;
-    let after_colon = after_colon.trim_start();
-
-    // Collect digits and optional minus sign
-    let mut num_str = String::new();
-    for ch in after_colon.chars() {
-        if ch == '-' || ch.is_ascii_digit() {
-            num_str.push(ch);
-        } else if !num_str.is_empty() {
-            break;
-        }
-    }
-
-    num_str.parse().ok()
-}
-
 /// Parse a start date string into a Timestamp.
 ///
 /// Supports:
 /// - ISO 8601 / RFC 3339 timestamps (e.g., "2024-07-11T01:14:00Z")
 /// - Civil dates (e.g., "2024-07-11") — interpreted as midnight UTC
 /// - Civil datetimes (e.g., "2024-07-11T12:00:00") — interpreted as UTC
-fn parse_start_date(start_date: &str) -> Result<Timestamp, String> {
+/// - Civil datetimes or bare times with a zone abbreviation or offset (e.g.,
+///   "2024-07-11 12:00 CEST", "2024-07-11 12:00 GMT-3", "10:00 PST"); a bare time is
+///   on today's date at that offset; see `abbreviation`
+pub(crate) fn parse_start_date(start_date: &str) -> Result<Timestamp, String> {
+    parse_start_date_in(
+        start_date,
+        &jiff::tz::TimeZone::UTC,
+        Disambiguation::Compatible,
+    )
+}
+
+/// Like `parse_start_date`, but civil dates and datetimes are wall-clock times in `tz`;
+/// `disambiguation` decides times skipped or repeated by a DST transition.
+pub(crate) fn parse_start_date_in(
+    start_date: &str,
+    tz: &jiff::tz::TimeZone,
+    disambiguation: Disambiguation,
+) -> Result<Timestamp, String> {
     let trimmed = start_date.trim();
 
     // Try as Timestamp first (most specific)
@@ -133,21 +122,50 @@ fn parse_start_date(start_date: &str) -> Result<Timestamp, String> {
         return Ok(ts);
     }
 
-    // Try as civil DateTime, convert to UTC Timestamp
+    // A civil datetime with a zone annotation ("2024-03-10T01:00[America/New_York]") is a
+    // wall-clock time in that zone; jiff's civil parser would drop the annotation
+    if let Some((civil, name)) = trimmed.strip_suffix(']').and_then(|s| s.rsplit_once('[')) {
+        if let Ok(dt) = civil.parse::<jiff::civil::DateTime>() {
+            let zone_tz = zone::resolve_zone(name.trim_start_matches('!'))?;
+            return zone::resolve_local(dt, &zone_tz, disambiguation);
+        }
+    }
+
+    // Try as civil DateTime (a bare date parses as midnight)
     if let Ok(dt) = trimmed.parse::<jiff::civil::DateTime>() {
-        return dt
-            .to_zoned(jiff::tz::TimeZone::UTC)
-            .map(|z| z.timestamp())
-            .map_err(|e| format!("Failed to convert datetime to UTC: {}", e));
+        return zone::resolve_local(dt, tz, disambiguation);
+    }
+
+    // Try as civil DateTime or bare time with an abbreviation or offset ("2024-07-11
+    // 10:00 PST", "10:00 PST"); ambiguous abbreviations take their most common reading,
+    // as `validate` reports
+    if let Some((head, suffix)) = abbreviation::split_zone_suffix(trimmed) {
+        let civil = head.parse::<jiff::civil::DateTime>().ok().or_else(|| {
+            let time = head.parse::<jiff::civil::Time>().ok()?;
+            let today = Timestamp::now().to_zoned(jiff::tz::TimeZone::fixed(suffix.offset));
+            Some(today.date().to_datetime(time))
+        });
+        if let Some(dt) = civil {
+            return suffix
+                .offset
+                .to_timestamp(dt)
+                .map_err(|e| format!("Failed to convert datetime: {}", e));
+        }
+    }
+
+    // Windows encodings behind a prefix ("filetime:0x01DAD33A4B1F7000", "ole:45484.5");
+    // OLE and DOS values are wall-clock times in `tz`
+    if let Some(decoded) = windows::parse_input(trimmed) {
+        return match decoded? {
+            Decoded::Instant(ts) => Ok(ts),
+            Decoded::Civil(dt) => zone::resolve_local(dt, tz, disambiguation),
+        };
     }
 
-    // Try as civil Date, convert to midnight UTC
-    if let Ok(date) = trimmed.parse::<jiff::civil::Date>() {
-        return date
-            .at(0, 0, 0, 0)
-            .to_zoned(jiff::tz::TimeZone::UTC)
-            .map(|z| z.timestamp())
-            .map_err(|e| format!("Failed to convert date to UTC: {}", e));
+    // Spreadsheet serials behind a prefix ("excel:45484.5", "sheets:-1.25"), wall-clock
+    // times in `tz`
+    if let Some(dt) = spreadsheet::parse_input(trimmed) {
+        return zone::resolve_local(dt?, tz, disambiguation);
     }
 
     Err(format!(
@@ -170,8 +188,164 @@ pub fn calculate(start_date: &str, operations_json: &str) -> Result<FormattedRes
     Ok(FormattedResult::from_timestamp(current))
 }
 
+/// Like `calculate`, but also renders the requested named output formats.
+///
+/// `formats_json` is a JSON array of `NamedFormat` names (e.g., `["rfc9557","isoWeek"]`)
+/// and `zone` the zone they are rendered in (see `zone::resolve_zone`).
+pub fn calculate_with_formats(
+    start_date: &str,
+    operations_json: &str,
+    formats_json: &str,
+    zone: &str,
+) -> Result<FormattedResult, String> {
+    let names = parse_string_array(formats_json)?;
+    let formats = names
+        .iter()
+        .map(|name| NamedFormat::from_name(name))
+        .collect::<Result<Vec<_>, _>>()?;
+    let tz = resolve_zone(zone)?;
+
+    let mut result = calculate(start_date, operations_json)?;
+    // From the ISO string rather than `unix_timestamp`, to keep fractional seconds
+    let ts: Timestamp = result
+        .iso_8601
+        .parse()
+        .map_err(|e| format!("Invalid result timestamp: {}", e))?;
+    // A format that cannot represent the instant (a FILETIME before 1601) fails alone
+    for f in formats {
+        result
+            .formats
+            .push((f.name().to_string(), f.render(ts, &tz)));
+    }
+
+    Ok(result)
+}
+
+/// Like `calculate`, but renders `local_human` in the given zone and locale
+/// (see `Locale::get` for accepted tags).
+pub fn calculate_localized(
+    start_date: &str,
+    operations_json: &str,
+    zone: &str,
+    locale: &str,
+) -> Result<FormattedResult, String> {
+    let tz = resolve_zone(zone)?;
+    let locale = Locale::get(locale)?;
+    let result = calculate(start_date, operations_json)?;
+    // From the ISO string rather than `unix_timestamp`, to keep fractional seconds
+    let ts: Timestamp = result
+        .iso_8601
+        .parse()
+        .map_err(|e| format!("Invalid result timestamp: {}", e))?;
+    Ok(FormattedResult::from_timestamp_localized(ts, &tz, locale))
+}
+
+/// Like `calculate`, but civil inputs and calendar arithmetic use the wall clock of a zone.
+///
+/// `options_json` is an optional JSON object: `zone` (see `zone::resolve_zone`) and
+/// `disambiguation` (`compatible`, `earlier`, `later` or `reject`) for local times
+/// skipped or repeated by DST, both in the start date and after each operation.
+/// `local_human` is rendered in the zone.
+pub fn calculate_in_zone(
+    start_date: &str,
+    operations_json: &str,
+    options_json: &str,
+) -> Result<FormattedResult, String> {
+    let tz = match extract_string_field(options_json, "zone") {
+        Some(zone) => resolve_zone(&zone)?,
+        None => jiff::tz::TimeZone::UTC,
+    };
+    let disambiguation = match extract_string_field(options_json, "disambiguation") {
+        Some(name) => Disambiguation::from_name(&name)?,
+        None => Disambiguation::default(),
+    };
+    let operations = parse_operations(operations_json)?;
+
+    let mut current = parse_start_date_in(start_date, &tz, disambiguation)?;
+    for op in &operations {
+        current = apply_operation_in(current, op, &tz, disambiguation)?;
+    }
+
+    Ok(FormattedResult::from_timestamp_localized(
+        current,
+        &tz,
+        &crate::locale::EN,
+    ))
+}
+
+/// Render a date input with an arbitrary strftime pattern in the given zone and locale.
+///
+/// `input` accepts the same forms as the start date of `calculate`; `locale` may be
+/// `""` for English.
+pub fn format_with_pattern(
+    input: &str,
+    pattern: &str,
+    zone: &str,
+    locale: &str,
+) -> Result<String, String> {
+    let ts = parse_start_date(input)?;
+    let tz = resolve_zone(zone)?;
+    let locale = Locale::get(locale)?;
+    format::format_pattern_localized(ts, pattern, &tz, locale)
+}
+
 /// Apply a single operation to a timestamp.
-fn apply_operation(ts: Timestamp, op: &Operation) -> Result<Timestamp, String> {
+pub(crate) fn apply_operation(ts: Timestamp, op: &Operation) -> Result<Timestamp, String> {
+    apply_operation_in(ts, op, &jiff::tz::TimeZone::UTC, Disambiguation::Compatible)
+}
+
+/// Apply a single operation on the wall clock of `tz`.
+///
+/// Calendar units and "set" work on the local date-time, so the result can land in a
+/// DST gap or fold; `disambiguation` picks the instant then. Time units are exact.
+fn apply_operation_in(
+    ts: Timestamp,
+    op: &Operation,
+    tz: &jiff::tz::TimeZone,
+    disambiguation: Disambiguation,
+) -> Result<Timestamp, String> {
+    let civil = ts.to_zoned(tz.clone()).datetime();
+    if op.op_type == "set" {
+        let with = civil.with();
+        let value = op.value;
+        let with = match op.unit.as_str() {
+            // Clamp the day to the new month's length, as adding months does
+            "years" | "months" => {
+                let (year, month) = if op.unit == "years" {
+                    (
+                        value.try_into().map_err(|_| field_error(op))?,
+                        civil.month(),
+                    )
+                } else {
+                    (civil.year(), value.try_into().map_err(|_| field_error(op))?)
+                };
+                let first = jiff::civil::Date::new(year, month, 1).map_err(|_| field_error(op))?;
+                with.year(year)
+                    .month(month)
+                    .day(civil.day().min(first.days_in_month()))
+            }
+            "days" => {
+                let day: i8 = value.try_into().map_err(|_| field_error(op))?;
+                if !(1..=civil.date().days_in_month()).contains(&day) {
+                    return Err(format!(
+                        "Set operation failed: {} has no day {}",
+                        civil.strftime("%B %Y"),
+                        value
+                    ));
+                }
+                with.day(day)
+            }
+            "hours" => with.hour(value.try_into().map_err(|_| field_error(op))?),
+            "minutes" => with.minute(value.try_into().map_err(|_| field_error(op))?),
+            "seconds" => with.second(value.try_into().map_err(|_| field_error(op))?),
+            other => return Err(format!("Unknown unit: '{}'", other)),
+        };
+        let civil = with
+            .build()
+            .map_err(|e| format!("Set operation failed: {}", e))?;
+        return zone::resolve_local(civil, tz, disambiguation);
+    }
+
     let span = match op.unit.as_str() {
         "years" => op.value.years(),
         "months" => op.value.months(),
@@ -182,24 +356,22 @@ fn apply_operation(ts: Timestamp, op: &Operation) -> Result<Timestamp, String> {
         other => return Err(format!("Unknown unit: '{}'", other)),
     };
 
-    // For calendar units (years, months, days), we need to go through Zoned
-    // to handle DST and calendar edge cases correctly.
+    // For calendar units (years, months, days), we need to go through the civil
+    // date-time to handle DST and calendar edge cases correctly.
     let needs_calendar = matches!(op.unit.as_str(), "years" | "months" | "days");
 
     if needs_calendar {
-        let zoned = ts.to_zoned(jiff::tz::TimeZone::UTC);
-
         let result = match op.op_type.as_str() {
-            "add" => zoned
+            "add" => civil
                 .checked_add(span)
                 .map_err(|e| format!("Add operation failed: {}", e))?,
-            "subtract" => zoned
+            "subtract" => civil
                 .checked_sub(span)
                 .map_err(|e| format!("Subtract operation failed: {}", e))?,
             other => return Err(format!("Unknown operation type: '{}'", other)),
         };
 
-        Ok(result.timestamp())
+        zone::resolve_local(result, tz, disambiguation)
     } else {
         // For time-only units, operate directly on Timestamp
         match op.op_type.as_str() {
@@ -214,6 +386,36 @@ fn apply_operation(ts: Timestamp, op: &Operation) -> Result<Timestamp, String> {
     }
 }
 
+fn field_error(op: &Operation) -> String {
+    format!(
+        "Set operation failed: {} is out of range for {}",
+        op.value, op.unit
+    )
+}
+
+/// Parse a `[start, end)` window; an empty `end` means one year after `start` on the
+/// wall clock of `zone`.
+pub(crate) fn parse_window(
+    start: &str,
+    end: &str,
+    zone: &jiff::tz::TimeZone,
+) -> Result<(Timestamp, Timestamp), String> {
+    let start = parse_start_date(start)?;
+    let end = if end.trim().is_empty() {
+        start
+            .to_zoned(zone.clone())
+            .checked_add(1.year())
+            .map_err(|e| format!("Window end out of range: {}", e))?
+            .timestamp()
+    } else {
+        parse_start_date(end)?
+    };
+    if end <= start {
+        return Err("Window end must be after its start".to_string());
+    }
+    Ok((start, end))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -296,4 +498,74 @@ mod tests {
         assert_eq!(ops[0].unit, "months");
         assert_eq!(ops[0].value, 3);
     }
+
+    #[test]
+    fn test_calculate_with_formats() {
+        let result = calculate_with_formats(
+            "2024-07-11T01:14:00Z",
+            "[]",
+            r#"["basicIso","rfc3339"]"#,
+            "-04:00",
+        )
+        .unwrap();
+        assert_eq!(
+            result.formats,
+            vec![
+                ("basicIso".to_string(), Ok("20240711T011400Z".to_string())),
+                (
+                    "rfc3339".to_string(),
+                    Ok("2024-07-10T21:14:00-04:00".to_string())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_calculate_with_formats_keeps_fraction() {
+        let result =
+            calculate_with_formats("2024-07-11T01:14:00.25Z", "[]", r#"["rfc3339"]"#, "UTC")
+                .unwrap();
+        assert_eq!(
+            result.formats[0].1.as_deref(),
+            Ok("2024-07-11T01:14:00.25+00:00")
+        );
+    }
+
+    #[test]
+    fn test_calculate_with_unknown_format() {
+        let result = calculate_with_formats("2024-07-11", "[]", r#"["nope"]"#, "UTC");
+        assert!(result.unwrap_err().contains("Unknown output format"));
+    }
+
+    #[test]
+    fn test_format_with_pattern() {
+        let out = format_with_pattern(
+            "2024-07-11T01:14:00Z",
+            "%A %e %B %Y, %H:%M %Z",
+            "Asia/Tokyo",
+            "",
+        )
+        .unwrap();
+        assert_eq!(out, "Thursday 11 July 2024, 10:14 JST");
+    }
+
+    #[cfg(feature = "locales")]
+    #[test]
+    fn test_calculate_localized() {
+        let result = calculate_localized(
+            "2024-01-31T12:00:00Z",
+            r#"[{"type":"add","unit":"months","value":1}]"#,
+            "America/Sao_Paulo",
+            "pt-BR",
+        )
+        .unwrap();
+        assert_eq!(result.iso_8601, "2024-02-29T12:00:00Z");
+        assert_eq!(result.local_human, "29 de fevereiro de 2024 09:00:00 -03");
+    }
+
+    #[test]
+    fn test_calculate_localized_keeps_fraction() {
+        let result = calculate_localized("2024-07-11T01:14:00.25Z", "[]", "UTC", "en").unwrap();
+        assert_eq!(result.iso_8601, "2024-07-11T01:14:00.25Z");
+    }
 }
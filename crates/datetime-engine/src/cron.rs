@@ -0,0 +1,1850 @@
+use jiff::civil::{Date, DateTime};
+use jiff::tz::{AmbiguousOffset, TimeZone};
+use jiff::{Timestamp, ToSpan};
+
+use crate::calc::parse_start_date;
+use crate::json_utils::{extract_string_field, json_escape};
+use crate::zone::{resolve_zone, zone_name};
+
+/// How far `next_*` / `previous_*` look before concluding a schedule never fires.
+///
+/// Dates and weekdays repeat every 400 years in the Gregorian calendar, so any
+/// day-of-month and day-of-week combination that can occur at all occurs within it
+/// (February 29 on a Monday can be 40 years apart, e.g. 2072 and 2112).
+const SEARCH_YEARS: i16 = 400;
+
+const MONTH_NAMES: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const WEEKDAY_NAMES: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// The set of allowed values of one cron field, as a bitmask over `0..64`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FieldSet(u64);
+
+impl FieldSet {
+    /// The values in `min..=max` accepted by `keep`.
+    pub(crate) fn from_fn(min: u8, max: u8, keep: impl Fn(u8) -> bool) -> Self {
+        FieldSet(
+            (min..=max)
+                .filter(|v| keep(*v))
+                .fold(0, |bits, v| bits | 1 << v),
+        )
+    }
+
+    pub(crate) fn single(value: u8) -> Self {
+        FieldSet(1 << value)
+    }
+
+    fn range(min: u8, max: u8, step: u8) -> Self {
+        let mut bits = 0;
+        let mut v = min;
+        while v <= max {
+            bits |= 1u64 << v;
+            v = match v.checked_add(step) {
+                Some(next) => next,
+                None => break,
+            };
+        }
+        FieldSet(bits)
+    }
+
+    pub fn contains(self, value: i8) -> bool {
+        (0..64).contains(&value) && self.0 & (1 << value) != 0
+    }
+
+    /// The smallest allowed value `>= from`.
+    fn next_from(self, from: i8) -> Option<i8> {
+        if !(0..64).contains(&from) {
+            return None;
+        }
+        let masked = self.0 & (u64::MAX << from);
+        (masked != 0).then(|| masked.trailing_zeros() as i8)
+    }
+
+    /// The largest allowed value `<= from`.
+    fn prev_from(self, from: i8) -> Option<i8> {
+        if from < 0 {
+            return None;
+        }
+        let masked = if from >= 63 {
+            self.0
+        } else {
+            self.0 & ((1u64 << (from + 1)) - 1)
+        };
+        (masked != 0).then(|| 63 - masked.leading_zeros() as i8)
+    }
+
+    /// Allowed values in ascending order.
+    pub fn values(self) -> Vec<u8> {
+        (0..64).filter(|v| self.0 & (1 << v) != 0).collect()
+    }
+}
+
+/// Which part of the schedule a field describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldKind {
+    Time,
+    DayOfMonth,
+    Month,
+    DayOfWeek,
+}
+
+/// Static description of one cron field.
+struct FieldSpec {
+    name: &'static str,
+    kind: FieldKind,
+    min: u8,
+    max: u8,
+    names: &'static [&'static str],
+    /// Offset added to a name's index (months are 1-based, weekdays 0-based)
+    name_base: u8,
+}
+
+impl FieldSpec {
+    /// The range Jenkins picks a bare `H` from; day-of-month stops at 28 so
+    /// the hashed day exists in every month.
+    fn hash_range(&self) -> (u8, u8) {
+        match self.kind {
+            FieldKind::DayOfMonth => (1, 28),
+            FieldKind::DayOfWeek => (0, 6),
+            _ => (self.min, self.max),
+        }
+    }
+}
+
+const SECOND: FieldSpec = FieldSpec {
+    name: "second",
+    kind: FieldKind::Time,
+    min: 0,
+    max: 59,
+    names: &[],
+    name_base: 0,
+};
+const MINUTE: FieldSpec = FieldSpec {
+    name: "minute",
+    kind: FieldKind::Time,
+    min: 0,
+    max: 59,
+    names: &[],
+    name_base: 0,
+};
+const HOUR: FieldSpec = FieldSpec {
+    name: "hour",
+    kind: FieldKind::Time,
+    min: 0,
+    max: 23,
+    names: &[],
+    name_base: 0,
+};
+const DAY_OF_MONTH: FieldSpec = FieldSpec {
+    name: "day-of-month",
+    kind: FieldKind::DayOfMonth,
+    min: 1,
+    max: 31,
+    names: &[],
+    name_base: 0,
+};
+const MONTH: FieldSpec = FieldSpec {
+    name: "month",
+    kind: FieldKind::Month,
+    min: 1,
+    max: 12,
+    names: &MONTH_NAMES,
+    name_base: 1,
+};
+/// 7 is accepted as Sunday and folded onto 0 after parsing.
+const DAY_OF_WEEK: FieldSpec = FieldSpec {
+    name: "day-of-week",
+    kind: FieldKind::DayOfWeek,
+    min: 0,
+    max: 7,
+    names: &WEEKDAY_NAMES,
+    name_base: 0,
+};
+/// Quartz and AWS number weekdays 1 (Sunday) through 7 (Saturday).
+const DAY_OF_WEEK_FROM_ONE: FieldSpec = FieldSpec {
+    name: "day-of-week",
+    kind: FieldKind::DayOfWeek,
+    min: 1,
+    max: 7,
+    names: &WEEKDAY_NAMES,
+    name_base: 1,
+};
+
+/// A cron flavour: field layout, weekday numbering and special characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CronDialect {
+    /// POSIX / Vixie cron: `minute hour day-of-month month day-of-week`, Sunday is 0 or 7
+    Standard,
+    /// Quartz: `second minute hour day-of-month month day-of-week [year]`, Sunday is 1,
+    /// with `?`, `L`, `W` and `#`
+    Quartz,
+    /// AWS EventBridge: `cron(minute hour day-of-month month day-of-week year)` with the
+    /// Quartz special characters, or `rate(5 minutes)`
+    Aws,
+    /// Jenkins: standard fields plus `H` hash tokens; day-of-month and day-of-week
+    /// must both match
+    Jenkins,
+}
+
+impl CronDialect {
+    /// Parse a dialect name from the Wasm boundary; `""` selects `Standard`.
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "" | "standard" | "posix" | "unix" | "vixie" => Ok(CronDialect::Standard),
+            "quartz" => Ok(CronDialect::Quartz),
+            "aws" | "eventbridge" => Ok(CronDialect::Aws),
+            "jenkins" => Ok(CronDialect::Jenkins),
+            other => Err(format!(
+                "Unknown cron dialect '{}' (expected standard, quartz, aws or jenkins)",
+                other
+            )),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CronDialect::Standard => "standard",
+            CronDialect::Quartz => "quartz",
+            CronDialect::Aws => "aws",
+            CronDialect::Jenkins => "jenkins",
+        }
+    }
+
+    /// Human-readable name for error messages.
+    fn label(self) -> &'static str {
+        match self {
+            CronDialect::Standard => "standard cron",
+            CronDialect::Quartz => "Quartz",
+            CronDialect::Aws => "AWS EventBridge",
+            CronDialect::Jenkins => "Jenkins",
+        }
+    }
+
+    /// Whether `?`, `L`, `W` and `#` are available.
+    fn has_day_specials(self) -> bool {
+        matches!(self, CronDialect::Quartz | CronDialect::Aws)
+    }
+
+    /// The `@` macros this dialect understands, expanded to fields.
+    fn expand_macro(self, name: &str) -> Result<&'static str, String> {
+        let expanded = match (self, name) {
+            (CronDialect::Standard, "@yearly" | "@annually") => "0 0 1 1 *",
+            (CronDialect::Standard, "@monthly") => "0 0 1 * *",
+            (CronDialect::Standard, "@weekly") => "0 0 * * 0",
+            (CronDialect::Standard, "@daily" | "@midnight") => "0 0 * * *",
+            (CronDialect::Standard, "@hourly") => "0 * * * *",
+            (CronDialect::Jenkins, "@yearly" | "@annually") => "H H H H *",
+            (CronDialect::Jenkins, "@monthly") => "H H H * *",
+            (CronDialect::Jenkins, "@weekly") => "H H * * H",
+            (CronDialect::Jenkins, "@daily") => "H H * * *",
+            (CronDialect::Jenkins, "@midnight") => "H H(0-2) * * *",
+            (CronDialect::Jenkins, "@hourly") => "H * * * *",
+            (CronDialect::Standard | CronDialect::Jenkins, "@reboot") => {
+                return Err("@reboot has no schedule to evaluate".to_string())
+            }
+            (CronDialect::Standard | CronDialect::Jenkins, _) => {
+                return Err(format!("Unknown cron macro: '{}'", name))
+            }
+            (_, _) => {
+                return Err(format!(
+                    "Macros like '{}' are not valid in {}",
+                    name,
+                    self.label()
+                ))
+            }
+        };
+        Ok(expanded)
+    }
+}
+
+/// Day rules that cannot be expressed as a plain set of numbers (Quartz and AWS).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DaySpecial {
+    /// `L` / `L-3`: the last day of the month, or that many days before it
+    LastDay(u8),
+    /// `LW`: the last Monday–Friday of the month
+    LastWeekday,
+    /// `15W`: the Monday–Friday nearest the given day, without leaving the month
+    NearestWeekday(u8),
+    /// `6L` / `FRIL`: the last given weekday of the month (0 = Sunday)
+    LastOfMonth(u8),
+    /// `6#3` / `FRI#3`: the nth given weekday of the month (0 = Sunday)
+    Nth(u8, u8),
+}
+
+impl DaySpecial {
+    pub fn matches(self, date: Date) -> bool {
+        let day = date.day() as u8;
+        let last = date.days_in_month() as u8;
+        let weekday = date.weekday().to_sunday_zero_offset() as u8;
+        match self {
+            DaySpecial::LastDay(offset) => last.checked_sub(offset) == Some(day),
+            DaySpecial::LastWeekday => {
+                let last_date = date.last_of_month();
+                let back = match last_date.weekday().to_sunday_zero_offset() {
+                    0 => 2,
+                    6 => 1,
+                    _ => 0,
+                };
+                day == last - back
+            }
+            DaySpecial::NearestWeekday(target) => {
+                if target > last {
+                    return false;
+                }
+                let Ok(target_date) = date.with().day(target as i8).build() else {
+                    return false;
+                };
+                let nearest = match target_date.weekday().to_sunday_zero_offset() {
+                    6 if target == 1 => target + 2,
+                    6 => target - 1,
+                    0 if target == last => target - 2,
+                    0 => target + 1,
+                    _ => target,
+                };
+                day == nearest
+            }
+            DaySpecial::LastOfMonth(target) => weekday == target && day + 7 > last,
+            DaySpecial::Nth(target, n) => weekday == target && (day - 1) / 7 + 1 == n,
+        }
+    }
+}
+
+/// A parsed cron schedule.
+///
+/// Standard five-field expressions (`minute hour day-of-month month day-of-week`)
+/// run at second 0. Lists (`1,15`), ranges (`1-5`), steps (`*/15`, `10-40/10`,
+/// `5/20`), month and weekday names (`JAN`, `mon-fri`) and the `@yearly`,
+/// `@monthly`, `@weekly`, `@daily`, `@hourly` macros are supported. Other
+/// dialects are parsed with `parse_dialect`.
+///
+/// When both day-of-month and day-of-week are restricted (neither starts with
+/// `*`), a standard cron day matches if *either* matches, as POSIX and Vixie
+/// cron specify: `0 0 13 * FRI` runs on every 13th and on every Friday.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    pub expression: String,
+    pub dialect: CronDialect,
+    pub seconds: FieldSet,
+    pub minutes: FieldSet,
+    pub hours: FieldSet,
+    pub days_of_month: FieldSet,
+    pub months: FieldSet,
+    /// 0 (Sunday) through 6 (Saturday)
+    pub days_of_week: FieldSet,
+    /// `L` / `W` rules from the day-of-month field
+    pub dom_specials: Vec<DaySpecial>,
+    /// `L` / `#` rules from the day-of-week field
+    pub dow_specials: Vec<DaySpecial>,
+    /// Allowed years in ascending order, when a year field restricts them
+    pub years: Option<Vec<i16>>,
+    pub dom_restricted: bool,
+    pub dow_restricted: bool,
+    /// Minute or hour starts with `*`; see `DstPolicy`
+    pub wildcard_time: bool,
+}
+
+impl CronSchedule {
+    /// Parse a five-field standard cron expression or `@` macro.
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        Self::parse_dialect(expression, CronDialect::Standard, "")
+    }
+
+    /// Parse an expression in `dialect`.
+    ///
+    /// `seed` feeds Jenkins `H` tokens (Jenkins uses the job name). Jenkins itself
+    /// derives `H` from an MD5 of the job name; this engine uses a stable FNV-1a
+    /// hash of the seed and field, so values are spread and repeatable but need
+    /// not equal the ones Jenkins picks.
+    pub fn parse_dialect(
+        expression: &str,
+        dialect: CronDialect,
+        seed: &str,
+    ) -> Result<Self, String> {
+        let trimmed = expression.trim();
+        let mut body = trimmed;
+        if dialect == CronDialect::Aws {
+            if let Some(inner) = body.strip_prefix("cron(").and_then(|b| b.strip_suffix(')')) {
+                body = inner.trim();
+            }
+        }
+        if body.starts_with('@') {
+            body = dialect.expand_macro(&body.to_ascii_lowercase())?;
+        }
+
+        let fields: Vec<&str> = body.split_whitespace().collect();
+        let (second, rest, year) = match (dialect, fields.len()) {
+            (CronDialect::Standard | CronDialect::Jenkins, 5) => (None, &fields[..], None),
+            (CronDialect::Quartz, 6) => (Some(fields[0]), &fields[1..], None),
+            (CronDialect::Quartz, 7) => (Some(fields[0]), &fields[1..6], Some(fields[6])),
+            (CronDialect::Aws, 6) => (None, &fields[..5], Some(fields[5])),
+            (_, n) => {
+                let layout = match dialect {
+                    CronDialect::Quartz => {
+                        "6 or 7 fields (second minute hour day-of-month month day-of-week [year])"
+                    }
+                    CronDialect::Aws => {
+                        "6 fields (minute hour day-of-month month day-of-week year)"
+                    }
+                    _ => "5 fields (minute hour day-of-month month day-of-week)",
+                };
+                return Err(format!(
+                    "{} expressions must have {}, got {}",
+                    capitalize(dialect.label()),
+                    layout,
+                    n
+                ));
+            }
+        };
+
+        let parser = FieldParser { dialect, seed };
+        let dow_spec = if dialect.has_day_specials() {
+            &DAY_OF_WEEK_FROM_ONE
+        } else {
+            &DAY_OF_WEEK
+        };
+        let dom = parser.field(rest[2], &DAY_OF_MONTH)?;
+        let dow = parser.field(rest[4], dow_spec)?;
+        if dialect.has_day_specials() && dom.no_spec == dow.no_spec {
+            return Err(format!(
+                "{} requires '?' in exactly one of day-of-month and day-of-week",
+                dialect.label()
+            ));
+        }
+
+        Ok(CronSchedule {
+            expression: trimmed.to_string(),
+            dialect,
+            seconds: match second {
+                Some(raw) => parser.field(raw, &SECOND)?.set,
+                None => FieldSet::single(0),
+            },
+            minutes: parser.field(rest[0], &MINUTE)?.set,
+            hours: parser.field(rest[1], &HOUR)?.set,
+            days_of_month: dom.set,
+            months: parser.field(rest[3], &MONTH)?.set,
+            days_of_week: dow.set,
+            dom_specials: dom.specials,
+            dow_specials: dow.specials,
+            years: match year {
+                Some(raw) => parse_years(raw, dialect)?,
+                None => None,
+            },
+            dom_restricted: !dom.no_spec && !rest[2].starts_with('*'),
+            dow_restricted: !dow.no_spec && !rest[4].starts_with('*'),
+            wildcard_time: rest[0].starts_with('*') || rest[1].starts_with('*'),
+        })
+    }
+
+    /// Whether the schedule fires on `date` (ignoring the time fields).
+    pub fn matches_date(&self, date: Date) -> bool {
+        if let Some(years) = &self.years {
+            if years.binary_search(&date.year()).is_err() {
+                return false;
+            }
+        }
+        if !self.months.contains(date.month()) {
+            return false;
+        }
+        let dom = self.days_of_month.contains(date.day())
+            || self.dom_specials.iter().any(|s| s.matches(date));
+        let dow = self
+            .days_of_week
+            .contains(date.weekday().to_sunday_zero_offset())
+            || self.dow_specials.iter().any(|s| s.matches(date));
+        if self.dialect == CronDialect::Standard && self.dom_restricted && self.dow_restricted {
+            dom || dow
+        } else {
+            dom && dow
+        }
+    }
+
+    /// Whether the schedule fires at exactly `dt` (to the second).
+    pub fn matches(&self, dt: DateTime) -> bool {
+        dt.subsec_nanosecond() == 0
+            && self.seconds.contains(dt.second())
+            && self.minutes.contains(dt.minute())
+            && self.hours.contains(dt.hour())
+            && self.matches_date(dt.date())
+    }
+
+    /// The first civil datetime strictly after `after` at which the schedule fires.
+    ///
+    /// Non-matching months, days, hours and minutes are skipped whole rather
+    /// than scanned minute by minute.
+    pub fn next_civil(&self, after: DateTime) -> Option<DateTime> {
+        let start = after
+            .checked_sub(after.subsec_nanosecond().nanoseconds())
+            .ok()?
+            .checked_add(1.second())
+            .ok()?;
+        let limit = match &self.years {
+            Some(years) => *years.last()?,
+            None => start.year().saturating_add(SEARCH_YEARS),
+        };
+
+        let mut date = start.date();
+        let mut from = (start.hour(), start.minute(), start.second());
+        while date.year() <= limit {
+            if let Some(years) = &self.years {
+                let year = *years.iter().find(|y| **y >= date.year())?;
+                if year != date.year() {
+                    date = Date::new(year, 1, 1).ok()?;
+                    from = (0, 0, 0);
+                }
+            }
+            if !self.months.contains(date.month()) {
+                date = date.first_of_month().checked_add(1.month()).ok()?;
+                from = (0, 0, 0);
+                continue;
+            }
+            if self.matches_date(date) {
+                if let Some((h, m, s)) = self.next_time(from) {
+                    return date.at(h, m, s, 0).into();
+                }
+            }
+            date = date.tomorrow().ok()?;
+            from = (0, 0, 0);
+        }
+        None
+    }
+
+    /// The last civil datetime strictly before `before` at which the schedule fires.
+    pub fn previous_civil(&self, before: DateTime) -> Option<DateTime> {
+        // Any fractional second already puts the whole second strictly before `before`.
+        let start = if before.subsec_nanosecond() == 0 {
+            before.checked_sub(1.second()).ok()?
+        } else {
+            before
+                .checked_sub(before.subsec_nanosecond().nanoseconds())
+                .ok()?
+        };
+        let limit = match &self.years {
+            Some(years) => *years.first()?,
+            None => start.year().saturating_sub(SEARCH_YEARS),
+        };
+
+        let mut date = start.date();
+        let mut until = (start.hour(), start.minute(), start.second());
+        while date.year() >= limit {
+            if let Some(years) = &self.years {
+                let year = *years.iter().rev().find(|y| **y <= date.year())?;
+                if year != date.year() {
+                    date = Date::new(year, 12, 31).ok()?;
+                    until = (23, 59, 59);
+                }
+            }
+            if !self.months.contains(date.month()) {
+                date = date.first_of_month().yesterday().ok()?;
+                until = (23, 59, 59);
+                continue;
+            }
+            if self.matches_date(date) {
+                if let Some((h, m, s)) = self.previous_time(until) {
+                    return date.at(h, m, s, 0).into();
+                }
+            }
+            date = date.yesterday().ok()?;
+            until = (23, 59, 59);
+        }
+        None
+    }
+
+    /// The earliest (hour, minute, second) `>= from` allowed by the time fields.
+    fn next_time(&self, from: (i8, i8, i8)) -> Option<(i8, i8, i8)> {
+        let mut hour = self.hours.next_from(from.0)?;
+        loop {
+            let min_from = if hour == from.0 { from.1 } else { 0 };
+            let mut minute = self.minutes.next_from(min_from);
+            while let Some(m) = minute {
+                let sec_from = if hour == from.0 && m == from.1 {
+                    from.2
+                } else {
+                    0
+                };
+                if let Some(s) = self.seconds.next_from(sec_from) {
+                    return Some((hour, m, s));
+                }
+                minute = self.minutes.next_from(m + 1);
+            }
+            hour = self.hours.next_from(hour + 1)?;
+        }
+    }
+
+    /// The latest (hour, minute, second) `<= until` allowed by the time fields.
+    fn previous_time(&self, until: (i8, i8, i8)) -> Option<(i8, i8, i8)> {
+        let mut hour = self.hours.prev_from(until.0)?;
+        loop {
+            let min_until = if hour == until.0 { until.1 } else { 59 };
+            let mut minute = self.minutes.prev_from(min_until);
+            while let Some(m) = minute {
+                let sec_until = if hour == until.0 && m == until.1 {
+                    until.2
+                } else {
+                    59
+                };
+                if let Some(s) = self.seconds.prev_from(sec_until) {
+                    return Some((hour, m, s));
+                }
+                minute = self.minutes.prev_from(m - 1);
+            }
+            hour = self.hours.prev_from(hour - 1)?;
+        }
+    }
+
+    /// Up to `count` runs strictly after `after`, evaluated on the wall clock of `tz`.
+    ///
+    /// Runs whose wall-clock time falls in a DST gap or fold are resolved by `policy`
+    /// and flagged with the `DstEffect` applied.
+    pub fn next_runs(
+        &self,
+        after: Timestamp,
+        count: usize,
+        tz: &TimeZone,
+        policy: DstPolicy,
+    ) -> Vec<CronRun> {
+        let mut runs = Vec::new();
+        if count == 0 {
+            return runs;
+        }
+        // A run resolved out of a fold or gap can land on either side of its
+        // neighbours, but never by more than the wall-clock jump of the transition.
+        let start = after.to_zoned(tz.clone()).datetime();
+        let Ok(mut cursor) = start.checked_sub(transition_span(tz, after)) else {
+            return runs;
+        };
+        let mut horizon = None;
+        while let Some(civil) = self.next_civil(cursor) {
+            if horizon.is_some_and(|limit| civil > limit) {
+                break;
+            }
+            for run in self.resolve(civil, tz, policy) {
+                // Gap times shifted onto the same transition arrive one after another
+                if run.timestamp > after
+                    && runs
+                        .last()
+                        .is_none_or(|last: &CronRun| last.timestamp != run.timestamp)
+                {
+                    runs.push(run);
+                }
+            }
+            if horizon.is_none() && runs.len() >= count {
+                let Some(last) = runs.iter().map(|run| run.timestamp).max() else {
+                    break;
+                };
+                horizon = Some(civil.saturating_add(transition_span(tz, last)));
+            }
+            cursor = civil;
+        }
+        sort_runs(&mut runs, false);
+        runs.truncate(count);
+        runs
+    }
+
+    /// Up to `count` runs strictly before `before`, most recent first.
+    ///
+    /// Zone and DST handling is the same as `next_runs`.
+    pub fn previous_runs(
+        &self,
+        before: Timestamp,
+        count: usize,
+        tz: &TimeZone,
+        policy: DstPolicy,
+    ) -> Vec<CronRun> {
+        let mut runs = Vec::new();
+        if count == 0 {
+            return runs;
+        }
+        let end = before.to_zoned(tz.clone()).datetime();
+        let Ok(mut cursor) = end.checked_add(transition_span(tz, before)) else {
+            return runs;
+        };
+        let mut horizon = None;
+        while let Some(civil) = self.previous_civil(cursor) {
+            if horizon.is_some_and(|limit| civil < limit) {
+                break;
+            }
+            for run in self.resolve(civil, tz, policy) {
+                // Gap times shifted onto the same transition arrive one after another
+                if run.timestamp < before
+                    && runs
+                        .last()
+                        .is_none_or(|last: &CronRun| last.timestamp != run.timestamp)
+                {
+                    runs.push(run);
+                }
+            }
+            if horizon.is_none() && runs.len() >= count {
+                let Some(last) = runs.iter().map(|run| run.timestamp).min() else {
+                    break;
+                };
+                horizon = Some(civil.saturating_sub(transition_span(tz, last)));
+            }
+            cursor = civil;
+        }
+        sort_runs(&mut runs, true);
+        runs.truncate(count);
+        runs
+    }
+
+    /// Runs in `[start, end)` in chronological order, stopping once more than `limit`
+    /// have been found. The caller can therefore see that the window was cut short.
+    ///
+    /// Zone and DST handling is the same as `next_runs`.
+    pub fn runs_between(
+        &self,
+        start: Timestamp,
+        end: Timestamp,
+        limit: usize,
+        tz: &TimeZone,
+        policy: DstPolicy,
+    ) -> Vec<CronRun> {
+        let mut runs = Vec::new();
+        let (Ok(mut cursor), Ok(stop)) = (
+            start.to_zoned(tz.clone()).datetime().checked_sub(1.day()),
+            end.to_zoned(tz.clone()).datetime().checked_add(1.day()),
+        ) else {
+            return runs;
+        };
+        while let Some(civil) = self.next_civil(cursor) {
+            if civil > stop || runs.len() > limit {
+                break;
+            }
+            runs.extend(
+                self.resolve(civil, tz, policy)
+                    .into_iter()
+                    .filter(|run| run.timestamp >= start && run.timestamp < end),
+            );
+            cursor = civil;
+        }
+        sort_runs(&mut runs, false);
+        runs.truncate(limit + 1);
+        runs
+    }
+
+    /// Map one matching wall-clock time in `tz` to the instants it runs at.
+    fn resolve(&self, civil: DateTime, tz: &TimeZone, policy: DstPolicy) -> Vec<CronRun> {
+        let ambiguous = tz.to_ambiguous_zoned(civil);
+        match ambiguous.offset() {
+            AmbiguousOffset::Unambiguous { .. } => ambiguous
+                .unambiguous()
+                .map(|z| vec![CronRun::new(z.timestamp(), None)])
+                .unwrap_or_default(),
+            AmbiguousOffset::Gap { .. } => {
+                if policy != DstPolicy::Vixie || self.wildcard_time {
+                    return Vec::new();
+                }
+                // Vixie runs fixed-time jobs from the skipped interval as soon as it ends.
+                let Ok(shifted) = ambiguous.compatible() else {
+                    return Vec::new();
+                };
+                // The search starts just past the shifted instant so that a time at the
+                // very start of the gap (which shifts onto the transition) finds it.
+                let after_shift = shifted.timestamp().checked_add(1.nanosecond());
+                let transition = after_shift
+                    .ok()
+                    .and_then(|ts| tz.preceding(ts).next())
+                    .map_or(shifted.timestamp(), |t| t.timestamp());
+                vec![CronRun::new(transition, Some(DstEffect::GapShifted))]
+            }
+            AmbiguousOffset::Fold { .. } => {
+                let mut runs = Vec::new();
+                if let Ok(first) = ambiguous.clone().earlier() {
+                    runs.push(CronRun::new(first.timestamp(), Some(DstEffect::FoldFirst)));
+                }
+                let second_pass = match policy {
+                    DstPolicy::Vixie => self.wildcard_time,
+                    DstPolicy::Kubernetes => true,
+                    DstPolicy::Systemd => false,
+                };
+                if second_pass {
+                    if let Ok(second) = ambiguous.later() {
+                        runs.push(CronRun::new(
+                            second.timestamp(),
+                            Some(DstEffect::FoldSecond),
+                        ));
+                    }
+                }
+                runs
+            }
+        }
+    }
+}
+
+/// The largest wall-clock jump of the transitions either side of `at`.
+///
+/// Resolving a gap or fold can move a run out of order by at most this much, so it
+/// bounds how far past the last needed civil time `next_runs` has to look.
+fn transition_span(tz: &TimeZone, at: Timestamp) -> jiff::Span {
+    let jump = |ts: Timestamp| {
+        let before = ts
+            .checked_sub(1.nanosecond())
+            .map_or(0, |just_before| tz.to_offset(just_before).seconds());
+        (tz.to_offset(ts).seconds() - before).abs()
+    };
+    let preceding = tz.preceding(at).next().map_or(0, |t| jump(t.timestamp()));
+    let following = tz.following(at).next().map_or(0, |t| jump(t.timestamp()));
+    preceding.max(following).seconds()
+}
+
+/// Sort runs chronologically (or newest first) and drop duplicate instants,
+/// such as several gap times shifted onto the same transition.
+fn sort_runs(runs: &mut Vec<CronRun>, descending: bool) {
+    if descending {
+        runs.sort_by_key(|run| std::cmp::Reverse(run.timestamp));
+    } else {
+        runs.sort_by_key(|run| run.timestamp);
+    }
+    runs.dedup_by_key(|run| run.timestamp);
+}
+
+/// How runs whose wall-clock time is skipped or repeated by a DST transition are handled.
+///
+/// A job is *fixed-time* when neither its minute nor its hour field starts with `*`
+/// (`30 2 * * *`); otherwise it is a *wildcard* job (`*/15 * * * *`, `0 * * * *`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DstPolicy {
+    /// Vixie/ISC cron: fixed-time jobs in a gap run once when the gap ends and run
+    /// only once in a fold (at the first occurrence); wildcard jobs follow real
+    /// time, skipping the gap and running in both passes of a fold.
+    Vixie,
+    /// Kubernetes CronJob (`robfig/cron` with `timeZone`): all jobs follow real
+    /// time, so gap runs are skipped and fold runs happen twice.
+    Kubernetes,
+    /// systemd timers: a calendar time that does not exist is skipped, and a repeated
+    /// one elapses once, at its first occurrence. Used for `OnCalendar=` events.
+    Systemd,
+}
+
+impl DstPolicy {
+    /// Parse a policy name from the Wasm boundary; `""` selects `Vixie`.
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "" | "vixie" | "cron" => Ok(DstPolicy::Vixie),
+            "kubernetes" | "k8s" => Ok(DstPolicy::Kubernetes),
+            other => Err(format!(
+                "Unknown DST policy '{}' (expected vixie or kubernetes)",
+                other
+            )),
+        }
+    }
+}
+
+/// How a DST transition changed a run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DstEffect {
+    /// The scheduled wall-clock time was skipped; the run moved to the end of the gap.
+    GapShifted,
+    /// The first pass through a repeated wall-clock time.
+    FoldFirst,
+    /// The second pass through a repeated wall-clock time.
+    FoldSecond,
+}
+
+impl DstEffect {
+    pub fn name(self) -> &'static str {
+        match self {
+            DstEffect::GapShifted => "gapShifted",
+            DstEffect::FoldFirst => "foldFirst",
+            DstEffect::FoldSecond => "foldSecond",
+        }
+    }
+}
+
+/// One run instant of a schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CronRun {
+    pub timestamp: Timestamp,
+    pub dst: Option<DstEffect>,
+}
+
+impl CronRun {
+    fn new(timestamp: Timestamp, dst: Option<DstEffect>) -> Self {
+        CronRun { timestamp, dst }
+    }
+}
+
+/// One parsed field: its plain values plus any `L`/`W`/`#` rules.
+#[derive(Debug, Default)]
+struct ParsedField {
+    set: FieldSet,
+    specials: Vec<DaySpecial>,
+    /// The field was `?`
+    no_spec: bool,
+}
+
+/// Parses fields according to a dialect's rules.
+struct FieldParser<'a> {
+    dialect: CronDialect,
+    seed: &'a str,
+}
+
+impl FieldParser<'_> {
+    /// Parse one comma-separated cron field into the values and rules it allows.
+    fn field(&self, raw: &str, spec: &FieldSpec) -> Result<ParsedField, String> {
+        if raw.is_empty() {
+            return Err(format!("Empty {} field", spec.name));
+        }
+        let mut parsed = ParsedField::default();
+        for item in raw.split(',') {
+            self.item(item, spec, &mut parsed)
+                .map_err(|e| format!("Invalid {} field '{}': {}", spec.name, raw, e))?;
+        }
+        if spec.kind == FieldKind::DayOfWeek {
+            // Normalize to 0 = Sunday: shift 1-based weekdays down, fold 7 onto 0.
+            parsed.set = if spec.min == 1 {
+                FieldSet(parsed.set.0 >> 1)
+            } else {
+                FieldSet((parsed.set.0 & !(1 << 7)) | ((parsed.set.0 >> 7) & 1))
+            };
+        }
+        Ok(parsed)
+    }
+
+    fn item(&self, item: &str, spec: &FieldSpec, parsed: &mut ParsedField) -> Result<(), String> {
+        let is_day = matches!(spec.kind, FieldKind::DayOfMonth | FieldKind::DayOfWeek);
+        if item == "?" {
+            if !self.dialect.has_day_specials() {
+                return Err(format!(
+                    "'?' is not valid in {} (use '*')",
+                    self.dialect.label()
+                ));
+            }
+            if !is_day {
+                return Err("'?' is only allowed in day-of-month and day-of-week".to_string());
+            }
+            parsed.no_spec = true;
+            parsed.set.0 |= FieldSet::range(spec.min, spec.max, 1).0;
+            return Ok(());
+        }
+
+        // A bare `L` in day-of-week means the last day of the week, Saturday.
+        let bare_last = spec.kind == FieldKind::DayOfWeek && item.eq_ignore_ascii_case("L");
+        let special = if bare_last {
+            None
+        } else {
+            parse_day_special(item, spec)?
+        };
+        if bare_last || special.is_some() {
+            if !self.dialect.has_day_specials() {
+                return Err(format!(
+                    "'{}' is only valid in Quartz and AWS EventBridge cron, not {}",
+                    item,
+                    self.dialect.label()
+                ));
+            }
+            match special {
+                Some(special) => parsed.specials.push(special),
+                None => parsed.set.0 |= FieldSet::single(spec.max).0,
+            }
+            return Ok(());
+        }
+
+        if item.starts_with('H') {
+            if self.dialect != CronDialect::Jenkins {
+                return Err(format!(
+                    "'H' hash tokens are only valid in Jenkins, not {}",
+                    self.dialect.label()
+                ));
+            }
+            parsed.set.0 |= self.hash_item(item, spec)?.0;
+            return Ok(());
+        }
+
+        parsed.set.0 |= parse_item(item, spec)?.0;
+        Ok(())
+    }
+
+    /// Parse a Jenkins `H`, `H/n`, `H(a-b)` or `H(a-b)/n` token.
+    fn hash_item(&self, item: &str, spec: &FieldSpec) -> Result<FieldSet, String> {
+        let (base, step) = split_step(item)?;
+        let (lo, hi) = if base == "H" {
+            spec.hash_range()
+        } else if let Some(range) = base.strip_prefix("H(").and_then(|b| b.strip_suffix(')')) {
+            parse_range(range, spec)?
+        } else {
+            return Err(format!("'{}' is not H, H/n, H(a-b) or H(a-b)/n", item));
+        };
+        let hash = hash_seed(self.seed, spec.name);
+        Ok(match step {
+            None => FieldSet::single(lo + (hash % u64::from(hi - lo + 1)) as u8),
+            Some(step) => {
+                let span = step.min(hi - lo + 1);
+                FieldSet::range(lo + (hash % u64::from(span)) as u8, hi, step)
+            }
+        })
+    }
+}
+
+/// FNV-1a over the seed and field name, standing in for Jenkins' per-job hash.
+fn hash_seed(seed: &str, field: &str) -> u64 {
+    seed.bytes()
+        .chain([0])
+        .chain(field.bytes())
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+/// Recognize the Quartz/AWS day rules `L`, `L-n`, `LW`, `nW`, `dL` and `d#n`.
+fn parse_day_special(item: &str, spec: &FieldSpec) -> Result<Option<DaySpecial>, String> {
+    let upper = item.to_ascii_uppercase();
+    let weekday = |raw: &str| -> Result<u8, String> {
+        let v = parse_value(raw, spec)?;
+        Ok(if spec.min == 1 { v - 1 } else { v % 7 })
+    };
+    match spec.kind {
+        FieldKind::DayOfMonth => {
+            if upper == "L" {
+                return Ok(Some(DaySpecial::LastDay(0)));
+            }
+            if upper == "LW" {
+                return Ok(Some(DaySpecial::LastWeekday));
+            }
+            if let Some(offset) = upper.strip_prefix("L-") {
+                let offset: u8 = offset
+                    .parse()
+                    .ok()
+                    .filter(|o| *o <= 30)
+                    .ok_or_else(|| format!("'{}' needs an offset of 0-30 days", item))?;
+                return Ok(Some(DaySpecial::LastDay(offset)));
+            }
+            if let Some(day) = upper.strip_suffix('W') {
+                if !day.is_empty() && day.bytes().all(|b| b.is_ascii_digit()) {
+                    let day = parse_value(day, spec)?;
+                    return Ok(Some(DaySpecial::NearestWeekday(day)));
+                }
+            }
+        }
+        FieldKind::DayOfWeek => {
+            if let Some((day, nth)) = upper.split_once('#') {
+                let nth: u8 = nth
+                    .parse()
+                    .ok()
+                    .filter(|n| (1..=5).contains(n))
+                    .ok_or_else(|| format!("'{}' needs an occurrence of 1-5 after '#'", item))?;
+                return Ok(Some(DaySpecial::Nth(weekday(day)?, nth)));
+            }
+            if let Some(day) = upper.strip_suffix('L') {
+                return Ok(Some(DaySpecial::LastOfMonth(weekday(day)?)));
+            }
+        }
+        _ => {}
+    }
+    Ok(None)
+}
+
+/// Split an optional `/n` step off a list item.
+fn split_step(item: &str) -> Result<(&str, Option<u8>), String> {
+    match item.split_once('/') {
+        Some((base, step)) => {
+            let step: u8 = step
+                .parse()
+                .map_err(|_| format!("step '{}' is not a number", step))?;
+            if step == 0 {
+                return Err("step must be at least 1".to_string());
+            }
+            Ok((base, Some(step)))
+        }
+        None => Ok((item, None)),
+    }
+}
+
+/// Parse an `a-b` range, rejecting reversed bounds.
+fn parse_range(range: &str, spec: &FieldSpec) -> Result<(u8, u8), String> {
+    let (a, b) = range
+        .split_once('-')
+        .ok_or_else(|| format!("'{}' is not a range", range))?;
+    let (a, b) = (parse_value(a, spec)?, parse_value(b, spec)?);
+    if a > b {
+        return Err(format!("range {}-{} is reversed", a, b));
+    }
+    Ok((a, b))
+}
+
+/// Parse one list item: `*`, `*/n`, `a`, `a-b`, `a-b/n` or `a/n`.
+fn parse_item(item: &str, spec: &FieldSpec) -> Result<FieldSet, String> {
+    let (base, step) = split_step(item)?;
+
+    let (lo, hi) = if base == "*" {
+        (spec.min, spec.max)
+    } else if base.contains('-') {
+        parse_range(base, spec)?
+    } else {
+        let v = parse_value(base, spec)?;
+        match step {
+            // `a/n` means "from a to the end of the range, every n"
+            Some(_) => (v, spec.max),
+            None => (v, v),
+        }
+    };
+
+    Ok(FieldSet::range(lo, hi, step.unwrap_or(1)))
+}
+
+fn parse_value(raw: &str, spec: &FieldSpec) -> Result<u8, String> {
+    let upper = raw.to_ascii_uppercase();
+    if let Some(index) = spec.names.iter().position(|n| *n == upper) {
+        return Ok(index as u8 + spec.name_base);
+    }
+    let value: u8 = raw
+        .parse()
+        .map_err(|_| format!("'{}' is not a number or name", raw))?;
+    if value < spec.min || value > spec.max {
+        return Err(format!(
+            "value {} out of range {}-{}",
+            value, spec.min, spec.max
+        ));
+    }
+    Ok(value)
+}
+
+/// Parse a Quartz/AWS year field; `*` and `?` leave years unrestricted.
+fn parse_years(raw: &str, dialect: CronDialect) -> Result<Option<Vec<i16>>, String> {
+    if raw == "*" || raw == "?" {
+        return Ok(None);
+    }
+    let max: i16 = if dialect == CronDialect::Aws {
+        2199
+    } else {
+        2099
+    };
+    let year = |s: &str| -> Result<i16, String> {
+        s.parse::<i16>()
+            .ok()
+            .filter(|y| (1970..=max).contains(y))
+            .ok_or_else(|| {
+                format!(
+                    "Invalid year field '{}': '{}' is not a year in 1970-{}",
+                    raw, s, max
+                )
+            })
+    };
+    let mut years = Vec::new();
+    for item in raw.split(',') {
+        let (base, step) =
+            split_step(item).map_err(|e| format!("Invalid year field '{}': {}", raw, e))?;
+        let (lo, hi) = if base == "*" {
+            (1970, max)
+        } else if let Some((a, b)) = base.split_once('-') {
+            (year(a)?, year(b)?)
+        } else {
+            let y = year(base)?;
+            (y, if step.is_some() { max } else { y })
+        };
+        if lo > hi {
+            return Err(format!(
+                "Invalid year field '{}': range {}-{} is reversed",
+                raw, lo, hi
+            ));
+        }
+        years.extend((lo..=hi).step_by(usize::from(step.unwrap_or(1))));
+    }
+    years.sort_unstable();
+    years.dedup();
+    Ok(Some(years))
+}
+
+pub(crate) fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// An AWS EventBridge `rate(n unit)` schedule: a fixed interval anchored at the
+/// search start, unaffected by zones or DST.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateSchedule {
+    pub expression: String,
+    pub interval_seconds: i64,
+}
+
+impl RateSchedule {
+    /// Parse `rate(1 minute)`, `rate(5 minutes)`, `rate(2 hours)`, `rate(1 day)`...
+    ///
+    /// As in EventBridge, a value of 1 takes the singular unit and larger values the plural.
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let trimmed = expression.trim();
+        let inner = trimmed
+            .strip_prefix("rate(")
+            .and_then(|r| r.strip_suffix(')'))
+            .ok_or_else(|| format!("Invalid rate expression: '{}'", trimmed))?;
+        let (value, unit) = inner
+            .trim()
+            .split_once(' ')
+            .ok_or_else(|| format!("rate() needs a value and a unit, got '{}'", inner))?;
+        let value: i64 =
+            value.parse().ok().filter(|v| *v > 0).ok_or_else(|| {
+                format!("rate() value must be a positive integer, got '{}'", value)
+            })?;
+        let unit = unit.trim();
+        let (singular, seconds) = match unit.trim_end_matches('s') {
+            "minute" => ("minute", 60),
+            "hour" => ("hour", 3600),
+            "day" => ("day", 86400),
+            _ => {
+                return Err(format!(
+                    "Unknown rate() unit '{}' (expected minutes, hours or days)",
+                    unit
+                ))
+            }
+        };
+        let expected = if value == 1 {
+            singular.to_string()
+        } else {
+            format!("{}s", singular)
+        };
+        if unit != expected {
+            return Err(format!(
+                "rate({} {}) must be written rate({} {})",
+                value, unit, value, expected
+            ));
+        }
+        Ok(RateSchedule {
+            expression: trimmed.to_string(),
+            interval_seconds: value * seconds,
+        })
+    }
+
+    fn runs(&self, anchor: Timestamp, count: usize, direction: i64) -> Vec<CronRun> {
+        (1..=count as i64)
+            .map_while(|k| {
+                anchor
+                    .checked_add((direction * k * self.interval_seconds).seconds())
+                    .ok()
+            })
+            .map(|ts| CronRun::new(ts, None))
+            .collect()
+    }
+
+    /// Runs in `[start, end)` counting intervals from `start`, at most `limit + 1`.
+    fn runs_between(&self, start: Timestamp, end: Timestamp, limit: usize) -> Vec<CronRun> {
+        let span = (end.as_second() - start.as_second()).max(0);
+        let count = (span / self.interval_seconds).min(limit as i64 + 1) as usize;
+        let mut runs = self.runs(start, count, 1);
+        runs.retain(|run| run.timestamp < end);
+        runs
+    }
+}
+
+/// Any schedule the engine can evaluate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Schedule {
+    Cron(CronSchedule),
+    Rate(RateSchedule),
+}
+
+impl Schedule {
+    /// Parse a cron expression in `dialect`, or an AWS `rate(...)` expression.
+    pub fn parse(expression: &str, dialect: CronDialect, seed: &str) -> Result<Self, String> {
+        if expression.trim_start().starts_with("rate(") {
+            if dialect != CronDialect::Aws {
+                return Err(format!(
+                    "rate() expressions are only valid in AWS EventBridge, not {}",
+                    dialect.label()
+                ));
+            }
+            return RateSchedule::parse(expression).map(Schedule::Rate);
+        }
+        CronSchedule::parse_dialect(expression, dialect, seed).map(Schedule::Cron)
+    }
+
+    pub fn expression(&self) -> &str {
+        match self {
+            Schedule::Cron(cron) => &cron.expression,
+            Schedule::Rate(rate) => &rate.expression,
+        }
+    }
+
+    /// See `CronSchedule::next_runs`; rate schedules count intervals from `after`.
+    pub fn next_runs(
+        &self,
+        after: Timestamp,
+        count: usize,
+        tz: &TimeZone,
+        policy: DstPolicy,
+    ) -> Vec<CronRun> {
+        match self {
+            Schedule::Cron(cron) => cron.next_runs(after, count, tz, policy),
+            Schedule::Rate(rate) => rate.runs(after, count, 1),
+        }
+    }
+
+    /// See `CronSchedule::previous_runs`; rate schedules count intervals back from `before`.
+    pub fn previous_runs(
+        &self,
+        before: Timestamp,
+        count: usize,
+        tz: &TimeZone,
+        policy: DstPolicy,
+    ) -> Vec<CronRun> {
+        match self {
+            Schedule::Cron(cron) => cron.previous_runs(before, count, tz, policy),
+            Schedule::Rate(rate) => rate.runs(before, count, -1),
+        }
+    }
+
+    /// See `CronSchedule::runs_between`; rate schedules count intervals from `start`.
+    pub fn runs_between(
+        &self,
+        start: Timestamp,
+        end: Timestamp,
+        limit: usize,
+        tz: &TimeZone,
+        policy: DstPolicy,
+    ) -> Vec<CronRun> {
+        match self {
+            Schedule::Cron(cron) => cron.runs_between(start, end, limit, tz, policy),
+            Schedule::Rate(rate) => rate.runs_between(start, end, limit),
+        }
+    }
+}
+
+/// Options for listing runs, parsed from the Wasm boundary.
+#[derive(Debug, Clone)]
+pub struct CronOptions {
+    pub zone: TimeZone,
+    pub dst_policy: DstPolicy,
+    pub dialect: CronDialect,
+    /// Jenkins `H` seed, normally the job name
+    pub seed: String,
+}
+
+impl Default for CronOptions {
+    fn default() -> Self {
+        CronOptions {
+            zone: TimeZone::UTC,
+            dst_policy: DstPolicy::Vixie,
+            dialect: CronDialect::Standard,
+            seed: String::new(),
+        }
+    }
+}
+
+impl CronOptions {
+    /// Parse options from JSON, e.g. `{"zone":"Europe/Berlin","dstPolicy":"kubernetes","dialect":"jenkins","seed":"nightly-build"}`.
+    /// Missing keys keep their defaults; `""` gives all defaults.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let mut options = CronOptions::default();
+        if let Some(zone) = extract_string_field(json, "zone") {
+            options.zone = resolve_zone(&zone)?;
+        }
+        if let Some(policy) = extract_string_field(json, "dstPolicy") {
+            options.dst_policy = DstPolicy::from_name(&policy)?;
+        }
+        if let Some(dialect) = extract_string_field(json, "dialect") {
+            options.dialect = CronDialect::from_name(&dialect)?;
+        }
+        if let Some(seed) = extract_string_field(json, "seed") {
+            options.seed = seed;
+        }
+        Ok(options)
+    }
+}
+
+/// Run instants of a schedule, serialized for the Wasm boundary.
+#[derive(Debug, PartialEq)]
+pub struct CronRuns {
+    pub expression: String,
+    pub dialect: CronDialect,
+    pub zone: TimeZone,
+    pub runs: Vec<CronRun>,
+}
+
+impl CronRuns {
+    /// Serialize to JSON string for Wasm boundary (manual serialization).
+    ///
+    /// Each run extends the TS `CronRunInstant` shape (`iso`, `unixTimestamp`) with
+    /// `local` (RFC 9557 in the schedule's zone) and, for DST-affected runs, `dst`.
+    pub fn to_json(&self) -> String {
+        let runs: Vec<String> = self
+            .runs
+            .iter()
+            .map(|run| {
+                let mut json = format!(
+                    r#"{{"iso":"{}","unixTimestamp":{},"local":"{}""#,
+                    run.timestamp,
+                    run.timestamp.as_second(),
+                    json_escape(&run.timestamp.to_zoned(self.zone.clone()).to_string())
+                );
+                if let Some(effect) = run.dst {
+                    json.push_str(&format!(r#","dst":"{}""#, effect.name()));
+                }
+                json.push('}');
+                json
+            })
+            .collect();
+        format!(
+            r#"{{"expression":"{}","dialect":"{}","zone":"{}","runs":[{}]}}"#,
+            json_escape(&self.expression),
+            self.dialect.name(),
+            json_escape(&zone_name(&self.zone)),
+            runs.join(",")
+        )
+    }
+}
+
+/// Upper bound on the number of runs a single cron query may return.
+pub(crate) const MAX_CRON_RUNS: usize = 1000;
+
+/// List up to `count` runs of a cron `expression` after `start` (or before it
+/// when `previous` is set, most recent first).
+///
+/// `options_json` is parsed by `CronOptions::from_json`: the schedule's
+/// `dialect` and Jenkins `seed`, the `zone` whose wall clock it follows, and the
+/// `dstPolicy` (`vixie` or `kubernetes`, see `DstPolicy`) for runs in skipped or
+/// repeated hours.
+pub fn cron_runs(
+    expression: &str,
+    start: &str,
+    count: u32,
+    previous: bool,
+    options_json: &str,
+) -> Result<CronRuns, String> {
+    let options = CronOptions::from_json(options_json)?;
+    let schedule = Schedule::parse(expression, options.dialect, &options.seed)?;
+    let start = parse_start_date(start)?;
+    let count = (count as usize).min(MAX_CRON_RUNS);
+    let runs = if previous {
+        schedule.previous_runs(start, count, &options.zone, options.dst_policy)
+    } else {
+        schedule.next_runs(start, count, &options.zone, options.dst_policy)
+    };
+    Ok(CronRuns {
+        expression: schedule.expression().to_string(),
+        dialect: options.dialect,
+        zone: options.zone,
+        runs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dt(s: &str) -> DateTime {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_field_set_navigation() {
+        let set = FieldSet::range(10, 40, 10);
+        assert_eq!(set.values(), vec![10, 20, 30, 40]);
+        assert_eq!(set.next_from(11), Some(20));
+        assert_eq!(set.next_from(41), None);
+        assert_eq!(set.prev_from(39), Some(30));
+        assert_eq!(set.prev_from(9), None);
+    }
+
+    #[test]
+    fn test_parse_lists_ranges_steps() {
+        let cron = CronSchedule::parse("5/20 9-17/4 1,15 * *").unwrap();
+        assert_eq!(cron.minutes.values(), vec![5, 25, 45]);
+        assert_eq!(cron.hours.values(), vec![9, 13, 17]);
+        assert_eq!(cron.days_of_month.values(), vec![1, 15]);
+        assert_eq!(cron.months.values(), (1..=12).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn test_parse_names_case_insensitive() {
+        let cron = CronSchedule::parse("0 0 * jan-Mar mon-FRI").unwrap();
+        assert_eq!(cron.months.values(), vec![1, 2, 3]);
+        assert_eq!(cron.days_of_week.values(), vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_sunday_as_seven() {
+        let cron = CronSchedule::parse("0 0 * * 5-7").unwrap();
+        assert_eq!(cron.days_of_week.values(), vec![0, 5, 6]);
+    }
+
+    #[test]
+    fn test_macros() {
+        assert_eq!(
+            CronSchedule::parse("@daily").unwrap().hours.values(),
+            vec![0]
+        );
+        assert_eq!(
+            CronSchedule::parse("@weekly")
+                .unwrap()
+                .days_of_week
+                .values(),
+            vec![0]
+        );
+        assert!(CronSchedule::parse("@reboot").is_err());
+        assert!(CronSchedule::parse("@fortnightly").is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(CronSchedule::parse("* * * *")
+            .unwrap_err()
+            .contains("5 fields"));
+        assert!(CronSchedule::parse("60 * * * *")
+            .unwrap_err()
+            .contains("out of range"));
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+        assert!(CronSchedule::parse("0 17-9 * * *")
+            .unwrap_err()
+            .contains("reversed"));
+        assert!(CronSchedule::parse("0 0 * FOO *").is_err());
+        assert!(CronSchedule::parse("0 0 0 * *").is_err());
+    }
+
+    #[test]
+    fn test_next_skips_to_matching_month() {
+        let cron = CronSchedule::parse("30 8 1 */6 *").unwrap();
+        assert_eq!(
+            cron.next_civil(dt("2024-02-10T00:00:00")),
+            Some(dt("2024-07-01T08:30:00"))
+        );
+    }
+
+    #[test]
+    fn test_next_is_strictly_after() {
+        let cron = CronSchedule::parse("0 * * * *").unwrap();
+        assert_eq!(
+            cron.next_civil(dt("2024-07-11T10:00:00")),
+            Some(dt("2024-07-11T11:00:00"))
+        );
+        assert_eq!(
+            cron.next_civil(dt("2024-07-11T10:00:00.5")),
+            Some(dt("2024-07-11T11:00:00"))
+        );
+    }
+
+    #[test]
+    fn test_dom_dow_or_rule() {
+        // 13th of the month OR any Friday
+        let cron = CronSchedule::parse("0 0 13 * FRI").unwrap();
+        assert_eq!(
+            cron.next_civil(dt("2024-09-01T00:00:00")),
+            Some(dt("2024-09-06T00:00:00"))
+        );
+        assert_eq!(
+            cron.next_civil(dt("2024-09-10T00:00:00")),
+            Some(dt("2024-09-13T00:00:00"))
+        );
+        // Wildcard-stepped day-of-week counts as unrestricted, so both must match.
+        let cron = CronSchedule::parse("0 0 13 * */1").unwrap();
+        assert_eq!(
+            cron.next_civil(dt("2024-09-01T00:00:00")),
+            Some(dt("2024-09-13T00:00:00"))
+        );
+    }
+
+    #[test]
+    fn test_leap_day_schedule() {
+        let cron = CronSchedule::parse("0 12 29 2 *").unwrap();
+        assert_eq!(
+            cron.next_civil(dt("2024-03-01T00:00:00")),
+            Some(dt("2028-02-29T12:00:00"))
+        );
+        assert_eq!(
+            cron.previous_civil(dt("2024-02-29T12:00:00")),
+            Some(dt("2020-02-29T12:00:00"))
+        );
+    }
+
+    #[test]
+    fn test_impossible_schedule() {
+        let cron = CronSchedule::parse("0 0 31 4 *").unwrap();
+        assert_eq!(cron.next_civil(dt("2024-01-01T00:00:00")), None);
+        assert_eq!(cron.previous_civil(dt("2024-01-01T00:00:00")), None);
+    }
+
+    #[test]
+    fn test_previous_run() {
+        let cron = CronSchedule::parse("15,45 9-17 * * MON-FRI").unwrap();
+        // Saturday morning → Friday 17:45
+        assert_eq!(
+            cron.previous_civil(dt("2024-07-13T08:00:00")),
+            Some(dt("2024-07-12T17:45:00"))
+        );
+        assert_eq!(
+            cron.previous_civil(dt("2024-07-12T09:45:00")),
+            Some(dt("2024-07-12T09:15:00"))
+        );
+    }
+
+    fn date(s: &str) -> Date {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_dialect_names() {
+        assert_eq!(CronDialect::from_name("").unwrap(), CronDialect::Standard);
+        assert_eq!(
+            CronDialect::from_name("EventBridge").unwrap(),
+            CronDialect::Aws
+        );
+        assert!(CronDialect::from_name("cronie").is_err());
+    }
+
+    #[test]
+    fn test_quartz_weekdays_start_at_sunday_one() {
+        let cron = CronSchedule::parse_dialect("0 0 0 ? * 1,7", CronDialect::Quartz, "").unwrap();
+        assert_eq!(cron.days_of_week.values(), vec![0, 6]);
+        let cron = CronSchedule::parse_dialect("0 0 0 ? * L", CronDialect::Quartz, "").unwrap();
+        assert_eq!(cron.days_of_week.values(), vec![6]);
+        let cron =
+            CronSchedule::parse_dialect("*/10 * * ? * MON-FRI", CronDialect::Quartz, "").unwrap();
+        assert_eq!(cron.seconds.values(), vec![0, 10, 20, 30, 40, 50]);
+        assert_eq!(cron.days_of_week.values(), vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_day_specials() {
+        assert!(DaySpecial::LastDay(0).matches(date("2024-02-29")));
+        assert!(DaySpecial::LastDay(2).matches(date("2023-02-26")));
+        // 2024-08-31 is a Saturday
+        assert!(DaySpecial::LastWeekday.matches(date("2024-08-30")));
+        // 2024-06-01 is a Saturday: 1W stays in the month and moves to Monday the 3rd
+        assert!(DaySpecial::NearestWeekday(1).matches(date("2024-06-03")));
+        // 2024-03-31 is a Sunday: 31W moves back to Friday the 29th
+        assert!(DaySpecial::NearestWeekday(31).matches(date("2024-03-29")));
+        assert!(!DaySpecial::NearestWeekday(31).matches(date("2024-04-30")));
+        assert!(DaySpecial::LastOfMonth(5).matches(date("2024-05-31")));
+        assert!(DaySpecial::Nth(1, 2).matches(date("2024-05-13")));
+        assert!(!DaySpecial::Nth(1, 2).matches(date("2024-05-06")));
+    }
+
+    #[test]
+    fn test_year_field() {
+        let cron = CronSchedule::parse_dialect("0 0 1 1 ? 2030/5", CronDialect::Aws, "").unwrap();
+        assert_eq!(cron.years.as_ref().unwrap()[..3], [2030, 2035, 2040]);
+        assert_eq!(
+            cron.next_civil(dt("2024-01-01T00:00:00")),
+            Some(dt("2030-01-01T00:00:00"))
+        );
+        assert_eq!(
+            cron.previous_civil(dt("2034-06-01T00:00:00")),
+            Some(dt("2030-01-01T00:00:00"))
+        );
+        assert!(CronSchedule::parse_dialect("0 0 1 1 ? 2200", CronDialect::Aws, "").is_err());
+    }
+
+    #[test]
+    fn test_jenkins_hash_tokens() {
+        let cron =
+            CronSchedule::parse_dialect("H(0-29)/10 H(9-17) H * *", CronDialect::Jenkins, "job")
+                .unwrap();
+        let minutes = cron.minutes.values();
+        assert_eq!(minutes.len(), 3);
+        assert!(minutes[0] < 10 && minutes.windows(2).all(|w| w[1] - w[0] == 10));
+        let hours = cron.hours.values();
+        assert!(hours.len() == 1 && (9..=17).contains(&hours[0]));
+        let days = cron.days_of_month.values();
+        assert!(days.len() == 1 && (1..=28).contains(&days[0]));
+    }
+
+    #[test]
+    fn test_jenkins_days_are_anded() {
+        let cron = CronSchedule::parse_dialect("0 0 13 * 5", CronDialect::Jenkins, "").unwrap();
+        // Only Friday the 13th
+        assert_eq!(
+            cron.next_civil(dt("2024-09-01T00:00:00")),
+            Some(dt("2024-09-13T00:00:00"))
+        );
+        assert_eq!(
+            cron.next_civil(dt("2024-09-14T00:00:00")),
+            Some(dt("2024-12-13T00:00:00"))
+        );
+    }
+
+    #[test]
+    fn test_rate_schedule() {
+        assert_eq!(
+            RateSchedule::parse("rate(2 hours)")
+                .unwrap()
+                .interval_seconds,
+            7200
+        );
+        assert_eq!(
+            RateSchedule::parse("rate(1 day)").unwrap().interval_seconds,
+            86400
+        );
+        assert!(RateSchedule::parse("rate(0 minutes)").is_err());
+        assert!(RateSchedule::parse("rate(2 hour)").is_err());
+        assert!(RateSchedule::parse("rate(5 weeks)").is_err());
+    }
+
+    fn run_list(runs: &[CronRun]) -> Vec<(String, Option<&'static str>)> {
+        runs.iter()
+            .map(|run| (run.timestamp.to_string(), run.dst.map(DstEffect::name)))
+            .collect()
+    }
+
+    fn new_york() -> TimeZone {
+        TimeZone::get("America/New_York").unwrap()
+    }
+
+    fn utc(s: &str) -> Timestamp {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_dst_policy_names() {
+        assert_eq!(DstPolicy::from_name("").unwrap(), DstPolicy::Vixie);
+        assert_eq!(DstPolicy::from_name("K8s").unwrap(), DstPolicy::Kubernetes);
+        assert!(DstPolicy::from_name("systemd").is_err());
+    }
+
+    #[test]
+    fn test_runs_in_zone() {
+        let cron = CronSchedule::parse("0 9 * * *").unwrap();
+        let runs = cron.next_runs(
+            utc("2024-07-11T00:00:00Z"),
+            1,
+            &new_york(),
+            DstPolicy::Vixie,
+        );
+        assert_eq!(run_list(&runs), vec![("2024-07-11T13:00:00Z".into(), None)]);
+    }
+
+    #[test]
+    fn test_gap_fixed_time_vixie_runs_at_transition() {
+        // 2024-03-10 02:00 EST jumps to 03:00 EDT in New York.
+        let cron = CronSchedule::parse("30 2 * * *").unwrap();
+        let start = utc("2024-03-09T12:00:00Z");
+        let runs = cron.next_runs(start, 2, &new_york(), DstPolicy::Vixie);
+        assert_eq!(
+            run_list(&runs),
+            vec![
+                ("2024-03-10T07:00:00Z".into(), Some("gapShifted")),
+                ("2024-03-11T06:30:00Z".into(), None),
+            ]
+        );
+        let runs = cron.next_runs(start, 1, &new_york(), DstPolicy::Kubernetes);
+        assert_eq!(run_list(&runs), vec![("2024-03-11T06:30:00Z".into(), None)]);
+    }
+
+    #[test]
+    fn test_gap_start_runs_at_transition() {
+        // 2024-03-31 02:00 CET jumps to 03:00 CEST in Berlin, so 02:00 itself is skipped.
+        let cron = CronSchedule::parse("0 2 * * *").unwrap();
+        let berlin = TimeZone::get("Europe/Berlin").unwrap();
+        let runs = cron.next_runs(utc("2024-03-30T12:00:00Z"), 2, &berlin, DstPolicy::Vixie);
+        assert_eq!(
+            run_list(&runs),
+            vec![
+                ("2024-03-31T01:00:00Z".into(), Some("gapShifted")),
+                ("2024-04-01T00:00:00Z".into(), None),
+            ]
+        );
+        let runs = cron.previous_runs(utc("2024-04-01T00:00:00Z"), 1, &berlin, DstPolicy::Vixie);
+        assert_eq!(
+            run_list(&runs),
+            vec![("2024-03-31T01:00:00Z".into(), Some("gapShifted"))]
+        );
+    }
+
+    #[test]
+    fn test_gap_wildcard_job_skips() {
+        let cron = CronSchedule::parse("*/30 * * * *").unwrap();
+        let runs = cron.next_runs(
+            utc("2024-03-10T06:15:00Z"),
+            2,
+            &new_york(),
+            DstPolicy::Vixie,
+        );
+        // 01:30 EST, then 03:00 EDT; 02:00 and 02:30 never happen
+        assert_eq!(
+            run_list(&runs),
+            vec![
+                ("2024-03-10T06:30:00Z".into(), None),
+                ("2024-03-10T07:00:00Z".into(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_gap_collapses_to_one_run() {
+        let cron = CronSchedule::parse("0,30 2 * * *").unwrap();
+        let runs = cron.next_runs(
+            utc("2024-03-10T00:00:00Z"),
+            2,
+            &new_york(),
+            DstPolicy::Vixie,
+        );
+        assert_eq!(
+            run_list(&runs),
+            vec![
+                ("2024-03-10T07:00:00Z".into(), Some("gapShifted")),
+                ("2024-03-11T06:00:00Z".into(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fold_fixed_time() {
+        // 2024-11-03 02:00 EDT falls back to 01:00 EST in New York.
+        let cron = CronSchedule::parse("30 1 * * *").unwrap();
+        let start = utc("2024-11-02T12:00:00Z");
+        let runs = cron.next_runs(start, 2, &new_york(), DstPolicy::Vixie);
+        assert_eq!(
+            run_list(&runs),
+            vec![
+                ("2024-11-03T05:30:00Z".into(), Some("foldFirst")),
+                ("2024-11-04T06:30:00Z".into(), None),
+            ]
+        );
+        let runs = cron.next_runs(start, 3, &new_york(), DstPolicy::Kubernetes);
+        assert_eq!(
+            run_list(&runs),
+            vec![
+                ("2024-11-03T05:30:00Z".into(), Some("foldFirst")),
+                ("2024-11-03T06:30:00Z".into(), Some("foldSecond")),
+                ("2024-11-04T06:30:00Z".into(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fold_wildcard_runs_in_real_time_order() {
+        let cron = CronSchedule::parse("*/30 * * * *").unwrap();
+        let expected = vec![
+            ("2024-11-03T05:30:00Z".to_string(), Some("foldFirst")),
+            ("2024-11-03T06:00:00Z".to_string(), Some("foldSecond")),
+            ("2024-11-03T06:30:00Z".to_string(), Some("foldSecond")),
+            ("2024-11-03T07:00:00Z".to_string(), None),
+        ];
+        let runs = cron.next_runs(
+            utc("2024-11-03T05:00:00Z"),
+            4,
+            &new_york(),
+            DstPolicy::Vixie,
+        );
+        assert_eq!(run_list(&runs), expected);
+
+        let runs = cron.previous_runs(
+            utc("2024-11-03T07:00:01Z"),
+            4,
+            &new_york(),
+            DstPolicy::Vixie,
+        );
+        let mut reversed = expected;
+        reversed.reverse();
+        assert_eq!(run_list(&runs), reversed);
+    }
+
+    #[test]
+    fn test_runs_json() {
+        let cron = CronSchedule::parse("30 2 * * *").unwrap();
+        let zone = new_york();
+        let runs = CronRuns {
+            expression: cron.expression.clone(),
+            dialect: cron.dialect,
+            runs: cron.next_runs(utc("2024-03-09T12:00:00Z"), 2, &zone, DstPolicy::Vixie),
+            zone,
+        };
+        assert_eq!(
+            runs.to_json(),
+            r#"{"expression":"30 2 * * *","dialect":"standard","zone":"America/New_York","runs":[{"iso":"2024-03-10T07:00:00Z","unixTimestamp":1710054000,"local":"2024-03-10T03:00:00-04:00[America/New_York]","dst":"gapShifted"},{"iso":"2024-03-11T06:30:00Z","unixTimestamp":1710138600,"local":"2024-03-11T02:30:00-04:00[America/New_York]"}]}"#
+        );
+    }
+}
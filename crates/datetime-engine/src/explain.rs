@@ -0,0 +1,727 @@
+use crate::cron::{
+    capitalize, CronDialect, CronOptions, CronSchedule, DaySpecial, FieldSet, RateSchedule,
+    Schedule,
+};
+use crate::json_utils::{extract_string_field, json_escape};
+use crate::locale::EN;
+use crate::oncalendar::CalendarEvent;
+use crate::zone::zone_name;
+
+const ORDINALS: [&str; 5] = ["first", "second", "third", "fourth", "fifth"];
+
+/// A plain-English description of a schedule.
+#[derive(Debug, PartialEq)]
+pub struct Explanation {
+    pub expression: String,
+    /// One sentence, e.g. "At 09:30 on every weekday in March"
+    pub summary: String,
+    /// `(field, description)` pairs in expression order
+    pub fields: Vec<(String, String)>,
+}
+
+impl Explanation {
+    /// Serialize to JSON string for Wasm boundary (manual serialization).
+    pub fn to_json(&self) -> String {
+        let fields: Vec<String> = self
+            .fields
+            .iter()
+            .map(|(field, description)| {
+                format!(
+                    r#"{{"field":"{}","description":"{}"}}"#,
+                    json_escape(field),
+                    json_escape(description)
+                )
+            })
+            .collect();
+        format!(
+            r#"{{"expression":"{}","summary":"{}","fields":[{}]}}"#,
+            json_escape(&self.expression),
+            json_escape(&self.summary),
+            fields.join(",")
+        )
+    }
+}
+
+/// Explain any schedule the cron engine parses.
+pub fn explain(schedule: &Schedule) -> Explanation {
+    match schedule {
+        Schedule::Cron(cron) => explain_cron(cron, None),
+        Schedule::Rate(rate) => explain_rate(rate),
+    }
+}
+
+/// Explain a systemd calendar event through its cron-engine lowering.
+pub fn explain_calendar(event: &CalendarEvent) -> Explanation {
+    let mut explanation = explain_cron(&event.to_schedule(), event.zone.as_ref().map(zone_name));
+    explanation.expression = event.original.clone();
+    explanation
+}
+
+fn explain_rate(rate: &RateSchedule) -> Explanation {
+    let (value, unit) = [(86400, "day"), (3600, "hour"), (60, "minute")]
+        .into_iter()
+        .find(|(seconds, _)| rate.interval_seconds % seconds == 0)
+        .map_or((rate.interval_seconds, "second"), |(seconds, unit)| {
+            (rate.interval_seconds / seconds, unit)
+        });
+    let description = if value == 1 {
+        format!("every {}", unit)
+    } else {
+        format!("every {} {}s", value, unit)
+    };
+    Explanation {
+        expression: rate.expression.clone(),
+        summary: capitalize(&description),
+        fields: vec![("rate".to_string(), description)],
+    }
+}
+
+fn explain_cron(cron: &CronSchedule, zone: Option<String>) -> Explanation {
+    let mut summary: Vec<String> = [
+        time_phrase(cron),
+        day_phrase(cron),
+        month_phrase(cron.months),
+        cron.years.as_deref().map(year_phrase).unwrap_or_default(),
+    ]
+    .into_iter()
+    .filter(|part| !part.is_empty())
+    .collect();
+    if let Some(zone) = zone {
+        summary.push(format!("({} time)", zone));
+    }
+
+    let mut fields = Vec::new();
+    if cron.dialect == CronDialect::Quartz || cron.seconds != FieldSet::single(0) {
+        fields.push(field("second", describe(cron.seconds, &SECOND)));
+    }
+    fields.push(field("minute", describe(cron.minutes, &MINUTE)));
+    fields.push(field("hour", describe(cron.hours, &HOUR)));
+    fields.push(field("day-of-month", dom_description(cron)));
+    fields.push(field("month", describe(cron.months, &MONTH)));
+    fields.push(field("day-of-week", dow_description(cron)));
+    if let Some(years) = &cron.years {
+        fields.push(field("year", year_phrase(years)));
+    }
+
+    Explanation {
+        expression: cron.expression.clone(),
+        summary: capitalize(&summary.join(" ")),
+        fields,
+    }
+}
+
+fn field(name: &str, description: String) -> (String, String) {
+    (name.to_string(), description)
+}
+
+/// How a field's values are laid out, for choosing a phrase.
+#[derive(Debug, PartialEq)]
+enum Pattern {
+    Empty,
+    All,
+    Single(u8),
+    /// Contiguous values
+    Range(u8, u8),
+    /// Evenly spaced values; `to_end` when the next step would pass the field maximum
+    Step {
+        start: u8,
+        end: u8,
+        step: u8,
+        to_end: bool,
+    },
+    List(Vec<u8>),
+}
+
+/// The wording for one field's values.
+struct Unit {
+    singular: &'static str,
+    plural: &'static str,
+    min: u8,
+    max: u8,
+    /// English name of a value, for fields whose values have names
+    names: Option<fn(u8) -> Option<&'static str>>,
+}
+
+const SECOND: Unit = Unit {
+    singular: "second",
+    plural: "seconds",
+    min: 0,
+    max: 59,
+    names: None,
+};
+const MINUTE: Unit = Unit {
+    singular: "minute",
+    plural: "minutes",
+    min: 0,
+    max: 59,
+    names: None,
+};
+const HOUR: Unit = Unit {
+    singular: "hour",
+    plural: "hours",
+    min: 0,
+    max: 23,
+    names: None,
+};
+const DAY: Unit = Unit {
+    singular: "day",
+    plural: "days",
+    min: 1,
+    max: 31,
+    names: None,
+};
+const MONTH: Unit = Unit {
+    singular: "month",
+    plural: "months",
+    min: 1,
+    max: 12,
+    names: Some(|month| EN.month_name(month)),
+};
+const WEEKDAY: Unit = Unit {
+    singular: "day of the week",
+    plural: "days of the week",
+    min: 0,
+    max: 6,
+    names: Some(|weekday| EN.weekday_name(weekday)),
+};
+
+impl Unit {
+    fn pattern(&self, set: FieldSet) -> Pattern {
+        let values = set.values();
+        let (first, last) = match (values.first(), values.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => return Pattern::Empty,
+        };
+        if values.len() == 1 {
+            return Pattern::Single(first);
+        }
+        if values.len() == usize::from(self.max - self.min + 1) {
+            return Pattern::All;
+        }
+        let step = values[1] - values[0];
+        if values.windows(2).all(|w| w[1] - w[0] == step) {
+            let to_end = last + step > self.max;
+            if step == 1 && values.len() >= 3 {
+                return Pattern::Range(first, last);
+            }
+            if step > 1 && (values.len() >= 3 || (first == self.min && to_end)) {
+                return Pattern::Step {
+                    start: first,
+                    end: last,
+                    step,
+                    to_end,
+                };
+            }
+        }
+        Pattern::List(values)
+    }
+
+    fn name(&self, value: u8) -> String {
+        match self.names.and_then(|names| names(value)) {
+            Some(name) => name.to_string(),
+            None => value.to_string(),
+        }
+    }
+
+    fn names_of(&self, values: &[u8]) -> String {
+        join(values.iter().map(|v| self.name(*v)).collect())
+    }
+}
+
+/// Describe a field on its own, e.g. "every 15 minutes" or "Monday through Friday".
+fn describe(set: FieldSet, unit: &Unit) -> String {
+    let named = unit.names.is_some();
+    match unit.pattern(set) {
+        Pattern::Empty => "never".to_string(),
+        Pattern::All => format!("every {}", unit.singular),
+        Pattern::Single(v) if named => unit.name(v),
+        Pattern::Single(v) => format!("{} {}", unit.singular, v),
+        Pattern::Range(a, b) if named => format!("{} through {}", unit.name(a), unit.name(b)),
+        Pattern::Range(a, b) => format!("{} {} through {}", unit.plural, a, b),
+        Pattern::Step {
+            start,
+            end,
+            step,
+            to_end,
+        } if !named => {
+            if start == unit.min && to_end {
+                format!("every {} {}", step, unit.plural)
+            } else if to_end {
+                format!(
+                    "every {} {} starting at {} {}",
+                    step, unit.plural, unit.singular, start
+                )
+            } else {
+                format!(
+                    "every {} {} from {} {} through {}",
+                    step, unit.plural, unit.singular, start, end
+                )
+            }
+        }
+        _ if named => unit.names_of(&set.values()),
+        _ => format!("{} {}", unit.plural, join_numbers(&set.values())),
+    }
+}
+
+fn hh_mm(hour: u8, minute: u8, second: Option<u8>) -> String {
+    match second {
+        Some(second) => format!("{:02}:{:02}:{:02}", hour, minute, second),
+        None => format!("{:02}:{:02}", hour, minute),
+    }
+}
+
+/// The time-of-day part of the summary.
+fn time_phrase(cron: &CronSchedule) -> String {
+    let (seconds, minutes, hours) = (
+        cron.seconds.values(),
+        cron.minutes.values(),
+        cron.hours.values(),
+    );
+    let seconds_zero = cron.seconds == FieldSet::single(0);
+
+    // A handful of fixed times reads best as a list: "At 09:00 and 17:30".
+    let fixed = hours.len() * minutes.len() * seconds.len();
+    if (1..=4).contains(&fixed) {
+        let mut times = Vec::new();
+        for h in &hours {
+            for m in &minutes {
+                for s in &seconds {
+                    times.push(hh_mm(*h, *m, (!seconds_zero).then_some(*s)));
+                }
+            }
+        }
+        return format!("At {}", join(times));
+    }
+
+    let mut parts = Vec::new();
+    if !seconds_zero {
+        parts.push(match SECOND.pattern(cron.seconds) {
+            Pattern::All => "every second".to_string(),
+            Pattern::Single(s) => format!("at {} seconds past the minute", s),
+            Pattern::Step { .. } => describe(cron.seconds, &SECOND),
+            _ => format!("at {}", describe(cron.seconds, &SECOND)),
+        });
+    }
+
+    let hour_pattern = HOUR.pattern(cron.hours);
+    // Hour steps that don't start at midnight: "At minute 0 of every 2nd hour from 01:00".
+    if let (
+        true,
+        Pattern::Single(m),
+        &Pattern::Step {
+            start,
+            end,
+            step,
+            to_end,
+        },
+    ) = (seconds_zero, MINUTE.pattern(cron.minutes), &hour_pattern)
+    {
+        if start != 0 || !to_end {
+            return format!(
+                "At minute {} of every {} hour from {}{}",
+                m,
+                ordinal(step),
+                hh_mm(start, m, None),
+                hour_step_end(end, m, to_end)
+            );
+        }
+    }
+
+    let on_the_hour = seconds_zero && cron.minutes == FieldSet::single(0);
+    if on_the_hour {
+        match hour_pattern {
+            Pattern::All => return "Every hour".to_string(),
+            Pattern::Step {
+                start: 0,
+                step,
+                to_end: true,
+                ..
+            } => return format!("Every {} hours", step),
+            Pattern::Range(a, b) => {
+                return format!(
+                    "Every hour between {} and {}",
+                    hh_mm(a, 0, None),
+                    hh_mm(b, 0, None)
+                )
+            }
+            _ => {}
+        }
+    }
+
+    match MINUTE.pattern(cron.minutes) {
+        // "every 10 seconds" already implies every minute
+        Pattern::All if !parts.is_empty() => {}
+        Pattern::All => parts.push("every minute".to_string()),
+        Pattern::Single(m) => parts.push(format!("at {} minutes past the hour", m)),
+        Pattern::Step { .. } => parts.push(describe(cron.minutes, &MINUTE)),
+        _ => parts.push(format!("at {}", describe(cron.minutes, &MINUTE))),
+    }
+
+    match hour_pattern {
+        Pattern::All => {}
+        Pattern::Step {
+            start: 0,
+            step,
+            to_end: true,
+            ..
+        } => parts.push(format!("every {} hours", step)),
+        Pattern::Step {
+            start,
+            end,
+            step,
+            to_end,
+        } => parts.push(format!(
+            "in every {} hour from {}{}",
+            ordinal(step),
+            hh_mm(start, 0, None),
+            hour_step_end(end, 59, to_end)
+        )),
+        Pattern::Range(a, b) => parts.push(format!(
+            "between {} and {}",
+            hh_mm(a, 0, None),
+            hh_mm(b, 59, None)
+        )),
+        Pattern::Single(h) => parts.push(format!(
+            "between {} and {}",
+            hh_mm(h, 0, None),
+            hh_mm(h, 59, None)
+        )),
+        _ => parts.push(format!("during {}", describe(cron.hours, &HOUR))),
+    }
+    parts.join(", ")
+}
+
+/// `" through 21:00"` for an hour step that stops before the end of the day.
+fn hour_step_end(end: u8, minute: u8, to_end: bool) -> String {
+    if to_end {
+        String::new()
+    } else {
+        format!(" through {}", hh_mm(end, minute, None))
+    }
+}
+
+/// `2` → `"2nd"`, `11` → `"11th"`.
+fn ordinal(n: u8) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
+fn dom_description(cron: &CronSchedule) -> String {
+    day_field_description(cron.days_of_month, &cron.dom_specials, &DAY)
+}
+
+fn dow_description(cron: &CronSchedule) -> String {
+    day_field_description(cron.days_of_week, &cron.dow_specials, &WEEKDAY)
+}
+
+fn day_field_description(set: FieldSet, specials: &[DaySpecial], unit: &Unit) -> String {
+    let mut parts = Vec::new();
+    if !set.values().is_empty() {
+        parts.push(describe(set, unit));
+    }
+    parts.extend(specials_phrase(specials));
+    if parts.is_empty() {
+        "never".to_string()
+    } else {
+        join(parts)
+    }
+}
+
+/// Phrases for `L` / `W` / `#` rules; runs of `L-n` (from systemd `~`) are merged.
+fn specials_phrase(specials: &[DaySpecial]) -> Vec<String> {
+    let mut phrases = Vec::new();
+    let last_days: Vec<u8> = specials
+        .iter()
+        .filter_map(|s| match s {
+            DaySpecial::LastDay(offset) => Some(*offset),
+            _ => None,
+        })
+        .collect();
+    let contiguous_from_end = last_days.len() > 1
+        && last_days
+            .iter()
+            .enumerate()
+            .all(|(i, offset)| usize::from(*offset) == i);
+    if contiguous_from_end {
+        phrases.push(format!("the last {} days of the month", last_days.len()));
+    }
+    for special in specials {
+        phrases.push(match special {
+            DaySpecial::LastDay(_) if contiguous_from_end => continue,
+            DaySpecial::LastDay(0) => "the last day of the month".to_string(),
+            DaySpecial::LastDay(1) => "the day before the last day of the month".to_string(),
+            DaySpecial::LastDay(n) => format!("{} days before the last day of the month", n),
+            DaySpecial::LastWeekday => "the last weekday of the month".to_string(),
+            DaySpecial::NearestWeekday(day) => {
+                format!("the weekday nearest day {} of the month", day)
+            }
+            DaySpecial::LastOfMonth(weekday) => {
+                format!("the last {} of the month", WEEKDAY.name(*weekday))
+            }
+            DaySpecial::Nth(weekday, n) => format!(
+                "the {} {} of the month",
+                ORDINALS[usize::from(n - 1).min(4)],
+                WEEKDAY.name(*weekday)
+            ),
+        });
+    }
+    phrases
+}
+
+/// The day part of the summary, applying the day-of-month/day-of-week rule.
+fn day_phrase(cron: &CronSchedule) -> String {
+    let dom_all = cron.days_of_month.values().len() == 31 && cron.dom_specials.is_empty();
+    let dow_all = cron.days_of_week.values().len() == 7 && cron.dow_specials.is_empty();
+
+    let dom = (!dom_all).then(|| {
+        let mut parts = Vec::new();
+        match DAY.pattern(cron.days_of_month) {
+            Pattern::Empty => {}
+            Pattern::Single(d) => parts.push(format!("day {} of the month", d)),
+            Pattern::Range(a, b) => parts.push(format!("days {} through {} of the month", a, b)),
+            Pattern::Step {
+                start: 1,
+                step,
+                to_end: true,
+                ..
+            } => parts.push(format!("every {} days of the month", step)),
+            _ => parts.push(format!(
+                "days {} of the month",
+                join_numbers(&cron.days_of_month.values())
+            )),
+        }
+        parts.extend(specials_phrase(&cron.dom_specials));
+        format!("on {}", join(parts))
+    });
+
+    let dow = (!dow_all).then(|| {
+        let days = cron.days_of_week.values();
+        let mut parts = Vec::new();
+        match days.as_slice() {
+            [] => {}
+            [1, 2, 3, 4, 5] => parts.push("every weekday".to_string()),
+            [0, 6] => parts.push("weekends".to_string()),
+            _ => parts.push(describe(cron.days_of_week, &WEEKDAY)),
+        }
+        parts.extend(specials_phrase(&cron.dow_specials));
+        join(parts)
+    });
+
+    match (dom, dow) {
+        (Some(dom), Some(dow)) => {
+            if cron.dialect == CronDialect::Standard && cron.dom_restricted && cron.dow_restricted {
+                format!("{} or on {}", dom, dow)
+            } else {
+                format!("{}, only on {}", dom, dow)
+            }
+        }
+        (Some(dom), None) => dom,
+        (None, Some(dow)) => format!("on {}", dow),
+        (None, None) => String::new(),
+    }
+}
+
+fn month_phrase(months: FieldSet) -> String {
+    match MONTH.pattern(months) {
+        Pattern::All | Pattern::Empty => String::new(),
+        Pattern::Range(a, b) => format!("from {} through {}", MONTH.name(a), MONTH.name(b)),
+        _ => format!("in {}", MONTH.names_of(&months.values())),
+    }
+}
+
+fn year_phrase(years: &[i16]) -> String {
+    match years {
+        [] => "never".to_string(),
+        [year] => format!("in {}", year),
+        [first, second, ..] => {
+            let last = years[years.len() - 1];
+            let step = second - first;
+            let even = years.windows(2).all(|w| w[1] - w[0] == step);
+            if even && step == 1 {
+                format!("from {} through {}", first, last)
+            } else if even && years.len() >= 3 {
+                format!("every {} years from {} through {}", step, first, last)
+            } else {
+                format!("in {}", join(years.iter().map(|y| y.to_string()).collect()))
+            }
+        }
+    }
+}
+
+fn join_numbers(values: &[u8]) -> String {
+    join(values.iter().map(|v| v.to_string()).collect())
+}
+
+/// Join with commas and a final "and": "1, 2 and 3".
+fn join(mut items: Vec<String>) -> String {
+    match items.len() {
+        0 => String::new(),
+        1 => items.remove(0),
+        _ => {
+            let last = items.pop().unwrap_or_default();
+            format!("{} and {}", items.join(", "), last)
+        }
+    }
+}
+
+/// Explain a schedule expression; a `dialect` of `systemd` (or `oncalendar`) in
+/// `options_json` reads it as a calendar event, anything else as cron.
+pub fn explain_schedule(expression: &str, options_json: &str) -> Result<Explanation, String> {
+    let dialect = extract_string_field(options_json, "dialect").unwrap_or_default();
+    if matches!(
+        dialect.to_ascii_lowercase().as_str(),
+        "systemd" | "oncalendar"
+    ) {
+        return Ok(explain_calendar(&CalendarEvent::parse(expression)?));
+    }
+    let options = CronOptions::from_json(options_json)?;
+    let schedule = Schedule::parse(expression, options.dialect, &options.seed)?;
+    Ok(explain(&schedule))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(expression: &str) -> String {
+        explain(&Schedule::parse(expression, CronDialect::Standard, "").unwrap()).summary
+    }
+
+    fn summary_in(expression: &str, dialect: CronDialect) -> String {
+        explain(&Schedule::parse(expression, dialect, "").unwrap()).summary
+    }
+
+    #[test]
+    fn test_fixed_times() {
+        assert_eq!(
+            summary("30 9 * 3 1-5"),
+            "At 09:30 on every weekday in March"
+        );
+        assert_eq!(summary("0 9,17 * * *"), "At 09:00 and 17:00");
+        assert_eq!(
+            summary("@yearly"),
+            "At 00:00 on day 1 of the month in January"
+        );
+    }
+
+    #[test]
+    fn test_repeating_times() {
+        assert_eq!(summary("* * * * *"), "Every minute");
+        assert_eq!(summary("@hourly"), "Every hour");
+        assert_eq!(summary("0 */2 * * *"), "Every 2 hours");
+        assert_eq!(summary("5 * * * *"), "At 5 minutes past the hour");
+        assert_eq!(
+            summary("*/15 9-17 * * 1-5"),
+            "Every 15 minutes, between 09:00 and 17:59 on every weekday"
+        );
+        assert_eq!(
+            summary("5/20 * * * *"),
+            "Every 20 minutes starting at minute 5"
+        );
+        assert_eq!(
+            summary("0 9-17 * * *"),
+            "Every hour between 09:00 and 17:00"
+        );
+    }
+
+    #[test]
+    fn test_offset_hour_steps() {
+        assert_eq!(
+            summary("0 1-23/2 * * *"),
+            "At minute 0 of every 2nd hour from 01:00"
+        );
+        assert_eq!(
+            summary("30 6-18/3 * * *"),
+            "At minute 30 of every 3rd hour from 06:30 through 18:30"
+        );
+        assert_eq!(
+            summary("*/15 1-23/2 * * *"),
+            "Every 15 minutes, in every 2nd hour from 01:00"
+        );
+    }
+
+    #[test]
+    fn test_days() {
+        assert_eq!(
+            summary("0 0 13 * FRI"),
+            "At 00:00 on day 13 of the month or on Friday"
+        );
+        assert_eq!(
+            summary_in("0 0 13 * FRI", CronDialect::Jenkins),
+            "At 00:00 on day 13 of the month, only on Friday"
+        );
+        assert_eq!(summary("0 12 * * SAT,SUN"), "At 12:00 on weekends");
+        assert_eq!(
+            summary("0 12 1,15 1-6 *"),
+            "At 12:00 on days 1 and 15 of the month from January through June"
+        );
+    }
+
+    #[test]
+    fn test_quartz_specials() {
+        assert_eq!(
+            summary_in("0 0 18 ? * 6L", CronDialect::Quartz),
+            "At 18:00 on the last Friday of the month"
+        );
+        assert_eq!(
+            summary_in("0 0 12 ? NOV THU#4 2024-2030", CronDialect::Quartz),
+            "At 12:00 on the fourth Thursday of the month in November from 2024 through 2030"
+        );
+        assert_eq!(
+            summary_in("0 30 8 LW * ?", CronDialect::Quartz),
+            "At 08:30 on the last weekday of the month"
+        );
+        assert_eq!(
+            summary_in("*/10 * * ? * *", CronDialect::Quartz),
+            "Every 10 seconds"
+        );
+    }
+
+    #[test]
+    fn test_rate() {
+        assert_eq!(
+            summary_in("rate(5 minutes)", CronDialect::Aws),
+            "Every 5 minutes"
+        );
+        assert_eq!(summary_in("rate(1 day)", CronDialect::Aws), "Every day");
+    }
+
+    #[test]
+    fn test_calendar_event() {
+        let event = CalendarEvent::parse("Mon..Fri *-*-* 09:00:00 Europe/Berlin").unwrap();
+        assert_eq!(
+            explain_calendar(&event).summary,
+            "At 09:00 on every weekday (Europe/Berlin time)"
+        );
+        let event = CalendarEvent::parse("*-*~07/1").unwrap();
+        assert_eq!(
+            explain_calendar(&event).summary,
+            "At 00:00 on the last 7 days of the month"
+        );
+    }
+
+    #[test]
+    fn test_field_breakdown() {
+        let explanation =
+            explain(&Schedule::parse("*/15 9-17 * 3 MON-FRI", CronDialect::Standard, "").unwrap());
+        assert_eq!(
+            explanation.fields,
+            vec![
+                field("minute", "every 15 minutes".to_string()),
+                field("hour", "hours 9 through 17".to_string()),
+                field("day-of-month", "every day".to_string()),
+                field("month", "March".to_string()),
+                field("day-of-week", "Monday through Friday".to_string()),
+            ]
+        );
+        assert!(explanation
+            .to_json()
+            .starts_with(r#"{"expression":"*/15 9-17 * 3 MON-FRI","summary":"Every 15 minutes"#));
+    }
+}
@@ -1,6 +1,11 @@
+use jiff::fmt::strtime;
+use jiff::tz::TimeZone;
 use jiff::Timestamp;
 
 use crate::json_utils::json_escape;
+use crate::locale::{self, Locale};
+use crate::spreadsheet::SpreadsheetSerial;
+use crate::windows::WindowsTime;
 
 /// The formatted result returned across the Wasm boundary as JSON.
 /// All four fields represent the exact same instant in time.
@@ -15,44 +20,227 @@ pub struct FormattedResult {
     pub unix_timestamp: i64,
     pub iso_8601: String,
     pub rfc_2822: String,
-    /// Human-readable format. Currently UTC; will use browser-local timezone
-    /// once timezone support is added in a future story.
+    /// Human-readable format. English in UTC from `from_timestamp`; any zone and
+    /// locale via `from_timestamp_localized`.
     pub local_human: String,
+    /// Extra named outputs requested via `calc::calculate_with_formats`, in request order,
+    /// each rendered or the reason it cannot be. Serialized as a `formats` object only
+    /// when non-empty.
+    pub formats: Vec<(String, Result<String, String>)>,
 }
 
 impl FormattedResult {
     /// Format a `Timestamp` into all four output representations.
     pub fn from_timestamp(ts: Timestamp) -> Self {
+        // Human-readable format is English in UTC for MVP (see struct-level doc comment).
+        // Output example: "January 01, 1970 12:00:00 AM UTC"
+        Self::from_timestamp_localized(ts, &TimeZone::UTC, &locale::EN)
+    }
+
+    /// Format a `Timestamp`, rendering `local_human` in the given zone and locale.
+    ///
+    /// The other outputs stay zone-independent (`rfc_2822` is always `+0000`).
+    /// Output example (de, Europe/Berlin): "11. Juli 2024, 03:14:00 CEST"
+    pub fn from_timestamp_localized(ts: Timestamp, tz: &TimeZone, locale: &Locale) -> Self {
         let unix_timestamp = ts.as_second();
         let iso_8601 = ts.to_string();
 
         // RFC 2822 format: "Thu, 01 Jan 1970 00:00:00 +0000"
         let rfc_2822 = ts.strftime("%a, %d %b %Y %H:%M:%S +0000").to_string();
 
-        // Human-readable format (UTC for MVP, see struct-level doc comment).
-        // Output example: "January 01, 1970 12:00:00 AM UTC"
-        let local_human = ts.strftime("%B %d, %Y %I:%M:%S %p UTC").to_string();
+        let zoned = ts.to_zoned(tz.clone());
+        let pattern = format!("{} %Z", locale.long_pattern(&zoned));
+        let local_human = strtime::format(&pattern, &zoned).unwrap_or_default();
 
         FormattedResult {
             unix_timestamp,
             iso_8601,
             rfc_2822,
             local_human,
+            formats: Vec::new(),
         }
     }
 
     /// Serialize to JSON string for Wasm boundary (manual serialization to avoid serde).
     pub fn to_json(&self) -> String {
+        let formats = if self.formats.is_empty() {
+            String::new()
+        } else {
+            let entries: Vec<String> = self
+                .formats
+                .iter()
+                .map(|(name, value)| match value {
+                    Ok(value) => format!(r#""{}":"{}""#, json_escape(name), json_escape(value)),
+                    Err(_) => format!(r#""{}":null"#, json_escape(name)),
+                })
+                .collect();
+            let errors: Vec<String> = self
+                .formats
+                .iter()
+                .filter_map(|(name, value)| {
+                    let error = value.as_ref().err()?;
+                    Some(format!(
+                        r#""{}":"{}""#,
+                        json_escape(name),
+                        json_escape(error)
+                    ))
+                })
+                .collect();
+            let mut json = format!(r#","formats":{{{}}}"#, entries.join(","));
+            if !errors.is_empty() {
+                json.push_str(&format!(r#","formatErrors":{{{}}}"#, errors.join(",")));
+            }
+            json
+        };
         format!(
-            r#"{{"unixTimestamp":{},"iso8601":"{}","rfc2822":"{}","localHuman":"{}"}}"#,
+            r#"{{"unixTimestamp":{},"iso8601":"{}","rfc2822":"{}","localHuman":"{}"{}}}"#,
             self.unix_timestamp,
             json_escape(&self.iso_8601),
             json_escape(&self.rfc_2822),
             json_escape(&self.local_human),
+            formats,
         )
     }
 }
 
+/// Named output formats that can be requested alongside the four fixed outputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NamedFormat {
+    /// RFC 3339 with the zone's offset: `2024-07-11T03:14:00+02:00`
+    Rfc3339,
+    /// RFC 9557 with bracketed zone: `2024-07-11T03:14:00+02:00[Europe/Berlin]`
+    Rfc9557,
+    /// ISO 8601 week date: `2024-W28-4`
+    IsoWeek,
+    /// ISO 8601 ordinal date: `2024-193`
+    Ordinal,
+    /// SQL `DATETIME` literal in the zone: `2024-07-11 03:14:00`
+    SqlDatetime,
+    /// RFC 9110 HTTP-date, always GMT: `Thu, 11 Jul 2024 01:14:00 GMT`
+    HttpDate,
+    /// Compact ISO 8601 basic format, always UTC: `20240711T011400Z`
+    BasicIso,
+    /// Windows FILETIME in hex, 100 ns since 1601 UTC: `0x01DAD33A4B1F7000`
+    FileTime,
+    /// .NET `DateTime.Ticks` of the UTC time: `638562308400000000`
+    DotNetTicks,
+    /// Active Directory 18-digit timestamp (a decimal FILETIME): `133650164400000000`
+    ActiveDirectory,
+    /// OLE Automation date of the wall-clock time in the zone: `45484.1347222222`
+    OleDate,
+    /// DOS/FAT packed date-time in the zone, 2-second resolution: `0x58EB19C0`
+    DosDateTime,
+    /// Excel 1900-system serial of the wall-clock time in the zone: `45484.1347222222`
+    Excel1900,
+    /// Excel 1904-system serial of the wall-clock time in the zone: `44022.1347222222`
+    Excel1904,
+    /// Google Sheets serial of the wall-clock time in the zone: `45484.1347222222`
+    GoogleSheets,
+}
+
+impl NamedFormat {
+    /// Every named format, in catalogue order.
+    pub const ALL: [NamedFormat; 15] = [
+        NamedFormat::Rfc3339,
+        NamedFormat::Rfc9557,
+        NamedFormat::IsoWeek,
+        NamedFormat::Ordinal,
+        NamedFormat::SqlDatetime,
+        NamedFormat::HttpDate,
+        NamedFormat::BasicIso,
+        NamedFormat::FileTime,
+        NamedFormat::DotNetTicks,
+        NamedFormat::ActiveDirectory,
+        NamedFormat::OleDate,
+        NamedFormat::DosDateTime,
+        NamedFormat::Excel1900,
+        NamedFormat::Excel1904,
+        NamedFormat::GoogleSheets,
+    ];
+
+    /// The name used for this format in JSON (request and response).
+    pub fn name(self) -> &'static str {
+        match self {
+            NamedFormat::Rfc3339 => "rfc3339",
+            NamedFormat::Rfc9557 => "rfc9557",
+            NamedFormat::IsoWeek => "isoWeek",
+            NamedFormat::Ordinal => "ordinal",
+            NamedFormat::SqlDatetime => "sqlDatetime",
+            NamedFormat::HttpDate => "httpDate",
+            NamedFormat::BasicIso => "basicIso",
+            NamedFormat::FileTime => WindowsTime::FileTime.name(),
+            NamedFormat::DotNetTicks => WindowsTime::DotNetTicks.name(),
+            NamedFormat::ActiveDirectory => WindowsTime::ActiveDirectory.name(),
+            NamedFormat::OleDate => WindowsTime::OleDate.name(),
+            NamedFormat::DosDateTime => WindowsTime::DosDateTime.name(),
+            NamedFormat::Excel1900 => SpreadsheetSerial::Excel1900.name(),
+            NamedFormat::Excel1904 => SpreadsheetSerial::Excel1904.name(),
+            NamedFormat::GoogleSheets => SpreadsheetSerial::GoogleSheets.name(),
+        }
+    }
+
+    /// Look up a format by its JSON name.
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        NamedFormat::ALL
+            .into_iter()
+            .find(|f| f.name() == name)
+            .ok_or_else(|| format!("Unknown output format: '{}'", name))
+    }
+
+    /// Render a timestamp in this format. Formats pinned to UTC/GMT ignore `tz`.
+    pub fn render(self, ts: Timestamp, tz: &TimeZone) -> Result<String, String> {
+        match self {
+            NamedFormat::Rfc3339 => format_pattern(ts, "%Y-%m-%dT%H:%M:%S%.f%:z", tz),
+            NamedFormat::Rfc9557 => Ok(ts.to_zoned(tz.clone()).to_string()),
+            NamedFormat::IsoWeek => format_pattern(ts, "%G-W%V-%u", tz),
+            NamedFormat::Ordinal => format_pattern(ts, "%Y-%j", tz),
+            NamedFormat::SqlDatetime => format_pattern(ts, "%Y-%m-%d %H:%M:%S", tz),
+            NamedFormat::HttpDate => {
+                format_pattern(ts, "%a, %d %b %Y %H:%M:%S GMT", &TimeZone::UTC)
+            }
+            NamedFormat::BasicIso => format_pattern(ts, "%Y%m%dT%H%M%SZ", &TimeZone::UTC),
+            NamedFormat::FileTime => WindowsTime::FileTime.encode(ts, tz),
+            NamedFormat::DotNetTicks => WindowsTime::DotNetTicks.encode(ts, tz),
+            NamedFormat::ActiveDirectory => WindowsTime::ActiveDirectory.encode(ts, tz),
+            NamedFormat::OleDate => WindowsTime::OleDate.encode(ts, tz),
+            NamedFormat::DosDateTime => WindowsTime::DosDateTime.encode(ts, tz),
+            NamedFormat::Excel1900 => {
+                SpreadsheetSerial::Excel1900.encode(ts.to_zoned(tz.clone()).datetime())
+            }
+            NamedFormat::Excel1904 => {
+                SpreadsheetSerial::Excel1904.encode(ts.to_zoned(tz.clone()).datetime())
+            }
+            NamedFormat::GoogleSheets => {
+                SpreadsheetSerial::GoogleSheets.encode(ts.to_zoned(tz.clone()).datetime())
+            }
+        }
+    }
+}
+
+/// Render a timestamp with a strftime pattern, with names (`%B`, `%a`, `%p`, ...)
+/// and locale layouts (`%c`, `%x`, `%X`) in the given locale.
+pub fn format_pattern_localized(
+    ts: Timestamp,
+    pattern: &str,
+    tz: &TimeZone,
+    locale: &Locale,
+) -> Result<String, String> {
+    let zoned = ts.to_zoned(tz.clone());
+    let localized = locale.localize_pattern(pattern, &zoned);
+    strtime::format(&localized, &zoned)
+        .map_err(|e| format!("Invalid format pattern '{}': {}", pattern, e))
+}
+
+/// Render a timestamp with an arbitrary strftime pattern in the given zone.
+///
+/// Returns an error (rather than panicking) for patterns jiff can't format,
+/// e.g. an unknown directive like `%Ä`.
+pub fn format_pattern(ts: Timestamp, pattern: &str, tz: &TimeZone) -> Result<String, String> {
+    let zoned = ts.to_zoned(tz.clone());
+    strtime::format(pattern, &zoned)
+        .map_err(|e| format!("Invalid format pattern '{}': {}", pattern, e))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -78,5 +266,109 @@ mod tests {
         assert!(json.contains("\"iso8601\""));
         assert!(json.contains("\"rfc2822\""));
         assert!(json.contains("\"localHuman\""));
+        assert!(!json.contains("\"formats\""));
+    }
+
+    #[test]
+    fn test_json_serialization_with_formats() {
+        let ts = Timestamp::from_second(0).unwrap();
+        let mut result = FormattedResult::from_timestamp(ts);
+        result
+            .formats
+            .push(("ordinal".to_string(), Ok("1970-001".to_string())));
+        let json = result.to_json();
+        assert!(json.ends_with(r#","formats":{"ordinal":"1970-001"}}"#));
+    }
+
+    #[test]
+    fn test_format_pattern_in_zone() {
+        let ts: Timestamp = "2024-07-11T01:14:00Z".parse().unwrap();
+        let tz = crate::zone::resolve_zone("+05:30").unwrap();
+        assert_eq!(
+            format_pattern(ts, "%d/%m/%Y %H:%M %:z", &tz).unwrap(),
+            "11/07/2024 06:44 +05:30"
+        );
+    }
+
+    #[test]
+    fn test_format_pattern_invalid_directive() {
+        let ts = Timestamp::from_second(0).unwrap();
+        assert!(format_pattern(ts, "%Ä", &TimeZone::UTC).is_err());
+    }
+
+    #[test]
+    fn test_named_formats() {
+        let ts: Timestamp = "2024-07-11T01:14:00Z".parse().unwrap();
+        let tz = crate::zone::resolve_zone("Europe/Berlin").unwrap();
+        let render = |f: NamedFormat| f.render(ts, &tz).unwrap();
+
+        assert_eq!(render(NamedFormat::Rfc3339), "2024-07-11T03:14:00+02:00");
+        assert_eq!(
+            render(NamedFormat::Rfc9557),
+            "2024-07-11T03:14:00+02:00[Europe/Berlin]"
+        );
+        assert_eq!(render(NamedFormat::IsoWeek), "2024-W28-4");
+        assert_eq!(render(NamedFormat::Ordinal), "2024-193");
+        assert_eq!(render(NamedFormat::SqlDatetime), "2024-07-11 03:14:00");
+        assert_eq!(
+            render(NamedFormat::HttpDate),
+            "Thu, 11 Jul 2024 01:14:00 GMT"
+        );
+        assert_eq!(render(NamedFormat::BasicIso), "20240711T011400Z");
+        assert_eq!(render(NamedFormat::FileTime), "0x01DAD32F9C4BFC00");
+        assert_eq!(render(NamedFormat::DotNetTicks), "638562572400000000");
+        assert_eq!(render(NamedFormat::ActiveDirectory), "133651340400000000");
+        assert_eq!(render(NamedFormat::OleDate), "45484.1347222222");
+        assert_eq!(render(NamedFormat::DosDateTime), "0x58EB19C0");
+        assert_eq!(render(NamedFormat::Excel1900), "45484.1347222222");
+        assert_eq!(render(NamedFormat::Excel1904), "44022.1347222222");
+        assert_eq!(render(NamedFormat::GoogleSheets), "45484.1347222222");
+    }
+
+    #[test]
+    fn test_named_format_lookup() {
+        for f in NamedFormat::ALL {
+            assert_eq!(NamedFormat::from_name(f.name()).unwrap(), f);
+        }
+        assert!(NamedFormat::from_name("rfc822").is_err());
+    }
+
+    #[test]
+    fn test_default_local_human_unchanged() {
+        let ts: Timestamp = "2024-07-11T13:14:05Z".parse().unwrap();
+        let result = FormattedResult::from_timestamp(ts);
+        assert_eq!(result.local_human, "July 11, 2024 01:14:05 PM UTC");
+    }
+
+    #[cfg(feature = "locales")]
+    #[test]
+    fn test_localized_local_human() {
+        let ts: Timestamp = "2024-07-11T01:14:00Z".parse().unwrap();
+        let berlin = crate::zone::resolve_zone("Europe/Berlin").unwrap();
+        let de = Locale::get("de").unwrap();
+        let result = FormattedResult::from_timestamp_localized(ts, &berlin, de);
+        assert_eq!(result.local_human, "11. Juli 2024, 03:14:00 CEST");
+        assert_eq!(result.rfc_2822, "Thu, 11 Jul 2024 01:14:00 +0000");
+
+        let es = Locale::get("es").unwrap();
+        let result = FormattedResult::from_timestamp_localized(ts, &TimeZone::UTC, es);
+        assert_eq!(result.local_human, "11 de julio de 2024, 01:14:00 UTC");
+    }
+
+    #[cfg(feature = "locales")]
+    #[test]
+    fn test_format_pattern_localized() {
+        let ts: Timestamp = "2024-12-25T18:00:00Z".parse().unwrap();
+        let tokyo = crate::zone::resolve_zone("Asia/Tokyo").unwrap();
+        let ja = Locale::get("ja").unwrap();
+        assert_eq!(
+            format_pattern_localized(ts, "%c (%A)", &tokyo, ja).unwrap(),
+            "2024年12月26日 03:00:00 (木曜日)"
+        );
+        let fr = Locale::get("fr").unwrap();
+        assert_eq!(
+            format_pattern_localized(ts, "%a %-d %b, %x", &TimeZone::UTC, fr).unwrap(),
+            "mer. 25 déc., 25/12/2024"
+        );
     }
 }
@@ -0,0 +1,403 @@
+//! Offset history of a zone: how its standard offset and DST rules changed over the
+//! years, and how much a historical conversion is off when a fixed offset is assumed.
+//!
+//! Years with the same standard time and the same daylight saving time (offset and
+//! abbreviation) are grouped into eras, so "Moscow abolished DST in 2011" shows up as
+//! the start of a new era.
+
+use jiff::civil::DateTime;
+use jiff::tz::{Offset, TimeZone};
+use jiff::Timestamp;
+
+use crate::abbreviation::{self, format_offset};
+use crate::json_utils::{extract_number_field, json_escape};
+use crate::zone::{self, resolve_zone, zone_name, Disambiguation};
+
+/// Longest range of years a history may cover.
+pub const MAX_HISTORY_YEARS: i16 = 500;
+
+/// An offset with its abbreviation, as in effect at some instant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OffsetState {
+    pub offset: Offset,
+    pub abbreviation: String,
+    pub dst: bool,
+}
+
+impl OffsetState {
+    fn at(tz: &TimeZone, ts: Timestamp) -> Self {
+        let info = tz.to_offset_info(ts);
+        OffsetState {
+            offset: info.offset(),
+            abbreviation: info.abbreviation().to_string(),
+            dst: info.dst().is_dst(),
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("{} {}", self.abbreviation, format_offset(self.offset))
+    }
+
+    fn to_json(&self) -> String {
+        format!(
+            r#"{{"offset":"{}","abbreviation":"{}"}}"#,
+            format_offset(self.offset),
+            json_escape(&self.abbreviation)
+        )
+    }
+}
+
+/// A run of years with the same standard and daylight saving time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Era {
+    pub from_year: i16,
+    pub to_year: i16,
+    /// The standard time in effect at the end of each year (the last one seen);
+    /// `None` for years spent entirely on daylight saving time, like US war time in 1943
+    pub standard: Option<OffsetState>,
+    /// The daylight saving time observed, if any
+    pub dst: Option<OffsetState>,
+    pub transitions: usize,
+}
+
+impl Era {
+    fn same_rules(&self, other: &Era) -> bool {
+        self.standard == other.standard && self.dst == other.dst
+    }
+
+    /// What changed from `previous` to this era, e.g. "DST abolished; standard offset
+    /// changed from +03:00 to +04:00".
+    pub fn change_from(&self, previous: &Era) -> String {
+        let mut changes = Vec::new();
+        match (&previous.dst, &self.dst) {
+            (None, Some(dst)) => changes.push(format!("DST introduced ({})", dst.describe())),
+            (Some(_), None) => changes.push("DST abolished".to_string()),
+            (Some(old), Some(new)) if old != new => changes.push(format!(
+                "DST changed from {} to {}",
+                old.describe(),
+                new.describe()
+            )),
+            _ => {}
+        }
+        match (&previous.standard, &self.standard) {
+            (Some(old), Some(new)) => {
+                if old.offset != new.offset {
+                    changes.push(format!(
+                        "standard offset changed from {} to {}",
+                        format_offset(old.offset),
+                        format_offset(new.offset)
+                    ));
+                }
+                if old.abbreviation != new.abbreviation {
+                    changes.push(format!(
+                        "standard abbreviation changed from {} to {}",
+                        old.abbreviation, new.abbreviation
+                    ));
+                }
+            }
+            (Some(_), None) => changes.push("no standard time observed".to_string()),
+            (None, Some(new)) => {
+                changes.push(format!("standard time resumed ({})", new.describe()))
+            }
+            (None, None) => {}
+        }
+        changes.join("; ")
+    }
+}
+
+/// The eras of a zone between two years, inclusive.
+#[derive(Debug, PartialEq)]
+pub struct ZoneHistory {
+    pub zone: TimeZone,
+    pub from_year: i16,
+    pub to_year: i16,
+    pub eras: Vec<Era>,
+}
+
+/// Group the years `from_year..=to_year` of `tz` into eras. Years are local calendar years.
+pub fn history(tz: &TimeZone, from_year: i16, to_year: i16) -> Result<ZoneHistory, String> {
+    if to_year < from_year {
+        return Err(format!(
+            "End year {} is before start year {}",
+            to_year, from_year
+        ));
+    }
+    if to_year - from_year >= MAX_HISTORY_YEARS {
+        return Err(format!(
+            "A history may cover at most {} years",
+            MAX_HISTORY_YEARS
+        ));
+    }
+    let year_start = |year: i16| {
+        jiff::civil::date(year, 1, 1)
+            .to_zoned(tz.clone())
+            .map(|z| z.timestamp())
+            .map_err(|e| format!("Year {} out of range: {}", year, e))
+    };
+
+    let mut eras: Vec<Era> = Vec::new();
+    let mut start = year_start(from_year)?;
+    for year in from_year..=to_year {
+        let end = year_start(year + 1)?;
+        let transitions = zone::transitions_between(tz, start, end);
+        let mut states = vec![OffsetState::at(tz, start)];
+        states.extend(transitions.iter().map(|t| OffsetState::at(tz, t.timestamp)));
+        let standard = states.iter().rev().find(|s| !s.dst).cloned();
+        let dst = states.iter().rev().find(|s| s.dst).cloned();
+        let era = Era {
+            from_year: year,
+            to_year: year,
+            standard,
+            dst,
+            transitions: transitions.len(),
+        };
+        match eras.last_mut() {
+            Some(last) if last.same_rules(&era) => {
+                last.to_year = year;
+                last.transitions += era.transitions;
+            }
+            _ => eras.push(era),
+        }
+        start = end;
+    }
+    Ok(ZoneHistory {
+        zone: tz.clone(),
+        from_year,
+        to_year,
+        eras,
+    })
+}
+
+impl ZoneHistory {
+    /// Serialize to JSON string for Wasm boundary (manual serialization).
+    ///
+    /// Every era after the first carries a `change` sentence describing what differs
+    /// from the era before it.
+    pub fn to_json(&self) -> String {
+        let eras: Vec<String> = self
+            .eras
+            .iter()
+            .enumerate()
+            .map(|(i, era)| {
+                let change = match i.checked_sub(1).map(|p| era.change_from(&self.eras[p])) {
+                    Some(change) => format!(r#","change":"{}""#, json_escape(&change)),
+                    None => String::new(),
+                };
+                format!(
+                    r#"{{"from":{},"to":{},"standard":{},"dst":{},"transitions":{}{}}}"#,
+                    era.from_year,
+                    era.to_year,
+                    era.standard
+                        .as_ref()
+                        .map_or("null".to_string(), OffsetState::to_json),
+                    era.dst
+                        .as_ref()
+                        .map_or("null".to_string(), OffsetState::to_json),
+                    era.transitions,
+                    change
+                )
+            })
+            .collect();
+        format!(
+            r#"{{"zone":"{}","from":{},"to":{},"eras":[{}]}}"#,
+            json_escape(&zone_name(&self.zone)),
+            self.from_year,
+            self.to_year,
+            eras.join(",")
+        )
+    }
+}
+
+/// A past date read with the zone's real history and with a fixed offset.
+#[derive(Debug, PartialEq)]
+pub struct HistoricalConversion {
+    pub input: String,
+    pub zone: TimeZone,
+    /// The offset history gives at that instant
+    pub historical: OffsetState,
+    pub historical_utc: Timestamp,
+    pub naive_offset: Offset,
+    pub naive_utc: Timestamp,
+    /// Set when `input` named an instant, so the two readings differ in local time
+    /// rather than in UTC
+    pub absolute: bool,
+}
+
+impl HistoricalConversion {
+    /// Convert `input`, an instant or a local date-time in `tz`, both ways.
+    pub fn new(input: &str, tz: &TimeZone, naive_offset: Offset) -> Result<Self, String> {
+        let trimmed = input.trim();
+        let (historical_utc, naive_utc, absolute) = if let Ok(ts) = trimmed.parse::<Timestamp>() {
+            (ts, ts, true)
+        } else {
+            let civil: DateTime = trimmed
+                .parse()
+                .map_err(|_| format!("Could not parse '{}' as a date or datetime", trimmed))?;
+            let naive = naive_offset
+                .to_timestamp(civil)
+                .map_err(|e| format!("Failed to convert datetime: {}", e))?;
+            (
+                zone::resolve_local(civil, tz, Disambiguation::Compatible)?,
+                naive,
+                false,
+            )
+        };
+        Ok(HistoricalConversion {
+            input: trimmed.to_string(),
+            zone: tz.clone(),
+            historical: OffsetState::at(tz, historical_utc),
+            historical_utc,
+            naive_offset,
+            naive_utc,
+            absolute,
+        })
+    }
+
+    /// Historical offset minus the naive one; the naive reading is this many seconds
+    /// late in UTC for local input, or early on the wall clock for instants.
+    pub fn difference_seconds(&self) -> i32 {
+        self.historical.offset.seconds() - self.naive_offset.seconds()
+    }
+
+    /// Serialize to JSON string for Wasm boundary (manual serialization).
+    pub fn to_json(&self) -> String {
+        format!(
+            r#"{{"input":"{}","zone":"{}","absolute":{},"historical":{{"utc":"{}","local":"{}","offset":"{}","abbreviation":"{}","dst":{}}},"naive":{{"utc":"{}","local":"{}","offset":"{}"}},"differenceSeconds":{}}}"#,
+            json_escape(&self.input),
+            json_escape(&zone_name(&self.zone)),
+            self.absolute,
+            self.historical_utc,
+            self.historical.offset.to_datetime(self.historical_utc),
+            format_offset(self.historical.offset),
+            json_escape(&self.historical.abbreviation),
+            self.historical.dst,
+            self.naive_utc,
+            self.naive_offset.to_datetime(self.naive_utc),
+            format_offset(self.naive_offset),
+            self.difference_seconds()
+        )
+    }
+}
+
+/// Standard offset and DST eras of `zone` across local calendar years.
+///
+/// `options_json` is an optional JSON object: `from` (1900 by default) and `to`
+/// (the current year by default), both inclusive.
+pub fn zone_history(zone: &str, options_json: &str) -> Result<ZoneHistory, String> {
+    let tz = resolve_zone(zone)?;
+    let year = |key: &str, default: i64| {
+        let year = extract_number_field(options_json, key).unwrap_or(default);
+        i16::try_from(year)
+            .ok()
+            .filter(|y| (-9998..=9998).contains(y))
+            .ok_or_else(|| format!("Year {} is out of range", year))
+    };
+    let current = Timestamp::now().to_zoned(tz.clone()).year();
+    history(&tz, year("from", 1900)?, year("to", i64::from(current))?)
+}
+
+/// Read `input` in `zone` with its offset history and with the fixed `naive_offset`
+/// (e.g. `"+03:00"` or `"UTC+3"`).
+///
+/// An instant is shown on both wall clocks; a civil date-time is converted to UTC
+/// both ways. An empty `naive_offset` means the zone's standard offset this year,
+/// which is what code that ignores history would assume.
+pub fn compare_historical_offset(
+    zone: &str,
+    input: &str,
+    naive_offset: &str,
+) -> Result<HistoricalConversion, String> {
+    let tz = resolve_zone(zone)?;
+    let naive = if naive_offset.trim().is_empty() {
+        let year = Timestamp::now().to_zoned(tz.clone()).year();
+        let history = history(&tz, year, year)?;
+        match &history.eras[0].standard {
+            Some(standard) => standard.offset,
+            None => tz.to_offset(Timestamp::now()),
+        }
+    } else {
+        abbreviation::parse_offset_notation(naive_offset)
+            .ok_or_else(|| format!("Invalid offset '{}'", naive_offset.trim()))?
+    };
+    HistoricalConversion::new(input, &tz, naive)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zone::resolve_zone;
+
+    #[test]
+    fn test_moscow_eras() {
+        let tz = resolve_zone("Europe/Moscow").unwrap();
+        let history = history(&tz, 2008, 2016).unwrap();
+        let spans: Vec<(i16, i16)> = history
+            .eras
+            .iter()
+            .map(|e| (e.from_year, e.to_year))
+            .collect();
+        assert_eq!(spans, vec![(2008, 2010), (2011, 2013), (2014, 2016)]);
+        assert_eq!(
+            history.eras[1].change_from(&history.eras[0]),
+            "DST abolished; standard offset changed from +03:00 to +04:00"
+        );
+        assert_eq!(
+            history.eras[2].change_from(&history.eras[1]),
+            "standard offset changed from +04:00 to +03:00"
+        );
+    }
+
+    #[test]
+    fn test_year_without_standard_time() {
+        // New York kept Eastern War Time (daylight saving) from February 1942 until
+        // Eastern Peace Time ended in September 1945, so 1943 and 1944 had no
+        // standard time at all.
+        let tz = resolve_zone("America/New_York").unwrap();
+        let history = history(&tz, 1942, 1946).unwrap();
+        let spans: Vec<(i16, i16)> = history
+            .eras
+            .iter()
+            .map(|e| (e.from_year, e.to_year))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![(1942, 1942), (1943, 1944), (1945, 1945), (1946, 1946)]
+        );
+        assert_eq!(history.eras[1].standard, None);
+        assert_eq!(
+            history.eras[1].change_from(&history.eras[0]),
+            "no standard time observed"
+        );
+        assert_eq!(
+            history.eras[2].change_from(&history.eras[1]),
+            "DST changed from EWT -04:00 to EPT -04:00; standard time resumed (EST -05:00)"
+        );
+    }
+
+    #[test]
+    fn test_single_era_and_range_errors() {
+        let tz = resolve_zone("Asia/Tokyo").unwrap();
+        let history = history(&tz, 1990, 2020).unwrap();
+        assert_eq!(history.eras.len(), 1);
+        assert_eq!(history.eras[0].transitions, 0);
+        assert!(super::history(&tz, 2020, 1990).is_err());
+        assert!(super::history(&tz, 1000, 2000)
+            .unwrap_err()
+            .contains("at most"));
+    }
+
+    #[test]
+    fn test_conversion_difference() {
+        let tz = resolve_zone("Europe/Moscow").unwrap();
+        let naive = Offset::from_seconds(3 * 3600).unwrap();
+        let local = HistoricalConversion::new("1995-07-01T12:00:00", &tz, naive).unwrap();
+        assert_eq!(local.historical.abbreviation, "MSD");
+        assert_eq!(local.historical_utc.to_string(), "1995-07-01T08:00:00Z");
+        assert_eq!(local.naive_utc.to_string(), "1995-07-01T09:00:00Z");
+        assert_eq!(local.difference_seconds(), 3600);
+        let instant = HistoricalConversion::new("2012-01-01T00:00:00Z", &tz, naive).unwrap();
+        assert!(instant.absolute);
+        assert_eq!(instant.historical_utc, instant.naive_utc);
+        assert_eq!(instant.difference_seconds(), 3600);
+    }
+}
@@ -0,0 +1,951 @@
+//! Creation times embedded in IDs.
+//!
+//! Snowflake IDs are 64-bit integers whose high bits count time units since a custom
+//! epoch; the low bits hold generator fields such as a worker number and a per-unit
+//! sequence. Only the epoch and the bit widths differ between services.
+//!
+//! UUID versions 1, 6 and 7, ULIDs, KSUIDs and BSON ObjectIds carry a timestamp too;
+//! `decode_id` tells them apart by their string shape.
+
+use jiff::Timestamp;
+
+use crate::calc::parse_start_date;
+use crate::format::FormattedResult;
+use crate::json_utils::{extract_number_field, extract_string_field, json_escape};
+
+/// Names of the built-in Snowflake layouts, in the order alternatives are listed.
+pub const SNOWFLAKE_PRESETS: &[&str] = &["twitter", "discord", "instagram", "sonyflake"];
+
+/// Where the timestamp and generator fields sit in a Snowflake ID.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnowflakeLayout {
+    pub name: String,
+    /// Unix milliseconds of timestamp zero
+    pub epoch_ms: i64,
+    pub timestamp_bits: u32,
+    /// Milliseconds per timestamp tick (10 for Sonyflake)
+    pub unit_ms: i64,
+    /// Fields below the timestamp, most significant first, as (name, bits)
+    pub fields: Vec<(String, u32)>,
+}
+
+impl SnowflakeLayout {
+    /// A built-in layout: `twitter`, `discord`, `instagram` or `sonyflake`.
+    pub fn preset(name: &str) -> Option<Self> {
+        let layout = |name: &str, epoch_ms, timestamp_bits, unit_ms, fields: &[(&str, u32)]| {
+            SnowflakeLayout {
+                name: name.to_string(),
+                epoch_ms,
+                timestamp_bits,
+                unit_ms,
+                fields: fields
+                    .iter()
+                    .map(|(field, bits)| (field.to_string(), *bits))
+                    .collect(),
+            }
+        };
+        Some(match name.trim().to_ascii_lowercase().as_str() {
+            "twitter" | "x" => layout(
+                "twitter",
+                1_288_834_974_657,
+                41,
+                1,
+                &[("datacenter", 5), ("worker", 5), ("sequence", 12)],
+            ),
+            "discord" => layout(
+                "discord",
+                1_420_070_400_000,
+                42,
+                1,
+                &[("worker", 5), ("process", 5), ("increment", 12)],
+            ),
+            "instagram" => layout(
+                "instagram",
+                1_314_220_021_721,
+                41,
+                1,
+                &[("shard", 13), ("sequence", 10)],
+            ),
+            "sonyflake" => layout(
+                "sonyflake",
+                1_409_529_600_000,
+                39,
+                10,
+                &[("sequence", 8), ("machine", 16)],
+            ),
+            _ => return None,
+        })
+    }
+
+    /// A custom layout: `timestamp_bits` of `unit_ms` ticks since `epoch_ms`, then
+    /// `worker_bits` and `sequence_bits`.
+    pub fn custom(
+        epoch_ms: i64,
+        timestamp_bits: u32,
+        worker_bits: u32,
+        sequence_bits: u32,
+        unit_ms: i64,
+    ) -> Result<Self, String> {
+        if timestamp_bits == 0 || timestamp_bits + worker_bits + sequence_bits > 64 {
+            return Err(format!(
+                "Layout needs 1 to 64 bits in total, got {} timestamp + {} worker + {} sequence",
+                timestamp_bits, worker_bits, sequence_bits
+            ));
+        }
+        if unit_ms <= 0 {
+            return Err(format!("Time unit must be positive, got {} ms", unit_ms));
+        }
+        Ok(SnowflakeLayout {
+            name: "custom".to_string(),
+            epoch_ms,
+            timestamp_bits,
+            unit_ms,
+            fields: vec![
+                ("worker".to_string(), worker_bits),
+                ("sequence".to_string(), sequence_bits),
+            ],
+        })
+    }
+
+    fn shift(&self) -> u32 {
+        self.fields.iter().map(|(_, bits)| bits).sum()
+    }
+
+    /// Split `id` into its creation time and generator fields.
+    pub fn decode(&self, id: u64) -> Result<SnowflakeId, String> {
+        let shift = self.shift();
+        let ticks = id.checked_shr(shift).unwrap_or(0);
+        if ticks.checked_shr(self.timestamp_bits).unwrap_or(0) != 0 {
+            return Err(format!(
+                "ID {} does not fit the {} layout ({} timestamp bits)",
+                id, self.name, self.timestamp_bits
+            ));
+        }
+        let ms = i64::try_from(ticks)
+            .ok()
+            .and_then(|t| t.checked_mul(self.unit_ms))
+            .and_then(|t| t.checked_add(self.epoch_ms))
+            .ok_or_else(|| format!("ID {} is out of range", id))?;
+        let timestamp = Timestamp::from_millisecond(ms)
+            .map_err(|e| format!("ID {} is out of range: {}", id, e))?;
+        let mut remaining = shift;
+        let fields = self
+            .fields
+            .iter()
+            .map(|(name, bits)| {
+                remaining -= bits;
+                let mask = (1u64 << bits) - 1;
+                (name.clone(), (id >> remaining) & mask)
+            })
+            .collect();
+        Ok(SnowflakeId {
+            id,
+            layout: self.name.clone(),
+            timestamp,
+            fields,
+        })
+    }
+
+    /// Ticks from the epoch to `ts`, rounded up or down.
+    fn ticks(&self, ts: Timestamp, round_up: bool) -> Result<u64, String> {
+        let elapsed = ts.as_millisecond() - self.epoch_ms;
+        if elapsed < 0 {
+            return Err(format!(
+                "{} is before the {} epoch ({})",
+                ts,
+                self.name,
+                Timestamp::from_millisecond(self.epoch_ms)
+                    .map_or_else(|_| "?".to_string(), |e| e.to_string())
+            ));
+        }
+        let ticks = if round_up {
+            (elapsed + self.unit_ms - 1) / self.unit_ms
+        } else {
+            elapsed / self.unit_ms
+        };
+        u64::try_from(ticks)
+            .ok()
+            .filter(|t| t.checked_shr(self.timestamp_bits).unwrap_or(0) == 0)
+            .ok_or_else(|| format!("{} is past the end of the {} layout", ts, self.name))
+    }
+
+    /// The smallest ID generated at or after `ts`, for `id >= ...` range queries.
+    pub fn min_id(&self, ts: Timestamp) -> Result<u64, String> {
+        let ticks = self.ticks(ts, true)?;
+        ticks
+            .checked_shl(self.shift())
+            .ok_or_else(|| format!("{} is past the end of the {} layout", ts, self.name))
+    }
+
+    /// The smallest and largest IDs of the tick containing `ts`.
+    pub fn tick_ids(&self, ts: Timestamp) -> Result<(u64, u64), String> {
+        let shift = self.shift();
+        let min = self
+            .ticks(ts, false)?
+            .checked_shl(shift)
+            .ok_or_else(|| format!("{} is past the end of the {} layout", ts, self.name))?;
+        Ok((min, min | u64::MAX.checked_shr(64 - shift).unwrap_or(0)))
+    }
+}
+
+/// A Snowflake ID split into its parts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnowflakeId {
+    pub id: u64,
+    pub layout: String,
+    pub timestamp: Timestamp,
+    pub fields: Vec<(String, u64)>,
+}
+
+impl SnowflakeId {
+    fn fields_json(&self) -> String {
+        let fields: Vec<String> = self
+            .fields
+            .iter()
+            .map(|(name, value)| format!(r#""{}":{}"#, json_escape(name), value))
+            .collect();
+        format!("{{{}}}", fields.join(","))
+    }
+}
+
+/// One ID read with a chosen layout, and with the other presets for comparison.
+#[derive(Debug, PartialEq)]
+pub struct SnowflakeDecoding {
+    pub reading: SnowflakeId,
+    pub alternatives: Vec<SnowflakeId>,
+}
+
+impl SnowflakeDecoding {
+    /// Serialize to JSON string for Wasm boundary (manual serialization).
+    ///
+    /// The ID is a string: Snowflakes exceed JavaScript's safe integer range.
+    pub fn to_json(&self) -> String {
+        let reading = &self.reading;
+        let alternatives: Vec<String> = self
+            .alternatives
+            .iter()
+            .map(|alt| {
+                format!(
+                    r#"{{"layout":"{}","iso":"{}","unixTimestampMs":{}}}"#,
+                    json_escape(&alt.layout),
+                    alt.timestamp,
+                    alt.timestamp.as_millisecond()
+                )
+            })
+            .collect();
+        format!(
+            r#"{{"id":"{}","layout":"{}","iso":"{}","unixTimestamp":{},"unixTimestampMs":{},"fields":{},"alternatives":[{}]}}"#,
+            reading.id,
+            json_escape(&reading.layout),
+            reading.timestamp,
+            reading.timestamp.as_second(),
+            reading.timestamp.as_millisecond(),
+            reading.fields_json(),
+            alternatives.join(",")
+        )
+    }
+}
+
+/// The smallest ID of a layout for an instant.
+#[derive(Debug, PartialEq)]
+pub struct SnowflakeBound {
+    pub layout: String,
+    pub instant: Timestamp,
+    pub id: u64,
+}
+
+impl SnowflakeBound {
+    /// Serialize to JSON string for Wasm boundary (manual serialization).
+    pub fn to_json(&self) -> String {
+        format!(
+            r#"{{"layout":"{}","instant":"{}","id":"{}"}}"#,
+            json_escape(&self.layout),
+            self.instant,
+            self.id
+        )
+    }
+}
+
+/// Parse a decimal Snowflake ID.
+pub fn parse_snowflake(input: &str) -> Result<u64, String> {
+    input.trim().parse().map_err(|_| {
+        format!(
+            "Invalid Snowflake ID '{}' (expected a decimal 64-bit integer)",
+            input.trim()
+        )
+    })
+}
+
+/// 100 ns ticks from the Gregorian reform (1582-10-15), the UUID v1/v6 epoch, to 1970.
+const GREGORIAN_OFFSET_TICKS: i128 = 0x01B2_1DD2_1381_4000;
+
+/// KSUID timestamps count seconds from 2014-05-13T16:53:20Z.
+const KSUID_EPOCH: i64 = 1_400_000_000;
+
+const CROCKFORD: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const BASE62: &[u8; 62] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// The kinds of ID `decode_id` recognizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdKind {
+    Snowflake,
+    UuidV1,
+    UuidV6,
+    UuidV7,
+    Ulid,
+    Ksuid,
+    ObjectId,
+}
+
+impl IdKind {
+    pub const ALL: [IdKind; 7] = [
+        IdKind::UuidV1,
+        IdKind::UuidV6,
+        IdKind::UuidV7,
+        IdKind::Ulid,
+        IdKind::Ksuid,
+        IdKind::ObjectId,
+        IdKind::Snowflake,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            IdKind::Snowflake => "snowflake",
+            IdKind::UuidV1 => "uuid-v1",
+            IdKind::UuidV6 => "uuid-v6",
+            IdKind::UuidV7 => "uuid-v7",
+            IdKind::Ulid => "ulid",
+            IdKind::Ksuid => "ksuid",
+            IdKind::ObjectId => "objectid",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        let name = name.trim();
+        IdKind::ALL
+            .into_iter()
+            .find(|kind| {
+                kind.name().eq_ignore_ascii_case(name)
+                    || kind.name().replace('-', "").eq_ignore_ascii_case(name)
+            })
+            .ok_or_else(|| {
+                let names: Vec<&str> = IdKind::ALL.iter().map(|k| k.name()).collect();
+                format!("Unknown ID kind '{}' (expected {})", name, names.join(", "))
+            })
+    }
+}
+
+/// A decoded part of an ID other than its timestamp.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Component {
+    Number(u64),
+    Flag(bool),
+    Text(String),
+}
+
+/// An ID with its creation time and remaining parts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedId {
+    pub kind: IdKind,
+    /// The ID in canonical form (lowercase hyphenated UUID, uppercase ULID, ...)
+    pub id: String,
+    pub timestamp: Timestamp,
+    pub components: Vec<(String, Component)>,
+}
+
+impl DecodedId {
+    /// Serialize to JSON string for Wasm boundary (manual serialization).
+    ///
+    /// `result` is the creation time as a `FormattedResult`.
+    pub fn to_json(&self) -> String {
+        let components: Vec<String> = self
+            .components
+            .iter()
+            .map(|(name, value)| match value {
+                Component::Number(n) => format!(r#""{}":{}"#, json_escape(name), n),
+                Component::Flag(flag) => format!(r#""{}":{}"#, json_escape(name), flag),
+                Component::Text(text) => {
+                    format!(r#""{}":"{}""#, json_escape(name), json_escape(text))
+                }
+            })
+            .collect();
+        format!(
+            r#"{{"kind":"{}","id":"{}","unixTimestampMs":{},"result":{},"components":{{{}}}}}"#,
+            self.kind.name(),
+            json_escape(&self.id),
+            self.timestamp.as_millisecond(),
+            FormattedResult::from_timestamp(self.timestamp).to_json(),
+            components.join(",")
+        )
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn parse_hex<const N: usize>(text: &str) -> Option<[u8; N]> {
+    if text.len() != N * 2 || !text.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let mut bytes = [0u8; N];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&text[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(bytes)
+}
+
+fn format_uuid(b: &[u8; 16]) -> String {
+    format!(
+        "{}-{}-{}-{}-{}",
+        hex(&b[0..4]),
+        hex(&b[4..6]),
+        hex(&b[6..8]),
+        hex(&b[8..10]),
+        hex(&b[10..16])
+    )
+}
+
+fn be(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0, |acc, b| (acc << 8) | u64::from(*b))
+}
+
+/// Decode `input`, telling the kind apart by its shape: 36 (or 32) hex digits are a
+/// UUID, 24 an ObjectId, 26 Crockford base32 characters a ULID, 27 base62 ones a KSUID
+/// and up to 20 decimal digits a Snowflake read with `snowflake`.
+pub fn decode_id_with(input: &str, snowflake: &SnowflakeLayout) -> Result<DecodedId, String> {
+    let trimmed = input.trim();
+    let text = trimmed
+        .strip_prefix("urn:uuid:")
+        .unwrap_or(trimmed)
+        .trim_start_matches('{')
+        .trim_end_matches('}');
+    let hyphens = [8, 13, 18, 23];
+    let is_uuid_shape = text.len() == 36
+        && text
+            .char_indices()
+            .all(|(i, c)| (c == '-') == hyphens.contains(&i));
+    if is_uuid_shape || text.len() == 32 {
+        if let Some(bytes) = parse_hex::<16>(&text.replace('-', "")) {
+            return decode_uuid(&bytes);
+        }
+    }
+    if let Some(bytes) = parse_hex::<12>(text) {
+        return Ok(decode_object_id(&bytes));
+    }
+    if !text.is_empty() && text.len() <= 20 && text.bytes().all(|b| b.is_ascii_digit()) {
+        let id = snowflake.decode(parse_snowflake(text)?)?;
+        return Ok(DecodedId {
+            kind: IdKind::Snowflake,
+            id: id.id.to_string(),
+            timestamp: id.timestamp,
+            components: std::iter::once(("layout".to_string(), Component::Text(id.layout.clone())))
+                .chain(
+                    id.fields
+                        .into_iter()
+                        .map(|(name, value)| (name, Component::Number(value))),
+                )
+                .collect(),
+        });
+    }
+    match text.len() {
+        26 => decode_ulid(text),
+        27 => decode_ksuid(text),
+        _ => Err(format!(
+            "Unrecognized ID '{}' (expected a UUID, ULID, KSUID, ObjectId or Snowflake)",
+            trimmed
+        )),
+    }
+}
+
+fn decode_uuid(b: &[u8; 16]) -> Result<DecodedId, String> {
+    let version = b[6] >> 4;
+    if b[8] >> 6 != 0b10 {
+        return Err(format!(
+            "UUID {} is not an RFC 9562 UUID (variant bits {:02b})",
+            format_uuid(b),
+            b[8] >> 6
+        ));
+    }
+    let time_hi = be(&b[6..8]) & 0x0fff;
+    let (kind, timestamp) = match version {
+        1 | 6 => {
+            let ticks = if version == 1 {
+                (time_hi << 48) | (be(&b[4..6]) << 32) | be(&b[0..4])
+            } else {
+                (be(&b[0..4]) << 28) | (be(&b[4..6]) << 12) | time_hi
+            };
+            let nanos = (i128::from(ticks) - GREGORIAN_OFFSET_TICKS) * 100;
+            let kind = if version == 1 {
+                IdKind::UuidV1
+            } else {
+                IdKind::UuidV6
+            };
+            (kind, Timestamp::from_nanosecond(nanos))
+        }
+        7 => (
+            IdKind::UuidV7,
+            Timestamp::from_millisecond(be(&b[0..6]) as i64),
+        ),
+        _ => {
+            return Err(format!(
+                "UUID version {} has no timestamp (only versions 1, 6 and 7 do)",
+                version
+            ))
+        }
+    };
+    let timestamp = timestamp.map_err(|e| format!("UUID time out of range: {}", e))?;
+    let mut components = vec![("version".to_string(), Component::Number(version.into()))];
+    if kind == IdKind::UuidV7 {
+        components.push(("randA".to_string(), Component::Number(time_hi)));
+        let mut rand_b = [0u8; 8];
+        rand_b.copy_from_slice(&b[8..16]);
+        rand_b[0] &= 0x3f;
+        components.push(("randB".to_string(), Component::Text(hex(&rand_b))));
+    } else {
+        components.push((
+            "clockSequence".to_string(),
+            Component::Number(be(&b[8..10]) & 0x3fff),
+        ));
+        let node: Vec<String> = b[10..16].iter().map(|x| format!("{:02x}", x)).collect();
+        components.push(("node".to_string(), Component::Text(node.join(":"))));
+        // RFC 9562 6.10: a random node ID has the multicast bit set
+        components.push(("randomNode".to_string(), Component::Flag(b[10] & 1 == 1)));
+    }
+    Ok(DecodedId {
+        kind,
+        id: format_uuid(b),
+        timestamp,
+        components,
+    })
+}
+
+fn decode_object_id(b: &[u8; 12]) -> DecodedId {
+    DecodedId {
+        kind: IdKind::ObjectId,
+        id: hex(b),
+        timestamp: Timestamp::from_second(be(&b[0..4]) as i64).expect("u32 seconds are in range"),
+        components: vec![
+            ("random".to_string(), Component::Text(hex(&b[4..9]))),
+            ("counter".to_string(), Component::Number(be(&b[9..12]))),
+        ],
+    }
+}
+
+fn decode_ulid(text: &str) -> Result<DecodedId, String> {
+    let mut value: u128 = 0;
+    for (i, ch) in text.chars().enumerate() {
+        let invalid = || format!("Invalid ULID character '{}' in '{}'", ch, text);
+        let digit = match ch.to_ascii_uppercase() {
+            'I' | 'L' => 1,
+            'O' => 0,
+            c => CROCKFORD
+                .iter()
+                .position(|&d| char::from(d) == c)
+                .ok_or_else(invalid)? as u128,
+        };
+        if i == 0 && digit > 7 {
+            return Err(format!("ULID '{}' exceeds 128 bits", text));
+        }
+        value = (value << 5) | digit;
+    }
+    let ms = (value >> 80) as i64;
+    let randomness = value & ((1u128 << 80) - 1);
+    Ok(DecodedId {
+        kind: IdKind::Ulid,
+        id: encode_ulid(value),
+        timestamp: Timestamp::from_millisecond(ms)
+            .map_err(|e| format!("ULID time out of range: {}", e))?,
+        components: vec![(
+            "randomness".to_string(),
+            Component::Text(format!("{:020x}", randomness)),
+        )],
+    })
+}
+
+fn encode_ulid(value: u128) -> String {
+    (0..26)
+        .rev()
+        .map(|i| CROCKFORD[((value >> (i * 5)) & 31) as usize] as char)
+        .collect()
+}
+
+fn decode_ksuid(text: &str) -> Result<DecodedId, String> {
+    // Base62 to 160 bits, one digit at a time over a big-endian byte array
+    let mut bytes = [0u8; 20];
+    for ch in text.chars() {
+        let digit = BASE62
+            .iter()
+            .position(|&d| char::from(d) == ch)
+            .ok_or_else(|| format!("Invalid KSUID character '{}' in '{}'", ch, text))?
+            as u32;
+        let mut carry = digit;
+        for byte in bytes.iter_mut().rev() {
+            let value = u32::from(*byte) * 62 + carry;
+            *byte = value as u8;
+            carry = value >> 8;
+        }
+        if carry != 0 {
+            return Err(format!("KSUID '{}' exceeds 160 bits", text));
+        }
+    }
+    Ok(DecodedId {
+        kind: IdKind::Ksuid,
+        id: text.to_string(),
+        timestamp: Timestamp::from_second(be(&bytes[0..4]) as i64 + KSUID_EPOCH)
+            .expect("u32 seconds are in range"),
+        components: vec![("payload".to_string(), Component::Text(hex(&bytes[4..])))],
+    })
+}
+
+fn encode_ksuid(mut bytes: [u8; 20]) -> String {
+    let mut digits = [b'0'; 27];
+    for digit in digits.iter_mut().rev() {
+        let mut remainder = 0u32;
+        for byte in bytes.iter_mut() {
+            let value = (remainder << 8) | u32::from(*byte);
+            *byte = (value / 62) as u8;
+            remainder = value % 62;
+        }
+        *digit = BASE62[remainder as usize];
+    }
+    String::from_utf8(digits.to_vec()).expect("base62 is ASCII")
+}
+
+/// The smallest and largest IDs of `kind` created in the same tick as `ts` (100 ns
+/// for UUID v1/v6, a millisecond for v7, ULID and Snowflake, a second for KSUID and
+/// ObjectId).
+pub fn bounds(
+    kind: IdKind,
+    ts: Timestamp,
+    snowflake: &SnowflakeLayout,
+) -> Result<(String, String), String> {
+    let before = |epoch: &str| format!("{} is before the {} epoch ({})", ts, kind.name(), epoch);
+    let past = || format!("{} is past the range of {}", ts, kind.name());
+    let seconds = ts.as_second();
+    let ms = ts.as_millisecond();
+    Ok(match kind {
+        IdKind::Snowflake => {
+            let (min, max) = snowflake.tick_ids(ts)?;
+            (min.to_string(), max.to_string())
+        }
+        IdKind::UuidV1 | IdKind::UuidV6 => {
+            let ticks = ts.as_nanosecond().div_euclid(100) + GREGORIAN_OFFSET_TICKS;
+            if ticks < 0 {
+                return Err(before("1582-10-15"));
+            }
+            let ticks = u64::try_from(ticks)
+                .ok()
+                .filter(|t| t >> 60 == 0)
+                .ok_or_else(past)?;
+            let (time, version) = if kind == IdKind::UuidV1 {
+                (
+                    ((ticks & 0xffff_ffff) << 32)
+                        | (((ticks >> 32) & 0xffff) << 16)
+                        | (ticks >> 48),
+                    0x1000,
+                )
+            } else {
+                (((ticks >> 12) << 16) | (ticks & 0x0fff), 0x6000)
+            };
+            let mut min = [0u8; 16];
+            min[..8].copy_from_slice(&(time | version).to_be_bytes());
+            min[8] = 0x80;
+            let mut max = [0xffu8; 16];
+            max[..8].copy_from_slice(&min[..8]);
+            max[8] = 0xbf;
+            (format_uuid(&min), format_uuid(&max))
+        }
+        IdKind::UuidV7 => {
+            if ms < 0 {
+                return Err(before("1970-01-01"));
+            }
+            let mut min = [0u8; 16];
+            min[..8].copy_from_slice(&((ms as u64) << 16 | 0x7000).to_be_bytes());
+            min[8] = 0x80;
+            let mut max = [0xffu8; 16];
+            max[..6].copy_from_slice(&min[..6]);
+            max[6] = 0x7f;
+            max[8] = 0xbf;
+            (format_uuid(&min), format_uuid(&max))
+        }
+        IdKind::Ulid => {
+            if ms < 0 {
+                return Err(before("1970-01-01"));
+            }
+            let time = (ms as u128) << 80;
+            (encode_ulid(time), encode_ulid(time | ((1u128 << 80) - 1)))
+        }
+        IdKind::Ksuid => {
+            let elapsed = seconds - KSUID_EPOCH;
+            if elapsed < 0 {
+                return Err(before("2014-05-13T16:53:20Z"));
+            }
+            let elapsed = u32::try_from(elapsed).map_err(|_| past())?;
+            let mut min = [0u8; 20];
+            min[..4].copy_from_slice(&elapsed.to_be_bytes());
+            let mut max = [0xffu8; 20];
+            max[..4].copy_from_slice(&min[..4]);
+            (encode_ksuid(min), encode_ksuid(max))
+        }
+        IdKind::ObjectId => {
+            if seconds < 0 {
+                return Err(before("1970-01-01"));
+            }
+            let seconds = u32::try_from(seconds).map_err(|_| past())?;
+            let prefix = hex(&seconds.to_be_bytes());
+            (
+                format!("{}0000000000000000", prefix),
+                format!("{}ffffffffffffffff", prefix),
+            )
+        }
+    })
+}
+
+/// Boundary IDs of one or more kinds for an instant.
+#[derive(Debug, PartialEq)]
+pub struct IdBounds {
+    pub instant: Timestamp,
+    /// (kind, smallest, largest)
+    pub bounds: Vec<(IdKind, String, String)>,
+}
+
+impl IdBounds {
+    /// Serialize to JSON string for Wasm boundary (manual serialization).
+    pub fn to_json(&self) -> String {
+        let bounds: Vec<String> = self
+            .bounds
+            .iter()
+            .map(|(kind, min, max)| {
+                format!(
+                    r#"{{"kind":"{}","min":"{}","max":"{}"}}"#,
+                    kind.name(),
+                    json_escape(min),
+                    json_escape(max)
+                )
+            })
+            .collect();
+        format!(
+            r#"{{"instant":"{}","bounds":[{}]}}"#,
+            self.instant,
+            bounds.join(",")
+        )
+    }
+}
+
+/// The Snowflake layout named by `options_json`, and whether one was named at all.
+///
+/// `layout` is a preset (`twitter` by default) or `custom`, which reads `epoch` (Unix
+/// milliseconds or a date), `timestampBits` (41), `workerBits` (10), `sequenceBits`
+/// (12) and `unitMs` (1).
+fn snowflake_layout(options_json: &str) -> Result<(SnowflakeLayout, bool), String> {
+    let name = extract_string_field(options_json, "layout");
+    let layout = match name.as_deref().map(str::trim) {
+        None | Some("") => SnowflakeLayout::preset("twitter"),
+        Some(name) if name.eq_ignore_ascii_case("custom") => {
+            let epoch_ms = match extract_string_field(options_json, "epoch") {
+                Some(date) => parse_start_date(&date)?.as_millisecond(),
+                None => extract_number_field(options_json, "epoch")
+                    .ok_or("A custom layout needs an 'epoch'")?,
+            };
+            let bits = |key: &str, default: i64| {
+                let bits = extract_number_field(options_json, key).unwrap_or(default);
+                u32::try_from(bits).map_err(|_| format!("'{}' must not be negative", key))
+            };
+            Some(SnowflakeLayout::custom(
+                epoch_ms,
+                bits("timestampBits", 41)?,
+                bits("workerBits", 10)?,
+                bits("sequenceBits", 12)?,
+                extract_number_field(options_json, "unitMs").unwrap_or(1),
+            )?)
+        }
+        Some(name) => Some(SnowflakeLayout::preset(name).ok_or_else(|| {
+            format!(
+                "Unknown Snowflake layout '{}' (expected {} or custom)",
+                name,
+                SNOWFLAKE_PRESETS.join(", ")
+            )
+        })?),
+    };
+    Ok((layout.expect("twitter is a preset"), name.is_some()))
+}
+
+/// Decode the creation time and generator fields of Snowflake `id`.
+///
+/// See `snowflake_layout` for `options_json`. Without a layout the ID is read as a
+/// Twitter ID and the other presets' readings are listed as alternatives.
+pub fn decode_snowflake(id: &str, options_json: &str) -> Result<SnowflakeDecoding, String> {
+    let id = parse_snowflake(id)?;
+    let (layout, named) = snowflake_layout(options_json)?;
+    let alternatives = if named {
+        Vec::new()
+    } else {
+        SNOWFLAKE_PRESETS
+            .iter()
+            .filter(|name| **name != layout.name)
+            .filter_map(|name| SnowflakeLayout::preset(name)?.decode(id).ok())
+            .collect()
+    };
+    Ok(SnowflakeDecoding {
+        reading: layout.decode(id)?,
+        alternatives,
+    })
+}
+
+/// The smallest Snowflake generated at or after `instant`, for ID range queries.
+///
+/// See `snowflake_layout` for `options_json`.
+pub fn snowflake_for_instant(instant: &str, options_json: &str) -> Result<SnowflakeBound, String> {
+    let instant = parse_start_date(instant)?;
+    let (layout, _) = snowflake_layout(options_json)?;
+    Ok(SnowflakeBound {
+        id: layout.min_id(instant)?,
+        layout: layout.name,
+        instant,
+    })
+}
+
+/// Decode the creation time of a UUID (v1, v6, v7), ULID, KSUID, ObjectId or
+/// Snowflake, detected from the shape of `input`.
+///
+/// `options_json` selects the Snowflake layout for decimal input, as for
+/// `decode_snowflake`.
+pub fn decode_id(input: &str, options_json: &str) -> Result<DecodedId, String> {
+    let (layout, _) = snowflake_layout(options_json)?;
+    decode_id_with(input, &layout)
+}
+
+/// The smallest and largest IDs created in the same tick as `instant`.
+///
+/// `options_json` is an optional JSON object: `kind` (e.g. `"uuid-v7"`; every kind
+/// by default, leaving out those whose range does not cover `instant`) and the
+/// Snowflake layout options of `decode_snowflake`.
+pub fn id_bounds(instant: &str, options_json: &str) -> Result<IdBounds, String> {
+    let instant = parse_start_date(instant)?;
+    let (layout, _) = snowflake_layout(options_json)?;
+    let bounds = match extract_string_field(options_json, "kind") {
+        Some(kind) => {
+            let kind = IdKind::from_name(&kind)?;
+            let (min, max) = bounds(kind, instant, &layout)?;
+            vec![(kind, min, max)]
+        }
+        None => IdKind::ALL
+            .into_iter()
+            .filter_map(|kind| {
+                let (min, max) = bounds(kind, instant, &layout).ok()?;
+                Some((kind, min, max))
+            })
+            .collect(),
+    };
+    Ok(IdBounds { instant, bounds })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discord_documentation_example() {
+        let layout = SnowflakeLayout::preset("discord").unwrap();
+        let id = layout.decode(175928847299117063).unwrap();
+        assert_eq!(id.timestamp.to_string(), "2016-04-30T11:18:25.796Z");
+        assert_eq!(
+            id.fields,
+            vec![
+                ("worker".to_string(), 1),
+                ("process".to_string(), 0),
+                ("increment".to_string(), 7)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_min_id_round_trip() {
+        for name in SNOWFLAKE_PRESETS {
+            let layout = SnowflakeLayout::preset(name).unwrap();
+            let ts: Timestamp = "2024-05-01T12:00:00.005Z".parse().unwrap();
+            let min = layout.min_id(ts).unwrap();
+            let decoded = layout.decode(min).unwrap();
+            assert!(decoded.timestamp >= ts, "{}", name);
+            assert!(layout.decode(min - 1).unwrap().timestamp < ts, "{}", name);
+            assert!(decoded.fields.iter().all(|(_, v)| *v == 0));
+        }
+    }
+
+    #[test]
+    fn test_layout_limits() {
+        let layout = SnowflakeLayout::preset("twitter").unwrap();
+        assert!(layout
+            .decode(u64::MAX)
+            .unwrap_err()
+            .contains("does not fit"));
+        assert!(layout
+            .min_id("2000-01-01T00:00:00Z".parse().unwrap())
+            .unwrap_err()
+            .contains("before"));
+        assert!(SnowflakeLayout::custom(0, 50, 10, 12, 1).is_err());
+        assert!(SnowflakeLayout::custom(0, 41, 10, 12, 0).is_err());
+        assert!(SnowflakeLayout::preset("flickr").is_none());
+    }
+
+    #[test]
+    fn test_detects_kinds_by_shape() {
+        let twitter = SnowflakeLayout::preset("twitter").unwrap();
+        let kind = |id: &str| decode_id_with(id, &twitter).map(|d| d.kind);
+        assert_eq!(
+            kind("c232ab00-9414-11ec-b3c8-9f6bdeced846"),
+            Ok(IdKind::UuidV1)
+        );
+        assert_eq!(
+            kind("{1EC9414C-232A-6B00-B3C8-9F6BDECED846}"),
+            Ok(IdKind::UuidV6)
+        );
+        assert_eq!(
+            kind("urn:uuid:017f22e2-79b0-7cc3-98c4-dc0c0c07398f"),
+            Ok(IdKind::UuidV7)
+        );
+        assert_eq!(kind("01ARZ3NDEKTSV4RRFFQ69G5FAV"), Ok(IdKind::Ulid));
+        assert_eq!(kind("0ujtsYcgvSTl8PAuAdqWYSMnLOv"), Ok(IdKind::Ksuid));
+        assert_eq!(kind("507f1f77bcf86cd799439011"), Ok(IdKind::ObjectId));
+        assert_eq!(kind("1212092628029698048"), Ok(IdKind::Snowflake));
+        assert!(kind("f47ac10b-58cc-4372-a567-0e02b2c3d479")
+            .unwrap_err()
+            .contains("version 4"));
+        assert!(kind("hello").unwrap_err().contains("Unrecognized"));
+    }
+
+    #[test]
+    fn test_rfc9562_examples_agree() {
+        // RFC 9562 appendix A: the v1 and v6 examples encode the same time
+        let twitter = SnowflakeLayout::preset("twitter").unwrap();
+        let v1 = decode_id_with("C232AB00-9414-11EC-B3C8-9F6BDECED846", &twitter).unwrap();
+        let v6 = decode_id_with("1EC9414C-232A-6B00-B3C8-9F6BDECED846", &twitter).unwrap();
+        assert_eq!(v1.timestamp.to_string(), "2022-02-22T19:22:22Z");
+        assert_eq!(v1.timestamp, v6.timestamp);
+        assert_eq!(v1.components[1..], v6.components[1..]);
+        let v7 = decode_id_with("017F22E2-79B0-7CC3-98C4-DC0C0C07398F", &twitter).unwrap();
+        assert_eq!(v7.timestamp.to_string(), "2022-02-22T19:22:22Z");
+    }
+
+    #[test]
+    fn test_bounds_decode_back() {
+        let twitter = SnowflakeLayout::preset("twitter").unwrap();
+        let ts: Timestamp = "2024-05-01T12:34:56.789Z".parse().unwrap();
+        for kind in IdKind::ALL {
+            let (min, max) = bounds(kind, ts, &twitter).unwrap();
+            let min = decode_id_with(&min, &twitter).unwrap();
+            let max = decode_id_with(&max, &twitter).unwrap();
+            assert_eq!((min.kind, max.kind), (kind, kind), "{}", kind.name());
+            assert!(min.timestamp <= ts, "{}", kind.name());
+            assert_eq!(min.timestamp, max.timestamp, "{}", kind.name());
+        }
+        assert!(bounds(
+            IdKind::Ksuid,
+            "2010-01-01T00:00:00Z".parse().unwrap(),
+            &twitter
+        )
+        .unwrap_err()
+        .contains("before"));
+    }
+}
@@ -0,0 +1,288 @@
+use jiff::fmt::strtime;
+
+use crate::json_utils::json_escape;
+
+/// strptime patterns tried during inference, most specific first.
+///
+/// Day-first and month-first variants are listed as pairs so that ambiguous
+/// samples such as `03/04/2024` surface both readings.
+const CANDIDATE_PATTERNS: &[&str] = &[
+    // ISO 8601 / RFC 3339 family
+    "%Y-%m-%dT%H:%M:%S%.fZ",
+    "%Y-%m-%dT%H:%M:%S%.f%:z",
+    "%Y-%m-%dT%H:%M:%S%.f%z",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M:%S%.f%:z",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%d",
+    "%Y%m%dT%H%M%SZ",
+    "%Y%m%d",
+    "%Y/%m/%d %H:%M:%S",
+    "%Y/%m/%d",
+    // Numeric day/month orders
+    "%d/%m/%Y %H:%M:%S",
+    "%m/%d/%Y %H:%M:%S",
+    "%d/%m/%Y %H:%M",
+    "%m/%d/%Y %H:%M",
+    "%m/%d/%Y %I:%M:%S %p",
+    "%m/%d/%Y %I:%M %p",
+    "%d/%m/%Y",
+    "%m/%d/%Y",
+    "%d/%m/%y",
+    "%m/%d/%y",
+    "%d-%m-%Y",
+    "%m-%d-%Y",
+    "%d.%m.%Y %H:%M:%S",
+    "%d.%m.%Y %H:%M",
+    "%d.%m.%Y",
+    // Month and weekday names
+    "%a, %d %b %Y %H:%M:%S %z",
+    "%a, %d %b %Y %H:%M:%S GMT",
+    "%a %b %e %H:%M:%S %Y",
+    "%d/%b/%Y:%H:%M:%S %z",
+    "%B %d, %Y %I:%M:%S %p",
+    "%B %d, %Y",
+    "%b %d, %Y",
+    "%d %B %Y",
+    "%d %b %Y",
+];
+
+/// A strptime pattern that matched some or all of the samples.
+#[derive(Debug, PartialEq)]
+pub struct FormatCandidate {
+    /// strptime/strftime pattern, e.g. `%d/%m/%Y`
+    pub pattern: String,
+    /// Share of samples matched, halved when another reading fits equally well
+    pub confidence: f64,
+    /// Number of samples the pattern parsed
+    pub matched: usize,
+    /// The first matched sample rendered as ISO 8601
+    pub normalized: String,
+    /// The day/month-swapped pattern that fits the samples equally well, if any
+    pub ambiguous_with: Option<String>,
+}
+
+/// Result of inferring the format of one or more sample strings.
+#[derive(Debug, PartialEq)]
+pub struct InferenceResult {
+    pub sample_count: usize,
+    /// Whether the best candidates disagree on day/month order
+    pub ambiguous: bool,
+    /// Matching patterns, best first
+    pub candidates: Vec<FormatCandidate>,
+}
+
+impl InferenceResult {
+    /// The highest-confidence pattern, if any sample could be parsed.
+    pub fn best(&self) -> Option<&FormatCandidate> {
+        self.candidates.first()
+    }
+
+    /// Serialize to JSON string for Wasm boundary (manual serialization).
+    pub fn to_json(&self) -> String {
+        let candidates: Vec<String> = self
+            .candidates
+            .iter()
+            .map(|c| {
+                let ambiguous_with = match &c.ambiguous_with {
+                    Some(p) => format!(r#""{}""#, json_escape(p)),
+                    None => "null".to_string(),
+                };
+                format!(
+                    r#"{{"pattern":"{}","confidence":{},"matched":{},"normalized":"{}","ambiguousWith":{}}}"#,
+                    json_escape(&c.pattern),
+                    c.confidence,
+                    c.matched,
+                    json_escape(&c.normalized),
+                    ambiguous_with,
+                )
+            })
+            .collect();
+        format!(
+            r#"{{"samples":{},"ambiguous":{},"candidates":[{}]}}"#,
+            self.sample_count,
+            self.ambiguous,
+            candidates.join(","),
+        )
+    }
+}
+
+/// Guess the strptime pattern of one or more sample timestamps.
+///
+/// Every sample is tried against a fixed catalogue of common layouts. A pattern's
+/// confidence is the share of samples it parses; when the day-first and
+/// month-first readings both fit every sample (e.g. `03/04/2024`), both are
+/// returned with halved confidence and the result is flagged as ambiguous.
+/// A sample such as `13/04/2024` rules out the month-first reading.
+pub fn infer_format(samples: &[&str]) -> Result<InferenceResult, String> {
+    let samples: Vec<&str> = samples
+        .iter()
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .collect();
+    if samples.is_empty() {
+        return Err("No samples to infer a format from".to_string());
+    }
+
+    let mut candidates: Vec<FormatCandidate> = Vec::new();
+    for pattern in CANDIDATE_PATTERNS {
+        let mut matched = 0;
+        let mut normalized = None;
+        for sample in &samples {
+            if let Some(iso) = parse_with(pattern, sample) {
+                matched += 1;
+                normalized.get_or_insert(iso);
+            }
+        }
+        if let Some(normalized) = normalized {
+            candidates.push(FormatCandidate {
+                pattern: pattern.to_string(),
+                confidence: matched as f64 / samples.len() as f64,
+                matched,
+                normalized,
+                ambiguous_with: None,
+            });
+        }
+    }
+
+    // Pair up day-first/month-first readings that fit equally well.
+    let matched_by_pattern: Vec<(String, usize)> = candidates
+        .iter()
+        .map(|c| (c.pattern.clone(), c.matched))
+        .collect();
+    for candidate in &mut candidates {
+        let Some(swapped) = swap_day_month(&candidate.pattern) else {
+            continue;
+        };
+        let twin = matched_by_pattern
+            .iter()
+            .find(|(p, m)| *p == swapped && *m == candidate.matched);
+        if twin.is_some() {
+            candidate.confidence /= 2.0;
+            candidate.ambiguous_with = Some(swapped);
+        }
+    }
+
+    for candidate in &mut candidates {
+        candidate.confidence = (candidate.confidence * 100.0).round() / 100.0;
+    }
+    // Stable sort keeps catalogue order (day-first before month-first) among ties.
+    candidates.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+
+    let ambiguous = candidates
+        .first()
+        .is_some_and(|best| best.ambiguous_with.is_some());
+
+    Ok(InferenceResult {
+        sample_count: samples.len(),
+        ambiguous,
+        candidates,
+    })
+}
+
+/// Parse `sample` with `pattern`, returning its ISO 8601 rendering on success.
+///
+/// Samples carrying an offset normalize to a UTC timestamp, others to a civil
+/// datetime or date, matching the forms `validate::validate` accepts.
+fn parse_with(pattern: &str, sample: &str) -> Option<String> {
+    let tm = strtime::parse(pattern, sample).ok()?;
+
+    if tm.offset().is_some() {
+        Some(tm.to_timestamp().ok()?.to_string())
+    } else if tm.hour().is_some() {
+        Some(tm.to_datetime().ok()?.to_string())
+    } else {
+        Some(tm.to_date().ok()?.to_string())
+    }
+}
+
+/// Swap `%d` and `%m` in a pattern, or `None` if it doesn't contain both.
+fn swap_day_month(pattern: &str) -> Option<String> {
+    if !pattern.contains("%d") || !pattern.contains("%m") {
+        return None;
+    }
+    Some(
+        pattern
+            .replace("%d", "\u{0}")
+            .replace("%m", "%d")
+            .replace('\u{0}', "%m"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_iso_date() {
+        let result = infer_format(&["2024-07-11"]).unwrap();
+        let best = result.best().unwrap();
+        assert_eq!(best.pattern, "%Y-%m-%d");
+        assert_eq!(best.confidence, 1.0);
+        assert!(!result.ambiguous);
+    }
+
+    #[test]
+    fn test_rfc3339_with_offset() {
+        let result = infer_format(&["2024-07-11T01:14:00+02:00"]).unwrap();
+        let best = result.best().unwrap();
+        assert_eq!(best.pattern, "%Y-%m-%dT%H:%M:%S%.f%:z");
+        assert_eq!(best.normalized, "2024-07-10T23:14:00Z");
+    }
+
+    #[test]
+    fn test_ambiguous_day_month() {
+        let result = infer_format(&["03/04/2024"]).unwrap();
+        assert!(result.ambiguous);
+        let best = result.best().unwrap();
+        assert_eq!(best.pattern, "%d/%m/%Y");
+        assert_eq!(best.confidence, 0.5);
+        assert_eq!(best.ambiguous_with.as_deref(), Some("%m/%d/%Y"));
+    }
+
+    #[test]
+    fn test_second_sample_resolves_ambiguity() {
+        let result = infer_format(&["03/04/2024", "13/04/2024"]).unwrap();
+        assert!(!result.ambiguous);
+        let best = result.best().unwrap();
+        assert_eq!(best.pattern, "%d/%m/%Y");
+        assert_eq!(best.confidence, 1.0);
+        assert_eq!(best.normalized, "2024-04-03");
+    }
+
+    #[test]
+    fn test_month_first_when_day_exceeds_twelve() {
+        let result = infer_format(&["07/25/2024 14:30"]).unwrap();
+        assert_eq!(result.best().unwrap().pattern, "%m/%d/%Y %H:%M");
+        assert!(!result.ambiguous);
+    }
+
+    #[test]
+    fn test_partial_match_confidence() {
+        let result = infer_format(&["2024-07-11", "garbage"]).unwrap();
+        assert_eq!(result.best().unwrap().confidence, 0.5);
+    }
+
+    #[test]
+    fn test_no_samples() {
+        assert!(infer_format(&[]).is_err());
+        assert!(infer_format(&["  "]).is_err());
+    }
+
+    #[test]
+    fn test_unrecognized_sample() {
+        let result = infer_format(&["not-a-date"]).unwrap();
+        assert!(result.candidates.is_empty());
+        assert!(result.to_json().contains("\"candidates\":[]"));
+    }
+
+    #[test]
+    fn test_json_output() {
+        let json = infer_format(&["03/04/2024"]).unwrap().to_json();
+        assert!(json.contains("\"ambiguous\":true"));
+        assert!(json.contains("\"pattern\":\"%d/%m/%Y\""));
+        assert!(json.contains("\"ambiguousWith\":\"%m/%d/%Y\""));
+    }
+}
@@ -0,0 +1,486 @@
+//! Interval algebra on half-open ranges of instants: overlap tests, intersection,
+//! union, subtraction and gaps.
+//!
+//! All ranges are `[start, end)`, so `[09:00, 10:00)` and `[10:00, 11:00)` touch but do
+//! not overlap, and merge into `[09:00, 11:00)`.
+
+use jiff::Timestamp;
+
+use crate::iso_interval::IntervalExpression;
+use crate::json_utils::{json_escape, parse_string_array};
+
+/// A half-open range of instants `[start, end)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval {
+    pub start: Timestamp,
+    pub end: Timestamp,
+}
+
+impl Interval {
+    pub fn new(start: Timestamp, end: Timestamp) -> Result<Self, String> {
+        if end < start {
+            return Err(format!(
+                "Interval end {} is before its start {}",
+                end, start
+            ));
+        }
+        Ok(Interval { start, end })
+    }
+
+    /// Parse one ISO 8601 interval (`start/end`, `start/duration` or `duration/end`).
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let (start, end) = IntervalExpression::parse(text)?.bounds()?;
+        Interval::new(start, end)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    pub fn seconds(&self) -> i64 {
+        self.end.as_second() - self.start.as_second()
+    }
+
+    pub fn contains(&self, ts: Timestamp) -> bool {
+        self.start <= ts && ts < self.end
+    }
+
+    /// Whether the two ranges share at least one instant; an empty range shares none.
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        !self.is_empty() && !other.is_empty() && self.start < other.end && other.start < self.end
+    }
+
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        self.overlaps(other).then(|| Interval {
+            start: self.start.max(other.start),
+            end: self.end.min(other.end),
+        })
+    }
+
+    /// Allen's interval relation of `self` to `other`.
+    pub fn relation(&self, other: &Interval) -> Relation {
+        use std::cmp::Ordering::*;
+        match (
+            self.start.cmp(&other.start),
+            self.end.cmp(&other.end),
+            self.end.cmp(&other.start),
+            self.start.cmp(&other.end),
+        ) {
+            (Equal, Equal, _, _) => Relation::Equals,
+            (_, _, Less, _) => Relation::Before,
+            (_, _, _, Greater) => Relation::After,
+            (_, _, Equal, _) => Relation::Meets,
+            (_, _, _, Equal) => Relation::MetBy,
+            (Equal, Less, _, _) => Relation::Starts,
+            (Equal, Greater, _, _) => Relation::StartedBy,
+            (Greater, Equal, _, _) => Relation::Finishes,
+            (Less, Equal, _, _) => Relation::FinishedBy,
+            (Greater, Less, _, _) => Relation::During,
+            (Less, Greater, _, _) => Relation::Contains,
+            (Less, Less, _, _) => Relation::Overlaps,
+            (Greater, Greater, _, _) => Relation::OverlappedBy,
+        }
+    }
+}
+
+/// Allen's thirteen relations between two intervals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    Before,
+    After,
+    Meets,
+    MetBy,
+    Overlaps,
+    OverlappedBy,
+    Starts,
+    StartedBy,
+    During,
+    Contains,
+    Finishes,
+    FinishedBy,
+    Equals,
+}
+
+impl Relation {
+    pub fn name(self) -> &'static str {
+        match self {
+            Relation::Before => "before",
+            Relation::After => "after",
+            Relation::Meets => "meets",
+            Relation::MetBy => "metBy",
+            Relation::Overlaps => "overlaps",
+            Relation::OverlappedBy => "overlappedBy",
+            Relation::Starts => "starts",
+            Relation::StartedBy => "startedBy",
+            Relation::During => "during",
+            Relation::Contains => "contains",
+            Relation::Finishes => "finishes",
+            Relation::FinishedBy => "finishedBy",
+            Relation::Equals => "equals",
+        }
+    }
+}
+
+/// Union of `intervals` as sorted, disjoint ranges; touching ranges are joined and empty
+/// ones dropped.
+pub fn merge(intervals: &[Interval]) -> Vec<Interval> {
+    let mut sorted: Vec<Interval> = intervals
+        .iter()
+        .copied()
+        .filter(|i| !i.is_empty())
+        .collect();
+    sorted.sort();
+    let mut merged: Vec<Interval> = Vec::with_capacity(sorted.len());
+    for interval in sorted {
+        match merged.last_mut() {
+            Some(last) if interval.start <= last.end => last.end = last.end.max(interval.end),
+            _ => merged.push(interval),
+        }
+    }
+    merged
+}
+
+/// Instants covered by both `a` and `b`.
+pub fn intersect(a: &[Interval], b: &[Interval]) -> Vec<Interval> {
+    let (a, b) = (merge(a), merge(b));
+    let mut result = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if let Some(common) = a[i].intersection(&b[j]) {
+            result.push(common);
+        }
+        if a[i].end < b[j].end {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    result
+}
+
+/// Instants covered by `a` but not by `b`.
+pub fn subtract(a: &[Interval], b: &[Interval]) -> Vec<Interval> {
+    let removed = merge(b);
+    let mut result = Vec::new();
+    for interval in merge(a) {
+        let mut cursor = interval.start;
+        for cut in removed.iter().filter(|cut| cut.overlaps(&interval)) {
+            if cut.start > cursor {
+                result.push(Interval {
+                    start: cursor,
+                    end: cut.start,
+                });
+            }
+            cursor = cursor.max(cut.end);
+        }
+        if cursor < interval.end {
+            result.push(Interval {
+                start: cursor,
+                end: interval.end,
+            });
+        }
+    }
+    result
+}
+
+/// Uncovered stretches between the merged `intervals`, or, with a `window`, every part
+/// of the window they leave free (including before the first and after the last).
+pub fn gaps(intervals: &[Interval], window: Option<Interval>) -> Vec<Interval> {
+    match window {
+        Some(window) => subtract(&[window], intervals),
+        None => merge(intervals)
+            .windows(2)
+            .map(|pair| Interval {
+                start: pair[0].end,
+                end: pair[1].start,
+            })
+            .collect(),
+    }
+}
+
+/// A pair of intervals that share time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overlap {
+    pub left: usize,
+    pub right: usize,
+    /// Relation of the left interval to the right one
+    pub relation: Relation,
+    pub shared: Interval,
+}
+
+/// Every overlapping pair within `intervals` (`left < right`).
+pub fn overlaps_within(intervals: &[Interval]) -> Vec<Overlap> {
+    let mut order: Vec<usize> = (0..intervals.len()).collect();
+    order.sort_by_key(|&i| intervals[i]);
+    let mut found = Vec::new();
+    for (position, &i) in order.iter().enumerate() {
+        for &j in &order[position + 1..] {
+            if intervals[j].start >= intervals[i].end {
+                break;
+            }
+            let (left, right) = (i.min(j), i.max(j));
+            if let Some(shared) = intervals[left].intersection(&intervals[right]) {
+                found.push(Overlap {
+                    left,
+                    right,
+                    relation: intervals[left].relation(&intervals[right]),
+                    shared,
+                });
+            }
+        }
+    }
+    found.sort_by_key(|o| (o.left, o.right));
+    found
+}
+
+/// Every overlapping pair with one interval from `a` (`left`) and one from `b` (`right`).
+pub fn overlaps_between(a: &[Interval], b: &[Interval]) -> Vec<Overlap> {
+    let mut found = Vec::new();
+    for (left, x) in a.iter().enumerate() {
+        for (right, y) in b.iter().enumerate() {
+            if let Some(shared) = x.intersection(y) {
+                found.push(Overlap {
+                    left,
+                    right,
+                    relation: x.relation(y),
+                    shared,
+                });
+            }
+        }
+    }
+    found
+}
+
+/// The operations exposed over the Wasm boundary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntervalOp {
+    Union,
+    Intersection,
+    Subtract,
+    Gaps,
+    Overlaps,
+}
+
+impl IntervalOp {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "union" | "merge" => Ok(IntervalOp::Union),
+            "intersection" | "intersect" => Ok(IntervalOp::Intersection),
+            "subtract" | "difference" => Ok(IntervalOp::Subtract),
+            "gaps" => Ok(IntervalOp::Gaps),
+            "overlaps" => Ok(IntervalOp::Overlaps),
+            other => Err(format!(
+                "Unknown interval operation '{}' (expected union, intersection, subtract, gaps or overlaps)",
+                other
+            )),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            IntervalOp::Union => "union",
+            IntervalOp::Intersection => "intersection",
+            IntervalOp::Subtract => "subtract",
+            IntervalOp::Gaps => "gaps",
+            IntervalOp::Overlaps => "overlaps",
+        }
+    }
+}
+
+/// Result of an interval operation, serialized for the Wasm boundary.
+#[derive(Debug, PartialEq)]
+pub struct IntervalReport {
+    pub operation: IntervalOp,
+    /// The resulting ranges; for `Overlaps`, the merged time that is double-booked
+    pub intervals: Vec<Interval>,
+    /// Overlapping pairs, for `Overlaps` only
+    pub overlaps: Vec<Overlap>,
+}
+
+impl IntervalReport {
+    /// Serialize to JSON string for Wasm boundary (manual serialization).
+    pub fn to_json(&self) -> String {
+        let range = |i: &Interval| format!(r#""start":"{}","end":"{}""#, i.start, i.end);
+        let intervals: Vec<String> = self
+            .intervals
+            .iter()
+            .map(|i| format!(r#"{{{},"seconds":{}}}"#, range(i), i.seconds()))
+            .collect();
+        let mut json = format!(
+            r#"{{"operation":"{}","intervals":[{}],"totalSeconds":{}"#,
+            json_escape(self.operation.name()),
+            intervals.join(","),
+            self.intervals.iter().map(Interval::seconds).sum::<i64>()
+        );
+        if self.operation == IntervalOp::Overlaps {
+            let overlaps: Vec<String> = self
+                .overlaps
+                .iter()
+                .map(|o| {
+                    format!(
+                        r#"{{"left":{},"right":{},"relation":"{}",{}}}"#,
+                        o.left,
+                        o.right,
+                        o.relation.name(),
+                        range(&o.shared)
+                    )
+                })
+                .collect();
+            json.push_str(&format!(r#","overlaps":[{}]"#, overlaps.join(",")));
+        }
+        json.push('}');
+        json
+    }
+}
+
+/// Run `operation` on two interval lists; `right` is optional for `Union` and `Overlaps`
+/// (overlaps are then looked for within `left`) and, for `Gaps`, is an optional single
+/// window.
+pub fn apply(
+    operation: IntervalOp,
+    left: &[Interval],
+    right: &[Interval],
+) -> Result<IntervalReport, String> {
+    let mut overlaps = Vec::new();
+    let intervals = match operation {
+        IntervalOp::Union => merge(&[left, right].concat()),
+        IntervalOp::Intersection => intersect(left, right),
+        IntervalOp::Subtract => subtract(left, right),
+        IntervalOp::Gaps => match right {
+            [] => gaps(left, None),
+            [window] => gaps(left, Some(*window)),
+            _ => return Err("gaps takes at most one window interval".to_string()),
+        },
+        IntervalOp::Overlaps => {
+            overlaps = if right.is_empty() {
+                overlaps_within(left)
+            } else {
+                overlaps_between(left, right)
+            };
+            merge(&overlaps.iter().map(|o| o.shared).collect::<Vec<_>>())
+        }
+    };
+    Ok(IntervalReport {
+        operation,
+        intervals,
+        overlaps,
+    })
+}
+
+/// Parse a JSON array of ISO 8601 interval strings, e.g. `["2024-01-01T09:00Z/PT1H"]`.
+fn parse_interval_list(json: &str) -> Result<Vec<Interval>, String> {
+    parse_string_array(json)?
+        .iter()
+        .enumerate()
+        .map(|(i, text)| Interval::parse(text).map_err(|e| format!("Interval {}: {}", i, e)))
+        .collect()
+}
+
+/// Apply an interval `operation` (`union`, `intersection`, `subtract`, `gaps` or
+/// `overlaps`) to two JSON arrays of ISO 8601 intervals; see `apply`.
+pub fn interval_algebra(
+    operation: &str,
+    left_json: &str,
+    right_json: &str,
+) -> Result<IntervalReport, String> {
+    let operation = IntervalOp::from_name(operation)?;
+    let left = parse_interval_list(left_json)?;
+    let right = parse_interval_list(right_json)?;
+    apply(operation, &left, &right)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `[h1, h2)` on 2024-01-01 UTC
+    fn hours(h1: i64, h2: i64) -> Interval {
+        let midnight: Timestamp = "2024-01-01T00:00:00Z".parse().unwrap();
+        Interval::new(
+            Timestamp::from_second(midnight.as_second() + h1 * 3600).unwrap(),
+            Timestamp::from_second(midnight.as_second() + h2 * 3600).unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_half_open_overlap() {
+        assert!(hours(9, 10).overlaps(&hours(9, 12)));
+        assert!(!hours(9, 10).overlaps(&hours(10, 11)));
+        assert!(!hours(9, 9).overlaps(&hours(8, 10)));
+        assert!(hours(9, 10).contains(hours(9, 9).start));
+        assert!(!hours(9, 10).contains(hours(10, 10).start));
+    }
+
+    #[test]
+    fn test_allen_relations() {
+        let base = hours(10, 14);
+        let cases = [
+            (hours(6, 8), Relation::Before),
+            (hours(15, 16), Relation::After),
+            (hours(8, 10), Relation::Meets),
+            (hours(14, 16), Relation::MetBy),
+            (hours(8, 12), Relation::Overlaps),
+            (hours(12, 16), Relation::OverlappedBy),
+            (hours(10, 12), Relation::Starts),
+            (hours(10, 16), Relation::StartedBy),
+            (hours(11, 13), Relation::During),
+            (hours(8, 16), Relation::Contains),
+            (hours(12, 14), Relation::Finishes),
+            (hours(8, 14), Relation::FinishedBy),
+            (hours(10, 14), Relation::Equals),
+        ];
+        for (interval, expected) in cases {
+            assert_eq!(interval.relation(&base), expected, "{:?}", interval);
+        }
+    }
+
+    #[test]
+    fn test_merge_joins_touching() {
+        assert_eq!(
+            merge(&[
+                hours(12, 13),
+                hours(9, 10),
+                hours(10, 11),
+                hours(5, 5),
+                hours(9, 10)
+            ]),
+            vec![hours(9, 11), hours(12, 13)]
+        );
+    }
+
+    #[test]
+    fn test_intersect_and_subtract() {
+        let a = [hours(0, 6), hours(8, 12)];
+        let b = [hours(4, 9), hours(11, 20)];
+        assert_eq!(
+            intersect(&a, &b),
+            vec![hours(4, 6), hours(8, 9), hours(11, 12)]
+        );
+        assert_eq!(subtract(&a, &b), vec![hours(0, 4), hours(9, 11)]);
+        assert_eq!(subtract(&b, &a), vec![hours(6, 8), hours(12, 20)]);
+    }
+
+    #[test]
+    fn test_gaps() {
+        let busy = [hours(9, 10), hours(12, 13), hours(9, 11)];
+        assert_eq!(gaps(&busy, None), vec![hours(11, 12)]);
+        assert_eq!(
+            gaps(&busy, Some(hours(8, 18))),
+            vec![hours(8, 9), hours(11, 12), hours(13, 18)]
+        );
+    }
+
+    #[test]
+    fn test_overlaps_within() {
+        let rota = [hours(0, 8), hours(16, 24), hours(7, 16), hours(20, 22)];
+        let pairs: Vec<(usize, usize, Relation)> = overlaps_within(&rota)
+            .iter()
+            .map(|o| (o.left, o.right, o.relation))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![(0, 2, Relation::Overlaps), (1, 3, Relation::Contains)]
+        );
+    }
+}
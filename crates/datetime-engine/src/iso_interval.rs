@@ -0,0 +1,546 @@
+//! ISO 8601 time intervals: `start/end`, `start/duration`, `duration/end` and the
+//! repeating `Rn/...` forms built on them.
+//!
+//! Durations are applied unit by unit from years down, exactly like an operation list
+//! passed to `calc::calculate`, so month ends clamp the same way: `2024-01-31` plus
+//! `P1M` is `2024-02-29`. Repetitions are counted from the anchor (`start + k × P`) rather
+//! than chained, so a series that clamps once does not drift.
+
+use std::fmt;
+
+use jiff::{Span, Timestamp};
+
+use crate::calc::{apply_operation, parse_start_date, Operation};
+use crate::json_utils::json_escape;
+
+/// An ISO 8601 duration such as `P1Y2M10DT2H30M` or `P2W`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IsoDuration {
+    pub years: i64,
+    pub months: i64,
+    pub weeks: i64,
+    pub days: i64,
+    pub hours: i64,
+    pub minutes: i64,
+    pub seconds: i64,
+}
+
+impl IsoDuration {
+    /// Parse the designator form `PnYnMnWnDTnHnMnS`; fractional components are rejected.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let trimmed = text.trim();
+        let invalid = |why: &str| format!("Invalid ISO 8601 duration '{}': {}", trimmed, why);
+        let body = trimmed
+            .strip_prefix(['P', 'p'])
+            .ok_or_else(|| invalid("must start with P"))?;
+        let mut duration = IsoDuration::default();
+        let mut in_time = false;
+        let mut number = String::new();
+        let mut components = 0;
+        for ch in body.chars() {
+            match ch.to_ascii_uppercase() {
+                'T' if !in_time && number.is_empty() => in_time = true,
+                c if c.is_ascii_digit() => number.push(c),
+                '.' | ',' => return Err(invalid("fractional components are not supported")),
+                designator => {
+                    if number.is_empty() {
+                        return Err(invalid(&format!("'{}' has no number", designator)));
+                    }
+                    let value: i64 = number
+                        .parse()
+                        .map_err(|_| invalid(&format!("'{}' is too large", number)))?;
+                    number.clear();
+                    let slot = match (in_time, designator) {
+                        (false, 'Y') => &mut duration.years,
+                        (false, 'M') => &mut duration.months,
+                        (false, 'W') => &mut duration.weeks,
+                        (false, 'D') => &mut duration.days,
+                        (true, 'H') => &mut duration.hours,
+                        (true, 'M') => &mut duration.minutes,
+                        (true, 'S') => &mut duration.seconds,
+                        _ => {
+                            return Err(invalid(&format!("unexpected '{}'", designator)));
+                        }
+                    };
+                    *slot = value;
+                    components += 1;
+                }
+            }
+        }
+        if !number.is_empty() {
+            return Err(invalid("a number is missing its designator"));
+        }
+        if components == 0 {
+            return Err(invalid("no components"));
+        }
+        Ok(duration)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        *self == IsoDuration::default()
+    }
+
+    /// Add `times` copies of the duration to `ts` (subtract when `times` is negative).
+    pub fn add_to(&self, ts: Timestamp, times: i64) -> Result<Timestamp, String> {
+        let out_of_range = || "Duration multiple out of range".to_string();
+        let days = self
+            .weeks
+            .checked_mul(7)
+            .and_then(|weeks| weeks.checked_add(self.days))
+            .ok_or_else(out_of_range)?;
+        let units = [
+            ("years", self.years),
+            ("months", self.months),
+            ("days", days),
+            ("hours", self.hours),
+            ("minutes", self.minutes),
+            ("seconds", self.seconds),
+        ];
+        let mut current = ts;
+        for (unit, amount) in units {
+            let value = amount.checked_mul(times).ok_or_else(out_of_range)?;
+            if value == 0 {
+                continue;
+            }
+            // `apply_operation` builds its span unchecked, so reject amounts jiff
+            // cannot represent before handing them over.
+            let span = match unit {
+                "years" => Span::new().try_years(value),
+                "months" => Span::new().try_months(value),
+                "days" => Span::new().try_days(value),
+                "hours" => Span::new().try_hours(value),
+                "minutes" => Span::new().try_minutes(value),
+                _ => Span::new().try_seconds(value),
+            };
+            if span.is_err() {
+                return Err(out_of_range());
+            }
+            let op = Operation {
+                op_type: if value > 0 { "add" } else { "subtract" }.to_string(),
+                unit: unit.to_string(),
+                value: value.abs(),
+            };
+            current = apply_operation(current, &op)?;
+        }
+        Ok(current)
+    }
+}
+
+impl fmt::Display for IsoDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "P")?;
+        for (value, designator) in [
+            (self.years, 'Y'),
+            (self.months, 'M'),
+            (self.weeks, 'W'),
+            (self.days, 'D'),
+        ] {
+            if value != 0 {
+                write!(f, "{}{}", value, designator)?;
+            }
+        }
+        if self.hours != 0 || self.minutes != 0 || self.seconds != 0 {
+            write!(f, "T")?;
+            for (value, designator) in [(self.hours, 'H'), (self.minutes, 'M'), (self.seconds, 'S')]
+            {
+                if value != 0 {
+                    write!(f, "{}{}", value, designator)?;
+                }
+            }
+        } else if self.is_zero() {
+            write!(f, "T0S")?;
+        }
+        Ok(())
+    }
+}
+
+/// How often an interval repeats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Repeat {
+    /// A plain interval, no `R` prefix
+    Once,
+    /// `Rn/...`
+    Times(u64),
+    /// `R/...` (or `R-1/...`)
+    Unbounded,
+}
+
+/// The two bounds an interval is written with.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Form {
+    StartEnd(Timestamp, Timestamp),
+    StartDuration(Timestamp, IsoDuration),
+    DurationEnd(IsoDuration, Timestamp),
+}
+
+/// A parsed ISO 8601 interval or repeating interval.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntervalExpression {
+    pub expression: String,
+    pub repeat: Repeat,
+    form: Form,
+}
+
+impl IntervalExpression {
+    /// Parse `start/end`, `start/duration`, `duration/end`, optionally prefixed with
+    /// `Rn/` or `R/`. `--` is accepted in place of `/`.
+    ///
+    /// An end may be abbreviated by leaving out the leading parts it shares with the
+    /// start, as in `2024-02-10/15` or `2024-02-10T09:00Z/17:30`. Bounds may carry an
+    /// IANA zone annotation such as `2024-03-10T01:00[America/New_York]`.
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let trimmed = expression.trim();
+        let mut parts = split_outside_brackets(trimmed, "/");
+        if parts.len() == 1 {
+            parts = split_outside_brackets(trimmed, "--");
+        }
+        let repeat = if parts.len() == 3 {
+            parse_repeat(parts.remove(0))?
+        } else {
+            Repeat::Once
+        };
+        let [first, second] = parts[..] else {
+            return Err(format!(
+                "Cannot read '{}' as an ISO 8601 interval (expected start/end, start/duration, duration/end or Rn/...)",
+                trimmed
+            ));
+        };
+        let is_duration = |s: &str| s.starts_with(['P', 'p']);
+        let form = match (is_duration(first), is_duration(second)) {
+            (true, true) => {
+                return Err("An interval needs at least one date, not two durations".to_string())
+            }
+            (false, true) => {
+                Form::StartDuration(parse_start_date(first)?, IsoDuration::parse(second)?)
+            }
+            (true, false) => {
+                Form::DurationEnd(IsoDuration::parse(first)?, parse_start_date(second)?)
+            }
+            (false, false) => {
+                let start = parse_start_date(first)?;
+                let end = parse_start_date(second)
+                    .or_else(|_| parse_start_date(&complete_end(first, second)))
+                    .map_err(|_| format!("Could not parse interval end '{}'", second))?;
+                if end < start {
+                    return Err(format!(
+                        "Interval end {} is before its start {}",
+                        end, start
+                    ));
+                }
+                Form::StartEnd(start, end)
+            }
+        };
+        if repeat != Repeat::Once {
+            let empty = match form {
+                Form::StartEnd(start, end) => start == end,
+                Form::StartDuration(_, d) | Form::DurationEnd(d, _) => d.is_zero(),
+            };
+            if empty {
+                return Err("A repeating interval needs a non-zero duration".to_string());
+            }
+        }
+        Ok(IntervalExpression {
+            expression: trimmed.to_string(),
+            repeat,
+            form,
+        })
+    }
+
+    /// The written duration, if the expression has one.
+    pub fn duration(&self) -> Option<IsoDuration> {
+        match self.form {
+            Form::StartEnd(..) => None,
+            Form::StartDuration(_, d) | Form::DurationEnd(d, _) => Some(d),
+        }
+    }
+
+    /// The bounds of a plain (non-repeating) interval.
+    pub fn bounds(&self) -> Result<(Timestamp, Timestamp), String> {
+        if self.repeat != Repeat::Once {
+            return Err(format!(
+                "'{}' repeats; give a single interval here",
+                self.expression
+            ));
+        }
+        self.expand(1)?
+            .pop()
+            .ok_or_else(|| format!("'{}' has no interval", self.expression))
+    }
+
+    /// The interval bounds, expanded to at most `limit` intervals in chronological order.
+    ///
+    /// Repetitions of `duration/end` run backwards from the end, so an unbounded one
+    /// lists the `limit` intervals that finish last.
+    pub fn expand(&self, limit: usize) -> Result<Vec<(Timestamp, Timestamp)>, String> {
+        let count = match self.repeat {
+            Repeat::Once => 1,
+            Repeat::Times(n) => n.min(limit as u64) as i64,
+            Repeat::Unbounded => limit as i64,
+        };
+        let mut intervals = Vec::new();
+        for k in 0..count {
+            let interval = match self.form {
+                Form::StartEnd(start, end) => {
+                    let length = end.as_nanosecond() - start.as_nanosecond();
+                    let at = |n: i64| {
+                        Timestamp::from_nanosecond(start.as_nanosecond() + length * n as i128)
+                            .map_err(|_| "Repeated interval out of range".to_string())
+                    };
+                    (at(k)?, at(k + 1)?)
+                }
+                Form::StartDuration(start, d) => (d.add_to(start, k)?, d.add_to(start, k + 1)?),
+                Form::DurationEnd(d, end) => (d.add_to(end, -(k + 1))?, d.add_to(end, -k)?),
+            };
+            intervals.push(interval);
+        }
+        if matches!(self.form, Form::DurationEnd(..)) {
+            intervals.reverse();
+        }
+        Ok(intervals)
+    }
+}
+
+/// Expanded interval bounds, serialized for the Wasm boundary.
+#[derive(Debug, PartialEq)]
+pub struct IntervalExpansion {
+    pub expression: String,
+    pub repeat: Repeat,
+    pub duration: Option<IsoDuration>,
+    pub intervals: Vec<(Timestamp, Timestamp)>,
+}
+
+impl IntervalExpansion {
+    /// Serialize to JSON string for Wasm boundary (manual serialization).
+    ///
+    /// `repetitions` is present for repeating intervals only, and `null` when unbounded.
+    pub fn to_json(&self) -> String {
+        let mut json = format!(
+            r#"{{"expression":"{}","repeating":{}"#,
+            json_escape(&self.expression),
+            self.repeat != Repeat::Once
+        );
+        match self.repeat {
+            Repeat::Once => {}
+            Repeat::Times(n) => json.push_str(&format!(r#","repetitions":{}"#, n)),
+            Repeat::Unbounded => json.push_str(r#","repetitions":null"#),
+        }
+        if let Some(duration) = self.duration {
+            json.push_str(&format!(r#","duration":"{}""#, duration));
+        }
+        let intervals: Vec<String> = self
+            .intervals
+            .iter()
+            .map(|(start, end)| {
+                format!(
+                    r#"{{"start":"{}","end":"{}","seconds":{}}}"#,
+                    start,
+                    end,
+                    end.as_second() - start.as_second()
+                )
+            })
+            .collect();
+        json.push_str(&format!(r#","intervals":[{}]}}"#, intervals.join(",")));
+        json
+    }
+}
+
+fn parse_repeat(text: &str) -> Result<Repeat, String> {
+    let count = text.strip_prefix(['R', 'r']).ok_or_else(|| {
+        format!(
+            "Expected a repeat like R5 before the interval, got '{}'",
+            text
+        )
+    })?;
+    match count {
+        "" | "-1" => Ok(Repeat::Unbounded),
+        n => n
+            .parse()
+            .map(Repeat::Times)
+            .map_err(|_| format!("Invalid repeat count '{}'", text)),
+    }
+}
+
+/// Split on `separator` except inside `[...]`, so the `/` of a zone annotation like
+/// `[America/New_York]` doesn't end a bound.
+fn split_outside_brackets<'a>(text: &'a str, separator: &str) -> Vec<&'a str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    let mut i = 0;
+    while i < text.len() {
+        let rest = &text[i..];
+        if depth == 0 && rest.starts_with(separator) {
+            parts.push(text[start..i].trim());
+            i += separator.len();
+            start = i;
+            continue;
+        }
+        match rest.chars().next() {
+            Some('[') => depth += 1,
+            Some(']') => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        i += rest.chars().next().map_or(1, char::len_utf8);
+    }
+    parts.push(text[start..].trim());
+    parts
+}
+
+/// Fill in the parts of an abbreviated interval end from its start: a bare time takes
+/// the start's date (and offset), and a partial date its leading year or month.
+fn complete_end(start: &str, end: &str) -> String {
+    let (start_date, start_time) = start.split_once(['T', 't']).unwrap_or((start, ""));
+    if end.contains(':') || end.starts_with(['T', 't']) {
+        let time = end.trim_start_matches(['T', 't']);
+        let has_zone = time.ends_with(['Z', 'z', ']']) || time.contains(['+', '-']);
+        let zone = if has_zone {
+            ""
+        } else {
+            zone_suffix(start_time)
+        };
+        return format!("{}T{}{}", start_date, time, zone);
+    }
+    let (end_date, end_time) = end.split_once(['T', 't']).unwrap_or((end, ""));
+    let start_parts: Vec<&str> = start_date.split('-').collect();
+    let end_parts: Vec<&str> = end_date.split('-').collect();
+    let keep = start_parts.len().saturating_sub(end_parts.len());
+    let mut date = start_parts[..keep].to_vec();
+    date.extend(end_parts);
+    if end_time.is_empty() {
+        date.join("-")
+    } else {
+        format!("{}T{}", date.join("-"), end_time)
+    }
+}
+
+/// The `Z`, `±HH:MM` or `[Zone/Name]` at the end of a time, or `""`.
+///
+/// A zone annotation is taken without the offset before it, so an end on the other
+/// side of a DST transition gets the offset in force at that time.
+fn zone_suffix(time: &str) -> &str {
+    if time.ends_with(']') {
+        return time.rfind('[').map_or("", |i| &time[i..]);
+    }
+    if time.ends_with(['Z', 'z']) {
+        return &time[time.len() - 1..];
+    }
+    time.rfind(['+', '-']).map_or("", |i| &time[i..])
+}
+
+/// Upper bound on the intervals a repeating ISO 8601 interval expands to.
+const MAX_INTERVALS: usize = 1000;
+
+/// Read an ISO 8601 interval (`start/end`, `start/duration`, `duration/end`, or a
+/// repeating `Rn/...` form) and list up to `count` of its intervals.
+///
+/// Durations use the same calendar arithmetic as `calculate`.
+pub fn iso_interval(expression: &str, count: u32) -> Result<IntervalExpansion, String> {
+    let parsed = IntervalExpression::parse(expression)?;
+    let intervals = parsed.expand((count as usize).min(MAX_INTERVALS))?;
+    Ok(IntervalExpansion {
+        expression: parsed.expression.clone(),
+        repeat: parsed.repeat,
+        duration: parsed.duration(),
+        intervals,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ts(s: &str) -> Timestamp {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_duration_round_trip() {
+        for text in ["P1Y2M10DT2H30M", "P2W", "PT36H", "P1DT1S"] {
+            assert_eq!(IsoDuration::parse(text).unwrap().to_string(), text);
+        }
+        assert_eq!(IsoDuration::parse("P0D").unwrap().to_string(), "PT0S");
+    }
+
+    #[test]
+    fn test_duration_errors() {
+        assert!(IsoDuration::parse("1D")
+            .unwrap_err()
+            .contains("start with P"));
+        assert!(IsoDuration::parse("P")
+            .unwrap_err()
+            .contains("no components"));
+        assert!(IsoDuration::parse("P1H")
+            .unwrap_err()
+            .contains("unexpected 'H'"));
+        assert!(IsoDuration::parse("PT5")
+            .unwrap_err()
+            .contains("designator"));
+        assert!(IsoDuration::parse("PT0.5S")
+            .unwrap_err()
+            .contains("fractional"));
+    }
+
+    #[test]
+    fn test_duration_clamps_month_end_like_calculate() {
+        let month = IsoDuration::parse("P1M").unwrap();
+        let jan31 = ts("2024-01-31T12:00:00Z");
+        assert_eq!(month.add_to(jan31, 1).unwrap(), ts("2024-02-29T12:00:00Z"));
+        // Counted from the anchor, so March is back on the 31st
+        assert_eq!(month.add_to(jan31, 2).unwrap(), ts("2024-03-31T12:00:00Z"));
+        assert_eq!(month.add_to(jan31, -2).unwrap(), ts("2023-11-30T12:00:00Z"));
+    }
+
+    #[test]
+    fn test_huge_duration_is_an_error() {
+        let start = ts("2024-01-01T00:00:00Z");
+        for text in ["P99999999999D", "P999999999M", "P9999999999999999W"] {
+            let duration = IsoDuration::parse(text).unwrap();
+            assert!(duration
+                .add_to(start, 1)
+                .unwrap_err()
+                .contains("out of range"));
+        }
+        let months = IsoDuration::parse("P999999M").unwrap();
+        assert!(months.add_to(start, 5).is_err());
+    }
+
+    #[test]
+    fn test_complete_abbreviated_end() {
+        assert_eq!(complete_end("2024-02-10", "15"), "2024-02-15");
+        assert_eq!(complete_end("2024-02-10", "03-01"), "2024-03-01");
+        assert_eq!(
+            complete_end("2024-02-10T09:00+01:00", "17:30"),
+            "2024-02-10T17:30+01:00"
+        );
+        assert_eq!(
+            complete_end("2024-02-10T09:00Z", "T17:30Z"),
+            "2024-02-10T17:30Z"
+        );
+    }
+
+    #[test]
+    fn test_bracketed_zone_bounds() {
+        let interval =
+            IntervalExpression::parse("2024-03-10T01:00[America/New_York]/PT2H").unwrap();
+        assert_eq!(
+            interval.bounds().unwrap(),
+            (ts("2024-03-10T06:00:00Z"), ts("2024-03-10T08:00:00Z"))
+        );
+        // The abbreviated end keeps the zone and lands after the spring-forward gap.
+        let interval =
+            IntervalExpression::parse("2024-03-10T01:00[America/New_York]/03:30").unwrap();
+        assert_eq!(interval.bounds().unwrap().1, ts("2024-03-10T07:30:00Z"));
+        let interval =
+            IntervalExpression::parse("R2--2024-03-10T01:00-05:00[America/New_York]--PT1H")
+                .unwrap();
+        assert_eq!(interval.expand(5).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_repeat_prefixes() {
+        assert_eq!(parse_repeat("R5"), Ok(Repeat::Times(5)));
+        assert_eq!(parse_repeat("R"), Ok(Repeat::Unbounded));
+        assert_eq!(parse_repeat("R-1"), Ok(Repeat::Unbounded));
+        assert!(parse_repeat("X5").is_err());
+    }
+}
@@ -1,8 +1,8 @@
-/// Shared JSON utility functions for manual serialization across the Wasm boundary.
-///
-/// We use manual JSON serialization instead of serde to minimize the Wasm bundle
-/// size (NFR5: <100KB gzipped). These utilities provide correct JSON string escaping
-/// per RFC 8259 (The JSON Data Interchange Format).
+//! Shared JSON utility functions for manual serialization across the Wasm boundary.
+//!
+//! We use manual JSON serialization instead of serde to minimize the Wasm bundle
+//! size (NFR5). These utilities provide correct JSON string escaping
+//! per RFC 8259 (The JSON Data Interchange Format).
 
 /// Escape a string for inclusion in a JSON value, per RFC 8259 Section 7.
 ///
@@ -29,6 +29,225 @@ pub fn json_escape(s: &str) -> String {
     escaped
 }
 
+/// Parse a JSON array of strings, e.g. `["03/04/2024", "13/04/2024"]`.
+///
+/// Counterpart of `json_escape` for inputs crossing the Wasm boundary. Handles the
+/// RFC 8259 escapes (including `\uXXXX`, with surrogate pairs for characters outside
+/// the BMP); an empty input is treated as an empty array.
+pub fn parse_string_array(json: &str) -> Result<Vec<String>, String> {
+    let trimmed = json.trim();
+    if trimmed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let inner = trimmed
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .ok_or_else(|| "Expected a JSON array of strings".to_string())?;
+
+    let mut values = Vec::new();
+    let mut chars = inner.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        match chars.next() {
+            None => break,
+            Some('"') => {}
+            Some(other) => return Err(format!("Expected a string, found '{}'", other)),
+        }
+
+        let mut value = String::new();
+        loop {
+            match chars.next() {
+                None => return Err("Unterminated string in JSON array".to_string()),
+                Some('"') => break,
+                Some('\\') => match chars.next() {
+                    Some('"') => value.push('"'),
+                    Some('\\') => value.push('\\'),
+                    Some('/') => value.push('/'),
+                    Some('n') => value.push('\n'),
+                    Some('r') => value.push('\r'),
+                    Some('t') => value.push('\t'),
+                    Some('b') => value.push('\u{8}'),
+                    Some('f') => value.push('\u{c}'),
+                    Some('u') => {
+                        let mut code = read_hex4(&mut chars)?;
+                        // A high surrogate must be followed by an escaped low one
+                        if (0xD800..0xDC00).contains(&code) {
+                            let low = match (chars.next(), chars.next()) {
+                                (Some('\\'), Some('u')) => read_hex4(&mut chars)?,
+                                _ => return Err(unpaired_surrogate(code)),
+                            };
+                            if !(0xDC00..0xE000).contains(&low) {
+                                return Err(unpaired_surrogate(code));
+                            }
+                            code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                        }
+                        let ch = char::from_u32(code).ok_or_else(|| unpaired_surrogate(code))?;
+                        value.push(ch);
+                    }
+                    _ => return Err("Invalid escape sequence in JSON string".to_string()),
+                },
+                Some(c) => value.push(c),
+            }
+        }
+        values.push(value);
+
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        match chars.next() {
+            None => break,
+            Some(',') => {}
+            Some(other) => return Err(format!("Expected ',' between strings, found '{}'", other)),
+        }
+    }
+
+    Ok(values)
+}
+
+/// Read the four hex digits of a `\uXXXX` escape.
+fn read_hex4(chars: &mut impl Iterator<Item = char>) -> Result<u32, String> {
+    let hex: String = chars.take(4).collect();
+    if hex.len() != 4 {
+        return Err(format!("Invalid unicode escape '\\u{}'", hex));
+    }
+    u32::from_str_radix(&hex, 16).map_err(|_| format!("Invalid unicode escape '\\u{}'", hex))
+}
+
+fn unpaired_surrogate(code: u32) -> String {
+    format!("Unpaired surrogate in unicode escape '\\u{:04X}'", code)
+}
+
+/// Split a JSON array of objects into the raw text of each object, e.g. for
+/// `[{"zone":"UTC","holidays":["2024-12-25"]}, {...}]`.
+///
+/// Braces and brackets inside strings are ignored; the objects themselves are left
+/// to `extract_*_field`.
+pub fn split_object_array(json: &str) -> Result<Vec<String>, String> {
+    let trimmed = json.trim();
+    if trimmed.is_empty() {
+        return Ok(Vec::new());
+    }
+    let inner = trimmed
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .ok_or_else(|| "Expected a JSON array of objects".to_string())?;
+
+    let mut objects = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, ch) in inner.char_indices() {
+        if in_string {
+            match ch {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match ch {
+            ',' if depth == 0 => {}
+            c if depth == 0 && c.is_whitespace() => {}
+            '{' if depth == 0 => {
+                start = i;
+                depth = 1;
+            }
+            other if depth == 0 => return Err(format!("Expected an object, found '{}'", other)),
+            '"' => in_string = true,
+            '{' | '[' => depth += 1,
+            '}' | ']' => {
+                depth = depth
+                    .checked_sub(1)
+                    .ok_or_else(|| format!("Unexpected '{}' in JSON array", ch))?;
+                if depth == 0 {
+                    objects.push(inner[start..=i].to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    if depth != 0 || in_string {
+        return Err("Unterminated object in JSON array".to_string());
+    }
+    Ok(objects)
+}
+
+/// Extract the raw text of an array value for a given key from a JSON object string,
+/// for use with `parse_string_array`.
+pub fn extract_array_field(json: &str, key: &str) -> Option<String> {
+    let pattern = format!("\"{}\"", key);
+    let key_pos = json.find(&pattern)?;
+    let after_key = &json[key_pos + pattern.len()..];
+    let after_colon = after_key.trim_start().strip_prefix(':')?.trim_start();
+    if !after_colon.starts_with('[') {
+        return None;
+    }
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, ch) in after_colon.char_indices() {
+        match ch {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            ']' if !in_string => return Some(after_colon[..=i].to_string()),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Extract a string value for a given key from a JSON object string.
+pub fn extract_string_field(json: &str, key: &str) -> Option<String> {
+    let pattern = format!("\"{}\"", key);
+    let key_pos = json.find(&pattern)?;
+    let after_key = &json[key_pos + pattern.len()..];
+    // Skip whitespace and colon
+    let after_colon = after_key.trim_start().strip_prefix(':')?;
+    let after_colon = after_colon.trim_start();
+    // Find the opening quote
+    let after_quote = after_colon.strip_prefix('"')?;
+    // Find the closing quote (simple - no escape handling needed for our values)
+    let end_quote = after_quote.find('"')?;
+    Some(after_quote[..end_quote].to_string())
+}
+
+/// Extract a number value for a given key from a JSON object string.
+pub fn extract_number_field(json: &str, key: &str) -> Option<i64> {
+    let pattern = format!("\"{}\"", key);
+    let key_pos = json.find(&pattern)?;
+    let after_key = &json[key_pos + pattern.len()..];
+    let after_colon = after_key.trim_start().strip_prefix(':')?;
+    let after_colon = after_colon.trim_start();
+
+    // Collect digits and optional minus sign
+    let mut num_str = String::new();
+    for ch in after_colon.chars() {
+        if ch == '-' || ch.is_ascii_digit() {
+            num_str.push(ch);
+        } else if !num_str.is_empty() {
+            break;
+        }
+    }
+
+    num_str.parse().ok()
+}
+
+/// Extract a boolean value for a given key from a JSON object string.
+pub fn extract_bool_field(json: &str, key: &str) -> Option<bool> {
+    let pattern = format!("\"{}\"", key);
+    let key_pos = json.find(&pattern)?;
+    let after_key = &json[key_pos + pattern.len()..];
+    let after_colon = after_key.trim_start().strip_prefix(':')?.trim_start();
+    if after_colon.starts_with("true") {
+        Some(true)
+    } else if after_colon.starts_with("false") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -83,4 +302,65 @@ mod tests {
             "tab\\there \\\"quoted\\\"\\nnewline"
         );
     }
+
+    #[test]
+    fn test_parse_string_array() {
+        let values = parse_string_array(r#"["03/04/2024", "a\"b", "\u00e9"]"#).unwrap();
+        assert_eq!(values, vec!["03/04/2024", "a\"b", "é"]);
+    }
+
+    #[test]
+    fn test_parse_string_array_surrogate_pairs() {
+        let values = parse_string_array(r#"["\uD83D\uDE00", "\ud83d\ude00!"]"#).unwrap();
+        assert_eq!(values, vec!["😀", "😀!"]);
+        assert!(parse_string_array(r#"["\uD83D"]"#)
+            .unwrap_err()
+            .contains("Unpaired surrogate"));
+        assert!(parse_string_array(r#"["\uDE00"]"#).is_err());
+        assert!(parse_string_array(r#"["\uD83D\u0041"]"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_string_array_empty() {
+        assert!(parse_string_array("[]").unwrap().is_empty());
+        assert!(parse_string_array("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_string_array_rejects_non_strings() {
+        assert!(parse_string_array("[1, 2]").is_err());
+        assert!(parse_string_array(r#"["open"#).is_err());
+        assert!(parse_string_array("not-json").is_err());
+    }
+
+    #[test]
+    fn test_extract_fields() {
+        let json = r#"{"style": "short", "units": 2, "calendar": true}"#;
+        assert_eq!(
+            extract_string_field(json, "style").as_deref(),
+            Some("short")
+        );
+        assert_eq!(extract_number_field(json, "units"), Some(2));
+        assert_eq!(extract_bool_field(json, "calendar"), Some(true));
+        assert_eq!(extract_bool_field(json, "missing"), None);
+    }
+
+    #[test]
+    fn test_split_object_array() {
+        let objects = split_object_array(r#"[{"a":"}","b":[1,2]}, {"c":{"d":1}}]"#).unwrap();
+        assert_eq!(objects, vec![r#"{"a":"}","b":[1,2]}"#, r#"{"c":{"d":1}}"#]);
+        assert!(split_object_array("[]").unwrap().is_empty());
+        assert!(split_object_array(r#"[{"a":1}"#).is_err());
+        assert!(split_object_array(r#"["a"]"#).is_err());
+    }
+
+    #[test]
+    fn test_extract_array_field() {
+        let json = r#"{"name":"x","holidays": ["2024-12-25", "a]b"], "days":"Mon"}"#;
+        assert_eq!(
+            extract_array_field(json, "holidays").as_deref(),
+            Some(r#"["2024-12-25", "a]b"]"#)
+        );
+        assert_eq!(extract_array_field(json, "days"), None);
+    }
 }
@@ -1,9 +1,33 @@
 use wasm_bindgen::prelude::*;
 
+pub mod abbreviation;
+pub mod analysis;
 pub mod calc;
+pub mod cron;
+pub mod explain;
 pub mod format;
+pub mod history;
+pub mod id;
+pub mod infer;
+pub mod interval;
+pub mod iso_interval;
 pub mod json_utils;
+pub mod locale;
+pub mod meeting;
+pub mod oncalendar;
+pub mod relative;
+pub mod rrule;
+pub mod spreadsheet;
+pub mod translate;
+pub mod tzfile;
 pub mod validate;
+pub mod windows;
+pub mod zone;
+
+/// Wrap an engine error message in the `{"error": "..."}` object returned across the Wasm boundary.
+fn error_json(message: &str) -> String {
+    format!(r#"{{"error":"{}"}}"#, json_utils::json_escape(message))
+}
 
 /// Initialize the Wasm module.
 /// Currently a no-op; reserved for future initialization needs.
@@ -22,7 +46,11 @@ pub fn now_unix() -> f64 {
 /// Calculate formatted result from a start date and operations JSON.
 ///
 /// # Arguments
-/// * `start_date` - An ISO 8601 date/datetime string (e.g., "2024-07-11" or "2024-07-11T01:14:00Z")
+/// * `start_date` - An ISO 8601 date/datetime string (e.g., "2024-07-11" or "2024-07-11T01:14:00Z"),
+///                  or a prefixed Windows value: `filetime:`, `ticks:`, `ad:`, `ole:` or `dos:`
+///                  (e.g., "filetime:0x01DAD32F9C4BFC00", "ole:45484.5"), or a prefixed
+///                  spreadsheet serial: `excel:` (or `excel1900:`, `lotus:`), `excel1904:` or
+///                  `sheets:` (or `googleSheets:`) (e.g., "excel:45484.5")
 /// * `operations_json` - A JSON array of operations (e.g., `[{"type":"add","unit":"months","value":1}]`)
 ///                       Pass `""` or `"[]"` for no operations.
 ///
@@ -33,7 +61,7 @@ pub fn now_unix() -> f64 {
 pub fn calculate(start_date: &str, operations_json: &str) -> String {
     match calc::calculate(start_date, operations_json) {
         Ok(result) => result.to_json(),
-        Err(e) => format!(r#"{{"error":"{}"}}"#, e.replace('"', r#"\""#)),
+        Err(e) => error_json(&e),
     }
 }
 
@@ -43,8 +71,611 @@ pub fn calculate(start_date: &str, operations_json: &str) -> String {
 /// * `input` - A date or datetime string to validate
 ///
 /// # Returns
-/// A JSON string with `ValidationResult` fields: `valid` (bool), `error` (optional string), `normalized` (optional string)
+/// A JSON string with `ValidationResult` fields: `valid` (bool), `error` (optional string), `normalized` (optional string),
+/// for unrecognized layouts `detectedFormat` / `ambiguousWith` (optional strptime patterns), and for
+/// inputs ending in a zone abbreviation or offset (`"10:00 PST"`, `"14:00 UTC+5:30"`) the `offset`
+/// used plus, for ambiguous abbreviations, a `warning` and `candidates: [{"offset", "zone", "name"}]`;
+/// Excel's serial 60 (`"excel:60"`, the fictional 1900-02-29) also carries a `warning`
 #[wasm_bindgen]
 pub fn validate_date(input: &str) -> String {
     validate::validate(input).to_json()
 }
+
+/// Infer the strptime format of one or more sample timestamps.
+///
+/// # Arguments
+/// * `samples_json` - A JSON array of sample strings (e.g., `["03/04/2024", "13/04/2024"]`)
+///
+/// # Returns
+/// A JSON string with `samples`, `ambiguous` (bool) and `candidates` (each with `pattern`,
+/// `confidence`, `matched`, `normalized`, `ambiguousWith`), or `{"error": "..."}`
+#[wasm_bindgen]
+pub fn infer_format(samples_json: &str) -> String {
+    let samples = match json_utils::parse_string_array(samples_json) {
+        Ok(samples) => samples,
+        Err(e) => return error_json(&e),
+    };
+    let samples: Vec<&str> = samples.iter().map(String::as_str).collect();
+    match infer::infer_format(&samples) {
+        Ok(result) => result.to_json(),
+        Err(e) => error_json(&e),
+    }
+}
+
+/// Calculate like `calculate`, additionally rendering named output formats.
+///
+/// # Arguments
+/// * `start_date` - Same as `calculate`
+/// * `operations_json` - Same as `calculate`
+/// * `formats_json` - A JSON array of format names: `rfc3339`, `rfc9557`, `isoWeek`, `ordinal`,
+///                    `sqlDatetime`, `httpDate`, `basicIso`, `fileTime`, `dotnetTicks`,
+///                    `activeDirectory`, `oleDate`, `dosDateTime`, `excel1900`, `excel1904`,
+///                    `googleSheets`
+/// * `zone` - Zone the formats are rendered in: `"UTC"`, a fixed offset (`"+05:30"`) or an IANA name
+///
+/// # Returns
+/// A `FormattedResult` JSON string with an extra `formats` object keyed by format name,
+/// or `{"error": "..."}`. A format that cannot represent the result (e.g. a FILETIME
+/// before 1601) is `null` there, with the reason under the same name in `formatErrors`
+#[wasm_bindgen]
+pub fn calculate_with_formats(
+    start_date: &str,
+    operations_json: &str,
+    formats_json: &str,
+    zone: &str,
+) -> String {
+    match calc::calculate_with_formats(start_date, operations_json, formats_json, zone) {
+        Ok(result) => result.to_json(),
+        Err(e) => error_json(&e),
+    }
+}
+
+/// Render a date with an arbitrary strftime pattern (e.g., `"%d/%m/%Y %H:%M %Z"`).
+///
+/// `locale` (e.g., `"de"`, `"pt-BR"`, or `""` for English) localizes month/weekday
+/// names, AM/PM and the `%c` / `%x` / `%X` layouts.
+///
+/// # Returns
+/// A JSON string `{"formatted": "..."}` or `{"error": "..."}`
+#[wasm_bindgen]
+pub fn format_datetime(input: &str, pattern: &str, zone: &str, locale: &str) -> String {
+    match calc::format_with_pattern(input, pattern, zone, locale) {
+        Ok(formatted) => format!(
+            r#"{{"formatted":"{}"}}"#,
+            json_utils::json_escape(&formatted)
+        ),
+        Err(e) => error_json(&e),
+    }
+}
+
+/// Translate a date format pattern between strftime, Python, Java, LDML, moment/dayjs and .NET.
+///
+/// # Arguments
+/// * `pattern` - The pattern to translate (e.g., `"yyyy-MM-dd'T'HH:mm:ss.SSSXXX"`)
+/// * `from_dialect` - `strftime`, `python`, `java`, `ldml`, `moment` (or `dayjs`), `dotnet`
+/// * `sample` - Optional date input to preview each translation with; pass `""` to skip
+/// * `zone` - Zone the preview is rendered in
+///
+/// # Returns
+/// A JSON string `{"source": ..., "translations": [{"dialect", "pattern", "unsupported", "approximated", "preview"}]}`
+/// or `{"error": "..."}`
+#[wasm_bindgen]
+pub fn translate_format(pattern: &str, from_dialect: &str, sample: &str, zone: &str) -> String {
+    match translate::translate_pattern(pattern, from_dialect, sample, zone) {
+        Ok(report) => report.to_json(),
+        Err(e) => error_json(&e),
+    }
+}
+
+/// Calculate like `calculate`, rendering `localHuman` in a zone and locale.
+///
+/// # Arguments
+/// * `zone` - `"UTC"`, a fixed offset (`"+05:30"`) or an IANA name
+/// * `locale` - A BCP 47 tag such as `"de"`, `"fr"`, `"pt-BR"`, `"en-GB"`, `"ja"`; `""` for English
+///
+/// # Returns
+/// A `FormattedResult` JSON string or `{"error": "..."}`
+#[wasm_bindgen]
+pub fn calculate_localized(
+    start_date: &str,
+    operations_json: &str,
+    zone: &str,
+    locale: &str,
+) -> String {
+    match calc::calculate_localized(start_date, operations_json, zone, locale) {
+        Ok(result) => result.to_json(),
+        Err(e) => error_json(&e),
+    }
+}
+
+/// Describe a target instant relative to a reference ("about 3 months ago", "in 2h 5m").
+///
+/// # Arguments
+/// * `reference` - The "now" date input (e.g., from `now_unix`, as ISO 8601)
+/// * `target` - The date input to describe
+/// * `options_json` - Optional JSON object: `units` (1-6), `style` (`long`/`short`),
+///                    `rounding` (`round`/`floor`), `calendar` (bool), `zone`, `locale`
+///                    (a tag as for `calculate_localized`); pass `""` for defaults
+///
+/// # Returns
+/// A JSON string `{"phrase", "direction", "breakdown": {"sign", "years", ..., "totalSeconds"}}`
+/// or `{"error": "..."}`
+#[wasm_bindgen]
+pub fn relative_time(reference: &str, target: &str, options_json: &str) -> String {
+    match relative::relative_time(reference, target, options_json) {
+        Ok(result) => result.to_json(),
+        Err(e) => error_json(&e),
+    }
+}
+
+/// List upcoming runs of a cron expression.
+///
+/// # Arguments
+/// * `expression` - A cron expression in the chosen dialect: five standard fields (lists,
+///                  ranges, steps, month/weekday names) or a macro such as `@daily`;
+///                  Quartz `0 0 12 ? * MON-FRI`; AWS `cron(0 12 * * ? *)` or `rate(5 minutes)`;
+///                  Jenkins `H/15 * * * *`
+/// * `start` - Date input the search starts from (exclusive)
+/// * `count` - Number of runs to return (capped at 1000)
+/// * `options_json` - Optional JSON object: `dialect` (`standard`, `quartz`, `aws`, `jenkins`),
+///                    `seed` (Jenkins job name for `H`), `zone` (`"UTC"`, a fixed offset or an
+///                    IANA name), `dstPolicy` (`vixie` or `kubernetes`); pass `""` for defaults
+///
+/// # Returns
+/// A JSON string `{"expression", "dialect", "zone", "runs": [{"iso", "unixTimestamp", "local", "dst"?}]}`,
+/// where `dst` is `gapShifted`, `foldFirst` or `foldSecond` for DST-affected runs,
+/// or `{"error": "..."}`
+#[wasm_bindgen]
+pub fn cron_next_runs(expression: &str, start: &str, count: u32, options_json: &str) -> String {
+    match cron::cron_runs(expression, start, count, false, options_json) {
+        Ok(runs) => runs.to_json(),
+        Err(e) => error_json(&e),
+    }
+}
+
+/// List past runs of a cron expression before `start`, most recent first.
+///
+/// Arguments and return shape are the same as `cron_next_runs`.
+#[wasm_bindgen]
+pub fn cron_previous_runs(expression: &str, start: &str, count: u32, options_json: &str) -> String {
+    match cron::cron_runs(expression, start, count, true, options_json) {
+        Ok(runs) => runs.to_json(),
+        Err(e) => error_json(&e),
+    }
+}
+
+/// List the next elapses of a systemd calendar event (`OnCalendar=` in timer units).
+///
+/// # Arguments
+/// * `expression` - e.g. `"Mon..Fri *-*-* 09:00:00"`, `"*-*-01 00:00:00 Europe/Berlin"`, `"quarterly"`
+/// * `start` - Date input the search starts from (exclusive)
+/// * `count` - Number of elapses to return (capped at 1000)
+/// * `options_json` - Optional JSON object: `zone` (used when the expression names none);
+///                    pass `""` for defaults. Times skipped by DST never elapse and
+///                    repeated times elapse once, as in systemd
+///
+/// # Returns
+/// A JSON string `{"original", "normalized", "zone", "runs": [{"iso", "unixTimestamp", "local",
+/// "display", "displayUtc", "dst"?}]}` mirroring `systemd-analyze calendar`, or `{"error": "..."}`
+#[wasm_bindgen]
+pub fn oncalendar_next(expression: &str, start: &str, count: u32, options_json: &str) -> String {
+    match oncalendar::oncalendar_runs(expression, start, count, options_json) {
+        Ok(runs) => runs.to_json(),
+        Err(e) => error_json(&e),
+    }
+}
+
+/// Explain a cron or systemd calendar expression in plain English.
+///
+/// # Arguments
+/// * `expression` - The expression to explain
+/// * `options_json` - Optional JSON object: `dialect` (`standard`, `quartz`, `aws`, `jenkins`,
+///                    or `systemd` for `OnCalendar=` expressions), `seed` (Jenkins job name);
+///                    pass `""` for standard cron
+///
+/// # Returns
+/// A JSON string `{"expression", "summary", "fields": [{"field", "description"}]}`
+/// or `{"error": "..."}`
+#[wasm_bindgen]
+pub fn explain_schedule(expression: &str, options_json: &str) -> String {
+    match explain::explain_schedule(expression, options_json) {
+        Ok(explanation) => explanation.to_json(),
+        Err(e) => error_json(&e),
+    }
+}
+
+/// Summarize how a cron schedule's runs are spaced over a window.
+///
+/// # Arguments
+/// * `expression` - The cron expression (in the dialect from `options_json`)
+/// * `start` - Date input the window starts at (inclusive)
+/// * `end` - Date input the window ends at (exclusive); pass `""` for one year after `start`
+/// * `options_json` - Same options as `cron_next_runs`; runs per day are counted in `zone`
+///
+/// # Returns
+/// A JSON string `{"expression", "zone", "start", "end", "truncated", "runCount", "firstRun"?,
+/// "lastRun"?, "minIntervalSeconds"?, "maxIntervalSeconds"?, "averageIntervalSeconds"?,
+/// "longestGap"?: {"from", "to", "seconds"}, "runsPerDay": [{"date", "runs"}]}`
+/// or `{"error": "..."}`
+#[wasm_bindgen]
+pub fn analyze_schedule(expression: &str, start: &str, end: &str, options_json: &str) -> String {
+    match analysis::analyze_schedule(expression, start, end, options_json) {
+        Ok(analysis) => analysis.to_json(),
+        Err(e) => error_json(&e),
+    }
+}
+
+/// Find the minutes in which two or more cron schedules run together.
+///
+/// # Arguments
+/// * `expressions_json` - JSON array of at least two expressions, e.g. `["0 * * * *","*/20 * * * *"]`
+/// * `start`, `end`, `options_json` - As for `analyze_schedule`
+///
+/// # Returns
+/// A JSON string `{"expressions", "zone", "start", "end", "truncated", "collisionCount",
+/// "collisions": [{"iso", "unixTimestamp", "local", "schedules": [indices]}]}` listing at most
+/// 1000 collisions, or `{"error": "..."}`
+#[wasm_bindgen]
+pub fn schedule_collisions(
+    expressions_json: &str,
+    start: &str,
+    end: &str,
+    options_json: &str,
+) -> String {
+    match analysis::schedule_collisions(expressions_json, start, end, options_json) {
+        Ok(report) => report.to_json(),
+        Err(e) => error_json(&e),
+    }
+}
+
+/// Expand an iCalendar (RFC 5545) recurrence rule into occurrences.
+///
+/// # Arguments
+/// * `rule` - `RRULE:`, `RDATE` and `EXDATE` lines (e.g. `"RRULE:FREQ=MONTHLY;BYDAY=1FR"`),
+///            optionally with a `DTSTART` line; a bare `FREQ=...` is also accepted
+/// * `dtstart` - Start when `rule` has no `DTSTART`: e.g. `"2024-01-05T09:00[America/New_York]"`,
+///               `"2024-01-05T09:00"` (in `zone`), `"2024-01-05"` (all-day) or `"20240105T090000Z"`
+/// * `count` - Number of occurrences to return (capped at 1000)
+/// * `options_json` - Optional JSON object: `zone` for floating and all-day starts,
+///                    `after` to list only occurrences after that date input; pass `""` for none
+///
+/// # Returns
+/// A JSON string `{"dtstart", "zone", "allDay", "occurrences": [{"iso", "unixTimestamp",
+/// "local", "date"?}]}` or `{"error": "..."}`
+#[wasm_bindgen]
+pub fn rrule_expand(rule: &str, dtstart: &str, count: u32, options_json: &str) -> String {
+    match rrule::rrule_occurrences(rule, dtstart, count, options_json) {
+        Ok(recurrence) => recurrence.to_json(),
+        Err(e) => error_json(&e),
+    }
+}
+
+/// Read an ISO 8601 interval and return its bounds, expanding repeating intervals.
+///
+/// # Arguments
+/// * `expression` - `start/end`, `start/duration`, `duration/end`, or a repeating form such as
+///                  `"R5/2024-01-01T00:00Z/P1M"` (`R/...` repeats without end)
+/// * `count` - Maximum number of intervals to list for repeating forms (capped at 1000)
+///
+/// # Returns
+/// A JSON string `{"expression", "repeating", "repetitions"?, "duration"?,
+/// "intervals": [{"start", "end", "seconds"}]}` or `{"error": "..."}`
+#[wasm_bindgen]
+pub fn iso_interval(expression: &str, count: u32) -> String {
+    match iso_interval::iso_interval(expression, count) {
+        Ok(expansion) => expansion.to_json(),
+        Err(e) => error_json(&e),
+    }
+}
+
+/// Combine lists of half-open `[start, end)` intervals.
+///
+/// # Arguments
+/// * `operation` - `union`, `intersection`, `subtract` (left minus right), `gaps` (between the
+///                 left intervals, or within a single right-hand window) or `overlaps`
+///                 (pairs within left, or between left and right)
+/// * `left_json` - JSON array of ISO 8601 intervals, e.g. `["2024-01-01T09:00Z/PT1H"]`
+/// * `right_json` - Second array; pass `""` when the operation does not need one
+///
+/// # Returns
+/// A JSON string `{"operation", "intervals": [{"start", "end", "seconds"}], "totalSeconds",
+/// "overlaps"?: [{"left", "right", "relation", "start", "end"}]}` or `{"error": "..."}`
+#[wasm_bindgen]
+pub fn interval_algebra(operation: &str, left_json: &str, right_json: &str) -> String {
+    match interval::interval_algebra(operation, left_json, right_json) {
+        Ok(report) => report.to_json(),
+        Err(e) => error_json(&e),
+    }
+}
+
+/// List the UTC offset transitions of a time zone within a window.
+///
+/// # Arguments
+/// * `zone` - IANA identifier (e.g. `"Europe/Berlin"`); fixed offsets have no transitions
+/// * `start` - Window start as a date input, or a bare year such as `"2024"`
+/// * `end` - Exclusive window end, or a bare year to include that whole year; pass `""`
+///           for one year after `start`
+///
+/// # Returns
+/// A JSON string `{"zone", "transitions": [{"iso", "unixTimestamp", "kind", "offsetBefore",
+/// "offsetAfter", "shiftSeconds", "abbreviationBefore", "abbreviationAfter", "dst",
+/// "localBefore", "localAfter"}]}` where `kind` is `gap`, `fold` or `none`,
+/// or `{"error": "..."}`
+#[wasm_bindgen]
+pub fn zone_transitions(zone: &str, start: &str, end: &str) -> String {
+    match zone::zone_transitions(zone, start, end) {
+        Ok(transitions) => transitions.to_json(),
+        Err(e) => error_json(&e),
+    }
+}
+
+/// Find the next UTC offset transition of a time zone.
+///
+/// # Arguments
+/// * `zone` - IANA identifier (e.g. `"America/New_York"`)
+/// * `after` - Date input to search from; pass `""` for now
+///
+/// # Returns
+/// The same JSON shape as `zone_transitions`, with at most one transition,
+/// or `{"error": "..."}`
+#[wasm_bindgen]
+pub fn next_zone_transition(zone: &str, after: &str) -> String {
+    match zone::next_zone_transition(zone, after) {
+        Ok(transitions) => transitions.to_json(),
+        Err(e) => error_json(&e),
+    }
+}
+
+/// Validate a date input and check its wall-clock time in a time zone.
+///
+/// # Arguments
+/// * `input` - A date or datetime string to validate
+/// * `zone` - `"UTC"`, a fixed offset (`"+05:30"`) or an IANA name
+///
+/// # Returns
+/// The `validate_date` JSON, plus `"localTime": {"kind", "earlier", "later"}` when a civil
+/// input is skipped (`kind` is `gap`) or repeated (`fold`) in `zone`
+#[wasm_bindgen]
+pub fn validate_date_in_zone(input: &str, zone: &str) -> String {
+    validate::validate_in_zone(input, zone).to_json()
+}
+
+/// Like `calculate`, but on the wall clock of a time zone.
+///
+/// # Arguments
+/// * `start_date` - Same as `calculate`; civil dates and datetimes are local to `zone`
+/// * `operations_json` - Same as `calculate`; `{"type":"set","unit":"hours","value":2}`
+///                       replaces a field of the local date-time
+/// * `options_json` - Optional JSON object: `zone`, and `disambiguation` (`compatible`,
+///                    `earlier`, `later` or `reject`) for local times skipped or repeated by DST
+///
+/// # Returns
+/// A JSON string with the same shape as `calculate`, `localHuman` rendered in `zone`,
+/// or `{"error": "..."}`
+#[wasm_bindgen]
+pub fn calculate_in_zone(start_date: &str, operations_json: &str, options_json: &str) -> String {
+    match calc::calculate_in_zone(start_date, operations_json, options_json) {
+        Ok(result) => result.to_json(),
+        Err(e) => error_json(&e),
+    }
+}
+
+/// Show one instant on the wall clocks of several time zones.
+///
+/// # Arguments
+/// * `instant` - Date input; civil dates and datetimes are read in the `reference` zone
+/// * `zones_json` - JSON array of zones, e.g. `["America/New_York","Europe/Berlin","+05:30"]`
+/// * `options_json` - Optional JSON object: `reference` zone for day differences (default
+///                    UTC) and `disambiguation` as for `calculate_in_zone`; pass `""` for none
+///
+/// # Returns
+/// A JSON string `{"iso", "unixTimestamp", "reference", "clocks": [{"zone", "local", "date",
+/// "time", "weekday", "offset", "abbreviation", "dst", "dayDifference", "dayMarker"}]}`
+/// or `{"error": "..."}`
+#[wasm_bindgen]
+pub fn world_clock(instant: &str, zones_json: &str, options_json: &str) -> String {
+    match zone::world_clock(instant, zones_json, options_json) {
+        Ok(clock) => clock.to_json(),
+        Err(e) => error_json(&e),
+    }
+}
+
+/// Find the working hours shared by participants in different time zones.
+///
+/// # Arguments
+/// * `participants_json` - JSON array of objects: `zone` (required), `name`, `start` and `end`
+///                         local times (default `"09:00"`-`"17:00"`; an end at or before the
+///                         start runs past midnight), `days` (e.g. `"Mon..Fri"`, the default)
+///                         and `holidays` (array of local dates)
+/// * `start` - Window start as a date input (civil inputs are read in the `zone` option)
+/// * `end` - Exclusive window end; pass `""` for one week after `start` (at most 366 days)
+/// * `options_json` - Optional JSON object: `zone` for civil `start`/`end` (default UTC) and
+///                    `minMinutes`, the shortest slot to list (default 30)
+///
+/// # Returns
+/// A JSON string `{"start", "end", "minMinutes", "participants": [{"name", "zone"}],
+/// "slotCount", "totalSeconds", "slots": [{"start", "end", "seconds",
+/// "local": [{"name", "start", "end"}]}]}` or `{"error": "..."}`
+#[wasm_bindgen]
+pub fn meeting_slots(
+    participants_json: &str,
+    start: &str,
+    end: &str,
+    options_json: &str,
+) -> String {
+    match meeting::meeting_slots(participants_json, start, end, options_json) {
+        Ok(plan) => plan.to_json(),
+        Err(e) => error_json(&e),
+    }
+}
+
+/// Look up the readings of a time zone abbreviation or offset notation.
+///
+/// # Arguments
+/// * `text` - An abbreviation (`"IST"`, `"cst"`) or offset notation (`"UTC+5:30"`, `"GMT-3"`)
+///
+/// # Returns
+/// A JSON string `{"input", "offset", "ambiguous", "candidates": [{"offset", "zone", "name"}]}`
+/// where `offset` is the reading used by `validate_date` (candidates are empty for offsets),
+/// or `{"error": "..."}`
+#[wasm_bindgen]
+pub fn resolve_abbreviation(text: &str) -> String {
+    match abbreviation::ZoneSuffix::parse(text) {
+        Some(suffix) => suffix.to_json(),
+        None => error_json(&format!("Unknown time zone abbreviation '{}'", text.trim())),
+    }
+}
+
+/// Decode a POSIX TZ string and list its transitions in one year.
+///
+/// # Arguments
+/// * `posix` - e.g. `"CET-1CEST,M3.5.0,M10.5.0/3"` or `"<+0330>-3:30"`; any zone argument
+///             elsewhere also accepts these strings
+/// * `year` - Local calendar year to list transitions for
+///
+/// # Returns
+/// A JSON string `{"name", "source": "posix", "rule": {"text", "std": {"abbreviation",
+/// "offset"}, "dst": {"abbreviation", "offset", "start": {"rule", "description"}, "end"} | null},
+/// "transitions": [...]}` with transitions shaped as in `zone_transitions`,
+/// or `{"error": "..."}`
+#[wasm_bindgen]
+pub fn inspect_posix_tz(posix: &str, year: i32) -> String {
+    match tzfile::inspect_posix_tz(posix, year) {
+        Ok(inspection) => inspection.to_json(),
+        Err(e) => error_json(&e),
+    }
+}
+
+/// Load a TZif file (e.g. a device's `/etc/localtime`) as a named zone and dump it.
+///
+/// # Arguments
+/// * `name` - Name to register the zone under; afterwards any zone argument accepts it.
+///            Pick a name other than the IANA one to compare the file with upstream tzdata;
+///            `UTC`, `Z` and offsets such as `+05:30` are rejected.
+/// * `data` - The raw TZif bytes
+///
+/// # Returns
+/// A JSON string `{"name", "source": "tzif", "version", "types": [{"offset", "dst",
+/// "abbreviation"}], "transitionCount", "leapSeconds", "footer", "rule", "transitions": [...]}`
+/// where `rule` is the decoded footer as in `inspect_posix_tz`, or `{"error": "..."}`
+#[wasm_bindgen]
+pub fn load_tzif(name: &str, data: &[u8]) -> String {
+    match tzfile::load_tzif(name, data) {
+        Ok(inspection) => inspection.to_json(),
+        Err(e) => error_json(&e),
+    }
+}
+
+/// Report how a zone's standard offset and DST rules changed over the years.
+///
+/// # Arguments
+/// * `zone` - IANA zone name (e.g., `"Europe/Moscow"`)
+/// * `options_json` - Optional JSON object: `from` (default 1900) and `to` (default the
+///                    current year), inclusive local calendar years
+///
+/// # Returns
+/// A JSON string `{"zone", "from", "to", "eras": [{"from", "to", "standard": {"offset",
+/// "abbreviation"} | null, "dst": {...} | null, "transitions", "change"}]}` where `standard`
+/// is null for years spent entirely on DST and `change` (absent on the first era) says
+/// what differs from the era before, or `{"error": "..."}`
+#[wasm_bindgen]
+pub fn zone_history(zone: &str, options_json: &str) -> String {
+    match history::zone_history(zone, options_json) {
+        Ok(history) => history.to_json(),
+        Err(e) => error_json(&e),
+    }
+}
+
+/// Compare a historical conversion under the zone's real history with a fixed offset.
+///
+/// # Arguments
+/// * `zone` - IANA zone name
+/// * `input` - An instant (`"2012-01-01T00:00:00Z"`) or a local date-time in `zone`
+/// * `naive_offset` - The fixed offset to compare with (e.g., `"+03:00"`); empty for the
+///                    zone's standard offset this year
+///
+/// # Returns
+/// A JSON string `{"input", "zone", "absolute", "historical": {"utc", "local", "offset",
+/// "abbreviation", "dst"}, "naive": {"utc", "local", "offset"}, "differenceSeconds"}`
+/// where `differenceSeconds` is the historical offset minus the naive one,
+/// or `{"error": "..."}`
+#[wasm_bindgen]
+pub fn compare_historical_offset(zone: &str, input: &str, naive_offset: &str) -> String {
+    match history::compare_historical_offset(zone, input, naive_offset) {
+        Ok(conversion) => conversion.to_json(),
+        Err(e) => error_json(&e),
+    }
+}
+
+/// Decode the creation time of a Snowflake ID (Twitter, Discord, Instagram, Sonyflake or custom).
+///
+/// # Arguments
+/// * `id` - The decimal ID (e.g., `"175928847299117063"`)
+/// * `options_json` - Optional JSON object: `layout` (`"twitter"` by default, `"discord"`,
+///                    `"instagram"`, `"sonyflake"` or `"custom"`); a custom layout reads `epoch`
+///                    (Unix ms or a date), `timestampBits`, `workerBits`, `sequenceBits` and `unitMs`
+///
+/// # Returns
+/// A JSON string `{"id", "layout", "iso", "unixTimestamp", "unixTimestampMs", "fields": {...},
+/// "alternatives": [{"layout", "iso", "unixTimestampMs"}]}` where alternatives are the other
+/// presets' readings when no layout was given, or `{"error": "..."}`
+#[wasm_bindgen]
+pub fn decode_snowflake(id: &str, options_json: &str) -> String {
+    match id::decode_snowflake(id, options_json) {
+        Ok(decoding) => decoding.to_json(),
+        Err(e) => error_json(&e),
+    }
+}
+
+/// The smallest Snowflake ID generated at or after an instant, for `id >= ...` range queries.
+///
+/// # Arguments
+/// * `instant` - ISO 8601 date/datetime
+/// * `options_json` - Layout options as for `decode_snowflake`
+///
+/// # Returns
+/// A JSON string `{"layout", "instant", "id"}` (the ID as a string), or `{"error": "..."}`
+#[wasm_bindgen]
+pub fn snowflake_for_instant(instant: &str, options_json: &str) -> String {
+    match id::snowflake_for_instant(instant, options_json) {
+        Ok(bound) => bound.to_json(),
+        Err(e) => error_json(&e),
+    }
+}
+
+/// Decode the creation time of an ID, detecting its kind from its shape.
+///
+/// # Arguments
+/// * `input` - A UUID v1/v6/v7 (hyphenated, braced, `urn:uuid:` or 32 hex digits), ULID,
+///             KSUID, BSON ObjectId or decimal Snowflake
+/// * `options_json` - Optional JSON object with the Snowflake layout options of `decode_snowflake`
+///
+/// # Returns
+/// A JSON string `{"kind", "id", "unixTimestampMs", "result": {FormattedResult},
+/// "components": {...}}` where components are the non-time parts (e.g. `clockSequence` and
+/// `node` for UUID v1, `counter` for ObjectId), or `{"error": "..."}`
+#[wasm_bindgen]
+pub fn decode_id(input: &str, options_json: &str) -> String {
+    match id::decode_id(input, options_json) {
+        Ok(decoded) => decoded.to_json(),
+        Err(e) => error_json(&e),
+    }
+}
+
+/// Generate the smallest and largest IDs created at an instant, for range queries.
+///
+/// # Arguments
+/// * `instant` - ISO 8601 date/datetime
+/// * `options_json` - Optional JSON object: `kind` (`"uuid-v1"`, `"uuid-v6"`, `"uuid-v7"`,
+///                    `"ulid"`, `"ksuid"`, `"objectid"` or `"snowflake"`; all by default) and
+///                    the Snowflake layout options of `decode_snowflake`
+///
+/// # Returns
+/// A JSON string `{"instant", "bounds": [{"kind", "min", "max"}]}`, or `{"error": "..."}`
+#[wasm_bindgen]
+pub fn id_bounds(instant: &str, options_json: &str) -> String {
+    match id::id_bounds(instant, options_json) {
+        Ok(bounds) => bounds.to_json(),
+        Err(e) => error_json(&e),
+    }
+}
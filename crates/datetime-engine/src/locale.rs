@@ -0,0 +1,618 @@
+use jiff::Zoned;
+
+/// Month and weekday names plus date/time layouts for one locale.
+///
+/// English is always available. The other tables are compiled in with the
+/// `locales` feature (on by default); build with `--no-default-features` to
+/// drop them from the Wasm bundle.
+#[derive(Debug, PartialEq)]
+pub struct Locale {
+    /// BCP 47 tag, e.g. `pt-BR`
+    pub tag: &'static str,
+    months: [&'static str; 12],
+    months_abbr: [&'static str; 12],
+    /// Monday first
+    weekdays: [&'static str; 7],
+    /// Monday first
+    weekdays_abbr: [&'static str; 7],
+    /// AM/PM markers
+    meridiem: [&'static str; 2],
+    /// Long date and time, used for `localHuman` and `%c`
+    long: &'static str,
+    /// Numeric date in the locale's field order, used for `%x`
+    short_date: &'static str,
+    /// Clock time, used for `%X`
+    time: &'static str,
+}
+
+pub const EN: Locale = Locale {
+    tag: "en",
+    months: [
+        "January",
+        "February",
+        "March",
+        "April",
+        "May",
+        "June",
+        "July",
+        "August",
+        "September",
+        "October",
+        "November",
+        "December",
+    ],
+    months_abbr: [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ],
+    weekdays: [
+        "Monday",
+        "Tuesday",
+        "Wednesday",
+        "Thursday",
+        "Friday",
+        "Saturday",
+        "Sunday",
+    ],
+    weekdays_abbr: ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+    meridiem: ["AM", "PM"],
+    long: "%B %d, %Y %I:%M:%S %p",
+    short_date: "%m/%d/%Y",
+    time: "%I:%M:%S %p",
+};
+
+/// British English: the same names as [`EN`], with day-first dates and a 24-hour clock.
+pub const EN_GB: Locale = Locale {
+    tag: "en-GB",
+    months: EN.months,
+    months_abbr: EN.months_abbr,
+    weekdays: EN.weekdays,
+    weekdays_abbr: EN.weekdays_abbr,
+    meridiem: ["am", "pm"],
+    long: "%-d %B %Y %H:%M:%S",
+    short_date: "%d/%m/%Y",
+    time: "%H:%M:%S",
+};
+
+/// English regions that write dates day-first, as `en-GB` does.
+const EN_GB_REGIONS: &[&str] = &["AU", "NZ", "IE", "IN", "ZA", "SG"];
+
+#[cfg(feature = "locales")]
+mod tables {
+    use super::Locale;
+
+    pub const DE: Locale = Locale {
+        tag: "de",
+        months: [
+            "Januar",
+            "Februar",
+            "März",
+            "April",
+            "Mai",
+            "Juni",
+            "Juli",
+            "August",
+            "September",
+            "Oktober",
+            "November",
+            "Dezember",
+        ],
+        months_abbr: [
+            "Jan.", "Feb.", "März", "Apr.", "Mai", "Juni", "Juli", "Aug.", "Sept.", "Okt.", "Nov.",
+            "Dez.",
+        ],
+        weekdays: [
+            "Montag",
+            "Dienstag",
+            "Mittwoch",
+            "Donnerstag",
+            "Freitag",
+            "Samstag",
+            "Sonntag",
+        ],
+        weekdays_abbr: ["Mo.", "Di.", "Mi.", "Do.", "Fr.", "Sa.", "So."],
+        meridiem: ["AM", "PM"],
+        long: "%-d. %B %Y, %H:%M:%S",
+        short_date: "%d.%m.%Y",
+        time: "%H:%M:%S",
+    };
+
+    pub const FR: Locale = Locale {
+        tag: "fr",
+        months: [
+            "janvier",
+            "février",
+            "mars",
+            "avril",
+            "mai",
+            "juin",
+            "juillet",
+            "août",
+            "septembre",
+            "octobre",
+            "novembre",
+            "décembre",
+        ],
+        months_abbr: [
+            "janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août", "sept.", "oct.",
+            "nov.", "déc.",
+        ],
+        weekdays: [
+            "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche",
+        ],
+        weekdays_abbr: ["lun.", "mar.", "mer.", "jeu.", "ven.", "sam.", "dim."],
+        meridiem: ["AM", "PM"],
+        long: "%-d %B %Y à %H:%M:%S",
+        short_date: "%d/%m/%Y",
+        time: "%H:%M:%S",
+    };
+
+    pub const ES: Locale = Locale {
+        tag: "es",
+        months: [
+            "enero",
+            "febrero",
+            "marzo",
+            "abril",
+            "mayo",
+            "junio",
+            "julio",
+            "agosto",
+            "septiembre",
+            "octubre",
+            "noviembre",
+            "diciembre",
+        ],
+        months_abbr: [
+            "ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sept", "oct", "nov", "dic",
+        ],
+        weekdays: [
+            "lunes",
+            "martes",
+            "miércoles",
+            "jueves",
+            "viernes",
+            "sábado",
+            "domingo",
+        ],
+        weekdays_abbr: ["lun", "mar", "mié", "jue", "vie", "sáb", "dom"],
+        meridiem: ["a. m.", "p. m."],
+        long: "%-d de %B de %Y, %H:%M:%S",
+        short_date: "%d/%m/%Y",
+        time: "%H:%M:%S",
+    };
+
+    pub const IT: Locale = Locale {
+        tag: "it",
+        months: [
+            "gennaio",
+            "febbraio",
+            "marzo",
+            "aprile",
+            "maggio",
+            "giugno",
+            "luglio",
+            "agosto",
+            "settembre",
+            "ottobre",
+            "novembre",
+            "dicembre",
+        ],
+        months_abbr: [
+            "gen", "feb", "mar", "apr", "mag", "giu", "lug", "ago", "set", "ott", "nov", "dic",
+        ],
+        weekdays: [
+            "lunedì",
+            "martedì",
+            "mercoledì",
+            "giovedì",
+            "venerdì",
+            "sabato",
+            "domenica",
+        ],
+        weekdays_abbr: ["lun", "mar", "mer", "gio", "ven", "sab", "dom"],
+        meridiem: ["AM", "PM"],
+        long: "%-d %B %Y %H:%M:%S",
+        short_date: "%d/%m/%Y",
+        time: "%H:%M:%S",
+    };
+
+    pub const PT_BR: Locale = Locale {
+        tag: "pt-BR",
+        months: [
+            "janeiro",
+            "fevereiro",
+            "março",
+            "abril",
+            "maio",
+            "junho",
+            "julho",
+            "agosto",
+            "setembro",
+            "outubro",
+            "novembro",
+            "dezembro",
+        ],
+        months_abbr: [
+            "jan.", "fev.", "mar.", "abr.", "mai.", "jun.", "jul.", "ago.", "set.", "out.", "nov.",
+            "dez.",
+        ],
+        weekdays: [
+            "segunda-feira",
+            "terça-feira",
+            "quarta-feira",
+            "quinta-feira",
+            "sexta-feira",
+            "sábado",
+            "domingo",
+        ],
+        weekdays_abbr: ["seg.", "ter.", "qua.", "qui.", "sex.", "sáb.", "dom."],
+        meridiem: ["AM", "PM"],
+        long: "%-d de %B de %Y %H:%M:%S",
+        short_date: "%d/%m/%Y",
+        time: "%H:%M:%S",
+    };
+
+    pub const NL: Locale = Locale {
+        tag: "nl",
+        months: [
+            "januari",
+            "februari",
+            "maart",
+            "april",
+            "mei",
+            "juni",
+            "juli",
+            "augustus",
+            "september",
+            "oktober",
+            "november",
+            "december",
+        ],
+        months_abbr: [
+            "jan", "feb", "mrt", "apr", "mei", "jun", "jul", "aug", "sep", "okt", "nov", "dec",
+        ],
+        weekdays: [
+            "maandag",
+            "dinsdag",
+            "woensdag",
+            "donderdag",
+            "vrijdag",
+            "zaterdag",
+            "zondag",
+        ],
+        weekdays_abbr: ["ma", "di", "wo", "do", "vr", "za", "zo"],
+        meridiem: ["a.m.", "p.m."],
+        long: "%-d %B %Y %H:%M:%S",
+        short_date: "%d-%m-%Y",
+        time: "%H:%M:%S",
+    };
+
+    pub const JA: Locale = Locale {
+        tag: "ja",
+        months: [
+            "1月", "2月", "3月", "4月", "5月", "6月", "7月", "8月", "9月", "10月", "11月", "12月",
+        ],
+        months_abbr: [
+            "1月", "2月", "3月", "4月", "5月", "6月", "7月", "8月", "9月", "10月", "11月", "12月",
+        ],
+        weekdays: [
+            "月曜日",
+            "火曜日",
+            "水曜日",
+            "木曜日",
+            "金曜日",
+            "土曜日",
+            "日曜日",
+        ],
+        weekdays_abbr: ["月", "火", "水", "木", "金", "土", "日"],
+        meridiem: ["午前", "午後"],
+        long: "%Y年%-m月%-d日 %H:%M:%S",
+        short_date: "%Y/%m/%d",
+        time: "%H:%M:%S",
+    };
+
+    pub const ZH: Locale = Locale {
+        tag: "zh",
+        months: [
+            "一月",
+            "二月",
+            "三月",
+            "四月",
+            "五月",
+            "六月",
+            "七月",
+            "八月",
+            "九月",
+            "十月",
+            "十一月",
+            "十二月",
+        ],
+        months_abbr: [
+            "1月", "2月", "3月", "4月", "5月", "6月", "7月", "8月", "9月", "10月", "11月", "12月",
+        ],
+        weekdays: [
+            "星期一",
+            "星期二",
+            "星期三",
+            "星期四",
+            "星期五",
+            "星期六",
+            "星期日",
+        ],
+        weekdays_abbr: ["周一", "周二", "周三", "周四", "周五", "周六", "周日"],
+        meridiem: ["上午", "下午"],
+        long: "%Y年%-m月%-d日 %H:%M:%S",
+        short_date: "%Y/%m/%d",
+        time: "%H:%M:%S",
+    };
+}
+
+#[cfg(feature = "locales")]
+const LOCALES: &[&Locale] = &[
+    &EN,
+    &EN_GB,
+    &tables::DE,
+    &tables::FR,
+    &tables::ES,
+    &tables::IT,
+    &tables::PT_BR,
+    &tables::NL,
+    &tables::JA,
+    &tables::ZH,
+];
+
+#[cfg(not(feature = "locales"))]
+const LOCALES: &[&Locale] = &[&EN, &EN_GB];
+
+impl Locale {
+    /// Look up a locale by BCP 47 tag, falling back to its language subtag
+    /// (`de-AT` → `de`, `zh-Hans-CN` → `zh`, `pt` → `pt-BR`). `""` means English;
+    /// `en-AU`, `en-NZ` and other day-first English regions use `en-GB`.
+    pub fn get(tag: &str) -> Result<&'static Locale, String> {
+        let tag = tag.trim().replace('_', "-");
+        if tag.is_empty() {
+            return Ok(&EN);
+        }
+        let mut subtags = tag.split('-');
+        let language = subtags.next().unwrap_or_default();
+        if language.eq_ignore_ascii_case("en")
+            && subtags.any(|s| EN_GB_REGIONS.iter().any(|r| r.eq_ignore_ascii_case(s)))
+        {
+            return Ok(&EN_GB);
+        }
+
+        LOCALES
+            .iter()
+            .find(|l| l.tag.eq_ignore_ascii_case(&tag))
+            .or_else(|| {
+                LOCALES.iter().find(|l| {
+                    l.tag
+                        .split('-')
+                        .next()
+                        .is_some_and(|lang| lang.eq_ignore_ascii_case(language))
+                })
+            })
+            .copied()
+            .ok_or_else(|| {
+                if cfg!(feature = "locales") {
+                    format!("Unsupported locale: '{}'", tag)
+                } else {
+                    format!(
+                        "Unsupported locale: '{}' (non-English locales require the `locales` feature)",
+                        tag
+                    )
+                }
+            })
+    }
+
+    /// Tags of every compiled-in locale.
+    pub fn available() -> Vec<&'static str> {
+        LOCALES.iter().map(|l| l.tag).collect()
+    }
+
+    /// Rewrite a strftime pattern so name and locale directives render in this
+    /// locale: `%B %b %h %A %a %p %P %c %x %X` are replaced by literal text or the
+    /// locale's layouts; everything else is left for jiff to format.
+    ///
+    /// Case flags (`%^a`, `%#p`) and widths (`%10B`) apply to the localized name.
+    pub fn localize_pattern(&self, pattern: &str, zdt: &Zoned) -> String {
+        let month = zdt.month() as usize - 1;
+        let weekday = zdt.weekday().to_monday_zero_offset() as usize;
+        let meridiem = self.meridiem[usize::from(zdt.hour() >= 12)];
+
+        let mut out = String::with_capacity(pattern.len());
+        let mut chars = pattern.chars().peekable();
+        while let Some(ch) = chars.next() {
+            if ch != '%' {
+                out.push(ch);
+                continue;
+            }
+            let mut flags = String::new();
+            while let Some(c) = chars.next_if(|c| matches!(c, '-' | '_' | '0' | '^' | '#')) {
+                flags.push(c);
+            }
+            let mut width = String::new();
+            while let Some(c) = chars.next_if(char::is_ascii_digit) {
+                width.push(c);
+            }
+            let name = match chars.peek() {
+                Some('B') => self.months[month].to_string(),
+                Some('b' | 'h') => self.months_abbr[month].to_string(),
+                Some('A') => self.weekdays[weekday].to_string(),
+                Some('a') => self.weekdays_abbr[weekday].to_string(),
+                Some('p') => meridiem.to_string(),
+                Some('P') => meridiem.to_lowercase(),
+                Some(d @ ('c' | 'x' | 'X')) => {
+                    let layout = match d {
+                        'c' => self.localize_pattern(self.long, zdt),
+                        'x' => self.short_date.to_string(),
+                        _ => self.localize_pattern(self.time, zdt),
+                    };
+                    chars.next();
+                    out.push_str(&layout);
+                    continue;
+                }
+                // Keep `%%` and other directives, with their flags, intact for jiff.
+                Some(&next) => {
+                    chars.next();
+                    out.push('%');
+                    out.push_str(&flags);
+                    out.push_str(&width);
+                    out.push(next);
+                    continue;
+                }
+                None => {
+                    out.push('%');
+                    out.push_str(&flags);
+                    out.push_str(&width);
+                    continue;
+                }
+            };
+            chars.next();
+            out.push_str(&escape(&apply_flags(&name, &flags, &width)));
+        }
+        out
+    }
+
+    /// Full name of `month` (1 = January), or `None` outside 1..=12.
+    pub(crate) fn month_name(&self, month: u8) -> Option<&'static str> {
+        self.months.get(usize::from(month).wrapping_sub(1)).copied()
+    }
+
+    /// Full name of a weekday counted from Sunday = 0, as cron numbers them.
+    pub(crate) fn weekday_name(&self, sunday_zero: u8) -> Option<&'static str> {
+        (sunday_zero < 7).then(|| self.weekdays[usize::from((sunday_zero + 6) % 7)])
+    }
+
+    /// The long date-time layout (`localHuman`) of this locale, already localized.
+    pub fn long_pattern(&self, zdt: &Zoned) -> String {
+        self.localize_pattern(self.long, zdt)
+    }
+}
+
+/// Apply strftime flags and width (`^` upper case, `#` swapped case, `0`/`_` pad
+/// character, `-` no padding) to an already localized name.
+fn apply_flags(name: &str, flags: &str, width: &str) -> String {
+    let mut text = if flags.contains('^') {
+        name.to_uppercase()
+    } else if flags.contains('#') {
+        name.chars()
+            .map(|c| {
+                if c.is_uppercase() {
+                    c.to_lowercase().collect::<String>()
+                } else {
+                    c.to_uppercase().collect()
+                }
+            })
+            .collect()
+    } else {
+        name.to_string()
+    };
+    let width: usize = width.parse().unwrap_or(0);
+    let len = text.chars().count();
+    if width > len && !flags.contains('-') {
+        let pad = if flags.contains('0') && !flags.contains('_') {
+            '0'
+        } else {
+            ' '
+        };
+        text.insert_str(0, &pad.to_string().repeat(width - len));
+    }
+    text
+}
+
+/// Escape literal text for inclusion in a strftime pattern.
+fn escape(text: &str) -> String {
+    text.replace('%', "%%")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zoned(s: &str) -> Zoned {
+        s.parse::<jiff::Timestamp>()
+            .unwrap()
+            .to_zoned(jiff::tz::TimeZone::UTC)
+    }
+
+    #[test]
+    fn test_english_matches_local_human_layout() {
+        let zdt = zoned("2024-07-11T13:14:00Z");
+        let pattern = EN.long_pattern(&zdt);
+        assert_eq!(
+            jiff::fmt::strtime::format(&pattern, &zdt).unwrap(),
+            "July 11, 2024 01:14:00 PM"
+        );
+    }
+
+    #[test]
+    fn test_empty_tag_is_english() {
+        assert_eq!(Locale::get("").unwrap().tag, "en");
+        assert_eq!(Locale::get("en-US").unwrap().tag, "en");
+    }
+
+    #[test]
+    fn test_british_english() {
+        assert_eq!(Locale::get("en-GB").unwrap().tag, "en-GB");
+        assert_eq!(Locale::get("en_au").unwrap().tag, "en-GB");
+        assert_eq!(Locale::get("en-NZ").unwrap().tag, "en-GB");
+        let zdt = zoned("2024-07-11T13:14:00Z");
+        let gb = Locale::get("en-GB").unwrap();
+        let pattern = gb.long_pattern(&zdt);
+        assert_eq!(
+            jiff::fmt::strtime::format(&pattern, &zdt).unwrap(),
+            "11 July 2024 13:14:00"
+        );
+        assert_eq!(gb.localize_pattern("%x %p", &zdt), "%d/%m/%Y pm");
+    }
+
+    #[test]
+    fn test_unknown_locale() {
+        assert!(Locale::get("tlh")
+            .unwrap_err()
+            .contains("Unsupported locale"));
+    }
+
+    #[test]
+    fn test_percent_sequences_preserved() {
+        let zdt = zoned("2024-07-11T13:14:00Z");
+        assert_eq!(EN.localize_pattern("100%% %-d %B", &zdt), "100%% %-d July");
+    }
+
+    #[cfg(feature = "locales")]
+    #[test]
+    fn test_tag_fallbacks() {
+        assert_eq!(Locale::get("de-AT").unwrap().tag, "de");
+        assert_eq!(Locale::get("pt").unwrap().tag, "pt-BR");
+        assert_eq!(Locale::get("pt_br").unwrap().tag, "pt-BR");
+        assert_eq!(Locale::get("zh-Hans-CN").unwrap().tag, "zh");
+    }
+
+    #[cfg(feature = "locales")]
+    #[test]
+    fn test_localized_names() {
+        let zdt = zoned("2024-03-14T09:05:00Z");
+        let de = Locale::get("de").unwrap();
+        assert_eq!(de.localize_pattern("%A, %B", &zdt), "Donnerstag, März");
+        let ja = Locale::get("ja").unwrap();
+        assert_eq!(ja.localize_pattern("%a %p", &zdt), "木 午前");
+        let fr = Locale::get("fr").unwrap();
+        assert_eq!(fr.localize_pattern("%x", &zdt), "%d/%m/%Y");
+    }
+
+    #[cfg(feature = "locales")]
+    #[test]
+    fn test_flags_apply_to_localized_names() {
+        let zdt = zoned("2024-07-11T13:14:00Z");
+        let de = Locale::get("de").unwrap();
+        assert_eq!(de.localize_pattern("%^a %^B", &zdt), "DO. JULI");
+        assert_eq!(
+            de.localize_pattern("%10B|%-B|%#b", &zdt),
+            "      Juli|Juli|jULI"
+        );
+        let es = Locale::get("es").unwrap();
+        assert_eq!(es.localize_pattern("%P %^p", &zdt), "p. m. P. M.");
+        assert_eq!(es.localize_pattern("%-d %:z %.3f", &zdt), "%-d %:z %.3f");
+    }
+}
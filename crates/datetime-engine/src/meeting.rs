@@ -0,0 +1,342 @@
+//! Meeting planner: common working hours of participants in different zones.
+//!
+//! Each participant's working hours are wall-clock times in their own zone, so the
+//! shared slots move when one side changes to or from DST and the other does not yet
+//! (the US and Europe switch on different dates; the southern hemisphere goes the
+//! other way).
+
+use jiff::civil::{Date, Time};
+use jiff::tz::TimeZone;
+use jiff::{Timestamp, ToSpan};
+
+use crate::calc::parse_start_date_in;
+use crate::interval::{self, Interval};
+use crate::json_utils::{
+    extract_array_field, extract_number_field, extract_string_field, json_escape,
+    parse_string_array, split_object_array,
+};
+use crate::oncalendar::parse_weekdays;
+use crate::zone::{self, resolve_zone, Disambiguation};
+
+/// Longest window a plan may cover.
+pub const MAX_PLANNER_DAYS: i64 = 366;
+
+/// One person's zone and working hours.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Participant {
+    pub name: String,
+    pub zone: TimeZone,
+    pub start: Time,
+    /// End of the working day; at or before `start` means it ends the next day
+    /// (night shifts, or `24:00`)
+    pub end: Time,
+    /// Monday-first bitmask of working days, as from `oncalendar::parse_weekdays`
+    pub days: u8,
+    /// Local dates on which the participant does not work
+    pub holidays: Vec<Date>,
+}
+
+impl Participant {
+    /// Parse one participant object, e.g.
+    /// `{"name":"Ana","zone":"Europe/Lisbon","start":"09:00","end":"17:30","days":"Mon..Thu","holidays":["2024-06-13"]}`.
+    ///
+    /// Only `zone` is required; hours default to 09:00–17:00, days to `Mon..Fri` and the
+    /// name to `Participant <n>` (1-based `index + 1`).
+    pub fn from_json(json: &str, index: usize) -> Result<Self, String> {
+        let zone = extract_string_field(json, "zone")
+            .ok_or_else(|| "Missing 'zone' field in participant".to_string())?;
+        let time = |key: &str, default: Time| -> Result<Time, String> {
+            match extract_string_field(json, key) {
+                None => Ok(default),
+                Some(text) if text.trim() == "24:00" => Ok(Time::midnight()),
+                Some(text) => text
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("Invalid '{}' time '{}' (expected HH:MM)", key, text)),
+            }
+        };
+        let holidays = match extract_array_field(json, "holidays") {
+            Some(array) => parse_string_array(&array)?
+                .iter()
+                .map(|d| {
+                    d.trim()
+                        .parse::<Date>()
+                        .map_err(|_| format!("Invalid holiday date '{}'", d))
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            None => Vec::new(),
+        };
+        Ok(Participant {
+            name: extract_string_field(json, "name")
+                .unwrap_or_else(|| format!("Participant {}", index + 1)),
+            zone: resolve_zone(&zone)?,
+            start: time("start", Time::constant(9, 0, 0, 0))?,
+            end: time("end", Time::constant(17, 0, 0, 0))?,
+            days: parse_weekdays(
+                &extract_string_field(json, "days").unwrap_or_else(|| "Mon..Fri".to_string()),
+            )?,
+            holidays,
+        })
+    }
+
+    fn works_on(&self, date: Date) -> bool {
+        let day = date.weekday().to_monday_zero_offset();
+        self.days & (1 << day) != 0 && !self.holidays.contains(&date)
+    }
+
+    /// Working time within `window`, as sorted disjoint intervals.
+    ///
+    /// Shifts that start on a holiday or non-working day are dropped whole. Hours skipped
+    /// by a DST gap are simply lost; a shift edge inside a gap moves forward.
+    pub fn availability(&self, window: Interval) -> Result<Vec<Interval>, String> {
+        let mut date = window
+            .start
+            .to_zoned(self.zone.clone())
+            .date()
+            .yesterday()
+            .map_err(|e| e.to_string())?;
+        let last = window.end.to_zoned(self.zone.clone()).date();
+        let mut shifts = Vec::new();
+        while date <= last {
+            if self.works_on(date) {
+                let end_date = if self.end <= self.start {
+                    date.tomorrow().map_err(|e| e.to_string())?
+                } else {
+                    date
+                };
+                let start = zone::resolve_local(
+                    date.to_datetime(self.start),
+                    &self.zone,
+                    Disambiguation::Compatible,
+                )?;
+                let end = zone::resolve_local(
+                    end_date.to_datetime(self.end),
+                    &self.zone,
+                    Disambiguation::Compatible,
+                )?;
+                if let Some(shift) = Interval::new(start, end)
+                    .ok()
+                    .and_then(|shift| shift.intersection(&window))
+                {
+                    shifts.push(shift);
+                }
+            }
+            date = date.tomorrow().map_err(|e| e.to_string())?;
+        }
+        Ok(interval::merge(&shifts))
+    }
+}
+
+/// Slots in which every participant is working.
+#[derive(Debug, PartialEq)]
+pub struct MeetingPlan {
+    pub window: Interval,
+    pub participants: Vec<Participant>,
+    /// Shorter common stretches are left out
+    pub min_seconds: i64,
+    pub slots: Vec<Interval>,
+}
+
+/// Find the common working time of `participants` within `window`.
+pub fn plan(
+    participants: Vec<Participant>,
+    window: Interval,
+    min_seconds: i64,
+) -> Result<MeetingPlan, String> {
+    if participants.is_empty() {
+        return Err("At least one participant is needed".to_string());
+    }
+    if window.seconds() > MAX_PLANNER_DAYS * 86_400 {
+        return Err(format!(
+            "The window may cover at most {} days",
+            MAX_PLANNER_DAYS
+        ));
+    }
+    let mut slots = vec![window];
+    for participant in &participants {
+        slots = interval::intersect(&slots, &participant.availability(window)?);
+    }
+    slots.retain(|slot| slot.seconds() >= min_seconds);
+    Ok(MeetingPlan {
+        window,
+        participants,
+        min_seconds,
+        slots,
+    })
+}
+
+impl MeetingPlan {
+    /// Serialize to JSON string for Wasm boundary (manual serialization).
+    ///
+    /// Each slot lists its bounds on every participant's wall clock, with offsets, so a
+    /// DST change on one side is visible as a shifted local time.
+    pub fn to_json(&self) -> String {
+        let local = |ts: Timestamp, tz: &TimeZone| {
+            ts.to_zoned(tz.clone())
+                .strftime("%Y-%m-%dT%H:%M:%S%:z")
+                .to_string()
+        };
+        let slots: Vec<String> = self
+            .slots
+            .iter()
+            .map(|slot| {
+                let locals: Vec<String> = self
+                    .participants
+                    .iter()
+                    .map(|p| {
+                        format!(
+                            r#"{{"name":"{}","start":"{}","end":"{}"}}"#,
+                            json_escape(&p.name),
+                            local(slot.start, &p.zone),
+                            local(slot.end, &p.zone)
+                        )
+                    })
+                    .collect();
+                format!(
+                    r#"{{"start":"{}","end":"{}","seconds":{},"local":[{}]}}"#,
+                    slot.start,
+                    slot.end,
+                    slot.seconds(),
+                    locals.join(",")
+                )
+            })
+            .collect();
+        let participants: Vec<String> = self
+            .participants
+            .iter()
+            .map(|p| {
+                format!(
+                    r#"{{"name":"{}","zone":"{}"}}"#,
+                    json_escape(&p.name),
+                    json_escape(&zone::zone_name(&p.zone))
+                )
+            })
+            .collect();
+        format!(
+            r#"{{"start":"{}","end":"{}","minMinutes":{},"participants":[{}],"slotCount":{},"totalSeconds":{},"slots":[{}]}}"#,
+            self.window.start,
+            self.window.end,
+            self.min_seconds / 60,
+            participants.join(","),
+            self.slots.len(),
+            self.slots.iter().map(|s| s.seconds()).sum::<i64>(),
+            slots.join(",")
+        )
+    }
+}
+
+/// Common working-hour slots of the participants in `participants_json` over
+/// `[start, end)`; see `Participant::from_json` for the object layout.
+///
+/// `options_json` is an optional JSON object: `zone` for civil `start`/`end` (UTC by
+/// default) and `minMinutes`, the shortest slot kept (30 by default). An empty `end`
+/// means one week after `start`.
+pub fn meeting_slots(
+    participants_json: &str,
+    start: &str,
+    end: &str,
+    options_json: &str,
+) -> Result<MeetingPlan, String> {
+    let participants = split_object_array(participants_json)?
+        .iter()
+        .enumerate()
+        .map(|(i, json)| {
+            Participant::from_json(json, i).map_err(|e| format!("Participant {}: {}", i + 1, e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let tz = match extract_string_field(options_json, "zone") {
+        Some(zone) => resolve_zone(&zone)?,
+        None => jiff::tz::TimeZone::UTC,
+    };
+    let min_minutes = extract_number_field(options_json, "minMinutes").unwrap_or(30);
+    if min_minutes < 0 {
+        return Err(format!(
+            "'minMinutes' must not be negative, got {}",
+            min_minutes
+        ));
+    }
+
+    let start = parse_start_date_in(start, &tz, Disambiguation::Compatible)?;
+    let end = if end.trim().is_empty() {
+        start
+            .to_zoned(tz.clone())
+            .checked_add(1.week())
+            .map_err(|e| format!("Window end out of range: {}", e))?
+            .timestamp()
+    } else {
+        parse_start_date_in(end, &tz, Disambiguation::Compatible)?
+    };
+    if end <= start {
+        return Err("Window end must be after its start".to_string());
+    }
+    plan(participants, Interval::new(start, end)?, min_minutes * 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(start: &str, end: &str) -> Interval {
+        Interval::new(start.parse().unwrap(), end.parse().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_participant_defaults() {
+        let p = Participant::from_json(r#"{"zone":"Europe/Berlin"}"#, 2).unwrap();
+        assert_eq!(p.name, "Participant 3");
+        assert_eq!(
+            (p.start, p.end),
+            (Time::constant(9, 0, 0, 0), Time::constant(17, 0, 0, 0))
+        );
+        assert_eq!(p.days, 0b0011111);
+        assert!(Participant::from_json(r#"{"name":"x"}"#, 0).is_err());
+        assert!(Participant::from_json(r#"{"zone":"UTC","start":"9am"}"#, 0).is_err());
+    }
+
+    #[test]
+    fn test_night_shift_and_holidays() {
+        let p = Participant::from_json(
+            r#"{"zone":"UTC","start":"22:00","end":"06:00","days":"Mon..Sun","holidays":["2024-05-02"]}"#,
+            0,
+        )
+        .unwrap();
+        let shifts = p
+            .availability(window("2024-05-01T00:00:00Z", "2024-05-04T00:00:00Z"))
+            .unwrap();
+        let shifts: Vec<String> = shifts
+            .iter()
+            .map(|s| format!("{}/{}", s.start, s.end))
+            .collect();
+        assert_eq!(
+            shifts,
+            vec![
+                "2024-05-01T00:00:00Z/2024-05-01T06:00:00Z",
+                "2024-05-01T22:00:00Z/2024-05-02T06:00:00Z",
+                "2024-05-03T22:00:00Z/2024-05-04T00:00:00Z"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_min_duration_and_window_limit() {
+        let a =
+            Participant::from_json(r#"{"zone":"UTC","start":"09:00","end":"10:00"}"#, 0).unwrap();
+        let b =
+            Participant::from_json(r#"{"zone":"UTC","start":"09:45","end":"12:00"}"#, 1).unwrap();
+        let day = window("2024-05-06T00:00:00Z", "2024-05-07T00:00:00Z");
+        assert_eq!(
+            plan(vec![a.clone(), b.clone()], day, 15 * 60)
+                .unwrap()
+                .slots
+                .len(),
+            1
+        );
+        assert!(plan(vec![a.clone(), b], day, 30 * 60)
+            .unwrap()
+            .slots
+            .is_empty());
+        let long = window("2024-01-01T00:00:00Z", "2025-06-01T00:00:00Z");
+        assert!(plan(vec![a], long, 0).unwrap_err().contains("at most"));
+        assert!(plan(Vec::new(), day, 0).is_err());
+    }
+}
@@ -0,0 +1,634 @@
+use jiff::fmt::strtime;
+use jiff::tz::TimeZone;
+use jiff::Timestamp;
+
+use crate::calc::parse_start_date;
+use crate::cron::{
+    CronDialect, CronOptions, CronRun, CronSchedule, DaySpecial, DstPolicy, FieldSet, MAX_CRON_RUNS,
+};
+use crate::json_utils::json_escape;
+use crate::zone::{resolve_zone, zone_name};
+
+const WEEKDAY_NAMES: [(&str, &str); 7] = [
+    ("mon", "monday"),
+    ("tue", "tuesday"),
+    ("wed", "wednesday"),
+    ("thu", "thursday"),
+    ("fri", "friday"),
+    ("sat", "saturday"),
+    ("sun", "sunday"),
+];
+const WEEKDAY_ABBR: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// The systemd.time(7) shorthands and the expressions they stand for.
+const SHORTHANDS: [(&str, &str); 9] = [
+    ("minutely", "*-*-* *:*:00"),
+    ("hourly", "*-*-* *:00:00"),
+    ("daily", "*-*-* 00:00:00"),
+    ("monthly", "*-*-01 00:00:00"),
+    ("weekly", "Mon *-*-* 00:00:00"),
+    ("yearly", "*-01-01 00:00:00"),
+    ("annually", "*-01-01 00:00:00"),
+    ("quarterly", "*-01,04,07,10-01 00:00:00"),
+    ("semiannually", "*-01,07-01 00:00:00"),
+];
+
+/// One element of a comma-separated component: `5`, `1..5`, `0/15` or `1..20/5`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Component {
+    start: i32,
+    stop: Option<i32>,
+    repeat: Option<i32>,
+}
+
+impl Component {
+    fn matches(self, value: i32) -> bool {
+        if value < self.start || self.stop.is_some_and(|stop| value > stop) {
+            return false;
+        }
+        match self.repeat {
+            Some(repeat) => (value - self.start) % repeat == 0,
+            None => value <= self.stop.unwrap_or(self.start),
+        }
+    }
+
+    /// Match a `~` day, counted back from the end of the month (1 = last day).
+    /// Repetitions move towards the month's end: `~07/1` is the last seven days.
+    fn matches_from_end(self, from_end: i32) -> bool {
+        match (self.stop, self.repeat) {
+            (_, Some(repeat)) => {
+                from_end <= self.start
+                    && (self.start - from_end) % repeat == 0
+                    && self.stop.is_none_or(|stop| from_end >= stop)
+            }
+            (Some(stop), None) => (self.start.min(stop)..=self.start.max(stop)).contains(&from_end),
+            (None, None) => from_end == self.start,
+        }
+    }
+
+    fn format(self, width: usize) -> String {
+        let mut out = format!("{:0width$}", self.start);
+        if let Some(stop) = self.stop {
+            out.push_str(&format!("..{:0width$}", stop));
+        }
+        if let Some(repeat) = self.repeat {
+            out.push_str(&format!("/{}", repeat));
+        }
+        out
+    }
+}
+
+/// A comma-separated list of components; `None` is `*`.
+type Chain = Option<Vec<Component>>;
+
+fn chain_matches(chain: &Chain, value: i32) -> bool {
+    chain
+        .as_ref()
+        .is_none_or(|items| items.iter().any(|c| c.matches(value)))
+}
+
+fn format_chain(chain: &Chain, width: usize) -> String {
+    match chain {
+        None => "*".to_string(),
+        Some(items) => items
+            .iter()
+            .map(|c| c.format(width))
+            .collect::<Vec<_>>()
+            .join(","),
+    }
+}
+
+/// A systemd calendar event expression, as used by `OnCalendar=` in timer units.
+///
+/// The full form is `[weekdays] [year-month-day] [hour:minute[:second]] [zone]`:
+/// weekdays are names or ranges (`Mon..Fri`, `Sat,Sun`); date and time components
+/// take `*`, lists (`1,15`), ranges (`1..5`) and repetitions (`0/15`); `~` counts
+/// days back from the end of the month (`*-02~03`, `Mon *-05~07/1`). A missing
+/// date means every day, a missing time midnight, a missing second `00`. The
+/// shorthands `minutely` through `semiannually` are expanded first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarEvent {
+    pub original: String,
+    /// Monday-first bitmask (bit 0 = Monday); 0 means any day
+    weekdays: u8,
+    year: Chain,
+    month: Chain,
+    day: Chain,
+    /// The day component was written with `~`
+    end_of_month: bool,
+    hour: Chain,
+    minute: Chain,
+    second: Chain,
+    /// The zone named at the end of the expression, if any
+    pub zone: Option<TimeZone>,
+}
+
+impl CalendarEvent {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let original = expression.trim();
+        if original.is_empty() {
+            return Err("Empty calendar expression".to_string());
+        }
+        let mut tokens: Vec<&str> = original.split_whitespace().collect();
+
+        if let Some((_, expanded)) = SHORTHANDS
+            .iter()
+            .find(|(name, _)| tokens[0].eq_ignore_ascii_case(name))
+        {
+            let mut expanded: Vec<&str> = expanded.split_whitespace().collect();
+            expanded.extend(&tokens[1..]);
+            tokens = expanded;
+        }
+
+        let mut zone = None;
+        if let Some(last) = tokens.last() {
+            let alphabetic = last.starts_with(|c: char| c.is_ascii_alphabetic());
+            if alphabetic && parse_weekdays(last).is_err() {
+                zone = Some(resolve_zone(last)?);
+                tokens.pop();
+            }
+        }
+
+        let mut weekdays = 0;
+        if tokens
+            .first()
+            .is_some_and(|t| t.starts_with(|c: char| c.is_ascii_alphabetic()))
+        {
+            weekdays = parse_weekdays(tokens[0])?;
+            tokens.remove(0);
+        }
+
+        let (mut date, mut time) = (None, None);
+        for token in tokens {
+            let slot = if token.contains(':') {
+                &mut time
+            } else {
+                &mut date
+            };
+            if slot.replace(token).is_some() {
+                return Err(format!(
+                    "Unexpected '{}' in calendar expression '{}'",
+                    token, original
+                ));
+            }
+        }
+
+        let mut event = CalendarEvent {
+            original: original.to_string(),
+            weekdays,
+            year: None,
+            month: None,
+            day: None,
+            end_of_month: false,
+            hour: Some(vec![single(0)]),
+            minute: Some(vec![single(0)]),
+            second: Some(vec![single(0)]),
+            zone,
+        };
+        if let Some(date) = date {
+            event.parse_date(date)?;
+        }
+        if let Some(time) = time {
+            event.parse_time(time)?;
+        }
+        Ok(event)
+    }
+
+    /// `year-month-day`, `month-day`, or either with `~` before the day.
+    fn parse_date(&mut self, date: &str) -> Result<(), String> {
+        let (rest, day, end_of_month) = match date.split_once('~') {
+            Some((rest, day)) => (rest, day, true),
+            None => match date.rsplit_once('-') {
+                Some((rest, day)) => (rest, day, false),
+                None => return Err(format!("Invalid date '{}': expected year-month-day", date)),
+            },
+        };
+        let parts: Vec<&str> = rest.split('-').collect();
+        let (year, month) = match parts.as_slice() {
+            [year, month] => (Some(*year), *month),
+            [month] => (None, *month),
+            _ => return Err(format!("Invalid date '{}': expected year-month-day", date)),
+        };
+        if let Some(year) = year {
+            self.year = parse_chain(year, "year", 1970, 2199)?;
+        }
+        self.month = parse_chain(month, "month", 1, 12)?;
+        self.day = parse_chain(day, "day", 1, 31)?;
+        self.end_of_month = end_of_month;
+        Ok(())
+    }
+
+    /// `hour:minute` or `hour:minute:second`.
+    fn parse_time(&mut self, time: &str) -> Result<(), String> {
+        let parts: Vec<&str> = time.split(':').collect();
+        let (hour, minute, second) = match parts.as_slice() {
+            [hour, minute] => (*hour, *minute, "00"),
+            [hour, minute, second] => (*hour, *minute, *second),
+            _ => {
+                return Err(format!(
+                    "Invalid time '{}': expected hour:minute[:second]",
+                    time
+                ))
+            }
+        };
+        if second.contains('.') {
+            return Err(format!(
+                "Invalid time '{}': fractional seconds are not supported",
+                time
+            ));
+        }
+        self.hour = parse_chain(hour, "hour", 0, 23)?;
+        self.minute = parse_chain(minute, "minute", 0, 59)?;
+        self.second = parse_chain(second, "second", 0, 59)?;
+        Ok(())
+    }
+
+    /// The expression in the form `systemd-analyze calendar` prints as "Normalized form".
+    pub fn normalized(&self) -> String {
+        let mut out = String::new();
+        if self.weekdays != 0 {
+            out.push_str(&format_weekdays(self.weekdays));
+            out.push(' ');
+        }
+        out.push_str(&format!(
+            "{}-{}{}{} {}:{}:{}",
+            format_chain(&self.year, 4),
+            format_chain(&self.month, 2),
+            if self.end_of_month { '~' } else { '-' },
+            format_chain(&self.day, 2),
+            format_chain(&self.hour, 2),
+            format_chain(&self.minute, 2),
+            format_chain(&self.second, 2),
+        ));
+        if let Some(zone) = &self.zone {
+            out.push(' ');
+            out.push_str(&zone_name(zone));
+        }
+        out
+    }
+
+    /// Lower the event onto the cron engine so both share one run search.
+    pub(crate) fn to_schedule(&self) -> CronSchedule {
+        let set = |chain: &Chain, min: u8, max: u8| {
+            FieldSet::from_fn(min, max, |v| chain_matches(chain, i32::from(v)))
+        };
+        let (days_of_month, dom_specials) = match (&self.day, self.end_of_month) {
+            (Some(items), true) => (
+                FieldSet::default(),
+                (1..=31u8)
+                    .filter(|from_end| {
+                        items
+                            .iter()
+                            .any(|c| c.matches_from_end(i32::from(*from_end)))
+                    })
+                    .map(|from_end| DaySpecial::LastDay(from_end - 1))
+                    .collect(),
+            ),
+            _ => (set(&self.day, 1, 31), Vec::new()),
+        };
+        // systemd numbers weekdays from Monday; the cron engine from Sunday.
+        let days_of_week = FieldSet::from_fn(0, 6, |sunday_zero| {
+            self.weekdays == 0 || self.weekdays & (1 << ((sunday_zero + 6) % 7)) != 0
+        });
+        CronSchedule {
+            expression: self.original.clone(),
+            dialect: CronDialect::Standard,
+            seconds: set(&self.second, 0, 59),
+            minutes: set(&self.minute, 0, 59),
+            hours: set(&self.hour, 0, 23),
+            days_of_month,
+            months: set(&self.month, 1, 12),
+            days_of_week,
+            dom_specials,
+            dow_specials: Vec::new(),
+            years: self.year.as_ref().map(|_| {
+                (1970..=2199)
+                    .filter(|y| chain_matches(&self.year, i32::from(*y)))
+                    .collect()
+            }),
+            // Weekday and date must both match in systemd, never either one as in
+            // cron's day-of-month/day-of-week rule.
+            dom_restricted: false,
+            dow_restricted: false,
+            wildcard_time: self.hour.is_none() || self.minute.is_none(),
+        }
+    }
+
+    /// Up to `count` elapses strictly after `after`.
+    ///
+    /// The expression's own zone wins over `default_zone` (systemd's local zone).
+    /// Times skipped by a DST gap never elapse, as in systemd (`DstPolicy::Systemd`).
+    pub fn next_elapses(
+        &self,
+        after: Timestamp,
+        count: usize,
+        default_zone: &TimeZone,
+    ) -> CalendarRuns {
+        let zone = self.zone.clone().unwrap_or_else(|| default_zone.clone());
+        CalendarRuns {
+            original: self.original.clone(),
+            normalized: self.normalized(),
+            runs: self
+                .to_schedule()
+                .next_runs(after, count, &zone, DstPolicy::Systemd),
+            zone,
+        }
+    }
+}
+
+fn single(value: i32) -> Component {
+    Component {
+        start: value,
+        stop: None,
+        repeat: None,
+    }
+}
+
+/// Parse a comma-separated date or time component.
+fn parse_chain(raw: &str, name: &str, min: i32, max: i32) -> Result<Chain, String> {
+    if raw == "*" {
+        return Ok(None);
+    }
+    let number = |s: &str| -> Result<i32, String> {
+        s.parse::<i32>()
+            .ok()
+            .filter(|v| (min..=max).contains(v))
+            .ok_or_else(|| format!("Invalid {} '{}': expected {}-{}", name, s, min, max))
+    };
+    let mut items = Vec::new();
+    for item in raw.split(',') {
+        let (base, repeat) = match item.split_once('/') {
+            Some((base, repeat)) => {
+                let repeat = repeat
+                    .parse::<i32>()
+                    .ok()
+                    .filter(|r| *r > 0)
+                    .ok_or_else(|| format!("Invalid {} repetition '{}'", name, repeat))?;
+                (base, Some(repeat))
+            }
+            None => (item, None),
+        };
+        let (start, stop) = if base == "*" && repeat.is_some() {
+            (min, None)
+        } else if let Some((a, b)) = base.split_once("..") {
+            (number(a)?, Some(number(b)?))
+        } else {
+            (number(base)?, None)
+        };
+        if stop.is_some_and(|stop| stop < start) {
+            return Err(format!(
+                "Invalid {} range '{}': end is before start",
+                name, base
+            ));
+        }
+        items.push(Component {
+            start,
+            stop,
+            repeat,
+        });
+    }
+    items.sort();
+    items.dedup();
+    Ok(Some(items))
+}
+
+/// Parse `Mon`, `Monday`, `Mon..Fri`, `Mon-Fri` and comma lists into a Monday-first bitmask.
+pub(crate) fn parse_weekdays(raw: &str) -> Result<u8, String> {
+    let day = |name: &str| -> Result<u8, String> {
+        let lower = name.to_ascii_lowercase();
+        WEEKDAY_NAMES
+            .iter()
+            .position(|(abbr, full)| lower == *abbr || lower == *full)
+            .map(|i| i as u8)
+            .ok_or_else(|| format!("Invalid weekday '{}'", name))
+    };
+    let mut bits = 0u8;
+    for item in raw.split(',').filter(|i| !i.is_empty()) {
+        let range = item.split_once("..").or_else(|| item.split_once('-'));
+        let (start, end) = match range {
+            Some((a, b)) => (day(a)?, day(b)?),
+            None => (day(item)?, day(item)?),
+        };
+        if end < start {
+            return Err(format!(
+                "Invalid weekday range '{}': end is before start",
+                item
+            ));
+        }
+        for d in start..=end {
+            bits |= 1 << d;
+        }
+    }
+    if bits == 0 {
+        return Err(format!("Invalid weekday list '{}'", raw));
+    }
+    Ok(bits)
+}
+
+/// Format weekdays as systemd does: runs of three or more as `Mon..Wed`, pairs as `Mon,Tue`.
+fn format_weekdays(bits: u8) -> String {
+    let mut parts = Vec::new();
+    let mut d = 0;
+    while d < 7 {
+        if bits & (1 << d) == 0 {
+            d += 1;
+            continue;
+        }
+        let start = d;
+        while d + 1 < 7 && bits & (1 << (d + 1)) != 0 {
+            d += 1;
+        }
+        parts.push(match d - start {
+            0 => WEEKDAY_ABBR[start].to_string(),
+            1 => format!("{},{}", WEEKDAY_ABBR[start], WEEKDAY_ABBR[d]),
+            _ => format!("{}..{}", WEEKDAY_ABBR[start], WEEKDAY_ABBR[d]),
+        });
+        d += 1;
+    }
+    parts.join(",")
+}
+
+/// Next elapses of a calendar event, serialized for the Wasm boundary.
+#[derive(Debug, PartialEq)]
+pub struct CalendarRuns {
+    pub original: String,
+    pub normalized: String,
+    pub zone: TimeZone,
+    pub runs: Vec<CronRun>,
+}
+
+impl CalendarRuns {
+    /// Serialize to JSON string for Wasm boundary (manual serialization).
+    ///
+    /// `display` / `displayUtc` match the "Next elapse" / "(in UTC)" lines of
+    /// `systemd-analyze calendar`, e.g. `Mon 2024-07-15 09:00:00 UTC`.
+    pub fn to_json(&self) -> String {
+        let display = |ts: Timestamp, tz: &TimeZone| {
+            strtime::format("%a %Y-%m-%d %H:%M:%S %Z", &ts.to_zoned(tz.clone())).unwrap_or_default()
+        };
+        let runs: Vec<String> = self
+            .runs
+            .iter()
+            .map(|run| {
+                let mut json = format!(
+                    r#"{{"iso":"{}","unixTimestamp":{},"local":"{}","display":"{}","displayUtc":"{}""#,
+                    run.timestamp,
+                    run.timestamp.as_second(),
+                    json_escape(&run.timestamp.to_zoned(self.zone.clone()).to_string()),
+                    json_escape(&display(run.timestamp, &self.zone)),
+                    json_escape(&display(run.timestamp, &TimeZone::UTC)),
+                );
+                if let Some(effect) = run.dst {
+                    json.push_str(&format!(r#","dst":"{}""#, effect.name()));
+                }
+                json.push('}');
+                json
+            })
+            .collect();
+        format!(
+            r#"{{"original":"{}","normalized":"{}","zone":"{}","runs":[{}]}}"#,
+            json_escape(&self.original),
+            json_escape(&self.normalized),
+            json_escape(&zone_name(&self.zone)),
+            runs.join(",")
+        )
+    }
+}
+
+/// List up to `count` elapses of a systemd `OnCalendar=` expression after `start`.
+///
+/// `options_json` takes the same `zone` key as `cron_runs`; the zone stands in for the
+/// machine's local zone when the expression does not name one. DST gaps and folds are
+/// always handled as systemd does (see `DstPolicy::Systemd`).
+pub fn oncalendar_runs(
+    expression: &str,
+    start: &str,
+    count: u32,
+    options_json: &str,
+) -> Result<CalendarRuns, String> {
+    let options = CronOptions::from_json(options_json)?;
+    let event = CalendarEvent::parse(expression)?;
+    let start = parse_start_date(start)?;
+    let count = (count as usize).min(MAX_CRON_RUNS);
+    Ok(event.next_elapses(start, count, &options.zone))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalized(expression: &str) -> String {
+        CalendarEvent::parse(expression).unwrap().normalized()
+    }
+
+    fn next(expression: &str, after: &str, count: usize) -> Vec<String> {
+        let event = CalendarEvent::parse(expression).unwrap();
+        let after: Timestamp = after.parse().unwrap();
+        event
+            .next_elapses(after, count, &TimeZone::UTC)
+            .runs
+            .iter()
+            .map(|run| run.timestamp.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_normalized_forms() {
+        // Cases from systemd's own calendar spec tests
+        assert_eq!(
+            normalized("Sat,Thu,Mon-Wed,Sat-Sun"),
+            "Mon..Thu,Sat,Sun *-*-* 00:00:00"
+        );
+        assert_eq!(normalized("Wed *-1"), "Wed *-*-01 00:00:00");
+        assert_eq!(normalized("*-*-7 0:0:0"), "*-*-07 00:00:00");
+        assert_eq!(normalized("10-15"), "*-10-15 00:00:00");
+        assert_eq!(normalized("monday *-12-* 17:00"), "Mon *-12-* 17:00:00");
+        assert_eq!(
+            normalized("Mon,Fri *-*-3,1,2 *:30:45"),
+            "Mon,Fri *-*-01,02,03 *:30:45"
+        );
+        assert_eq!(
+            normalized("12,14,13,12:20,10,30"),
+            "*-*-* 12,13,14:10,20,30:00"
+        );
+        assert_eq!(normalized("12..14:10,20,30"), "*-*-* 12..14:10,20,30:00");
+        assert_eq!(
+            normalized("mon,fri *-1/2-1,3 *:30:45"),
+            "Mon,Fri *-01/2-01,03 *:30:45"
+        );
+        assert_eq!(normalized("03-05 08:05:40"), "*-03-05 08:05:40");
+        assert_eq!(normalized("2003-03-05"), "2003-03-05 00:00:00");
+        assert_eq!(normalized("*:2/3"), "*-*-* *:02/3:00");
+        assert_eq!(normalized("*-*~1"), "*-*~01 00:00:00");
+    }
+
+    #[test]
+    fn test_shorthands() {
+        assert_eq!(normalized("hourly"), "*-*-* *:00:00");
+        assert_eq!(normalized("weekly"), "Mon *-*-* 00:00:00");
+        assert_eq!(normalized("quarterly"), "*-01,04,07,10-01 00:00:00");
+        assert_eq!(normalized("daily UTC"), "*-*-* 00:00:00 UTC");
+    }
+
+    #[test]
+    fn test_zone_suffix() {
+        let event = CalendarEvent::parse("*-*-01 00:00:00 Europe/Berlin").unwrap();
+        assert_eq!(event.normalized(), "*-*-01 00:00:00 Europe/Berlin");
+        assert!(CalendarEvent::parse("daily Mars/Olympus").is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(CalendarEvent::parse("").is_err());
+        assert!(CalendarEvent::parse("Mon..Fri *-13-* 09:00").is_err());
+        assert!(CalendarEvent::parse("Fri..Mon").is_err());
+        assert!(CalendarEvent::parse("*-*-* 25:00").is_err());
+        assert!(CalendarEvent::parse("*-*-* 10:00:00.5").is_err());
+        assert!(CalendarEvent::parse("*-*-* 10:00 11:00").is_err());
+    }
+
+    #[test]
+    fn test_next_weekdays() {
+        assert_eq!(
+            next("Mon..Fri *-*-* 09:00:00", "2024-07-12T10:00:00Z", 2),
+            vec!["2024-07-15T09:00:00Z", "2024-07-16T09:00:00Z"]
+        );
+    }
+
+    #[test]
+    fn test_next_end_of_month() {
+        // Last Monday in May
+        assert_eq!(
+            next("Mon *-05~07/1", "2024-01-01T00:00:00Z", 2),
+            vec!["2024-05-27T00:00:00Z", "2025-05-26T00:00:00Z"]
+        );
+        // Third-to-last day of February
+        assert_eq!(
+            next("*-02~03", "2024-01-01T00:00:00Z", 2),
+            vec!["2024-02-27T00:00:00Z", "2025-02-26T00:00:00Z"]
+        );
+    }
+
+    #[test]
+    fn test_next_with_year_and_repetition() {
+        assert_eq!(
+            next("2025/2-01-01", "2024-01-01T00:00:00Z", 2),
+            vec!["2025-01-01T00:00:00Z", "2027-01-01T00:00:00Z"]
+        );
+        assert_eq!(
+            next("*:0/20", "2024-01-01T00:05:00Z", 2),
+            vec!["2024-01-01T00:20:00Z", "2024-01-01T00:40:00Z"]
+        );
+    }
+
+    #[test]
+    fn test_zone_and_display() {
+        let event = CalendarEvent::parse("*-*-01 00:00:00 Europe/Berlin").unwrap();
+        let after: Timestamp = "2024-07-11T00:00:00Z".parse().unwrap();
+        let runs = event.next_elapses(after, 1, &TimeZone::UTC);
+        let json = runs.to_json();
+        assert!(json.contains(r#""iso":"2024-07-31T22:00:00Z""#));
+        assert!(json.contains(r#""display":"Thu 2024-08-01 00:00:00 CEST""#));
+        assert!(json.contains(r#""displayUtc":"Wed 2024-07-31 22:00:00 UTC""#));
+    }
+}
@@ -0,0 +1,832 @@
+use jiff::tz::TimeZone;
+use jiff::{RoundMode, Span, Timestamp, Unit, Zoned, ZonedDifference};
+
+use crate::calc::parse_start_date;
+use crate::json_utils::{
+    extract_bool_field, extract_number_field, extract_string_field, json_escape,
+};
+use crate::locale::Locale;
+use crate::zone::resolve_zone;
+
+/// Calendar units used for phrases and countdowns, largest first.
+const UNITS: [Unit; 6] = [
+    Unit::Year,
+    Unit::Month,
+    Unit::Day,
+    Unit::Hour,
+    Unit::Minute,
+    Unit::Second,
+];
+
+/// How unit names are spelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelativeStyle {
+    /// "2 hours and 5 minutes"
+    Long,
+    /// "2h 5m"
+    Short,
+}
+
+/// Word lists for relative phrases. Implement this to add a language.
+pub trait RelativeLocale {
+    /// `count` of `unit`, e.g. "3 days" / "3d".
+    fn unit(&self, unit: Unit, count: i64, style: RelativeStyle) -> String;
+    /// Join unit phrases, e.g. "2 hours and 5 minutes".
+    fn join(&self, parts: &[String], style: RelativeStyle) -> String;
+    /// Mark a rounded single-unit phrase as approximate, e.g. "about 3 months".
+    fn approximately(&self, phrase: &str) -> String;
+    fn past(&self, phrase: &str) -> String;
+    fn future(&self, phrase: &str) -> String;
+    fn now(&self) -> String;
+    /// `time` is the target's clock time, e.g. "yesterday at 14:00".
+    fn yesterday(&self, time: &str) -> String;
+    fn tomorrow(&self, time: &str) -> String;
+}
+
+/// English relative phrases.
+pub struct English;
+
+impl RelativeLocale for English {
+    fn unit(&self, unit: Unit, count: i64, style: RelativeStyle) -> String {
+        let (long, short) = match unit {
+            Unit::Year => ("year", "y"),
+            Unit::Month => ("month", "mo"),
+            Unit::Week => ("week", "w"),
+            Unit::Day => ("day", "d"),
+            Unit::Hour => ("hour", "h"),
+            Unit::Minute => ("minute", "m"),
+            _ => ("second", "s"),
+        };
+        match style {
+            RelativeStyle::Short => format!("{}{}", count, short),
+            RelativeStyle::Long if count == 1 => format!("1 {}", long),
+            RelativeStyle::Long => format!("{} {}s", count, long),
+        }
+    }
+
+    fn join(&self, parts: &[String], style: RelativeStyle) -> String {
+        match (style, parts) {
+            (RelativeStyle::Short, _) => parts.join(" "),
+            (RelativeStyle::Long, [init @ .., last]) if !init.is_empty() => {
+                format!("{} and {}", init.join(", "), last)
+            }
+            (RelativeStyle::Long, _) => parts.join(""),
+        }
+    }
+
+    fn approximately(&self, phrase: &str) -> String {
+        format!("about {}", phrase)
+    }
+
+    fn past(&self, phrase: &str) -> String {
+        format!("{} ago", phrase)
+    }
+
+    fn future(&self, phrase: &str) -> String {
+        format!("in {}", phrase)
+    }
+
+    fn now(&self) -> String {
+        "just now".to_string()
+    }
+
+    fn yesterday(&self, time: &str) -> String {
+        format!("yesterday at {}", time)
+    }
+
+    fn tomorrow(&self, time: &str) -> String {
+        format!("tomorrow at {}", time)
+    }
+}
+
+/// Relative phrases from a table of words and templates (`{}` marks the phrase).
+/// Used for the non-English locales.
+pub struct PhraseTable {
+    /// Singular and plural long unit names, year, month, week, day, hour, minute,
+    /// second; written right after the count, so they carry their own leading space
+    units: [(&'static str, &'static str); 7],
+    /// Short unit names, in the same order and written the same way
+    short: [&'static str; 7],
+    /// Separator between short units
+    short_separator: &'static str,
+    /// Separator between long units, and before the last one
+    list_separator: &'static str,
+    last_separator: &'static str,
+    about: &'static str,
+    past: &'static str,
+    future: &'static str,
+    now: &'static str,
+    yesterday: &'static str,
+    tomorrow: &'static str,
+}
+
+impl RelativeLocale for PhraseTable {
+    fn unit(&self, unit: Unit, count: i64, style: RelativeStyle) -> String {
+        let index = match unit {
+            Unit::Year => 0,
+            Unit::Month => 1,
+            Unit::Week => 2,
+            Unit::Day => 3,
+            Unit::Hour => 4,
+            Unit::Minute => 5,
+            _ => 6,
+        };
+        let name = match style {
+            RelativeStyle::Short => self.short[index],
+            RelativeStyle::Long if count == 1 => self.units[index].0,
+            RelativeStyle::Long => self.units[index].1,
+        };
+        format!("{}{}", count, name)
+    }
+
+    fn join(&self, parts: &[String], style: RelativeStyle) -> String {
+        match (style, parts) {
+            (RelativeStyle::Short, _) => parts.join(self.short_separator),
+            (RelativeStyle::Long, [init @ .., last]) if !init.is_empty() => format!(
+                "{}{}{}",
+                init.join(self.list_separator),
+                self.last_separator,
+                last
+            ),
+            (RelativeStyle::Long, _) => parts.join(""),
+        }
+    }
+
+    fn approximately(&self, phrase: &str) -> String {
+        self.about.replace("{}", phrase)
+    }
+
+    fn past(&self, phrase: &str) -> String {
+        self.past.replace("{}", phrase)
+    }
+
+    fn future(&self, phrase: &str) -> String {
+        self.future.replace("{}", phrase)
+    }
+
+    fn now(&self) -> String {
+        self.now.to_string()
+    }
+
+    fn yesterday(&self, time: &str) -> String {
+        self.yesterday.replace("{}", time)
+    }
+
+    fn tomorrow(&self, time: &str) -> String {
+        self.tomorrow.replace("{}", time)
+    }
+}
+
+#[cfg(feature = "locales")]
+mod tables {
+    use super::PhraseTable;
+
+    // German units follow "vor"/"in", which take the dative plural ("vor 3 Tagen").
+    pub const DE: PhraseTable = PhraseTable {
+        units: [
+            (" Jahr", " Jahren"),
+            (" Monat", " Monaten"),
+            (" Woche", " Wochen"),
+            (" Tag", " Tagen"),
+            (" Stunde", " Stunden"),
+            (" Minute", " Minuten"),
+            (" Sekunde", " Sekunden"),
+        ],
+        short: [" J.", " Mon.", " Wo.", " T.", " Std.", " Min.", " Sek."],
+        short_separator: " ",
+        list_separator: ", ",
+        last_separator: " und ",
+        about: "etwa {}",
+        past: "vor {}",
+        future: "in {}",
+        now: "gerade eben",
+        yesterday: "gestern um {}",
+        tomorrow: "morgen um {}",
+    };
+
+    pub const FR: PhraseTable = PhraseTable {
+        units: [
+            (" an", " ans"),
+            (" mois", " mois"),
+            (" semaine", " semaines"),
+            (" jour", " jours"),
+            (" heure", " heures"),
+            (" minute", " minutes"),
+            (" seconde", " secondes"),
+        ],
+        short: [" an", " mois", " sem.", " j", " h", " min", " s"],
+        short_separator: " ",
+        list_separator: ", ",
+        last_separator: " et ",
+        about: "environ {}",
+        past: "il y a {}",
+        future: "dans {}",
+        now: "à l'instant",
+        yesterday: "hier à {}",
+        tomorrow: "demain à {}",
+    };
+
+    pub const ES: PhraseTable = PhraseTable {
+        units: [
+            (" año", " años"),
+            (" mes", " meses"),
+            (" semana", " semanas"),
+            (" día", " días"),
+            (" hora", " horas"),
+            (" minuto", " minutos"),
+            (" segundo", " segundos"),
+        ],
+        short: [" a", " mes", " sem", " d", " h", " min", " s"],
+        short_separator: " ",
+        list_separator: ", ",
+        last_separator: " y ",
+        about: "aproximadamente {}",
+        past: "hace {}",
+        future: "dentro de {}",
+        now: "ahora mismo",
+        yesterday: "ayer a las {}",
+        tomorrow: "mañana a las {}",
+    };
+
+    pub const IT: PhraseTable = PhraseTable {
+        units: [
+            (" anno", " anni"),
+            (" mese", " mesi"),
+            (" settimana", " settimane"),
+            (" giorno", " giorni"),
+            (" ora", " ore"),
+            (" minuto", " minuti"),
+            (" secondo", " secondi"),
+        ],
+        short: [" a", " mes.", " sett.", " g", " h", " min", " s"],
+        short_separator: " ",
+        list_separator: ", ",
+        last_separator: " e ",
+        about: "circa {}",
+        past: "{} fa",
+        future: "tra {}",
+        now: "proprio ora",
+        yesterday: "ieri alle {}",
+        tomorrow: "domani alle {}",
+    };
+
+    pub const PT_BR: PhraseTable = PhraseTable {
+        units: [
+            (" ano", " anos"),
+            (" mês", " meses"),
+            (" semana", " semanas"),
+            (" dia", " dias"),
+            (" hora", " horas"),
+            (" minuto", " minutos"),
+            (" segundo", " segundos"),
+        ],
+        short: [" a", " mês", " sem", " d", " h", " min", " s"],
+        short_separator: " ",
+        list_separator: ", ",
+        last_separator: " e ",
+        about: "cerca de {}",
+        past: "há {}",
+        future: "em {}",
+        now: "agora mesmo",
+        yesterday: "ontem às {}",
+        tomorrow: "amanhã às {}",
+    };
+
+    pub const NL: PhraseTable = PhraseTable {
+        units: [
+            (" jaar", " jaar"),
+            (" maand", " maanden"),
+            (" week", " weken"),
+            (" dag", " dagen"),
+            (" uur", " uur"),
+            (" minuut", " minuten"),
+            (" seconde", " seconden"),
+        ],
+        short: [" j", " mnd", " w", " d", " u", " min", " s"],
+        short_separator: " ",
+        list_separator: ", ",
+        last_separator: " en ",
+        about: "ongeveer {}",
+        past: "{} geleden",
+        future: "over {}",
+        now: "zojuist",
+        yesterday: "gisteren om {}",
+        tomorrow: "morgen om {}",
+    };
+
+    pub const JA: PhraseTable = PhraseTable {
+        units: [
+            ("年", "年"),
+            ("か月", "か月"),
+            ("週間", "週間"),
+            ("日", "日"),
+            ("時間", "時間"),
+            ("分", "分"),
+            ("秒", "秒"),
+        ],
+        short: ["年", "か月", "週", "日", "時間", "分", "秒"],
+        short_separator: "",
+        list_separator: "",
+        last_separator: "",
+        about: "約{}",
+        past: "{}前",
+        future: "{}後",
+        now: "たった今",
+        yesterday: "昨日 {}",
+        tomorrow: "明日 {}",
+    };
+
+    pub const ZH: PhraseTable = PhraseTable {
+        units: [
+            ("年", "年"),
+            ("个月", "个月"),
+            ("周", "周"),
+            ("天", "天"),
+            ("小时", "小时"),
+            ("分钟", "分钟"),
+            ("秒", "秒"),
+        ],
+        short: ["年", "个月", "周", "天", "小时", "分", "秒"],
+        short_separator: "",
+        list_separator: "",
+        last_separator: "",
+        about: "大约{}",
+        past: "{}前",
+        future: "{}后",
+        now: "刚刚",
+        yesterday: "昨天 {}",
+        tomorrow: "明天 {}",
+    };
+
+    /// The table for a tag returned by `Locale::get`.
+    pub fn get(tag: &str) -> Option<&'static PhraseTable> {
+        match tag {
+            "de" => Some(&DE),
+            "fr" => Some(&FR),
+            "es" => Some(&ES),
+            "it" => Some(&IT),
+            "pt-BR" => Some(&PT_BR),
+            "nl" => Some(&NL),
+            "ja" => Some(&JA),
+            "zh" => Some(&ZH),
+            _ => None,
+        }
+    }
+}
+
+/// Look up the relative-phrase words for a locale tag, with the same tags and
+/// fallbacks as `Locale::get` (`""` means English).
+pub fn relative_locale(tag: &str) -> Result<&'static dyn RelativeLocale, String> {
+    let locale = Locale::get(tag)?;
+    if locale.tag == "en" {
+        return Ok(&English);
+    }
+    #[cfg(feature = "locales")]
+    if let Some(table) = tables::get(locale.tag) {
+        return Ok(table);
+    }
+    Err(format!(
+        "Relative phrases are not available for locale '{}'",
+        tag
+    ))
+}
+
+/// Options for `humanize`.
+pub struct RelativeOptions {
+    /// Maximum number of units in the phrase (1 gives "about 3 months ago")
+    pub max_units: usize,
+    pub style: RelativeStyle,
+    /// How the smallest shown unit is rounded: `Trunc` or `HalfExpand`
+    pub rounding: RoundMode,
+    /// Say "yesterday at 14:00" / "tomorrow at 09:00" for adjacent calendar days
+    pub calendar: bool,
+    /// Zone whose calendar defines days and months
+    pub zone: TimeZone,
+    /// Words the phrase is written in
+    pub locale: &'static dyn RelativeLocale,
+}
+
+impl Default for RelativeOptions {
+    fn default() -> Self {
+        RelativeOptions {
+            max_units: 1,
+            style: RelativeStyle::Long,
+            rounding: RoundMode::HalfExpand,
+            calendar: false,
+            zone: TimeZone::UTC,
+            locale: &English,
+        }
+    }
+}
+
+impl RelativeOptions {
+    /// Parse options from JSON, e.g. `{"units":2,"style":"short","rounding":"floor","calendar":true,"zone":"Europe/Berlin","locale":"de"}`.
+    /// Missing keys keep their defaults; `""` gives all defaults.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let mut options = RelativeOptions::default();
+        if let Some(units) = extract_number_field(json, "units") {
+            if !(1..=6).contains(&units) {
+                return Err(format!("'units' must be between 1 and 6, got {}", units));
+            }
+            options.max_units = units as usize;
+        }
+        if let Some(style) = extract_string_field(json, "style") {
+            options.style = match style.as_str() {
+                "long" => RelativeStyle::Long,
+                "short" => RelativeStyle::Short,
+                other => return Err(format!("Unknown style: '{}'", other)),
+            };
+        }
+        if let Some(rounding) = extract_string_field(json, "rounding") {
+            options.rounding = match rounding.as_str() {
+                "round" => RoundMode::HalfExpand,
+                "floor" => RoundMode::Trunc,
+                other => return Err(format!("Unknown rounding: '{}'", other)),
+            };
+        }
+        if let Some(calendar) = extract_bool_field(json, "calendar") {
+            options.calendar = calendar;
+        }
+        if let Some(zone) = extract_string_field(json, "zone") {
+            options.zone = resolve_zone(&zone)?;
+        }
+        if let Some(locale) = extract_string_field(json, "locale") {
+            options.locale = relative_locale(&locale)?;
+        }
+        Ok(options)
+    }
+}
+
+/// An exact signed difference in calendar units, as shown by a countdown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Breakdown {
+    /// -1 if the target is before the reference, 1 if after, 0 if equal
+    pub sign: i8,
+    pub years: i64,
+    pub months: i64,
+    pub days: i64,
+    pub hours: i64,
+    pub minutes: i64,
+    pub seconds: i64,
+    /// The whole difference in seconds (signed)
+    pub total_seconds: i64,
+}
+
+impl Breakdown {
+    fn to_json(self) -> String {
+        format!(
+            r#"{{"sign":{},"years":{},"months":{},"days":{},"hours":{},"minutes":{},"seconds":{},"totalSeconds":{}}}"#,
+            self.sign,
+            self.years,
+            self.months,
+            self.days,
+            self.hours,
+            self.minutes,
+            self.seconds,
+            self.total_seconds,
+        )
+    }
+}
+
+/// A humanized difference between a reference instant and a target.
+#[derive(Debug, PartialEq)]
+pub struct RelativeTime {
+    pub phrase: String,
+    pub breakdown: Breakdown,
+}
+
+impl RelativeTime {
+    /// Serialize to JSON string for Wasm boundary (manual serialization).
+    pub fn to_json(&self) -> String {
+        let direction = match self.breakdown.sign {
+            -1 => "past",
+            1 => "future",
+            _ => "now",
+        };
+        format!(
+            r#"{{"phrase":"{}","direction":"{}","breakdown":{}}}"#,
+            json_escape(&self.phrase),
+            direction,
+            self.breakdown.to_json(),
+        )
+    }
+}
+
+/// The exact difference from `reference` to `target` in years through seconds.
+///
+/// Calendar units follow the zone's calendar, so a month is however long that
+/// month is and a day across a DST change may be 23 or 25 hours.
+pub fn countdown(
+    reference: Timestamp,
+    target: Timestamp,
+    tz: &TimeZone,
+) -> Result<Breakdown, String> {
+    let start = reference.to_zoned(tz.clone());
+    let end = target.to_zoned(tz.clone());
+    let span = difference(&start, &end, Unit::Second, RoundMode::Trunc)?;
+
+    Ok(Breakdown {
+        sign: span.signum(),
+        years: unit_value(&span, Unit::Year).abs(),
+        months: unit_value(&span, Unit::Month).abs(),
+        days: unit_value(&span, Unit::Day).abs(),
+        hours: unit_value(&span, Unit::Hour).abs(),
+        minutes: unit_value(&span, Unit::Minute).abs(),
+        seconds: unit_value(&span, Unit::Second).abs(),
+        total_seconds: target.as_second() - reference.as_second(),
+    })
+}
+
+/// Describe `target` relative to `reference`, e.g. "about 3 months ago" or "in 2h 5m".
+pub fn humanize(
+    reference: Timestamp,
+    target: Timestamp,
+    options: &RelativeOptions,
+) -> Result<RelativeTime, String> {
+    let locale = options.locale;
+    let breakdown = countdown(reference, target, &options.zone)?;
+    let start = reference.to_zoned(options.zone.clone());
+    let end = target.to_zoned(options.zone.clone());
+
+    if options.calendar {
+        let days = (end.date() - start.date()).get_days();
+        if days == -1 || days == 1 {
+            let time = end.strftime("%H:%M").to_string();
+            let phrase = if days == -1 {
+                locale.yesterday(&time)
+            } else {
+                locale.tomorrow(&time)
+            };
+            return Ok(RelativeTime { phrase, breakdown });
+        }
+    }
+
+    let exact = difference(&start, &end, Unit::Second, RoundMode::Trunc)?;
+    let Some(leading) = UNITS.iter().position(|u| unit_value(&exact, *u) != 0) else {
+        return Ok(RelativeTime {
+            phrase: locale.now(),
+            breakdown,
+        });
+    };
+
+    let smallest = (leading + options.max_units - 1).min(UNITS.len() - 1);
+    let rounded = difference(&start, &end, UNITS[smallest], options.rounding)?;
+    let parts: Vec<String> = UNITS[..=smallest]
+        .iter()
+        .map(|u| (*u, unit_value(&rounded, *u).abs()))
+        .filter(|(_, count)| *count != 0)
+        .map(|(u, count)| locale.unit(u, count, options.style))
+        .collect();
+    if parts.is_empty() {
+        return Ok(RelativeTime {
+            phrase: locale.now(),
+            breakdown,
+        });
+    }
+
+    let mut phrase = locale.join(&parts, options.style);
+    if options.max_units == 1
+        && options.style == RelativeStyle::Long
+        && rounded.fieldwise() != exact.fieldwise()
+    {
+        phrase = locale.approximately(&phrase);
+    }
+    let phrase = if exact.signum() < 0 {
+        locale.past(&phrase)
+    } else {
+        locale.future(&phrase)
+    };
+
+    Ok(RelativeTime { phrase, breakdown })
+}
+
+fn difference(start: &Zoned, end: &Zoned, smallest: Unit, mode: RoundMode) -> Result<Span, String> {
+    start
+        .until(
+            ZonedDifference::new(end)
+                .largest(Unit::Year)
+                .smallest(smallest)
+                .mode(mode),
+        )
+        .map_err(|e| format!("Failed to compute difference: {}", e))
+}
+
+fn unit_value(span: &Span, unit: Unit) -> i64 {
+    match unit {
+        Unit::Year => span.get_years().into(),
+        Unit::Month => span.get_months().into(),
+        Unit::Day => span.get_days().into(),
+        Unit::Hour => span.get_hours().into(),
+        Unit::Minute => span.get_minutes(),
+        _ => span.get_seconds(),
+    }
+}
+
+/// Describe `target` relative to `reference` (both date inputs as accepted by `calculate`).
+///
+/// `options_json` is parsed by `RelativeOptions::from_json`, including the phrase
+/// language (`locale`).
+pub fn relative_time(
+    reference: &str,
+    target: &str,
+    options_json: &str,
+) -> Result<RelativeTime, String> {
+    let reference = parse_start_date(reference)?;
+    let target = parse_start_date(target)?;
+    let options = RelativeOptions::from_json(options_json)?;
+    humanize(reference, target, &options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ts(s: &str) -> Timestamp {
+        s.parse().unwrap()
+    }
+
+    fn phrase(reference: &str, target: &str, options: &RelativeOptions) -> String {
+        humanize(ts(reference), ts(target), options).unwrap().phrase
+    }
+
+    #[test]
+    fn test_about_months_ago() {
+        let options = RelativeOptions::default();
+        assert_eq!(
+            phrase("2024-07-11T00:00:00Z", "2024-04-02T00:00:00Z", &options),
+            "about 3 months ago"
+        );
+    }
+
+    #[test]
+    fn test_exact_single_unit_has_no_about() {
+        let options = RelativeOptions::default();
+        assert_eq!(
+            phrase("2024-07-11T00:00:00Z", "2024-07-14T00:00:00Z", &options),
+            "in 3 days"
+        );
+    }
+
+    #[test]
+    fn test_short_two_units() {
+        let options = RelativeOptions {
+            max_units: 2,
+            style: RelativeStyle::Short,
+            ..RelativeOptions::default()
+        };
+        assert_eq!(
+            phrase("2024-07-11T10:00:00Z", "2024-07-11T12:05:20Z", &options),
+            "in 2h 5m"
+        );
+    }
+
+    #[test]
+    fn test_long_join_and_floor() {
+        let options = RelativeOptions {
+            max_units: 3,
+            rounding: RoundMode::Trunc,
+            ..RelativeOptions::default()
+        };
+        assert_eq!(
+            phrase("2024-07-11T10:00:00Z", "2024-07-10T07:58:59Z", &options),
+            "1 day, 2 hours and 1 minute ago"
+        );
+    }
+
+    #[test]
+    fn test_rounding_carries_into_larger_unit() {
+        let options = RelativeOptions::default();
+        assert_eq!(
+            phrase("2024-01-01T00:00:00Z", "2024-12-20T00:00:00Z", &options),
+            "in about 1 year"
+        );
+    }
+
+    #[test]
+    fn test_calendar_phrases() {
+        let options = RelativeOptions {
+            calendar: true,
+            zone: resolve_zone("Europe/Berlin").unwrap(),
+            ..RelativeOptions::default()
+        };
+        assert_eq!(
+            phrase("2024-07-11T08:00:00Z", "2024-07-10T12:00:00Z", &options),
+            "yesterday at 14:00"
+        );
+        assert_eq!(
+            phrase("2024-07-11T08:00:00Z", "2024-07-12T07:30:00Z", &options),
+            "tomorrow at 09:30"
+        );
+        assert_eq!(
+            phrase("2024-07-11T08:00:00Z", "2024-07-11T11:00:00Z", &options),
+            "in 3 hours"
+        );
+    }
+
+    #[test]
+    fn test_just_now() {
+        let options = RelativeOptions::default();
+        assert_eq!(
+            phrase("2024-07-11T08:00:00Z", "2024-07-11T08:00:00.4Z", &options),
+            "just now"
+        );
+    }
+
+    #[test]
+    fn test_countdown_breakdown() {
+        let b = countdown(
+            ts("2024-01-31T10:00:00Z"),
+            ts("2025-03-01T12:30:15Z"),
+            &TimeZone::UTC,
+        )
+        .unwrap();
+        assert_eq!(
+            (b.sign, b.years, b.months, b.days, b.hours, b.minutes, b.seconds),
+            (1, 1, 1, 1, 2, 30, 15)
+        );
+
+        let b = countdown(
+            ts("2024-07-11T00:00:00Z"),
+            ts("2024-07-10T00:00:00Z"),
+            &TimeZone::UTC,
+        )
+        .unwrap();
+        assert_eq!((b.sign, b.days, b.total_seconds), (-1, 1, -86400));
+    }
+
+    #[test]
+    fn test_countdown_day_across_dst() {
+        let ny = resolve_zone("America/New_York").unwrap();
+        let b = countdown(ts("2024-03-09T17:00:00Z"), ts("2024-03-10T16:00:00Z"), &ny).unwrap();
+        assert_eq!((b.days, b.hours), (1, 0));
+        assert_eq!(b.total_seconds, 23 * 3600);
+    }
+
+    #[test]
+    fn test_options_from_json() {
+        let options = RelativeOptions::from_json(
+            r#"{"units":2,"style":"short","rounding":"floor","calendar":true}"#,
+        )
+        .unwrap();
+        assert_eq!(options.max_units, 2);
+        assert_eq!(options.style, RelativeStyle::Short);
+        assert_eq!(options.rounding, RoundMode::Trunc);
+        assert!(options.calendar);
+        assert!(RelativeOptions::from_json(r#"{"units":9}"#).is_err());
+        assert!(RelativeOptions::from_json(r#"{"style":"poetic"}"#).is_err());
+    }
+
+    #[test]
+    fn test_json_output() {
+        let result = humanize(
+            ts("2024-07-11T00:00:00Z"),
+            ts("2024-07-14T00:00:00Z"),
+            &RelativeOptions::default(),
+        )
+        .unwrap();
+        let json = result.to_json();
+        assert!(
+            json.starts_with(r#"{"phrase":"in 3 days","direction":"future","breakdown":{"sign":1"#)
+        );
+        assert!(json.contains(r#""totalSeconds":259200"#));
+    }
+
+    #[test]
+    fn test_relative_locale_lookup() {
+        assert!(relative_locale("en-US").is_ok());
+        assert!(relative_locale("").is_ok());
+        assert!(relative_locale("tlh")
+            .err()
+            .unwrap()
+            .contains("Unsupported locale"));
+    }
+
+    #[cfg(feature = "locales")]
+    #[test]
+    fn test_locale_tables() {
+        let phrase_in = |tag: &str, reference: &str, target: &str, json: &str| {
+            let mut options = RelativeOptions::from_json(json).unwrap();
+            options.locale = relative_locale(tag).unwrap();
+            phrase(reference, target, &options)
+        };
+        let (now, earlier) = ("2024-07-11T00:00:00Z", "2024-04-02T00:00:00Z");
+        assert_eq!(phrase_in("de", now, earlier, ""), "vor etwa 3 Monaten");
+        assert_eq!(phrase_in("de-AT", earlier, now, ""), "in etwa 3 Monaten");
+        assert_eq!(phrase_in("fr", now, earlier, ""), "il y a environ 3 mois");
+        assert_eq!(phrase_in("pt", now, earlier, ""), "há cerca de 3 meses");
+        assert_eq!(phrase_in("ja", now, earlier, ""), "約3か月前");
+        assert_eq!(phrase_in("zh-Hans-CN", earlier, now, ""), "大约3个月后");
+        let (from, to) = ("2024-07-11T10:00:00Z", "2024-07-10T07:58:59Z");
+        let three = r#"{"units":3,"rounding":"floor"}"#;
+        assert_eq!(
+            phrase_in("nl", from, to, three),
+            "1 dag, 2 uur en 1 minuut geleden"
+        );
+        assert_eq!(phrase_in("ja", from, to, three), "1日2時間1分前");
+        assert_eq!(
+            phrase_in("it", from, to, r#"{"units":2,"style":"short"}"#),
+            "1 g 2 h fa"
+        );
+        assert_eq!(phrase_in("es", from, from, ""), "ahora mismo");
+    }
+}
@@ -0,0 +1,1156 @@
+//! RFC 5545 recurrence sets: expand `RRULE`, `RDATE` and `EXDATE` from a `DTSTART`.
+//!
+//! Rules are evaluated on the wall clock of the `DTSTART` zone. Each local time is then
+//! mapped to an instant the way RFC 5545 prescribes: a time skipped by a DST gap takes the
+//! offset from before the gap, and a repeated time is its first occurrence.
+
+use std::collections::VecDeque;
+
+use jiff::civil::{Date, DateTime, Weekday};
+use jiff::tz::TimeZone;
+use jiff::{Span, Timestamp, ToSpan};
+
+use crate::calc::parse_start_date;
+use crate::json_utils::{extract_string_field, json_escape};
+use crate::zone::{resolve_zone, zone_name};
+
+/// Give up looking for the next occurrence after this many years without one: dates
+/// repeat every 400 years in the Gregorian calendar, so a rule that has not matched in
+/// that time never will.
+const SEARCH_HORIZON_YEARS: i16 = 400;
+
+const WEEKDAY_CODES: [&str; 7] = ["MO", "TU", "WE", "TH", "FR", "SA", "SU"];
+
+/// `FREQ` values, finest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Frequency {
+    Secondly,
+    Minutely,
+    Hourly,
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Frequency {
+    fn from_name(name: &str) -> Result<Self, String> {
+        match name.to_ascii_uppercase().as_str() {
+            "SECONDLY" => Ok(Frequency::Secondly),
+            "MINUTELY" => Ok(Frequency::Minutely),
+            "HOURLY" => Ok(Frequency::Hourly),
+            "DAILY" => Ok(Frequency::Daily),
+            "WEEKLY" => Ok(Frequency::Weekly),
+            "MONTHLY" => Ok(Frequency::Monthly),
+            "YEARLY" => Ok(Frequency::Yearly),
+            other => Err(format!("Unknown FREQ '{}'", other)),
+        }
+    }
+
+    /// Period length in seconds for sub-daily frequencies.
+    fn seconds(self) -> Option<i64> {
+        match self {
+            Frequency::Secondly => Some(1),
+            Frequency::Minutely => Some(60),
+            Frequency::Hourly => Some(3600),
+            _ => None,
+        }
+    }
+}
+
+/// An `UNTIL` bound, in whichever form the rule gave it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Until {
+    /// A date; occurrences on that day are still included
+    Date(Date),
+    /// A floating local time in the `DTSTART` zone
+    Local(DateTime),
+    /// A UTC time (`...Z`)
+    Instant(Timestamp),
+}
+
+/// One parsed `RRULE`, with parts left unset filled in from `DTSTART`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecurrenceRule {
+    freq: Frequency,
+    interval: i64,
+    count: Option<u32>,
+    until: Option<Until>,
+    by_month: Vec<i16>,
+    by_week_no: Vec<i16>,
+    by_year_day: Vec<i16>,
+    by_month_day: Vec<i16>,
+    /// `(ordinal, weekday)`; ordinal `0` means every such weekday
+    by_day: Vec<(i16, Weekday)>,
+    by_hour: Vec<i16>,
+    by_minute: Vec<i16>,
+    by_second: Vec<i16>,
+    by_set_pos: Vec<i16>,
+    wkst: Weekday,
+}
+
+impl RecurrenceRule {
+    /// Parse an `RRULE` value such as `FREQ=MONTHLY;BYDAY=1FR;COUNT=10`.
+    ///
+    /// As in RFC 5545, a rule that names no day (`BYDAY`, `BYMONTHDAY`, ...) or time
+    /// (`BYHOUR`, ...) parts repeats the day and time of `dtstart`.
+    pub fn parse(value: &str, dtstart: DateTime, all_day: bool) -> Result<Self, String> {
+        let mut freq = None;
+        let mut rule = RecurrenceRule {
+            freq: Frequency::Yearly,
+            interval: 1,
+            count: None,
+            until: None,
+            by_month: Vec::new(),
+            by_week_no: Vec::new(),
+            by_year_day: Vec::new(),
+            by_month_day: Vec::new(),
+            by_day: Vec::new(),
+            by_hour: Vec::new(),
+            by_minute: Vec::new(),
+            by_second: Vec::new(),
+            by_set_pos: Vec::new(),
+            wkst: Weekday::Monday,
+        };
+        for part in value.split(';').map(str::trim).filter(|p| !p.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("RRULE part '{}' is not KEY=VALUE", part))?;
+            let value = value.trim();
+            match key.trim().to_ascii_uppercase().as_str() {
+                "FREQ" => freq = Some(Frequency::from_name(value)?),
+                "INTERVAL" => {
+                    rule.interval =
+                        value
+                            .parse::<i64>()
+                            .ok()
+                            .filter(|n| *n >= 1)
+                            .ok_or_else(|| {
+                                format!("INTERVAL must be a positive integer, got '{}'", value)
+                            })?
+                }
+                "COUNT" => {
+                    rule.count = Some(value.parse::<u32>().ok().filter(|n| *n >= 1).ok_or_else(
+                        || format!("COUNT must be a positive integer, got '{}'", value),
+                    )?)
+                }
+                "UNTIL" => rule.until = Some(parse_until(value)?),
+                "BYMONTH" => rule.by_month = parse_numbers(value, "BYMONTH", 1, 12, false)?,
+                "BYWEEKNO" => rule.by_week_no = parse_numbers(value, "BYWEEKNO", 1, 53, true)?,
+                "BYYEARDAY" => rule.by_year_day = parse_numbers(value, "BYYEARDAY", 1, 366, true)?,
+                "BYMONTHDAY" => {
+                    rule.by_month_day = parse_numbers(value, "BYMONTHDAY", 1, 31, true)?
+                }
+                "BYDAY" => rule.by_day = parse_by_day(value)?,
+                "BYHOUR" => rule.by_hour = parse_numbers(value, "BYHOUR", 0, 23, false)?,
+                "BYMINUTE" => rule.by_minute = parse_numbers(value, "BYMINUTE", 0, 59, false)?,
+                "BYSECOND" => rule.by_second = parse_numbers(value, "BYSECOND", 0, 59, false)?,
+                "BYSETPOS" => rule.by_set_pos = parse_numbers(value, "BYSETPOS", 1, 366, true)?,
+                "WKST" => rule.wkst = parse_weekday(value)?,
+                other => return Err(format!("Unknown RRULE part '{}'", other)),
+            }
+        }
+        rule.freq = freq.ok_or("RRULE needs a FREQ part")?;
+        rule.validate(all_day)?;
+        rule.fill_from(dtstart);
+        Ok(rule)
+    }
+
+    /// Reject the combinations RFC 5545 forbids.
+    fn validate(&self, all_day: bool) -> Result<(), String> {
+        if self.count.is_some() && self.until.is_some() {
+            return Err("COUNT and UNTIL cannot be used together".to_string());
+        }
+        if !self.by_week_no.is_empty() && self.freq != Frequency::Yearly {
+            return Err("BYWEEKNO is only valid with FREQ=YEARLY".to_string());
+        }
+        if !self.by_year_day.is_empty()
+            && matches!(
+                self.freq,
+                Frequency::Daily | Frequency::Weekly | Frequency::Monthly
+            )
+        {
+            return Err("BYYEARDAY is not valid with FREQ=DAILY, WEEKLY or MONTHLY".to_string());
+        }
+        if !self.by_month_day.is_empty() && self.freq == Frequency::Weekly {
+            return Err("BYMONTHDAY is not valid with FREQ=WEEKLY".to_string());
+        }
+        if self.by_day.iter().any(|&(n, _)| n != 0) {
+            let allowed = self.freq == Frequency::Monthly
+                || (self.freq == Frequency::Yearly && self.by_week_no.is_empty());
+            if !allowed {
+                return Err(
+                    "BYDAY ordinals (like 1FR) need FREQ=MONTHLY, or FREQ=YEARLY without BYWEEKNO"
+                        .to_string(),
+                );
+            }
+        }
+        if all_day
+            && (self.freq < Frequency::Daily
+                || !self.by_hour.is_empty()
+                || !self.by_minute.is_empty()
+                || !self.by_second.is_empty())
+        {
+            return Err("An all-day DTSTART cannot repeat by hour, minute or second".to_string());
+        }
+        Ok(())
+    }
+
+    /// Take the day and time parts the rule leaves open from `dtstart`.
+    fn fill_from(&mut self, dtstart: DateTime) {
+        let no_day = self.by_week_no.is_empty()
+            && self.by_year_day.is_empty()
+            && self.by_month_day.is_empty()
+            && self.by_day.is_empty();
+        if no_day {
+            match self.freq {
+                Frequency::Yearly => {
+                    if self.by_month.is_empty() {
+                        self.by_month = vec![dtstart.month() as i16];
+                    }
+                    self.by_month_day = vec![dtstart.day() as i16];
+                }
+                Frequency::Monthly => self.by_month_day = vec![dtstart.day() as i16],
+                Frequency::Weekly => self.by_day = vec![(0, dtstart.weekday())],
+                _ => {}
+            }
+        }
+        if self.freq > Frequency::Hourly && self.by_hour.is_empty() {
+            self.by_hour = vec![dtstart.hour() as i16];
+        }
+        if self.freq > Frequency::Minutely && self.by_minute.is_empty() {
+            self.by_minute = vec![dtstart.minute() as i16];
+        }
+        if self.freq > Frequency::Secondly && self.by_second.is_empty() {
+            self.by_second = vec![dtstart.second() as i16];
+        }
+    }
+
+    /// Local times generated by the rule from `dtstart`, which always counts as the first
+    /// occurrence. With `from` set (and no `COUNT` to keep), the search skips ahead to the
+    /// period containing it.
+    fn iter<'a>(
+        &'a self,
+        dtstart: DateTime,
+        zone: &'a TimeZone,
+        from: Option<DateTime>,
+    ) -> RuleIter<'a> {
+        let skip_to = from.filter(|_| self.count.is_none());
+        let mut buffer = VecDeque::new();
+        if skip_to.is_none() {
+            buffer.push_back(dtstart);
+        }
+        RuleIter {
+            rule: self,
+            dtstart,
+            zone,
+            period: skip_to.map_or(0, |from| self.period_index(dtstart, from)),
+            buffer,
+            emitted: 0,
+            last_hit: skip_to.map_or(dtstart.date(), |from| from.date()),
+            done: false,
+        }
+    }
+
+    /// Index of the period containing `from`, counting `dtstart`'s period as 0.
+    fn period_index(&self, dtstart: DateTime, from: DateTime) -> i64 {
+        let periods = match self.freq {
+            Frequency::Yearly => (from.year() - dtstart.year()) as i64,
+            Frequency::Monthly => month_index(from.date()) - month_index(dtstart.date()),
+            Frequency::Weekly => {
+                days_between(
+                    week_start(dtstart.date(), self.wkst),
+                    week_start(from.date(), self.wkst),
+                )
+                .unwrap_or(0)
+                    / 7
+            }
+            Frequency::Daily => days_between(dtstart.date(), from.date()).unwrap_or(0),
+            sub_daily => {
+                let unit = sub_daily.seconds().unwrap_or(1);
+                (civil_seconds(from).unwrap_or(0) - civil_seconds(dtstart).unwrap_or(0))
+                    .div_euclid(unit)
+            }
+        };
+        periods.div_euclid(self.interval).max(0)
+    }
+
+    /// Candidate local times of period `index`, sorted, with the first day of the period
+    /// and the index of the next period worth looking at. `None` once out of range.
+    fn period(&self, dtstart: DateTime, index: i64) -> Option<(Date, Vec<DateTime>, i64)> {
+        let step = index.checked_mul(self.interval)?;
+        let dates: Vec<Date> = match self.freq {
+            Frequency::Yearly => {
+                let year = i16::try_from(dtstart.year() as i64 + step).ok()?;
+                let first = Date::new(year, 1, 1).ok()?;
+                dates_from(first, first.days_in_year())
+            }
+            Frequency::Monthly => {
+                let months = month_index(dtstart.date()) + step;
+                let year = i16::try_from(months.div_euclid(12)).ok()?;
+                let first = Date::new(year, (months.rem_euclid(12) + 1) as i8, 1).ok()?;
+                dates_from(first, first.days_in_month() as i16)
+            }
+            Frequency::Weekly => {
+                let days = Span::new().try_days(step.checked_mul(7)?).ok()?;
+                let first = week_start(dtstart.date(), self.wkst)
+                    .checked_add(days)
+                    .ok()?;
+                dates_from(first, 7)
+            }
+            Frequency::Daily => {
+                let days = Span::new().try_days(step).ok()?;
+                vec![dtstart.date().checked_add(days).ok()?]
+            }
+            sub_daily => return self.sub_daily_period(dtstart, index, sub_daily),
+        };
+        let first = *dates.first()?;
+        let mut candidates = Vec::new();
+        for date in dates.into_iter().filter(|d| self.day_matches(*d)) {
+            for &hour in &self.by_hour {
+                for &minute in &self.by_minute {
+                    for &second in &self.by_second {
+                        candidates.push(date.at(hour as i8, minute as i8, second as i8, 0));
+                    }
+                }
+            }
+        }
+        Some((first, self.select_positions(candidates), index + 1))
+    }
+
+    /// `period` for `HOURLY`, `MINUTELY` and `SECONDLY` rules. Periods on days, hours or
+    /// minutes the rule excludes are skipped in one step.
+    fn sub_daily_period(
+        &self,
+        dtstart: DateTime,
+        index: i64,
+        freq: Frequency,
+    ) -> Option<(Date, Vec<DateTime>, i64)> {
+        let origin = civil_seconds(dtstart)?;
+        let step = freq.seconds()? * self.interval;
+        let start = origin.checked_add(index.checked_mul(step)?)?;
+        let base = from_civil_seconds(start)?;
+        // First period starting at or after `boundary` seconds past the Unix epoch
+        let skip_to =
+            |boundary: i64| Some(index.max((boundary - origin + step - 1).div_euclid(step)));
+
+        if !self.day_matches(base.date()) {
+            let midnight = civil_seconds(base.date().tomorrow().ok()?.at(0, 0, 0, 0))?;
+            return Some((base.date(), Vec::new(), skip_to(midnight)?.max(index + 1)));
+        }
+        let hour_start = start - start.rem_euclid(3600);
+        if !contains_or_empty(&self.by_hour, base.hour()) {
+            return Some((
+                base.date(),
+                Vec::new(),
+                skip_to(hour_start + 3600)?.max(index + 1),
+            ));
+        }
+        let minute_start = start - start.rem_euclid(60);
+        if freq < Frequency::Minutely && !contains_or_empty(&self.by_minute, base.minute()) {
+            return Some((
+                base.date(),
+                Vec::new(),
+                skip_to(minute_start + 60)?.max(index + 1),
+            ));
+        }
+
+        let mut candidates = Vec::new();
+        match freq {
+            Frequency::Hourly => {
+                for &minute in &self.by_minute {
+                    for &second in &self.by_second {
+                        candidates.push(base.date().at(base.hour(), minute as i8, second as i8, 0));
+                    }
+                }
+            }
+            Frequency::Minutely => {
+                for &second in &self.by_second {
+                    candidates.push(base.date().at(base.hour(), base.minute(), second as i8, 0));
+                }
+            }
+            _ => {
+                if contains_or_empty(&self.by_second, base.second()) {
+                    candidates.push(base);
+                }
+            }
+        }
+        Some((base.date(), self.select_positions(candidates), index + 1))
+    }
+
+    /// Whether `date` passes the rule's month, week, year-day, month-day and weekday parts.
+    fn day_matches(&self, date: Date) -> bool {
+        contains_or_empty(&self.by_month, date.month())
+            && (self.by_week_no.is_empty() || self.week_no_matches(date))
+            && (self.by_year_day.is_empty()
+                || matches_signed(&self.by_year_day, date.day_of_year(), date.days_in_year()))
+            && (self.by_month_day.is_empty()
+                || matches_signed(
+                    &self.by_month_day,
+                    date.day() as i16,
+                    date.days_in_month() as i16,
+                ))
+            && (self.by_day.is_empty()
+                || self
+                    .by_day
+                    .iter()
+                    .any(|&(n, weekday)| self.by_day_matches(date, n, weekday)))
+    }
+
+    /// `BYDAY` with an ordinal counts weekdays within the month for `MONTHLY` rules and
+    /// for `YEARLY` rules with `BYMONTH`; otherwise within the year.
+    fn by_day_matches(&self, date: Date, n: i16, weekday: Weekday) -> bool {
+        if date.weekday() != weekday {
+            return false;
+        }
+        if n == 0 {
+            return true;
+        }
+        let (position, length) = if self.freq == Frequency::Monthly || !self.by_month.is_empty() {
+            (date.day() as i16, date.days_in_month() as i16)
+        } else {
+            (date.day_of_year(), date.days_in_year())
+        };
+        if n > 0 {
+            (position - 1) / 7 + 1 == n
+        } else {
+            (length - position) / 7 + 1 == -n
+        }
+    }
+
+    /// `BYWEEKNO`: weeks start on `WKST`, and week 1 is the first with at least four
+    /// days in the year. Dates at the ends of a year can belong to a neighbouring year's
+    /// weeks.
+    fn week_no_matches(&self, date: Date) -> bool {
+        let week_one = |year: i16| -> Option<Date> {
+            let jan1 = Date::new(year, 1, 1).ok()?;
+            let offset = weekday_offset(jan1.weekday(), self.wkst);
+            let days = if offset <= 3 { -offset } else { 7 - offset };
+            jan1.checked_add((days as i64).days()).ok()
+        };
+        let year = date.year();
+        let (Some(previous), Some(this), Some(next), Some(after)) = (
+            week_one(year - 1),
+            week_one(year),
+            week_one(year + 1),
+            week_one(year + 2),
+        ) else {
+            return false;
+        };
+        let (start, end) = if date < this {
+            (previous, this)
+        } else if date >= next {
+            (next, after)
+        } else {
+            (this, next)
+        };
+        let (Some(elapsed), Some(length)) = (days_between(start, date), days_between(start, end))
+        else {
+            return false;
+        };
+        matches_signed(
+            &self.by_week_no,
+            (elapsed / 7 + 1) as i16,
+            (length / 7) as i16,
+        )
+    }
+
+    /// Apply `BYSETPOS` to one period's sorted candidates.
+    fn select_positions(&self, candidates: Vec<DateTime>) -> Vec<DateTime> {
+        if self.by_set_pos.is_empty() {
+            return candidates;
+        }
+        let len = candidates.len() as i16;
+        let mut selected: Vec<DateTime> = self
+            .by_set_pos
+            .iter()
+            .filter_map(|&pos| {
+                let index = if pos > 0 { pos - 1 } else { len + pos };
+                (0..len)
+                    .contains(&index)
+                    .then(|| candidates[index as usize])
+            })
+            .collect();
+        selected.sort();
+        selected.dedup();
+        selected
+    }
+}
+
+/// Lazily generated local times of one rule, in order.
+struct RuleIter<'a> {
+    rule: &'a RecurrenceRule,
+    dtstart: DateTime,
+    zone: &'a TimeZone,
+    period: i64,
+    buffer: VecDeque<DateTime>,
+    emitted: u32,
+    last_hit: Date,
+    done: bool,
+}
+
+impl RuleIter<'_> {
+    fn past_until(&self, civil: DateTime) -> bool {
+        match self.rule.until {
+            None => false,
+            Some(Until::Date(date)) => civil.date() > date,
+            Some(Until::Local(limit)) => civil > limit,
+            Some(Until::Instant(limit)) => resolve(civil, self.zone).is_some_and(|ts| ts > limit),
+        }
+    }
+}
+
+impl Iterator for RuleIter<'_> {
+    type Item = DateTime;
+
+    fn next(&mut self) -> Option<DateTime> {
+        while !self.done {
+            if let Some(civil) = self.buffer.pop_front() {
+                if self.rule.count.is_some_and(|count| self.emitted >= count)
+                    || self.past_until(civil)
+                {
+                    self.done = true;
+                    break;
+                }
+                self.emitted += 1;
+                self.last_hit = civil.date();
+                return Some(civil);
+            }
+            match self.rule.period(self.dtstart, self.period) {
+                Some((first, candidates, next))
+                    if first.year() - self.last_hit.year() <= SEARCH_HORIZON_YEARS =>
+                {
+                    let dtstart = self.dtstart;
+                    self.buffer
+                        .extend(candidates.into_iter().filter(|c| *c > dtstart));
+                    self.period = next;
+                }
+                _ => self.done = true,
+            }
+        }
+        None
+    }
+}
+
+/// A `DTSTART`, `RDATE` or `EXDATE` value.
+#[derive(Debug, Clone, PartialEq)]
+enum DateValue {
+    Date(Date),
+    Floating(DateTime),
+    Utc(Timestamp),
+    Zoned(DateTime, TimeZone),
+}
+
+impl DateValue {
+    /// The local time of this value in `zone`; floating times and dates are taken as is.
+    fn civil_in(&self, zone: &TimeZone) -> DateTime {
+        match self {
+            DateValue::Date(date) => date.at(0, 0, 0, 0),
+            DateValue::Floating(civil) => *civil,
+            DateValue::Utc(ts) => ts.to_zoned(zone.clone()).datetime(),
+            DateValue::Zoned(civil, tz) => {
+                resolve(*civil, tz).map_or(*civil, |ts| ts.to_zoned(zone.clone()).datetime())
+            }
+        }
+    }
+}
+
+/// Parse a comma-separated iCalendar date or date-time list, honouring `TZID` and
+/// `VALUE` parameters.
+fn parse_values(value: &str, params: &[(String, String)]) -> Result<Vec<DateValue>, String> {
+    let mut zone = None;
+    for (key, param) in params {
+        match key.as_str() {
+            "TZID" => zone = Some(resolve_zone(param)?),
+            "VALUE" if param.eq_ignore_ascii_case("PERIOD") => {
+                return Err("PERIOD values are not supported".to_string())
+            }
+            _ => {}
+        }
+    }
+    value
+        .split(',')
+        .map(|item| {
+            let item = item.trim();
+            let parsed = parse_ical_value(item)?;
+            Ok(match (parsed, &zone) {
+                (DateValue::Floating(civil), Some(tz)) => DateValue::Zoned(civil, tz.clone()),
+                (other, _) => other,
+            })
+        })
+        .collect()
+}
+
+/// Parse one iCalendar `DATE` (`20240101`) or `DATE-TIME` (`20240101T090000[Z]`).
+fn parse_ical_value(item: &str) -> Result<DateValue, String> {
+    let invalid = || format!("Invalid iCalendar date or date-time '{}'", item);
+    if let Some(utc) = item.strip_suffix(['Z', 'z']) {
+        let civil = DateTime::strptime("%Y%m%dT%H%M%S", utc).map_err(|_| invalid())?;
+        return civil
+            .to_zoned(TimeZone::UTC)
+            .map(|z| DateValue::Utc(z.timestamp()))
+            .map_err(|_| invalid());
+    }
+    if item.len() == 8 {
+        return Date::strptime("%Y%m%d", item)
+            .map(DateValue::Date)
+            .map_err(|_| invalid());
+    }
+    DateTime::strptime("%Y%m%dT%H%M%S", item)
+        .map(DateValue::Floating)
+        .map_err(|_| invalid())
+}
+
+/// Parse the `dtstart` argument: RFC 9557 (`2024-01-01T09:00[Europe/Paris]`), UTC
+/// (`...Z`), a local date-time or date, or the iCalendar forms.
+fn parse_dtstart(input: &str) -> Result<DateValue, String> {
+    let input = input.trim();
+    if let Ok(zoned) = input.parse::<jiff::Zoned>() {
+        return Ok(DateValue::Zoned(
+            zoned.datetime(),
+            zoned.time_zone().clone(),
+        ));
+    }
+    if let Ok(ts) = input.parse::<Timestamp>() {
+        if input.ends_with(['Z', 'z']) {
+            return Ok(DateValue::Utc(ts));
+        }
+        return Err(format!(
+            "DTSTART '{}' has a UTC offset but no zone; name one, e.g. {}[Europe/Paris]",
+            input, input
+        ));
+    }
+    // jiff reads a bare date as midnight, so look for one before trying a date-time.
+    if !input.contains(['T', 't', ' ']) {
+        if let Ok(date) = input.parse::<Date>() {
+            return Ok(DateValue::Date(date));
+        }
+    }
+    if let Ok(civil) = input.parse::<DateTime>() {
+        return Ok(DateValue::Floating(civil));
+    }
+    parse_ical_value(input)
+}
+
+fn parse_until(value: &str) -> Result<Until, String> {
+    Ok(match parse_ical_value(value)? {
+        DateValue::Date(date) => Until::Date(date),
+        DateValue::Floating(civil) => Until::Local(civil),
+        DateValue::Utc(ts) => Until::Instant(ts),
+        DateValue::Zoned(civil, _) => Until::Local(civil),
+    })
+}
+
+/// Parse a comma-separated list of integers in `min..=max` (or its negation when `signed`).
+fn parse_numbers(
+    value: &str,
+    part: &str,
+    min: i16,
+    max: i16,
+    signed: bool,
+) -> Result<Vec<i16>, String> {
+    let mut numbers = value
+        .split(',')
+        .map(|item| {
+            let item = item.trim();
+            let n = item
+                .trim_start_matches('+')
+                .parse::<i16>()
+                .map_err(|_| format!("{} value '{}' is not a number", part, item))?;
+            let magnitude = if signed { n.abs() } else { n };
+            if (min..=max).contains(&magnitude) && (n >= 0 || signed) {
+                Ok(n)
+            } else if signed {
+                Err(format!(
+                    "{} value {} out of range ({}..{} or -{}..-{})",
+                    part, n, min, max, max, min
+                ))
+            } else {
+                Err(format!(
+                    "{} value {} out of range ({}..{})",
+                    part, n, min, max
+                ))
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    numbers.sort_unstable();
+    numbers.dedup();
+    Ok(numbers)
+}
+
+fn parse_weekday(code: &str) -> Result<Weekday, String> {
+    WEEKDAY_CODES
+        .iter()
+        .position(|c| c.eq_ignore_ascii_case(code.trim()))
+        .and_then(|i| Weekday::from_monday_zero_offset(i as i8).ok())
+        .ok_or_else(|| format!("Unknown weekday '{}' (expected MO, TU, ... SU)", code))
+}
+
+/// Parse `BYDAY` items such as `MO`, `1FR`, `+2TU` or `-1SU`.
+fn parse_by_day(value: &str) -> Result<Vec<(i16, Weekday)>, String> {
+    value
+        .split(',')
+        .map(|item| {
+            let item = item.trim();
+            let split = item.len().saturating_sub(2);
+            let (ordinal, code) = item.split_at(split);
+            let weekday = parse_weekday(code)?;
+            let n = match ordinal.trim_start_matches('+') {
+                "" => 0,
+                digits => digits
+                    .parse::<i16>()
+                    .ok()
+                    .filter(|n| *n != 0 && n.abs() <= 53)
+                    .ok_or_else(|| {
+                        format!("BYDAY ordinal in '{}' must be 1..53 or -53..-1", item)
+                    })?,
+            };
+            Ok((n, weekday))
+        })
+        .collect()
+}
+
+fn contains_or_empty(values: &[i16], value: i8) -> bool {
+    values.is_empty() || values.contains(&(value as i16))
+}
+
+/// Match 1-based positions counted from the start (`n > 0`) or end (`n < 0`).
+fn matches_signed(values: &[i16], position: i16, length: i16) -> bool {
+    values.iter().any(|&n| {
+        if n > 0 {
+            n == position
+        } else {
+            length + n + 1 == position
+        }
+    })
+}
+
+/// Days from `weekday` back to the most recent `wkst`.
+fn weekday_offset(weekday: Weekday, wkst: Weekday) -> i16 {
+    (weekday.to_monday_zero_offset() - wkst.to_monday_zero_offset()).rem_euclid(7) as i16
+}
+
+fn week_start(date: Date, wkst: Weekday) -> Date {
+    date.checked_sub((weekday_offset(date.weekday(), wkst) as i64).days())
+        .unwrap_or(date)
+}
+
+fn month_index(date: Date) -> i64 {
+    date.year() as i64 * 12 + date.month() as i64 - 1
+}
+
+fn dates_from(first: Date, count: i16) -> Vec<Date> {
+    let mut dates = Vec::with_capacity(count as usize);
+    let mut date = first;
+    for _ in 0..count {
+        dates.push(date);
+        match date.tomorrow() {
+            Ok(next) => date = next,
+            Err(_) => break,
+        }
+    }
+    dates
+}
+
+/// Seconds of a local time on a uniform clock (local time read as UTC).
+fn civil_seconds(civil: DateTime) -> Option<i64> {
+    civil
+        .to_zoned(TimeZone::UTC)
+        .ok()
+        .map(|z| z.timestamp().as_second())
+}
+
+fn from_civil_seconds(seconds: i64) -> Option<DateTime> {
+    Timestamp::from_second(seconds)
+        .ok()
+        .map(|ts| ts.to_zoned(TimeZone::UTC).datetime())
+}
+
+fn days_between(from: Date, to: Date) -> Option<i64> {
+    Some((civil_seconds(to.at(0, 0, 0, 0))? - civil_seconds(from.at(0, 0, 0, 0))?) / 86_400)
+}
+
+/// The instant of a local time in `zone` under RFC 5545: gap times use the offset from
+/// before the gap, repeated times their first occurrence.
+fn resolve(civil: DateTime, zone: &TimeZone) -> Option<Timestamp> {
+    zone.to_ambiguous_zoned(civil)
+        .compatible()
+        .ok()
+        .map(|z| z.timestamp())
+}
+
+/// A `DTSTART` with the `RRULE`s, `RDATE`s and `EXDATE`s that make up its recurrence set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecurrenceSet {
+    pub dtstart: DateTime,
+    /// `DTSTART` was a date (`VALUE=DATE`) rather than a date-time
+    pub all_day: bool,
+    pub zone: TimeZone,
+    rules: Vec<RecurrenceRule>,
+    rdates: Vec<DateTime>,
+    exdates: Vec<DateValue>,
+}
+
+impl RecurrenceSet {
+    /// Parse recurrence properties, one per line:
+    ///
+    /// ```text
+    /// DTSTART;TZID=America/New_York:19970902T090000
+    /// RRULE:FREQ=MONTHLY;BYDAY=FR;BYMONTHDAY=13
+    /// EXDATE;TZID=America/New_York:19970902T090000
+    /// ```
+    ///
+    /// A bare `FREQ=...` line is read as an `RRULE`, and other iCalendar properties are
+    /// ignored. `dtstart` gives the start when there is no `DTSTART` line; floating and
+    /// date starts are placed in `default_zone`.
+    pub fn parse(text: &str, dtstart: &str, default_zone: TimeZone) -> Result<Self, String> {
+        let unfolded = text
+            .replace("\r\n", "\n")
+            .replace("\n ", "")
+            .replace("\n\t", "");
+        let mut start = None;
+        let mut rules = Vec::new();
+        let mut rdates = Vec::new();
+        let mut exdates = Vec::new();
+        for line in unfolded.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            if line.to_ascii_uppercase().starts_with("FREQ=") {
+                rules.push(line.to_string());
+                continue;
+            }
+            let Some((head, value)) = line.split_once(':') else {
+                return Err(format!("Cannot read recurrence line '{}'", line));
+            };
+            let mut head = head.split(';');
+            let name = head.next().unwrap_or_default().trim().to_ascii_uppercase();
+            let params: Vec<(String, String)> = head
+                .filter_map(|p| p.split_once('='))
+                .map(|(k, v)| (k.trim().to_ascii_uppercase(), v.trim().to_string()))
+                .collect();
+            match name.as_str() {
+                "DTSTART" => {
+                    let mut values = parse_values(value, &params)?;
+                    if values.len() != 1 || start.is_some() {
+                        return Err("Give exactly one DTSTART value".to_string());
+                    }
+                    start = values.pop();
+                }
+                "RRULE" => rules.push(value.to_string()),
+                "RDATE" => rdates.extend(parse_values(value, &params)?),
+                "EXDATE" => exdates.extend(parse_values(value, &params)?),
+                "EXRULE" => return Err("EXRULE was removed in RFC 5545; use EXDATE".to_string()),
+                _ => {}
+            }
+        }
+        let start = match (start, dtstart.trim().is_empty()) {
+            (Some(_), false) => {
+                return Err("DTSTART is given both in the rule and as an argument".to_string())
+            }
+            (Some(start), true) => start,
+            (None, false) => parse_dtstart(dtstart)?,
+            (None, true) => return Err("A DTSTART is required".to_string()),
+        };
+        let (zone, all_day) = match &start {
+            DateValue::Date(_) => (default_zone, true),
+            DateValue::Floating(_) => (default_zone, false),
+            DateValue::Utc(_) => (TimeZone::UTC, false),
+            DateValue::Zoned(_, tz) => (tz.clone(), false),
+        };
+        let dtstart = start.civil_in(&zone);
+        let rules = rules
+            .iter()
+            .map(|rule| RecurrenceRule::parse(rule, dtstart, all_day))
+            .collect::<Result<Vec<_>, _>>()?;
+        let rdates = rdates
+            .iter()
+            .map(|value| match value {
+                // A date added to a timed series keeps the series' time of day.
+                DateValue::Date(date) if !all_day => date.to_datetime(dtstart.time()),
+                other => other.civil_in(&zone),
+            })
+            .collect();
+        Ok(RecurrenceSet {
+            dtstart,
+            all_day,
+            zone,
+            rules,
+            rdates,
+            exdates,
+        })
+    }
+
+    /// Up to `limit` occurrences strictly after `after` (or from `DTSTART`), in order.
+    ///
+    /// The set is `DTSTART`, every `RRULE` occurrence and every `RDATE`, minus the
+    /// `EXDATE`s; a date `EXDATE` removes every occurrence on that local day.
+    pub fn occurrences(&self, after: Option<Timestamp>, limit: usize) -> Recurrence {
+        let from = after.and_then(|ts| {
+            ts.to_zoned(self.zone.clone())
+                .datetime()
+                .checked_sub(1.day())
+                .ok()
+        });
+        let occurrence = |civil: DateTime| {
+            resolve(civil, &self.zone).map(|timestamp| Occurrence { civil, timestamp })
+        };
+        let wanted = |o: &Occurrence| after.is_none_or(|a| o.timestamp > a) && !self.excluded(o);
+
+        let mut occurrences: Vec<Occurrence> = std::iter::once(self.dtstart)
+            .chain(self.rdates.iter().copied())
+            .filter_map(occurrence)
+            .filter(wanted)
+            .collect();
+        // Each rule is pulled until it has `limit` occurrences the EXDATEs leave alone;
+        // one date EXDATE can remove any number of them.
+        for rule in &self.rules {
+            occurrences.extend(
+                rule.iter(self.dtstart, &self.zone, from)
+                    .filter_map(occurrence)
+                    .filter(wanted)
+                    .take(limit),
+            );
+        }
+        occurrences.sort_by_key(|o| o.timestamp);
+        occurrences.dedup_by_key(|o| o.timestamp);
+        occurrences.truncate(limit);
+        Recurrence {
+            dtstart: self.dtstart,
+            all_day: self.all_day,
+            zone: self.zone.clone(),
+            occurrences,
+        }
+    }
+
+    fn excluded(&self, occurrence: &Occurrence) -> bool {
+        self.exdates.iter().any(|ex| match ex {
+            DateValue::Date(date) => occurrence.civil.date() == *date,
+            other => resolve(other.civil_in(&self.zone), &self.zone) == Some(occurrence.timestamp),
+        })
+    }
+}
+
+/// One occurrence of a recurrence set.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Occurrence {
+    /// Local time the rule produced, before DST resolution
+    pub civil: DateTime,
+    pub timestamp: Timestamp,
+}
+
+/// Expanded occurrences, serialized for the Wasm boundary.
+#[derive(Debug, PartialEq)]
+pub struct Recurrence {
+    pub dtstart: DateTime,
+    pub all_day: bool,
+    pub zone: TimeZone,
+    pub occurrences: Vec<Occurrence>,
+}
+
+impl Recurrence {
+    /// Serialize to JSON string for Wasm boundary (manual serialization).
+    ///
+    /// All-day occurrences also carry their `date`.
+    pub fn to_json(&self) -> String {
+        let occurrences: Vec<String> = self
+            .occurrences
+            .iter()
+            .map(|o| {
+                let mut json = format!(
+                    r#"{{"iso":"{}","unixTimestamp":{},"local":"{}""#,
+                    o.timestamp,
+                    o.timestamp.as_second(),
+                    json_escape(&o.timestamp.to_zoned(self.zone.clone()).to_string())
+                );
+                if self.all_day {
+                    json.push_str(&format!(r#","date":"{}""#, o.civil.date()));
+                }
+                json.push('}');
+                json
+            })
+            .collect();
+        format!(
+            r#"{{"dtstart":"{}","zone":"{}","allDay":{},"occurrences":[{}]}}"#,
+            self.dtstart,
+            json_escape(&zone_name(&self.zone)),
+            self.all_day,
+            occurrences.join(",")
+        )
+    }
+}
+
+/// Upper bound on the occurrences a single recurrence query may return.
+const MAX_OCCURRENCES: usize = 1000;
+
+/// Expand up to `count` occurrences of an iCalendar recurrence set.
+///
+/// `rule` holds `RRULE`/`RDATE`/`EXDATE` lines (and optionally `DTSTART`, in which
+/// case `dtstart` must be empty; see `RecurrenceSet::parse`). `options_json` may set
+/// the `zone` for floating and all-day starts, and `after` (a date input) to list only
+/// occurrences after that instant.
+pub fn rrule_occurrences(
+    rule: &str,
+    dtstart: &str,
+    count: u32,
+    options_json: &str,
+) -> Result<Recurrence, String> {
+    let zone = resolve_zone(&extract_string_field(options_json, "zone").unwrap_or_default())?;
+    let after = extract_string_field(options_json, "after")
+        .map(|after| parse_start_date(&after))
+        .transpose()?;
+    let set = RecurrenceSet::parse(rule, dtstart, zone)?;
+    let count = (count as usize).min(MAX_OCCURRENCES);
+    Ok(set.occurrences(after, count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(text: &str, dtstart: &str, limit: usize) -> Vec<String> {
+        RecurrenceSet::parse(text, dtstart, TimeZone::UTC)
+            .unwrap()
+            .occurrences(None, limit)
+            .occurrences
+            .iter()
+            .map(|o| o.civil.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_rule_parts() {
+        let rule = RecurrenceRule::parse(
+            "FREQ=MONTHLY;INTERVAL=2;BYDAY=-1FR,+2MO;WKST=SU",
+            "2024-01-01T09:00".parse().unwrap(),
+            false,
+        )
+        .unwrap();
+        assert_eq!(rule.interval, 2);
+        assert_eq!(
+            rule.by_day,
+            vec![(-1, Weekday::Friday), (2, Weekday::Monday)]
+        );
+        assert_eq!(rule.wkst, Weekday::Sunday);
+        assert_eq!(
+            (rule.by_hour.clone(), rule.by_minute.clone()),
+            (vec![9], vec![0])
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let dtstart: DateTime = "2024-01-01T09:00".parse().unwrap();
+        let err = |rule: &str| RecurrenceRule::parse(rule, dtstart, false).unwrap_err();
+        assert!(err("INTERVAL=2").contains("FREQ"));
+        assert!(err("FREQ=FORTNIGHTLY").contains("Unknown FREQ"));
+        assert!(err("FREQ=DAILY;COUNT=2;UNTIL=20240301").contains("COUNT and UNTIL"));
+        assert!(err("FREQ=WEEKLY;BYDAY=1MO").contains("ordinals"));
+        assert!(err("FREQ=WEEKLY;BYMONTHDAY=1").contains("BYMONTHDAY"));
+        assert!(err("FREQ=MONTHLY;BYMONTHDAY=0").contains("out of range"));
+        assert!(err("FREQ=DAILY;BYHOUR=24").contains("out of range"));
+        assert!(err("FREQ=DAILY;COLOR=RED").contains("Unknown RRULE part"));
+    }
+
+    #[test]
+    fn test_dtstart_counts_as_first_occurrence() {
+        // 2024-01-01 is a Monday; the rule itself only produces Fridays.
+        assert_eq!(
+            expand("FREQ=WEEKLY;BYDAY=FR;COUNT=3", "2024-01-01T10:00", 10),
+            vec![
+                "2024-01-01T10:00:00",
+                "2024-01-05T10:00:00",
+                "2024-01-12T10:00:00"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_monthly_skips_short_months() {
+        assert_eq!(
+            expand("FREQ=MONTHLY;COUNT=4", "2024-01-31", 10),
+            vec![
+                "2024-01-31T00:00:00",
+                "2024-03-31T00:00:00",
+                "2024-05-31T00:00:00",
+                "2024-07-31T00:00:00"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_week_numbers_cross_year_boundaries() {
+        // ISO week 1 of 2025 starts on Monday 2024-12-30.
+        assert_eq!(
+            expand("FREQ=YEARLY;BYWEEKNO=1;BYDAY=MO;COUNT=3", "2024-12-30", 10),
+            vec![
+                "2024-12-30T00:00:00",
+                "2025-12-29T00:00:00",
+                "2027-01-04T00:00:00"
+            ]
+        );
+        assert_eq!(
+            expand("FREQ=YEARLY;BYWEEKNO=-1;BYDAY=SU;COUNT=2", "2026-12-27", 10),
+            vec!["2026-12-27T00:00:00", "2027-01-03T00:00:00"]
+        );
+    }
+
+    #[test]
+    fn test_sub_daily_limits() {
+        assert_eq!(
+            expand(
+                "FREQ=MINUTELY;INTERVAL=20;BYHOUR=9,10;COUNT=7",
+                "2024-01-01T09:00",
+                10
+            ),
+            vec![
+                "2024-01-01T09:00:00",
+                "2024-01-01T09:20:00",
+                "2024-01-01T09:40:00",
+                "2024-01-01T10:00:00",
+                "2024-01-01T10:20:00",
+                "2024-01-01T10:40:00",
+                "2024-01-02T09:00:00"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_impossible_rule_ends() {
+        assert_eq!(
+            expand("FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30", "2024-01-01", 5),
+            vec!["2024-01-01T00:00:00"]
+        );
+    }
+
+    #[test]
+    fn test_rdate_and_exdate() {
+        let text = "RRULE:FREQ=DAILY;COUNT=3\nRDATE:20240110\nEXDATE:20240102T090000";
+        assert_eq!(
+            expand(text, "2024-01-01T09:00", 10),
+            vec![
+                "2024-01-01T09:00:00",
+                "2024-01-03T09:00:00",
+                "2024-01-10T09:00:00"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_date_exdate_removes_whole_day() {
+        let text = "RRULE:FREQ=HOURLY\nEXDATE;VALUE=DATE:20240101";
+        assert_eq!(
+            expand(text, "2024-01-01T00:00", 2),
+            vec!["2024-01-02T00:00:00", "2024-01-02T01:00:00"]
+        );
+    }
+
+    #[test]
+    fn test_huge_interval_ends() {
+        assert_eq!(
+            expand("FREQ=DAILY;INTERVAL=10000000", "2024-01-01", 3),
+            vec!["2024-01-01T00:00:00"]
+        );
+        assert_eq!(
+            expand("FREQ=WEEKLY;INTERVAL=2000000", "2024-01-01", 3),
+            vec!["2024-01-01T00:00:00"]
+        );
+    }
+}
@@ -0,0 +1,278 @@
+//! Spreadsheet serial dates: days since a base date, with the time of day as the
+//! fractional part (`45484.5` is 2024-07-11 12:00 in Excel).
+//!
+//! Serials are wall-clock values without a zone. They are parsed and written as
+//! decimal text with integer arithmetic, so a value round-trips to the millisecond
+//! instead of drifting through `f64`.
+
+use jiff::civil::{self, Date, DateTime};
+use jiff::{Span, ToSpan};
+
+use crate::windows::{
+    format_day_count, nanos_of_day, parse_day_count, MILLIS_PER_DAY, NANOS_PER_DAY,
+};
+
+/// Excel's fictional 1900-02-29, kept from Lotus 1-2-3; decoded as 1900-02-28, the
+/// date Google Sheets gives it.
+const EXCEL_LEAP_BUG_SERIAL: i64 = 60;
+
+fn days_between(base: Date, date: Date) -> Result<i64, String> {
+    base.until(date)
+        .map(|span| i64::from(span.get_days()))
+        .map_err(|e| format!("Date out of range: {}", e))
+}
+
+fn add_days(base: Date, days: i64, millis: i64) -> Result<DateTime, String> {
+    // `try_days` because a serial such as 8000000 is past what a span can even hold
+    Span::new()
+        .try_days(days)
+        .and_then(|days| base.to_datetime(civil::Time::midnight()).checked_add(days))
+        .and_then(|dt| dt.checked_add(millis.milliseconds()))
+        .map_err(|e| format!("out of range: {}", e))
+}
+
+/// A spreadsheet date system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpreadsheetSerial {
+    /// Excel's default (and Lotus 1-2-3's) system: 1 is 1900-01-01, 60 is the
+    /// fictional 1900-02-29, so real dates from 1900-03-01 on count from 1899-12-30
+    /// (and 60 decodes as 1900-02-28)
+    Excel1900,
+    /// Excel's 1904 system (old Mac workbooks): 0 is 1904-01-01
+    Excel1904,
+    /// Google Sheets (and LibreOffice): days since 1899-12-30, negative before it
+    GoogleSheets,
+}
+
+impl SpreadsheetSerial {
+    pub const ALL: [SpreadsheetSerial; 3] = [
+        SpreadsheetSerial::Excel1900,
+        SpreadsheetSerial::Excel1904,
+        SpreadsheetSerial::GoogleSheets,
+    ];
+
+    /// The name used as an input prefix and output format name.
+    pub fn name(self) -> &'static str {
+        match self {
+            SpreadsheetSerial::Excel1900 => "excel1900",
+            SpreadsheetSerial::Excel1904 => "excel1904",
+            SpreadsheetSerial::GoogleSheets => "googleSheets",
+        }
+    }
+
+    /// Other input prefixes accepted for this system.
+    fn aliases(self) -> &'static [&'static str] {
+        match self {
+            SpreadsheetSerial::Excel1900 => &["excel", "lotus"],
+            SpreadsheetSerial::Excel1904 => &[],
+            SpreadsheetSerial::GoogleSheets => &["sheets"],
+        }
+    }
+
+    /// Look up an input prefix, by name or alias, ignoring case.
+    pub fn from_prefix(prefix: &str) -> Option<Self> {
+        let prefix = prefix.trim();
+        SpreadsheetSerial::ALL.into_iter().find(|s| {
+            s.name().eq_ignore_ascii_case(prefix)
+                || s.aliases().iter().any(|a| a.eq_ignore_ascii_case(prefix))
+        })
+    }
+
+    /// Decode a serial to the wall-clock time it stands for.
+    pub fn decode(self, value: &str) -> Result<DateTime, String> {
+        let count = parse_day_count(value)?;
+        match self {
+            SpreadsheetSerial::GoogleSheets => {
+                let millis = count
+                    .whole
+                    .checked_mul(MILLIS_PER_DAY)
+                    .and_then(|ms| ms.checked_add(count.millis))
+                    .ok_or_else(|| "out of range".to_string())?;
+                let millis = if count.negative { -millis } else { millis };
+                add_days(
+                    civil::date(1899, 12, 30),
+                    millis.div_euclid(MILLIS_PER_DAY),
+                    millis.rem_euclid(MILLIS_PER_DAY),
+                )
+            }
+            _ if count.negative && (count.whole != 0 || count.millis != 0) => {
+                Err(format!("{} serials cannot be negative", self.name()))
+            }
+            SpreadsheetSerial::Excel1900 => match count.whole {
+                // 0 is Excel's "1900-01-00", the day before 1900-01-01
+                whole if whole < EXCEL_LEAP_BUG_SERIAL => {
+                    add_days(civil::date(1899, 12, 31), whole, count.millis)
+                }
+                // From 60 on, as in Google Sheets; see `warning`
+                whole => add_days(civil::date(1899, 12, 30), whole, count.millis),
+            },
+            SpreadsheetSerial::Excel1904 => {
+                add_days(civil::date(1904, 1, 1), count.whole, count.millis)
+            }
+        }
+    }
+
+    /// Why `value` does not decode to the date the spreadsheet shows: Excel's serial 60
+    /// is 1900-02-29, which `decode` reads as 1900-02-28.
+    pub fn warning(self, value: &str) -> Option<String> {
+        let count = parse_day_count(value).ok()?;
+        (self == SpreadsheetSerial::Excel1900
+            && !count.negative
+            && count.whole == EXCEL_LEAP_BUG_SERIAL)
+            .then(|| {
+                "serial 60 is 1900-02-29, a day that never existed (Excel keeps it for \
+                 Lotus 1-2-3 compatibility); read as 1900-02-28"
+                    .to_string()
+            })
+    }
+
+    /// Encode a wall-clock time as a serial.
+    pub fn encode(self, dt: DateTime) -> Result<String, String> {
+        let date = dt.date();
+        let nanos = nanos_of_day(dt);
+        match self {
+            SpreadsheetSerial::GoogleSheets => {
+                let total = i128::from(days_between(civil::date(1899, 12, 30), date)?)
+                    * NANOS_PER_DAY
+                    + nanos;
+                let whole = (total.abs() / NANOS_PER_DAY) as i64;
+                Ok(format_day_count(
+                    total < 0,
+                    whole,
+                    total.abs() % NANOS_PER_DAY,
+                ))
+            }
+            SpreadsheetSerial::Excel1900 => {
+                let whole = if date >= civil::date(1900, 3, 1) {
+                    days_between(civil::date(1899, 12, 30), date)?
+                } else {
+                    days_between(civil::date(1899, 12, 31), date)?
+                };
+                if whole < 0 {
+                    return Err(format!(
+                        "{} is before 1900-01-01, the start of the Excel 1900 date system",
+                        date
+                    ));
+                }
+                Ok(format_day_count(false, whole, nanos))
+            }
+            SpreadsheetSerial::Excel1904 => {
+                let whole = days_between(civil::date(1904, 1, 1), date)?;
+                if whole < 0 {
+                    return Err(format!(
+                        "{} is before 1904-01-01, the start of the Excel 1904 date system",
+                        date
+                    ));
+                }
+                Ok(format_day_count(false, whole, nanos))
+            }
+        }
+    }
+}
+
+/// Split `prefix:serial` input into its system and serial.
+fn split_input(input: &str) -> Option<(SpreadsheetSerial, &str)> {
+    let (prefix, value) = input.trim().split_once(':')?;
+    Some((SpreadsheetSerial::from_prefix(prefix)?, value))
+}
+
+/// Split `prefix:serial` input such as `excel:45484.5`; `None` when the input does not
+/// start with a known prefix.
+pub fn parse_input(input: &str) -> Option<Result<DateTime, String>> {
+    let (system, value) = split_input(input)?;
+    Some(
+        system
+            .decode(value)
+            .map_err(|e| format!("Invalid {} serial '{}': {}", system.name(), value.trim(), e)),
+    )
+}
+
+/// The `warning` for `prefix:serial` input, e.g. `excel:60`.
+pub fn input_warning(input: &str) -> Option<String> {
+    let (system, value) = split_input(input)?;
+    system.warning(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(system: SpreadsheetSerial, value: &str) -> String {
+        system.decode(value).unwrap().to_string()
+    }
+
+    #[test]
+    fn test_excel_1900_leap_bug() {
+        let excel = SpreadsheetSerial::Excel1900;
+        assert_eq!(decode(excel, "1"), "1900-01-01T00:00:00");
+        assert_eq!(decode(excel, "59"), "1900-02-28T00:00:00");
+        assert_eq!(decode(excel, "61"), "1900-03-01T00:00:00");
+        assert_eq!(decode(excel, "0.5"), "1899-12-31T12:00:00");
+        // The fictional 1900-02-29 reads as Google Sheets reads it, with a warning
+        assert_eq!(decode(excel, "60.5"), "1900-02-28T12:00:00");
+        assert!(excel.warning("60.5").unwrap().contains("never existed"));
+        assert_eq!(excel.warning("61"), None);
+        assert_eq!(SpreadsheetSerial::GoogleSheets.warning("60"), None);
+        assert_eq!(
+            excel
+                .encode("1900-02-28T00:00:00".parse().unwrap())
+                .unwrap(),
+            "59"
+        );
+        assert_eq!(
+            excel
+                .encode("1900-03-01T00:00:00".parse().unwrap())
+                .unwrap(),
+            "61"
+        );
+        assert!(excel
+            .encode("1899-12-30T00:00:00".parse().unwrap())
+            .is_err());
+    }
+
+    #[test]
+    fn test_systems_agree_on_modern_dates() {
+        let dt: DateTime = "2024-07-11T12:00:00".parse().unwrap();
+        assert_eq!(SpreadsheetSerial::Excel1900.encode(dt).unwrap(), "45484.5");
+        assert_eq!(
+            SpreadsheetSerial::GoogleSheets.encode(dt).unwrap(),
+            "45484.5"
+        );
+        // The 1904 system is 1462 days behind
+        assert_eq!(SpreadsheetSerial::Excel1904.encode(dt).unwrap(), "44022.5");
+    }
+
+    #[test]
+    fn test_sheets_negative_serials_are_linear() {
+        let sheets = SpreadsheetSerial::GoogleSheets;
+        assert_eq!(decode(sheets, "-1.25"), "1899-12-28T18:00:00");
+        let dt: DateTime = "1899-12-28T18:00:00".parse().unwrap();
+        assert_eq!(sheets.encode(dt).unwrap(), "-1.25");
+        assert!(SpreadsheetSerial::Excel1904.decode("-1").is_err());
+    }
+
+    #[test]
+    fn test_round_trip_to_the_millisecond() {
+        for text in [
+            "1900-01-01T00:00:00.001",
+            "1904-01-01T23:59:59.999",
+            "2024-07-11T03:14:15.926",
+            "9999-12-31T23:59:59.999",
+        ] {
+            let dt: DateTime = text.parse().unwrap();
+            // 1900 is outside the 1904 system
+            for system in SpreadsheetSerial::ALL {
+                let Ok(serial) = system.encode(dt) else {
+                    continue;
+                };
+                assert_eq!(
+                    system.decode(&serial).unwrap(),
+                    dt,
+                    "{} {}",
+                    system.name(),
+                    serial
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,987 @@
+use jiff::fmt::strtime;
+use jiff::Zoned;
+
+use crate::calc::parse_start_date;
+use crate::json_utils::json_escape;
+use crate::zone::resolve_zone;
+
+/// A date format pattern syntax.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    /// C/jiff `strftime` directives: `%Y-%m-%dT%H:%M:%S%.3f%:z`
+    Strftime,
+    /// Python `datetime.strftime` directives: `%Y-%m-%d %H:%M:%S.%f`
+    Python,
+    /// Java `DateTimeFormatter` / `SimpleDateFormat` letters: `yyyy-MM-dd'T'HH:mm:ss.SSSXXX`
+    Java,
+    /// Unicode LDML (ICU, CLDR) date field symbols: `yyyy-MM-dd HH:mm`
+    Ldml,
+    /// moment.js / dayjs tokens: `YYYY-MM-DD[T]HH:mm:ssZ`
+    Moment,
+    /// .NET custom date and time format strings: `yyyy-MM-ddTHH:mm:ss.fffzzz`
+    DotNet,
+}
+
+impl Dialect {
+    /// Every dialect, in the order translations are reported.
+    pub const ALL: [Dialect; 6] = [
+        Dialect::Strftime,
+        Dialect::Python,
+        Dialect::Java,
+        Dialect::Ldml,
+        Dialect::Moment,
+        Dialect::DotNet,
+    ];
+
+    /// The name used for this dialect in JSON (request and response).
+    pub fn name(self) -> &'static str {
+        match self {
+            Dialect::Strftime => "strftime",
+            Dialect::Python => "python",
+            Dialect::Java => "java",
+            Dialect::Ldml => "ldml",
+            Dialect::Moment => "moment",
+            Dialect::DotNet => "dotnet",
+        }
+    }
+
+    /// Look up a dialect by name. `dayjs` is accepted as an alias for `moment`.
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        let lower = name.trim().to_ascii_lowercase();
+        if lower == "dayjs" {
+            return Ok(Dialect::Moment);
+        }
+        Dialect::ALL
+            .into_iter()
+            .find(|d| d.name() == lower)
+            .ok_or_else(|| format!("Unknown format dialect: '{}'", name))
+    }
+}
+
+/// A calendar or clock field that a format token stands for, independent of dialect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Year4,
+    Year2,
+    IsoWeekYear,
+    MonthNum2,
+    MonthNum,
+    MonthAbbr,
+    MonthFull,
+    Day2,
+    Day,
+    DaySpace,
+    DayOrdinal,
+    DayOfYear,
+    IsoWeek,
+    WeekdayAbbr,
+    WeekdayFull,
+    /// 1 (Monday) through 7 (Sunday)
+    WeekdayIso,
+    /// 0 (Sunday) through 6 (Saturday)
+    WeekdaySun0,
+    Hour24Pad,
+    Hour24,
+    Hour12Pad,
+    Hour12,
+    Minute2,
+    Minute,
+    Second2,
+    Second,
+    /// Exactly this many fractional-second digits, no leading dot
+    Frac(u8),
+    /// Fractional seconds with trailing zeros trimmed
+    FracVariable,
+    AmPmUpper,
+    AmPmLower,
+    /// `+05:30`
+    OffsetColon,
+    /// `+0530`
+    OffsetBasic,
+    /// `Z` for UTC, `+05:30` otherwise
+    OffsetColonZ,
+    /// `Z` for UTC, `+0530` otherwise
+    OffsetBasicZ,
+    /// `+05`
+    OffsetHours,
+    ZoneAbbr,
+    ZoneId,
+    UnixSeconds,
+    UnixMillis,
+    Quarter,
+}
+
+/// A parsed piece of a pattern.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Literal(String),
+    /// A field and the source text it was parsed from
+    Field(Field, String),
+    /// Source text the dialect doesn't define (or this module doesn't know)
+    Unknown(String),
+}
+
+// Token tables. When a field has several spellings, the first is used when rendering.
+
+const STRFTIME_TOKENS: &[(&str, Field)] = &[
+    ("%Y", Field::Year4),
+    ("%y", Field::Year2),
+    ("%G", Field::IsoWeekYear),
+    ("%m", Field::MonthNum2),
+    ("%-m", Field::MonthNum),
+    ("%b", Field::MonthAbbr),
+    ("%h", Field::MonthAbbr),
+    ("%B", Field::MonthFull),
+    ("%d", Field::Day2),
+    ("%-d", Field::Day),
+    ("%e", Field::DaySpace),
+    ("%j", Field::DayOfYear),
+    ("%V", Field::IsoWeek),
+    ("%a", Field::WeekdayAbbr),
+    ("%A", Field::WeekdayFull),
+    ("%u", Field::WeekdayIso),
+    ("%w", Field::WeekdaySun0),
+    ("%H", Field::Hour24Pad),
+    ("%-H", Field::Hour24),
+    ("%I", Field::Hour12Pad),
+    ("%-I", Field::Hour12),
+    ("%M", Field::Minute2),
+    ("%-M", Field::Minute),
+    ("%S", Field::Second2),
+    ("%-S", Field::Second),
+    ("%f", Field::FracVariable),
+    ("%p", Field::AmPmUpper),
+    ("%P", Field::AmPmLower),
+    ("%:z", Field::OffsetColon),
+    ("%z", Field::OffsetBasic),
+    ("%Z", Field::ZoneAbbr),
+    ("%Q", Field::ZoneId),
+    ("%s", Field::UnixSeconds),
+    ("%q", Field::Quarter),
+];
+
+const PYTHON_TOKENS: &[(&str, Field)] = &[
+    ("%Y", Field::Year4),
+    ("%y", Field::Year2),
+    ("%G", Field::IsoWeekYear),
+    ("%m", Field::MonthNum2),
+    ("%-m", Field::MonthNum),
+    ("%b", Field::MonthAbbr),
+    ("%B", Field::MonthFull),
+    ("%d", Field::Day2),
+    ("%-d", Field::Day),
+    ("%e", Field::DaySpace),
+    ("%j", Field::DayOfYear),
+    ("%V", Field::IsoWeek),
+    ("%a", Field::WeekdayAbbr),
+    ("%A", Field::WeekdayFull),
+    ("%u", Field::WeekdayIso),
+    ("%w", Field::WeekdaySun0),
+    ("%H", Field::Hour24Pad),
+    ("%-H", Field::Hour24),
+    ("%I", Field::Hour12Pad),
+    ("%-I", Field::Hour12),
+    ("%M", Field::Minute2),
+    ("%-M", Field::Minute),
+    ("%S", Field::Second2),
+    ("%-S", Field::Second),
+    ("%f", Field::Frac(6)),
+    ("%p", Field::AmPmUpper),
+    ("%:z", Field::OffsetColon),
+    ("%z", Field::OffsetBasic),
+    ("%Z", Field::ZoneAbbr),
+];
+
+/// Shared by Java and LDML: `DateTimeFormatter` letters are LDML-derived, and
+/// `SimpleDateFormat` agrees on this subset.
+const LDML_TOKENS: &[(&str, Field)] = &[
+    ("yyyy", Field::Year4),
+    ("uuuu", Field::Year4),
+    ("y", Field::Year4),
+    ("yy", Field::Year2),
+    ("uu", Field::Year2),
+    ("YYYY", Field::IsoWeekYear),
+    ("MM", Field::MonthNum2),
+    ("M", Field::MonthNum),
+    ("MMM", Field::MonthAbbr),
+    ("LLL", Field::MonthAbbr),
+    ("MMMM", Field::MonthFull),
+    ("LLLL", Field::MonthFull),
+    ("dd", Field::Day2),
+    ("d", Field::Day),
+    ("DDD", Field::DayOfYear),
+    ("ww", Field::IsoWeek),
+    ("EEE", Field::WeekdayAbbr),
+    ("E", Field::WeekdayAbbr),
+    ("EE", Field::WeekdayAbbr),
+    ("EEEE", Field::WeekdayFull),
+    ("HH", Field::Hour24Pad),
+    ("H", Field::Hour24),
+    ("hh", Field::Hour12Pad),
+    ("h", Field::Hour12),
+    ("mm", Field::Minute2),
+    ("m", Field::Minute),
+    ("ss", Field::Second2),
+    ("s", Field::Second),
+    ("a", Field::AmPmUpper),
+    ("xxx", Field::OffsetColon),
+    ("xx", Field::OffsetBasic),
+    ("Z", Field::OffsetBasic),
+    ("ZZ", Field::OffsetBasic),
+    ("ZZZ", Field::OffsetBasic),
+    ("XXX", Field::OffsetColonZ),
+    ("ZZZZZ", Field::OffsetColonZ),
+    ("XX", Field::OffsetBasicZ),
+    ("z", Field::ZoneAbbr),
+    ("zz", Field::ZoneAbbr),
+    ("zzz", Field::ZoneAbbr),
+    ("VV", Field::ZoneId),
+    ("Q", Field::Quarter),
+];
+
+const MOMENT_TOKENS: &[(&str, Field)] = &[
+    ("YYYY", Field::Year4),
+    ("YY", Field::Year2),
+    ("GGGG", Field::IsoWeekYear),
+    ("MM", Field::MonthNum2),
+    ("M", Field::MonthNum),
+    ("MMM", Field::MonthAbbr),
+    ("MMMM", Field::MonthFull),
+    ("DD", Field::Day2),
+    ("D", Field::Day),
+    ("Do", Field::DayOrdinal),
+    ("DDDD", Field::DayOfYear),
+    ("WW", Field::IsoWeek),
+    ("ddd", Field::WeekdayAbbr),
+    ("dddd", Field::WeekdayFull),
+    ("E", Field::WeekdayIso),
+    ("d", Field::WeekdaySun0),
+    ("HH", Field::Hour24Pad),
+    ("H", Field::Hour24),
+    ("hh", Field::Hour12Pad),
+    ("h", Field::Hour12),
+    ("mm", Field::Minute2),
+    ("m", Field::Minute),
+    ("ss", Field::Second2),
+    ("s", Field::Second),
+    ("A", Field::AmPmUpper),
+    ("a", Field::AmPmLower),
+    ("Z", Field::OffsetColon),
+    ("ZZ", Field::OffsetBasic),
+    ("z", Field::ZoneAbbr),
+    ("X", Field::UnixSeconds),
+    ("x", Field::UnixMillis),
+    ("Q", Field::Quarter),
+];
+
+const DOTNET_TOKENS: &[(&str, Field)] = &[
+    ("yyyy", Field::Year4),
+    ("yy", Field::Year2),
+    ("MM", Field::MonthNum2),
+    ("M", Field::MonthNum),
+    ("MMM", Field::MonthAbbr),
+    ("MMMM", Field::MonthFull),
+    ("dd", Field::Day2),
+    ("d", Field::Day),
+    ("ddd", Field::WeekdayAbbr),
+    ("dddd", Field::WeekdayFull),
+    ("HH", Field::Hour24Pad),
+    ("H", Field::Hour24),
+    ("hh", Field::Hour12Pad),
+    ("h", Field::Hour12),
+    ("mm", Field::Minute2),
+    ("m", Field::Minute),
+    ("ss", Field::Second2),
+    ("s", Field::Second),
+    ("tt", Field::AmPmUpper),
+    ("zzz", Field::OffsetColon),
+    ("zz", Field::OffsetHours),
+    ("K", Field::OffsetColonZ),
+];
+
+fn token_table(dialect: Dialect) -> &'static [(&'static str, Field)] {
+    match dialect {
+        Dialect::Strftime => STRFTIME_TOKENS,
+        Dialect::Python => PYTHON_TOKENS,
+        Dialect::Java | Dialect::Ldml => LDML_TOKENS,
+        Dialect::Moment => MOMENT_TOKENS,
+        Dialect::DotNet => DOTNET_TOKENS,
+    }
+}
+
+/// One pattern rendered in a target dialect.
+#[derive(Debug, PartialEq)]
+pub struct Translation {
+    pub dialect: Dialect,
+    pub pattern: String,
+    /// Source tokens with no equivalent in this dialect; they are left out of `pattern`
+    pub unsupported: Vec<String>,
+    /// Source fractional-second tokens rendered at a different width, because
+    /// this dialect has no exact equivalent (e.g. Java `SSS` becomes Python `%f`)
+    pub approximated: Vec<String>,
+    /// The sample instant formatted with the tokens this dialect kept
+    pub preview: Option<String>,
+}
+
+impl Translation {
+    fn to_json(&self) -> String {
+        let quoted = |tokens: &[String]| -> Vec<String> {
+            tokens
+                .iter()
+                .map(|t| format!(r#""{}""#, json_escape(t)))
+                .collect()
+        };
+        let preview = match &self.preview {
+            Some(p) => format!(r#""{}""#, json_escape(p)),
+            None => "null".to_string(),
+        };
+        format!(
+            r#"{{"dialect":"{}","pattern":"{}","unsupported":[{}],"approximated":[{}],"preview":{}}}"#,
+            self.dialect.name(),
+            json_escape(&self.pattern),
+            quoted(&self.unsupported).join(","),
+            quoted(&self.approximated).join(","),
+            preview,
+        )
+    }
+}
+
+/// A pattern translated into every dialect.
+#[derive(Debug, PartialEq)]
+pub struct TranslationReport {
+    pub source: Dialect,
+    pub translations: Vec<Translation>,
+}
+
+impl TranslationReport {
+    /// Serialize to JSON string for Wasm boundary (manual serialization).
+    pub fn to_json(&self) -> String {
+        let translations: Vec<String> = self.translations.iter().map(|t| t.to_json()).collect();
+        format!(
+            r#"{{"source":"{}","translations":[{}]}}"#,
+            self.source.name(),
+            translations.join(","),
+        )
+    }
+}
+
+/// Translate a pattern from one dialect to another.
+///
+/// Tokens with no equivalent in `to` are dropped from the output and listed in
+/// `unsupported`; fractional seconds rendered at a different width are listed
+/// in `approximated`. When `sample` is given, `preview` shows it formatted with the
+/// translated pattern's semantics.
+pub fn translate(
+    pattern: &str,
+    from: Dialect,
+    to: Dialect,
+    sample: Option<&Zoned>,
+) -> Result<Translation, String> {
+    let tokens = tokenize(pattern, from)?;
+    Ok(render(&tokens, to, sample))
+}
+
+/// Translate a pattern into every dialect (including its own, as a round trip).
+pub fn translate_all(
+    pattern: &str,
+    from: Dialect,
+    sample: Option<&Zoned>,
+) -> Result<TranslationReport, String> {
+    let tokens = tokenize(pattern, from)?;
+    Ok(TranslationReport {
+        source: from,
+        translations: Dialect::ALL
+            .into_iter()
+            .map(|to| render(&tokens, to, sample))
+            .collect(),
+    })
+}
+
+fn tokenize(pattern: &str, dialect: Dialect) -> Result<Vec<Token>, String> {
+    if pattern.is_empty() {
+        return Err("Format pattern is empty".to_string());
+    }
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut tokens = Vec::new();
+    match dialect {
+        Dialect::Strftime | Dialect::Python => tokenize_percent(&chars, dialect, &mut tokens)?,
+        Dialect::Java | Dialect::Ldml | Dialect::DotNet => {
+            tokenize_letter_runs(&chars, dialect, &mut tokens)?
+        }
+        Dialect::Moment => tokenize_moment(&chars, &mut tokens)?,
+    }
+    Ok(tokens)
+}
+
+fn push_literal(tokens: &mut Vec<Token>, text: &str) {
+    if let Some(Token::Literal(prev)) = tokens.last_mut() {
+        prev.push_str(text);
+    } else {
+        tokens.push(Token::Literal(text.to_string()));
+    }
+}
+
+fn lookup(dialect: Dialect, text: &str) -> Option<Field> {
+    token_table(dialect)
+        .iter()
+        .find(|(t, _)| *t == text)
+        .map(|(_, f)| *f)
+}
+
+/// `%`-directive dialects (strftime, Python), including flags, precision and `%F`-style composites.
+fn tokenize_percent(
+    chars: &[char],
+    dialect: Dialect,
+    tokens: &mut Vec<Token>,
+) -> Result<(), String> {
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] != '%' {
+            push_literal(tokens, &chars[i].to_string());
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        i += 1;
+        while i < chars.len() && matches!(chars[i], '-' | '_' | '0' | '^' | '#' | ':' | '.') {
+            i += 1;
+        }
+        while i < chars.len() && chars[i].is_ascii_digit() {
+            i += 1;
+        }
+        if i >= chars.len() {
+            return Err("Format pattern ends with an incomplete '%' directive".to_string());
+        }
+        let text: String = chars[start..=i].iter().collect();
+        i += 1;
+
+        match text.as_str() {
+            "%%" => push_literal(tokens, "%"),
+            "%n" => push_literal(tokens, "\n"),
+            "%t" => push_literal(tokens, "\t"),
+            "%F" => tokens.extend(tokenize("%Y-%m-%d", dialect)?),
+            "%T" => tokens.extend(tokenize("%H:%M:%S", dialect)?),
+            "%R" => tokens.extend(tokenize("%H:%M", dialect)?),
+            "%D" => tokens.extend(tokenize("%m/%d/%y", dialect)?),
+            _ if dialect == Dialect::Strftime && text.ends_with('f') && text != "%f" => {
+                // %.f, %3f, %.3f: precision digits (if any) between the flags and `f`
+                let digits: String = text.chars().filter(|c| c.is_ascii_digit()).collect();
+                if text.contains('.') {
+                    push_literal(tokens, ".");
+                }
+                match digits.parse::<u8>() {
+                    Ok(n) if (1..=9).contains(&n) => {
+                        tokens.push(Token::Field(Field::Frac(n), text))
+                    }
+                    _ => tokens.push(Token::Field(Field::FracVariable, text)),
+                }
+            }
+            _ => match lookup(dialect, &text) {
+                Some(field) => tokens.push(Token::Field(field, text)),
+                None => tokens.push(Token::Unknown(text)),
+            },
+        }
+    }
+    Ok(())
+}
+
+/// Pattern-letter dialects (Java, LDML, .NET): runs of the same letter form one token.
+fn tokenize_letter_runs(
+    chars: &[char],
+    dialect: Dialect,
+    tokens: &mut Vec<Token>,
+) -> Result<(), String> {
+    let mut i = 0;
+    while i < chars.len() {
+        let ch = chars[i];
+
+        // Quoted literals: 'text' ('' is a literal quote in Java/LDML); .NET also allows "text".
+        if ch == '\'' || (ch == '"' && dialect == Dialect::DotNet) {
+            if ch == '\'' && chars.get(i + 1) == Some(&'\'') && dialect != Dialect::DotNet {
+                push_literal(tokens, "'");
+                i += 2;
+                continue;
+            }
+            let mut literal = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None => return Err("Unterminated quoted literal in format pattern".to_string()),
+                    Some(&c) if c == ch => {
+                        if ch == '\''
+                            && chars.get(i + 1) == Some(&'\'')
+                            && dialect != Dialect::DotNet
+                        {
+                            literal.push('\'');
+                            i += 2;
+                            continue;
+                        }
+                        i += 1;
+                        break;
+                    }
+                    Some(&c) => {
+                        literal.push(c);
+                        i += 1;
+                    }
+                }
+            }
+            push_literal(tokens, &literal);
+            continue;
+        }
+        if ch == '\\' && dialect == Dialect::DotNet {
+            match chars.get(i + 1) {
+                Some(c) => push_literal(tokens, &c.to_string()),
+                None => return Err("Format pattern ends with a dangling '\\'".to_string()),
+            }
+            i += 2;
+            continue;
+        }
+        if !ch.is_ascii_alphabetic() {
+            push_literal(tokens, &ch.to_string());
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        while i < chars.len() && chars[i] == ch {
+            i += 1;
+        }
+        let text: String = chars[start..i].iter().collect();
+        let run = (i - start) as u8;
+
+        let field = match ch {
+            'S' if dialect != Dialect::DotNet && run <= 9 => Some(Field::Frac(run)),
+            'f' if dialect == Dialect::DotNet && run <= 7 => Some(Field::Frac(run)),
+            'F' if dialect == Dialect::DotNet && run <= 7 => Some(Field::FracVariable),
+            _ => lookup(dialect, &text),
+        };
+        match field {
+            Some(field) => tokens.push(Token::Field(field, text)),
+            None => tokens.push(Token::Unknown(text)),
+        }
+    }
+    Ok(())
+}
+
+/// moment.js / dayjs: longest known token wins, `[...]` is literal, other characters are literal.
+fn tokenize_moment(chars: &[char], tokens: &mut Vec<Token>) -> Result<(), String> {
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] == '[' {
+            let end = chars[i..]
+                .iter()
+                .position(|c| *c == ']')
+                .ok_or_else(|| "Unterminated [literal] in format pattern".to_string())?;
+            let literal: String = chars[i + 1..i + end].iter().collect();
+            push_literal(tokens, &literal);
+            i += end + 1;
+            continue;
+        }
+
+        if chars[i] == 'S' {
+            let start = i;
+            while i < chars.len() && chars[i] == 'S' {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let run = (i - start).min(9) as u8;
+            tokens.push(Token::Field(Field::Frac(run), text));
+            continue;
+        }
+
+        let rest: String = chars[i..].iter().collect();
+        let best = MOMENT_TOKENS
+            .iter()
+            .filter(|(t, _)| rest.starts_with(t))
+            .max_by_key(|(t, _)| t.len());
+        match best {
+            Some((text, field)) => {
+                tokens.push(Token::Field(*field, text.to_string()));
+                i += text.chars().count();
+            }
+            None => {
+                push_literal(tokens, &chars[i].to_string());
+                i += 1;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Spell a field in a dialect, or `None` if it has no equivalent.
+///
+/// Returns the field actually rendered: "`Z` for UTC" offsets fall back to the
+/// plain numeric offset in dialects (strftime, Python, moment) that can't say `Z`,
+/// and fractional seconds to the nearest width the dialect has (Python's `%f` is
+/// always 6 digits, .NET stops at 7, and only strftime and .NET trim zeros).
+fn field_token(field: Field, dialect: Dialect) -> Option<(String, Field)> {
+    let spelled = match (field, dialect) {
+        (Field::Frac(n), Dialect::Strftime) => Some(format!("%{}f", n)),
+        (Field::Frac(6), Dialect::Python) => Some("%f".to_string()),
+        (Field::Frac(_), Dialect::Python) => None,
+        (Field::Frac(n), Dialect::Java | Dialect::Ldml | Dialect::Moment) => {
+            Some("S".repeat(n as usize))
+        }
+        (Field::Frac(n), Dialect::DotNet) if n <= 7 => Some("f".repeat(n as usize)),
+        (Field::Frac(_), Dialect::DotNet) => None,
+        (Field::FracVariable, Dialect::DotNet) => Some("FFFFFFF".to_string()),
+        _ => token_table(dialect)
+            .iter()
+            .find(|(_, f)| *f == field)
+            .map(|(t, _)| t.to_string()),
+    };
+    match (spelled, field) {
+        (Some(text), _) => Some((text, field)),
+        (None, Field::OffsetColonZ) => field_token(Field::OffsetColon, dialect),
+        (None, Field::OffsetBasicZ) => field_token(Field::OffsetBasic, dialect),
+        (None, Field::Frac(_) | Field::FracVariable) => match dialect {
+            Dialect::Python => field_token(Field::Frac(6), dialect),
+            Dialect::DotNet => field_token(Field::Frac(7), dialect),
+            Dialect::Java | Dialect::Ldml => field_token(Field::Frac(9), dialect),
+            Dialect::Moment => field_token(Field::Frac(3), dialect),
+            Dialect::Strftime => None,
+        },
+        (None, _) => None,
+    }
+}
+
+/// Escape literal text so the target dialect doesn't read it as tokens.
+fn quote_literal(text: &str, dialect: Dialect) -> String {
+    match dialect {
+        Dialect::Strftime | Dialect::Python => text.replace('%', "%%"),
+        Dialect::Java | Dialect::Ldml => {
+            if text.chars().any(|c| c.is_ascii_alphabetic() || c == '\'') {
+                format!("'{}'", text.replace('\'', "''"))
+            } else {
+                text.to_string()
+            }
+        }
+        Dialect::Moment => {
+            if text.chars().any(|c| c.is_ascii_alphabetic() || c == '[') {
+                format!("[{}]", text)
+            } else {
+                text.to_string()
+            }
+        }
+        Dialect::DotNet => {
+            if text
+                .chars()
+                .any(|c| c.is_ascii_alphabetic() || matches!(c, '\'' | '"' | '\\' | '%'))
+            {
+                format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'"))
+            } else {
+                text.to_string()
+            }
+        }
+    }
+}
+
+fn render(tokens: &[Token], dialect: Dialect, sample: Option<&Zoned>) -> Translation {
+    let mut pattern = String::new();
+    let mut unsupported = Vec::new();
+    let mut approximated = Vec::new();
+    let mut preview = sample.map(|_| String::new());
+
+    for token in tokens {
+        match token {
+            Token::Literal(text) => {
+                pattern.push_str(&quote_literal(text, dialect));
+                if let Some(p) = preview.as_mut() {
+                    p.push_str(text);
+                }
+            }
+            Token::Field(field, source) => match field_token(*field, dialect) {
+                Some((text, rendered)) => {
+                    if rendered != *field && matches!(field, Field::Frac(_) | Field::FracVariable) {
+                        approximated.push(source.clone());
+                    }
+                    pattern.push_str(&text);
+                    if let (Some(p), Some(zdt)) = (preview.as_mut(), sample) {
+                        p.push_str(&preview_field(rendered, zdt));
+                    }
+                }
+                None => unsupported.push(source.clone()),
+            },
+            Token::Unknown(source) => unsupported.push(source.clone()),
+        }
+    }
+
+    Translation {
+        dialect,
+        pattern,
+        unsupported,
+        approximated,
+        preview,
+    }
+}
+
+/// Format one field of a sample instant.
+fn preview_field(field: Field, zdt: &Zoned) -> String {
+    let fmt = |pattern: &str| strtime::format(pattern, zdt).unwrap_or_default();
+    let offset = zdt.offset().seconds();
+    match field {
+        Field::Year4 => fmt("%Y"),
+        Field::Year2 => fmt("%y"),
+        Field::IsoWeekYear => fmt("%G"),
+        Field::MonthNum2 => fmt("%m"),
+        Field::MonthNum => fmt("%-m"),
+        Field::MonthAbbr => fmt("%b"),
+        Field::MonthFull => fmt("%B"),
+        Field::Day2 => fmt("%d"),
+        Field::Day => fmt("%-d"),
+        Field::DaySpace => fmt("%e"),
+        Field::DayOrdinal => {
+            let day = zdt.day();
+            let suffix = match (day % 10, day % 100) {
+                (_, 11..=13) => "th",
+                (1, _) => "st",
+                (2, _) => "nd",
+                (3, _) => "rd",
+                _ => "th",
+            };
+            format!("{}{}", day, suffix)
+        }
+        Field::DayOfYear => fmt("%j"),
+        Field::IsoWeek => fmt("%V"),
+        Field::WeekdayAbbr => fmt("%a"),
+        Field::WeekdayFull => fmt("%A"),
+        Field::WeekdayIso => fmt("%u"),
+        Field::WeekdaySun0 => fmt("%w"),
+        Field::Hour24Pad => fmt("%H"),
+        Field::Hour24 => fmt("%-H"),
+        Field::Hour12Pad => fmt("%I"),
+        Field::Hour12 => fmt("%-I"),
+        Field::Minute2 => fmt("%M"),
+        Field::Minute => fmt("%-M"),
+        Field::Second2 => fmt("%S"),
+        Field::Second => fmt("%-S"),
+        Field::Frac(n) => fmt(&format!("%{}f", n)),
+        Field::FracVariable => fmt("%f"),
+        Field::AmPmUpper => fmt("%p"),
+        Field::AmPmLower => fmt("%P"),
+        Field::OffsetColon => fmt("%:z"),
+        Field::OffsetBasic => fmt("%z"),
+        Field::OffsetColonZ if offset == 0 => "Z".to_string(),
+        Field::OffsetColonZ => fmt("%:z"),
+        Field::OffsetBasicZ if offset == 0 => "Z".to_string(),
+        Field::OffsetBasicZ => fmt("%z"),
+        Field::OffsetHours => fmt("%:z")[..3].to_string(),
+        Field::ZoneAbbr => fmt("%Z"),
+        Field::ZoneId => fmt("%Q"),
+        Field::UnixSeconds => zdt.timestamp().as_second().to_string(),
+        Field::UnixMillis => zdt.timestamp().as_millisecond().to_string(),
+        Field::Quarter => fmt("%q"),
+    }
+}
+
+/// Translate a format pattern from `from_dialect` into every dialect.
+///
+/// When `sample` is non-empty it is parsed like a start date and rendered in
+/// `zone` as a preview for each translation.
+pub fn translate_pattern(
+    pattern: &str,
+    from_dialect: &str,
+    sample: &str,
+    zone: &str,
+) -> Result<TranslationReport, String> {
+    let from = Dialect::from_name(from_dialect)?;
+    let sample = if sample.trim().is_empty() {
+        None
+    } else {
+        Some(parse_start_date(sample)?.to_zoned(resolve_zone(zone)?))
+    };
+    translate_all(pattern, from, sample.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Zoned {
+        "2024-07-11T01:14:00.123456789Z"
+            .parse::<jiff::Timestamp>()
+            .unwrap()
+            .to_zoned(jiff::tz::TimeZone::fixed(jiff::tz::offset(2)))
+    }
+
+    fn pattern(p: &str, from: Dialect, to: Dialect) -> String {
+        translate(p, from, to, None).unwrap().pattern
+    }
+
+    #[test]
+    fn test_java_to_strftime() {
+        let t = translate(
+            "yyyy-MM-dd'T'HH:mm:ss.SSSXXX",
+            Dialect::Java,
+            Dialect::Strftime,
+            Some(&sample()),
+        )
+        .unwrap();
+        // XXX prints `Z` for UTC; strftime has no such directive, so it becomes %:z.
+        assert_eq!(t.pattern, "%Y-%m-%dT%H:%M:%S.%3f%:z");
+        assert!(t.unsupported.is_empty());
+        assert_eq!(t.preview.unwrap(), "2024-07-11T03:14:00.123+02:00");
+    }
+
+    #[test]
+    fn test_strftime_to_java() {
+        assert_eq!(
+            pattern("%Y-%m-%dT%H:%M:%S%.3f%:z", Dialect::Strftime, Dialect::Java),
+            "yyyy-MM-dd'T'HH:mm:ss.SSSxxx"
+        );
+    }
+
+    #[test]
+    fn test_moment_round_trip() {
+        assert_eq!(
+            pattern("YYYY-MM-DD", Dialect::Moment, Dialect::Strftime),
+            "%Y-%m-%d"
+        );
+        assert_eq!(
+            pattern("%Y-%m-%dT%H:%M", Dialect::Strftime, Dialect::Moment),
+            "YYYY-MM-DD[T]HH:mm"
+        );
+    }
+
+    #[test]
+    fn test_moment_adjacent_tokens() {
+        assert_eq!(
+            pattern("YYYYMMDD", Dialect::Moment, Dialect::DotNet),
+            "yyyyMMdd"
+        );
+        assert_eq!(
+            pattern("dddd, MMMM Do", Dialect::Moment, Dialect::Java),
+            "EEEE, MMMM "
+        );
+    }
+
+    #[test]
+    fn test_unsupported_tokens_are_reported() {
+        let t = translate("MMMM Do YYYY", Dialect::Moment, Dialect::Strftime, None).unwrap();
+        assert_eq!(t.pattern, "%B  %Y");
+        assert_eq!(t.unsupported, vec!["Do"]);
+
+        let t = translate("%Y-%m-%d %Q", Dialect::Strftime, Dialect::Python, None).unwrap();
+        assert_eq!(t.unsupported, vec!["%Q"]);
+    }
+
+    #[test]
+    fn test_python_microseconds() {
+        assert_eq!(
+            pattern("%H:%M:%S.%f", Dialect::Python, Dialect::Java),
+            "HH:mm:ss.SSSSSS"
+        );
+        let t = translate("HH:mm:ss.SSS", Dialect::Java, Dialect::Python, None).unwrap();
+        assert_eq!(t.pattern, "%H:%M:%S.%f");
+        assert!(t.unsupported.is_empty());
+        assert_eq!(t.approximated, vec!["SSS"]);
+    }
+
+    #[test]
+    fn test_fraction_maps_to_nearest_width() {
+        assert_eq!(
+            pattern("%H:%M:%S%.3f", Dialect::Strftime, Dialect::Python),
+            "%H:%M:%S.%f"
+        );
+        assert_eq!(
+            pattern("%H:%M:%S%.f", Dialect::Strftime, Dialect::Java),
+            "HH:mm:ss.SSSSSSSSS"
+        );
+        assert_eq!(
+            pattern("%H:%M:%S%.f", Dialect::Strftime, Dialect::Moment),
+            "HH:mm:ss.SSS"
+        );
+        assert_eq!(
+            pattern("HH:mm:ss.SSSSSSSSS", Dialect::Java, Dialect::DotNet),
+            "HH:mm:ss.fffffff"
+        );
+        let sample: Zoned = "2024-07-11T01:14:00.123456789+00:00[UTC]".parse().unwrap();
+        let t = translate("%S%.3f", Dialect::Strftime, Dialect::Python, Some(&sample)).unwrap();
+        assert_eq!(t.approximated, vec!["%.3f"]);
+        assert_eq!(t.preview.unwrap(), "00.123456");
+
+        let exact = translate("%S%.6f", Dialect::Strftime, Dialect::Python, None).unwrap();
+        assert!(exact.approximated.is_empty());
+        let offset = translate("%S%:z", Dialect::Strftime, Dialect::Python, None).unwrap();
+        assert!(offset.approximated.is_empty());
+    }
+
+    #[test]
+    fn test_dotnet_literals_and_offsets() {
+        assert_eq!(
+            pattern(
+                "yyyy-MM-dd\\THH:mm:ss.fffzzz",
+                Dialect::DotNet,
+                Dialect::Strftime
+            ),
+            "%Y-%m-%dT%H:%M:%S.%3f%:z"
+        );
+        assert_eq!(
+            pattern("%d %B %Y at %H:%M", Dialect::Strftime, Dialect::DotNet),
+            "dd MMMM yyyy' at 'HH:mm"
+        );
+    }
+
+    #[test]
+    fn test_strftime_composites_and_escapes() {
+        assert_eq!(
+            pattern("%F %T 100%%", Dialect::Strftime, Dialect::Ldml),
+            "yyyy-MM-dd HH:mm:ss 100%"
+        );
+        assert_eq!(pattern("yyyy'%'", Dialect::Java, Dialect::Strftime), "%Y%%");
+    }
+
+    #[test]
+    fn test_java_quote_escape() {
+        assert_eq!(
+            pattern("hh 'o''clock' a", Dialect::Java, Dialect::Strftime),
+            "%I o'clock %p"
+        );
+    }
+
+    #[test]
+    fn test_unknown_java_letters() {
+        let t = translate("yyyy-MM-dd GGGG", Dialect::Java, Dialect::Moment, None).unwrap();
+        assert_eq!(t.pattern, "YYYY-MM-DD ");
+        assert_eq!(t.unsupported, vec!["GGGG"]);
+    }
+
+    #[test]
+    fn test_translate_all_previews() {
+        let report = translate_all("%d/%m/%Y %H:%M", Dialect::Strftime, Some(&sample())).unwrap();
+        assert_eq!(report.translations.len(), Dialect::ALL.len());
+        for t in &report.translations {
+            assert_eq!(
+                t.preview.as_deref(),
+                Some("11/07/2024 03:14"),
+                "{:?}",
+                t.dialect
+            );
+        }
+        let json = report.to_json();
+        assert!(json.starts_with(r#"{"source":"strftime","translations":[{"dialect":"strftime""#));
+        assert!(json.contains(r#""pattern":"dd/MM/yyyy HH:mm""#));
+    }
+
+    #[test]
+    fn test_preview_special_fields() {
+        let t = translate(
+            "Do [of] MMMM, X",
+            Dialect::Moment,
+            Dialect::Moment,
+            Some(&sample()),
+        )
+        .unwrap();
+        assert_eq!(t.preview.unwrap(), "11th of July, 1720660440");
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(translate("", Dialect::Java, Dialect::Strftime, None).is_err());
+        assert!(translate("yyyy 'oops", Dialect::Java, Dialect::Strftime, None).is_err());
+        assert!(translate("%Y %", Dialect::Strftime, Dialect::Java, None).is_err());
+        assert!(Dialect::from_name("cobol").is_err());
+        assert_eq!(Dialect::from_name("dayjs").unwrap(), Dialect::Moment);
+    }
+}
@@ -0,0 +1,705 @@
+//! POSIX TZ strings and TZif (RFC 8536) files.
+//!
+//! Both can be turned into a `TimeZone` with jiff; this module decodes them for
+//! inspection, so a device's `/etc/localtime` or `TZ` variable can be compared with
+//! upstream tzdata: the local time types, every transition stored in the file, and the
+//! footer rule that covers the time after the last one.
+
+use jiff::civil::Weekday;
+use jiff::tz::{Offset, TimeZone};
+use jiff::Timestamp;
+
+use crate::abbreviation::format_offset;
+use crate::json_utils::json_escape;
+use crate::zone::{self, Transition};
+
+/// The day a POSIX TZ rule switches on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateRule {
+    /// `Jn`: day 1-365, February 29 never counted
+    JulianNoLeap(u16),
+    /// `n`: day 0-365, February 29 counted in leap years
+    Julian(u16),
+    /// `Mm.w.d`: weekday `d` (0 = Sunday) of week `w` (5 = last) in month `m`
+    WeekOfMonth { month: u8, week: u8, weekday: u8 },
+}
+
+/// A switch to or from DST: the day and the local time of day (which may be negative
+/// or past 24:00 in TZif version 3+ footers).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuleTime {
+    pub date: DateRule,
+    pub seconds: i32,
+}
+
+impl RuleTime {
+    /// The rule as written, e.g. `M3.5.0/3`.
+    pub fn rule(&self) -> String {
+        let date = match self.date {
+            DateRule::JulianNoLeap(day) => format!("J{}", day),
+            DateRule::Julian(day) => day.to_string(),
+            DateRule::WeekOfMonth {
+                month,
+                week,
+                weekday,
+            } => format!("M{}.{}.{}", month, week, weekday),
+        };
+        format!("{}/{}", date, format_rule_seconds(self.seconds))
+    }
+
+    /// Plain-English form, e.g. "last Sunday of March at 02:00:00".
+    pub fn describe(&self) -> String {
+        let day = match self.date {
+            DateRule::JulianNoLeap(day) => {
+                format!("day {} of the year (February 29 not counted)", day)
+            }
+            DateRule::Julian(day) => format!("day {} of the year (counting from 0)", day),
+            DateRule::WeekOfMonth {
+                month,
+                week,
+                weekday,
+            } => {
+                let nth = ["first", "second", "third", "fourth", "last"][usize::from(week) - 1];
+                let weekday = Weekday::from_sunday_zero_offset(weekday as i8)
+                    .map(|w| format!("{:?}", w))
+                    .unwrap_or_default();
+                let month = jiff::civil::date(2000, month as i8, 1).strftime("%B");
+                format!("{} {} of {}", nth, weekday, month)
+            }
+        };
+        format!("{} at {}", day, format_rule_seconds(self.seconds))
+    }
+}
+
+/// `[-]HH:MM:SS`, with hours past 24 kept as they are.
+fn format_rule_seconds(seconds: i32) -> String {
+    let sign = if seconds < 0 { "-" } else { "" };
+    let s = seconds.abs();
+    format!("{}{:02}:{:02}:{:02}", sign, s / 3600, s / 60 % 60, s % 60)
+}
+
+/// The daylight saving part of a POSIX TZ string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PosixDst {
+    pub abbreviation: String,
+    /// Seconds east of UTC
+    pub offset: i32,
+    pub start: RuleTime,
+    pub end: RuleTime,
+}
+
+/// A decoded POSIX TZ string such as `CET-1CEST,M3.5.0,M10.5.0/3`.
+///
+/// Offsets are stored east of UTC, as elsewhere in the engine; the string itself
+/// counts them west (`CET-1` is UTC+1).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PosixTz {
+    pub text: String,
+    pub std_abbreviation: String,
+    pub std_offset: i32,
+    pub dst: Option<PosixDst>,
+}
+
+impl PosixTz {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let mut p = Cursor {
+            bytes: text.as_bytes(),
+            pos: 0,
+        };
+        let std_abbreviation = p.abbreviation()?;
+        let std_offset = -p.offset()?;
+        let dst = if p.done() {
+            None
+        } else {
+            let abbreviation = p.abbreviation()?;
+            let offset = if p.done() || p.peek() == Some(b',') {
+                std_offset + 3600
+            } else {
+                -p.offset()?
+            };
+            // Without rules, POSIX leaves the dates to the implementation; like jiff
+            // and glibc, use the current US rules
+            let (start, end) = if p.eat(b',') {
+                let start = p.rule_time()?;
+                p.expect(b',')?;
+                (start, p.rule_time()?)
+            } else {
+                (
+                    RuleTime {
+                        date: DateRule::WeekOfMonth {
+                            month: 3,
+                            week: 2,
+                            weekday: 0,
+                        },
+                        seconds: 7200,
+                    },
+                    RuleTime {
+                        date: DateRule::WeekOfMonth {
+                            month: 11,
+                            week: 1,
+                            weekday: 0,
+                        },
+                        seconds: 7200,
+                    },
+                )
+            };
+            Some(PosixDst {
+                abbreviation,
+                offset,
+                start,
+                end,
+            })
+        };
+        if !p.done() {
+            return Err(format!(
+                "Unexpected '{}' at position {} of POSIX TZ string '{}'",
+                &text[p.pos..],
+                p.pos + 1,
+                text
+            ));
+        }
+        Ok(PosixTz {
+            text: text.to_string(),
+            std_abbreviation,
+            std_offset,
+            dst,
+        })
+    }
+
+    fn to_json(&self) -> String {
+        let offset = |seconds: i32| {
+            Offset::from_seconds(seconds).map_or_else(|_| seconds.to_string(), format_offset)
+        };
+        let rule = |r: &RuleTime| {
+            format!(
+                r#"{{"rule":"{}","description":"{}"}}"#,
+                r.rule(),
+                json_escape(&r.describe())
+            )
+        };
+        let dst = match &self.dst {
+            Some(dst) => format!(
+                r#"{{"abbreviation":"{}","offset":"{}","start":{},"end":{}}}"#,
+                json_escape(&dst.abbreviation),
+                offset(dst.offset),
+                rule(&dst.start),
+                rule(&dst.end)
+            ),
+            None => "null".to_string(),
+        };
+        format!(
+            r#"{{"text":"{}","std":{{"abbreviation":"{}","offset":"{}"}},"dst":{}}}"#,
+            json_escape(&self.text),
+            json_escape(&self.std_abbreviation),
+            offset(self.std_offset),
+            dst
+        )
+    }
+}
+
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Cursor<'_> {
+    fn done(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, b: u8) -> bool {
+        let found = self.peek() == Some(b);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, b: u8) -> Result<(), String> {
+        if self.eat(b) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected '{}' at position {} of POSIX TZ string",
+                b as char,
+                self.pos + 1
+            ))
+        }
+    }
+
+    /// `CET`, or quoted `<+0530>` for names with digits or signs.
+    fn abbreviation(&mut self) -> Result<String, String> {
+        let start = self.pos;
+        let name = if self.eat(b'<') {
+            while self.peek().is_some_and(|b| b != b'>') {
+                self.pos += 1;
+            }
+            let name = &self.bytes[start + 1..self.pos];
+            self.expect(b'>')?;
+            name
+        } else {
+            while self.peek().is_some_and(|b| b.is_ascii_alphabetic()) {
+                self.pos += 1;
+            }
+            &self.bytes[start..self.pos]
+        };
+        if name.len() < 3 {
+            return Err(format!(
+                "Expected a zone abbreviation of at least 3 letters at position {} of POSIX TZ string",
+                start + 1
+            ));
+        }
+        Ok(String::from_utf8_lossy(name).into_owned())
+    }
+
+    fn number(&mut self, max_digits: usize) -> Result<i32, String> {
+        let start = self.pos;
+        while self.pos - start < max_digits && self.peek().is_some_and(|b| b.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        std::str::from_utf8(&self.bytes[start..self.pos])
+            .ok()
+            .and_then(|digits| digits.parse().ok())
+            .ok_or_else(|| {
+                format!(
+                    "Expected a number at position {} of POSIX TZ string",
+                    start + 1
+                )
+            })
+    }
+
+    /// `[+-]hh[:mm[:ss]]` in seconds, as written (west of UTC for zone offsets).
+    fn hms(&mut self, max_hours: i32) -> Result<i32, String> {
+        let sign = if self.eat(b'-') {
+            -1
+        } else {
+            self.eat(b'+');
+            1
+        };
+        let hours = self.number(3)?;
+        let minutes = if self.eat(b':') { self.number(2)? } else { 0 };
+        let seconds = if self.eat(b':') { self.number(2)? } else { 0 };
+        if hours > max_hours || minutes > 59 || seconds > 59 {
+            return Err(format!(
+                "Time {}:{:02}:{:02} out of range in POSIX TZ string",
+                hours, minutes, seconds
+            ));
+        }
+        Ok(sign * (hours * 3600 + minutes * 60 + seconds))
+    }
+
+    fn offset(&mut self) -> Result<i32, String> {
+        self.hms(24)
+    }
+
+    fn rule_time(&mut self) -> Result<RuleTime, String> {
+        let date = if self.eat(b'J') {
+            let day = self.number(3)?;
+            if !(1..=365).contains(&day) {
+                return Err(format!("Julian day J{} must be between 1 and 365", day));
+            }
+            DateRule::JulianNoLeap(day as u16)
+        } else if self.eat(b'M') {
+            let month = self.number(2)?;
+            self.expect(b'.')?;
+            let week = self.number(1)?;
+            self.expect(b'.')?;
+            let weekday = self.number(1)?;
+            if !(1..=12).contains(&month) || !(1..=5).contains(&week) || weekday > 6 {
+                return Err(format!(
+                    "Invalid rule M{}.{}.{} (month 1-12, week 1-5, weekday 0-6)",
+                    month, week, weekday
+                ));
+            }
+            DateRule::WeekOfMonth {
+                month: month as u8,
+                week: week as u8,
+                weekday: weekday as u8,
+            }
+        } else {
+            let day = self.number(3)?;
+            if day > 365 {
+                return Err(format!("Day {} must be between 0 and 365", day));
+            }
+            DateRule::Julian(day as u16)
+        };
+        let seconds = if self.eat(b'/') { self.hms(167)? } else { 7200 };
+        Ok(RuleTime { date, seconds })
+    }
+}
+
+/// One local time type of a TZif file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalTimeType {
+    pub offset: i32,
+    pub dst: bool,
+    pub abbreviation: String,
+}
+
+/// The contents of a TZif file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tzif {
+    pub version: u8,
+    pub types: Vec<LocalTimeType>,
+    /// Transition instants with the index of the type in effect from then on
+    pub transitions: Vec<(i64, usize)>,
+    pub leap_seconds: usize,
+    /// The POSIX TZ string after the data (version 2+), if not empty
+    pub footer: Option<String>,
+}
+
+impl Tzif {
+    /// Read TZif data; version 2+ files are read from their 64-bit block.
+    pub fn parse(data: &[u8]) -> Result<Self, String> {
+        let header = Header::parse(data)?;
+        let (header, block, time_size) = if header.version >= 2 {
+            let rest = data
+                .get(44 + header.block_len(4)..)
+                .ok_or_else(|| "TZif data is truncated".to_string())?;
+            let v2 = Header::parse(rest)?;
+            (v2, &rest[44..], 8)
+        } else {
+            (header, &data[44..], 4)
+        };
+        let block_len = header.block_len(time_size);
+        if block.len() < block_len {
+            return Err("TZif data is truncated".to_string());
+        }
+
+        let mut at = 0;
+        let mut take = |n: usize| {
+            let slice = &block[at..at + n];
+            at += n;
+            slice
+        };
+        let times: Vec<i64> = take(header.timecnt * time_size)
+            .chunks(time_size)
+            .map(|c| match time_size {
+                4 => i64::from(i32::from_be_bytes([c[0], c[1], c[2], c[3]])),
+                _ => i64::from_be_bytes([c[0], c[1], c[2], c[3], c[4], c[5], c[6], c[7]]),
+            })
+            .collect();
+        let indices = take(header.timecnt).to_vec();
+        let raw_types = take(header.typecnt * 6).to_vec();
+        let chars = take(header.charcnt).to_vec();
+
+        let types = raw_types
+            .chunks(6)
+            .map(|t| {
+                let index = usize::from(t[5]);
+                let name = chars
+                    .get(index..)
+                    .and_then(|rest| rest.split(|b| *b == 0).next())
+                    .ok_or_else(|| "TZif abbreviation index out of range".to_string())?;
+                Ok(LocalTimeType {
+                    offset: i32::from_be_bytes([t[0], t[1], t[2], t[3]]),
+                    dst: t[4] != 0,
+                    abbreviation: String::from_utf8_lossy(name).into_owned(),
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        let transitions = times
+            .into_iter()
+            .zip(indices)
+            .map(|(time, index)| {
+                let index = usize::from(index);
+                if index >= types.len() {
+                    return Err(format!("TZif transition uses undefined type {}", index));
+                }
+                Ok((time, index))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let footer = if header.version >= 2 {
+            let rest = &block[block_len..];
+            let text = rest
+                .strip_prefix(b"\n")
+                .and_then(|r| r.iter().position(|b| *b == b'\n').map(|end| &r[..end]))
+                .ok_or_else(|| "TZif footer is missing".to_string())?;
+            Some(String::from_utf8_lossy(text).into_owned()).filter(|f| !f.is_empty())
+        } else {
+            None
+        };
+
+        Ok(Tzif {
+            version: header.version,
+            types,
+            transitions,
+            leap_seconds: header.leapcnt,
+            footer,
+        })
+    }
+
+    /// The stored transitions that fall in jiff's supported range, oldest first.
+    ///
+    /// The first transition's "before" side is the file's first type, which RFC 8536
+    /// uses for instants before any transition.
+    pub fn zone_transitions(&self) -> Vec<Transition> {
+        let mut previous = 0;
+        let mut found = Vec::new();
+        for &(time, index) in &self.transitions {
+            let (before, after) = (&self.types[previous], &self.types[index]);
+            previous = index;
+            let (Ok(timestamp), Ok(offset_before), Ok(offset_after)) = (
+                Timestamp::from_second(time),
+                Offset::from_seconds(before.offset),
+                Offset::from_seconds(after.offset),
+            ) else {
+                continue;
+            };
+            found.push(Transition {
+                timestamp,
+                offset_before,
+                offset_after,
+                abbreviation_before: before.abbreviation.clone(),
+                abbreviation_after: after.abbreviation.clone(),
+                dst: after.dst,
+            });
+        }
+        found
+    }
+}
+
+struct Header {
+    version: u8,
+    isutcnt: usize,
+    isstdcnt: usize,
+    leapcnt: usize,
+    timecnt: usize,
+    typecnt: usize,
+    charcnt: usize,
+}
+
+impl Header {
+    fn parse(data: &[u8]) -> Result<Self, String> {
+        if !data.starts_with(b"TZif") {
+            return Err("Not TZif data (missing 'TZif' magic)".to_string());
+        }
+        if data.len() < 44 {
+            return Err("TZif data is truncated".to_string());
+        }
+        let version = match data[4] {
+            0 => 1,
+            v @ b'2'..=b'9' => v - b'0',
+            other => return Err(format!("Unknown TZif version byte 0x{:02x}", other)),
+        };
+        let count = |i: usize| {
+            let at = 20 + i * 4;
+            u32::from_be_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]]) as usize
+        };
+        let header = Header {
+            version,
+            isutcnt: count(0),
+            isstdcnt: count(1),
+            leapcnt: count(2),
+            timecnt: count(3),
+            typecnt: count(4),
+            charcnt: count(5),
+        };
+        if header.typecnt == 0 {
+            return Err("TZif data has no local time types".to_string());
+        }
+        Ok(header)
+    }
+
+    /// Length of the data block that follows this header.
+    fn block_len(&self, time_size: usize) -> usize {
+        self.timecnt * (time_size + 1)
+            + self.typecnt * 6
+            + self.charcnt
+            + self.leapcnt * (time_size + 4)
+            + self.isstdcnt
+            + self.isutcnt
+    }
+}
+
+/// What a POSIX TZ string or TZif file contains, for the Wasm boundary.
+#[derive(Debug, PartialEq)]
+pub struct ZoneInspection {
+    pub name: String,
+    /// Set for TZif input
+    pub tzif: Option<Tzif>,
+    /// The POSIX string itself, or the TZif footer
+    pub rule: Option<PosixTz>,
+    /// Stored transitions for TZif input; those of the requested year for POSIX input
+    pub transitions: Vec<Transition>,
+}
+
+/// Decode a POSIX TZ string and list its transitions in `year` (local calendar year).
+pub fn inspect_posix(text: &str, year: i16) -> Result<ZoneInspection, String> {
+    let rule = PosixTz::parse(text)?;
+    let tz = TimeZone::posix(text.trim())
+        .map_err(|e| format!("Invalid POSIX TZ string '{}': {}", text.trim(), e))?;
+    let bound = |y: i16| {
+        jiff::civil::date(y, 1, 1)
+            .to_zoned(tz.clone())
+            .map(|z| z.timestamp())
+            .map_err(|e| format!("Year {} out of range: {}", y, e))
+    };
+    let transitions = zone::transitions_between(&tz, bound(year)?, bound(year + 1)?);
+    Ok(ZoneInspection {
+        name: rule.text.clone(),
+        tzif: None,
+        rule: Some(rule),
+        transitions,
+    })
+}
+
+/// Decode TZif data and build the `TimeZone` it describes, named `name`.
+pub fn parse_tzif(name: &str, data: &[u8]) -> Result<(TimeZone, ZoneInspection), String> {
+    let tzif = Tzif::parse(data)?;
+    let tz = TimeZone::tzif(name, data).map_err(|e| format!("Invalid TZif data: {}", e))?;
+    let rule = match &tzif.footer {
+        Some(footer) => Some(PosixTz::parse(footer)?),
+        None => None,
+    };
+    let transitions = tzif.zone_transitions();
+    Ok((
+        tz,
+        ZoneInspection {
+            name: name.to_string(),
+            tzif: Some(tzif),
+            rule,
+            transitions,
+        },
+    ))
+}
+
+impl ZoneInspection {
+    /// Serialize to JSON string for Wasm boundary (manual serialization).
+    pub fn to_json(&self) -> String {
+        let rule = self
+            .rule
+            .as_ref()
+            .map_or("null".to_string(), PosixTz::to_json);
+        let transitions: Vec<String> = self.transitions.iter().map(Transition::to_json).collect();
+        let tzif = match &self.tzif {
+            Some(tzif) => {
+                let types: Vec<String> = tzif
+                    .types
+                    .iter()
+                    .map(|t| {
+                        let offset = Offset::from_seconds(t.offset)
+                            .map_or_else(|_| t.offset.to_string(), format_offset);
+                        format!(
+                            r#"{{"offset":"{}","dst":{},"abbreviation":"{}"}}"#,
+                            offset,
+                            t.dst,
+                            json_escape(&t.abbreviation)
+                        )
+                    })
+                    .collect();
+                format!(
+                    r#","version":{},"types":[{}],"transitionCount":{},"leapSeconds":{},"footer":{}"#,
+                    tzif.version,
+                    types.join(","),
+                    tzif.transitions.len(),
+                    tzif.leap_seconds,
+                    tzif.footer
+                        .as_ref()
+                        .map_or("null".to_string(), |f| format!(r#""{}""#, json_escape(f)))
+                )
+            }
+            None => String::new(),
+        };
+        format!(
+            r#"{{"name":"{}","source":"{}"{},"rule":{},"transitions":[{}]}}"#,
+            json_escape(&self.name),
+            if self.tzif.is_some() { "tzif" } else { "posix" },
+            tzif,
+            rule,
+            transitions.join(",")
+        )
+    }
+}
+
+/// Decode a POSIX TZ string and list its transitions in local calendar `year`.
+pub fn inspect_posix_tz(posix: &str, year: i32) -> Result<ZoneInspection, String> {
+    let year = i16::try_from(year)
+        .ok()
+        .filter(|y| (-9998..=9998).contains(y))
+        .ok_or_else(|| format!("Year {} is out of range", year))?;
+    inspect_posix(posix, year)
+}
+
+/// Decode TZif `data` and register it as zone `name` for every later calculation.
+pub fn load_tzif(name: &str, data: &[u8]) -> Result<ZoneInspection, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("A name is needed to load a TZif file under".to_string());
+    }
+    if zone::builtin_zone(name).is_some() {
+        return Err(format!(
+            "'{}' always means UTC or a fixed offset; load the TZif file under another name",
+            name
+        ));
+    }
+    let (tz, inspection) = parse_tzif(name, data)?;
+    zone::register_zone(name, tz);
+    Ok(inspection)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_posix_eu_rule() {
+        let tz = PosixTz::parse("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+        assert_eq!((tz.std_abbreviation.as_str(), tz.std_offset), ("CET", 3600));
+        let dst = tz.dst.unwrap();
+        assert_eq!((dst.abbreviation.as_str(), dst.offset), ("CEST", 7200));
+        assert_eq!(dst.start.describe(), "last Sunday of March at 02:00:00");
+        assert_eq!(dst.end.rule(), "M10.5.0/03:00:00");
+    }
+
+    #[test]
+    fn test_posix_quoted_names_and_julian_days() {
+        let tz = PosixTz::parse("<+0330>-3:30").unwrap();
+        assert_eq!(
+            (tz.std_abbreviation.as_str(), tz.std_offset),
+            ("+0330", 12_600)
+        );
+        assert!(tz.dst.is_none());
+        let tz = PosixTz::parse("XXX3EDT4,J60/-1,300/26").unwrap();
+        let dst = tz.dst.unwrap();
+        assert_eq!(dst.offset, -4 * 3600);
+        assert_eq!(
+            dst.start.describe(),
+            "day 60 of the year (February 29 not counted) at -01:00:00"
+        );
+        assert_eq!(dst.end.rule(), "300/26:00:00");
+    }
+
+    #[test]
+    fn test_posix_default_rules_and_errors() {
+        let dst = PosixTz::parse("EST5EDT").unwrap().dst.unwrap();
+        assert_eq!(dst.offset, -4 * 3600);
+        assert_eq!(dst.start.rule(), "M3.2.0/02:00:00");
+        assert!(PosixTz::parse("CE-1").unwrap_err().contains("at least 3"));
+        assert!(PosixTz::parse("CET-1CEST,M13.5.0,M10.5.0")
+            .unwrap_err()
+            .contains("month 1-12"));
+        assert!(PosixTz::parse("CET-1CEST,M3.5.0")
+            .unwrap_err()
+            .contains("Expected ','"));
+        assert!(PosixTz::parse("CET-1CEST,M3.5.0,M10.5.0 junk")
+            .unwrap_err()
+            .contains("Unexpected"));
+    }
+
+    #[test]
+    fn test_tzif_rejects_other_data() {
+        assert!(
+            Tzif::parse(b"not a tz file at all, but long enough to hold a header")
+                .unwrap_err()
+                .contains("magic")
+        );
+        assert!(Tzif::parse(b"TZif").is_err());
+    }
+}
@@ -1,4 +1,9 @@
+use crate::abbreviation::{self, format_offset, ZoneSuffix};
+use crate::infer;
 use crate::json_utils::json_escape;
+use crate::spreadsheet;
+use crate::windows::{self, Decoded};
+use crate::zone::{self, LocalTime, LocalTimeKind};
 
 /// Result of validating a date input string.
 #[derive(Debug, PartialEq)]
@@ -6,6 +11,18 @@ pub struct ValidationResult {
     pub valid: bool,
     pub error: Option<String>,
     pub normalized: Option<String>,
+    /// strptime pattern guessed for non-ISO input (e.g. `%d/%m/%Y`), so the UI
+    /// can say what it recognized instead of a bare "invalid".
+    pub detected_format: Option<String>,
+    /// The day/month-swapped reading of `detected_format`, when both fit the input.
+    pub ambiguous_with: Option<String>,
+    /// Where a civil input falls in the zone given to `validate_in_zone`; only kept when
+    /// the wall-clock time is skipped (gap) or repeated (fold) there.
+    pub local_time: Option<LocalTime>,
+    /// The abbreviation or offset notation that ended the input (e.g. `PST`, `UTC+5:30`)
+    pub zone_suffix: Option<ZoneSuffix>,
+    /// Why a valid input may not mean what it shows (e.g. Excel's serial 60)
+    pub warning: Option<String>,
 }
 
 impl ValidationResult {
@@ -14,6 +31,11 @@ impl ValidationResult {
             valid: true,
             error: None,
             normalized: Some(normalized),
+            detected_format: None,
+            ambiguous_with: None,
+            local_time: None,
+            zone_suffix: None,
+            warning: None,
         }
     }
 
@@ -22,22 +44,69 @@ impl ValidationResult {
             valid: false,
             error: Some(msg),
             normalized: None,
+            detected_format: None,
+            ambiguous_with: None,
+            local_time: None,
+            zone_suffix: None,
+            warning: None,
         }
     }
 
+    fn with_detected_format(mut self, candidate: &infer::FormatCandidate) -> Self {
+        self.detected_format = Some(candidate.pattern.clone());
+        self.ambiguous_with = candidate.ambiguous_with.clone();
+        self
+    }
+
     /// Serialize to JSON string for Wasm boundary (manual serialization).
     pub fn to_json(&self) -> String {
         if self.valid {
             let normalized = self.normalized.as_deref().unwrap_or("");
+            let local_time = match &self.local_time {
+                Some(local) => format!(
+                    r#","localTime":{{"kind":"{}","earlier":"{}","later":"{}"}}"#,
+                    local.kind.name(),
+                    local.earlier,
+                    local.later
+                ),
+                None => String::new(),
+            };
+            let zone = match &self.zone_suffix {
+                Some(suffix) => {
+                    let mut zone = format!(r#","offset":"{}""#, format_offset(suffix.offset));
+                    if let Some(warning) = suffix.warning() {
+                        zone.push_str(&format!(
+                            r#","warning":"{}","candidates":{}"#,
+                            json_escape(&warning),
+                            suffix.candidates_json()
+                        ));
+                    }
+                    zone
+                }
+                None => match &self.warning {
+                    Some(warning) => format!(r#","warning":"{}""#, json_escape(warning)),
+                    None => String::new(),
+                },
+            };
             format!(
-                r#"{{"valid":true,"normalized":"{}"}}"#,
-                json_escape(normalized)
+                r#"{{"valid":true,"normalized":"{}"{}{}}}"#,
+                json_escape(normalized),
+                local_time,
+                zone
             )
         } else {
             let error = self.error.as_deref().unwrap_or("Unknown error");
+            let mut extra = String::new();
+            if let Some(pattern) = &self.detected_format {
+                extra.push_str(&format!(r#","detectedFormat":"{}""#, json_escape(pattern)));
+            }
+            if let Some(pattern) = &self.ambiguous_with {
+                extra.push_str(&format!(r#","ambiguousWith":"{}""#, json_escape(pattern)));
+            }
             format!(
-                r#"{{"valid":false,"error":"{}"}}"#,
-                json_escape(error)
+                r#"{{"valid":false,"error":"{}"{}}}"#,
+                json_escape(error),
+                extra
             )
         }
     }
@@ -48,8 +117,18 @@ impl ValidationResult {
 /// Accepts:
 /// - ISO 8601 datetime strings (e.g., "2024-07-11T01:14:00Z")
 /// - Civil date strings (e.g., "2024-07-11")
+/// - Datetimes or times of day followed by a zone abbreviation or offset notation
+///   (e.g., "10:00 PST", "2024-07-11 14:00 UTC+5:30"); ambiguous abbreviations such
+///   as `IST` take their most common reading and list every candidate
+/// - Windows encodings behind a prefix (e.g., "filetime:0x01DAD33A4B1F7000",
+///   "ticks:638562308400000000", "ad:133650164400000000", "ole:45484.5",
+///   "dos:0x58EB19C0"); see `windows::parse_input`
+/// - Spreadsheet serials behind a prefix (e.g., "excel:45484.5", "excel1904:44022.5",
+///   "sheets:-1.25"); see `spreadsheet::parse_input`
 ///
-/// Returns a `ValidationResult` indicating whether the input is valid.
+/// Returns a `ValidationResult` indicating whether the input is valid. Input in
+/// another recognizable layout (e.g. "03/04/2024") is still rejected, but carries
+/// the detected strptime pattern and any day/month ambiguity.
 pub fn validate(input: &str) -> ValidationResult {
     let trimmed = input.trim();
 
@@ -62,9 +141,12 @@ pub fn validate(input: &str) -> ValidationResult {
         return ValidationResult::ok(ts.to_string());
     }
 
-    // Try parsing as a civil date (YYYY-MM-DD)
-    if let Ok(date) = trimmed.parse::<jiff::civil::Date>() {
-        return ValidationResult::ok(date.to_string());
+    // Try parsing as a civil date (YYYY-MM-DD); jiff would also accept a datetime
+    // here and drop its time
+    if !trimmed.contains(['T', 't', ' ']) {
+        if let Ok(date) = trimmed.parse::<jiff::civil::Date>() {
+            return ValidationResult::ok(date.to_string());
+        }
     }
 
     // Try parsing as a civil datetime (YYYY-MM-DDTHH:MM:SS without timezone)
@@ -72,7 +154,85 @@ pub fn validate(input: &str) -> ValidationResult {
         return ValidationResult::ok(dt.to_string());
     }
 
-    ValidationResult::err(format!("Invalid date input: '{}'", trimmed))
+    // A datetime or bare time followed by an abbreviation or offset ("10:00 PST",
+    // "2024-03-01 14:00 UTC+5:30"); ambiguous abbreviations carry a warning
+    if let Some((head, suffix)) = abbreviation::split_zone_suffix(trimmed) {
+        let local = if let Ok(dt) = head.parse::<jiff::civil::DateTime>() {
+            Some(dt.to_string())
+        } else {
+            head.parse::<jiff::civil::Time>()
+                .ok()
+                .map(|t| t.to_string())
+        };
+        if let Some(local) = local {
+            let mut result =
+                ValidationResult::ok(format!("{}{}", local, format_offset(suffix.offset)));
+            result.zone_suffix = Some(suffix);
+            return result;
+        }
+    }
+
+    // A Windows encoding with its prefix ("filetime:0x01DAD33A4B1F7000", "ole:45484.5");
+    // OLE and DOS values are wall-clock times and normalize to civil datetimes
+    if let Some(decoded) = windows::parse_input(trimmed) {
+        return match decoded {
+            Ok(Decoded::Instant(ts)) => ValidationResult::ok(ts.to_string()),
+            Ok(Decoded::Civil(dt)) => ValidationResult::ok(dt.to_string()),
+            Err(e) => ValidationResult::err(e),
+        };
+    }
+
+    // A spreadsheet serial with its prefix ("excel:45484.5"), a wall-clock time; Excel's
+    // serial 60 carries a warning
+    if let Some(decoded) = spreadsheet::parse_input(trimmed) {
+        return match decoded {
+            Ok(dt) => {
+                let mut result = ValidationResult::ok(dt.to_string());
+                result.warning = spreadsheet::input_warning(trimmed);
+                result
+            }
+            Err(e) => ValidationResult::err(e),
+        };
+    }
+
+    let result = ValidationResult::err(format!("Invalid date input: '{}'", trimmed));
+    match infer::infer_format(&[trimmed]) {
+        Ok(inference) => match inference.best() {
+            Some(best) => result.with_detected_format(best),
+            None => result,
+        },
+        Err(_) => result,
+    }
+}
+
+/// Like `validate`, but also places civil input on the wall clock of `zone` and
+/// reports when it falls in a DST gap or fold.
+///
+/// Gap and fold times stay valid; `local_time` lists both instants they could mean.
+pub fn validate_in_zone(input: &str, zone: &str) -> ValidationResult {
+    let tz = match zone::resolve_zone(zone) {
+        Ok(tz) => tz,
+        Err(e) => return ValidationResult::err(e),
+    };
+    let mut result = validate(input);
+    if !result.valid || input.trim().parse::<jiff::Timestamp>().is_ok() {
+        return result;
+    }
+    let civil = match windows::parse_input(input) {
+        Some(Ok(Decoded::Civil(dt))) => Ok(dt),
+        _ => match spreadsheet::parse_input(input) {
+            Some(Ok(dt)) => Ok(dt),
+            _ => input.trim().parse::<jiff::civil::DateTime>(),
+        },
+    };
+    if let Ok(civil) = civil {
+        match zone::local_time(civil, &tz) {
+            Ok(local) if local.kind != LocalTimeKind::Unique => result.local_time = Some(local),
+            Ok(_) => {}
+            Err(e) => return ValidationResult::err(e),
+        }
+    }
+    result
 }
 
 #[cfg(test)]
@@ -121,4 +281,73 @@ mod tests {
         assert!(json.contains("\"valid\":false"));
         assert!(json.contains("\"error\""));
     }
+
+    #[test]
+    fn test_detected_format_for_non_iso_input() {
+        let result = validate("25/12/2024");
+        assert!(!result.valid);
+        assert_eq!(result.detected_format.as_deref(), Some("%d/%m/%Y"));
+        assert_eq!(result.ambiguous_with, None);
+    }
+
+    #[test]
+    fn test_detected_format_flags_ambiguity() {
+        let result = validate("03/04/2024");
+        assert_eq!(result.detected_format.as_deref(), Some("%d/%m/%Y"));
+        assert_eq!(result.ambiguous_with.as_deref(), Some("%m/%d/%Y"));
+        let json = result.to_json();
+        assert!(json.contains("\"detectedFormat\":\"%d/%m/%Y\""));
+        assert!(json.contains("\"ambiguousWith\":\"%m/%d/%Y\""));
+    }
+
+    #[test]
+    fn test_in_zone_reports_gap_and_fold() {
+        let gap = validate_in_zone("2024-03-10T02:30", "America/New_York");
+        assert!(gap.valid);
+        assert_eq!(gap.local_time.as_ref().unwrap().kind, LocalTimeKind::Gap);
+        assert_eq!(
+            gap.to_json(),
+            r#"{"valid":true,"normalized":"2024-03-10T02:30:00","localTime":{"kind":"gap","earlier":"2024-03-10T06:30:00Z","later":"2024-03-10T07:30:00Z"}}"#
+        );
+        let fold = validate_in_zone("2024-11-03T01:30", "America/New_York");
+        assert_eq!(fold.local_time.unwrap().kind, LocalTimeKind::Fold);
+    }
+
+    #[test]
+    fn test_in_zone_ignores_unique_and_absolute_input() {
+        assert_eq!(
+            validate_in_zone("2024-03-10T12:00", "America/New_York").local_time,
+            None
+        );
+        assert_eq!(
+            validate_in_zone("2024-03-10T07:30:00Z", "America/New_York").local_time,
+            None
+        );
+        assert!(!validate_in_zone("2024-03-10", "Nowhere/City").valid);
+    }
+
+    #[test]
+    fn test_abbreviation_suffix_is_accepted() {
+        let result = validate("10:00 PST");
+        assert!(result.valid);
+        assert_eq!(result.normalized.as_deref(), Some("10:00:00-08:00"));
+        assert_eq!(
+            result.to_json(),
+            r#"{"valid":true,"normalized":"10:00:00-08:00","offset":"-08:00","warning":"'PST' is ambiguous: Pacific Standard Time (-08:00), Philippine Standard Time (+08:00); assumed Pacific Standard Time","candidates":[{"offset":"-08:00","zone":"America/Los_Angeles","name":"Pacific Standard Time"},{"offset":"+08:00","zone":"Asia/Manila","name":"Philippine Standard Time"}]}"#
+        );
+    }
+
+    #[test]
+    fn test_offset_notation_suffix_has_no_warning() {
+        let result = validate("2024-07-11 14:00 UTC+5:30");
+        assert_eq!(
+            result.normalized.as_deref(),
+            Some("2024-07-11T14:00:00+05:30")
+        );
+        assert_eq!(
+            result.to_json(),
+            r#"{"valid":true,"normalized":"2024-07-11T14:00:00+05:30","offset":"+05:30"}"#
+        );
+        assert!(validate("2024-07-11 14:00 XYZ").error.is_some());
+    }
 }
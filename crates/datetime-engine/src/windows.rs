@@ -0,0 +1,428 @@
+//! Windows and Microsoft time encodings: FILETIME, .NET ticks, Active Directory
+//! timestamps, OLE Automation dates and DOS/FAT packed date-times.
+//!
+//! FILETIME, ticks and Active Directory values count 100 ns intervals and name an
+//! instant in UTC. OLE dates and DOS date-times are wall-clock values without a zone,
+//! so they are read and written as civil times in the zone of the calculation.
+
+use jiff::civil::{self, Date, DateTime};
+use jiff::tz::TimeZone;
+use jiff::{Timestamp, ToSpan};
+
+/// Unix seconds of 1601-01-01T00:00:00Z, the FILETIME epoch.
+const FILETIME_EPOCH: i64 = -11_644_473_600;
+
+/// Unix seconds of 0001-01-01T00:00:00Z, the .NET `DateTime` epoch.
+const TICKS_EPOCH: i64 = -62_135_596_800;
+
+/// OLE dates count days from 1899-12-30.
+const OLE_EPOCH: Date = civil::date(1899, 12, 30);
+
+/// Days back to 0100-01-01 and forward to 9999-12-31 from `OLE_EPOCH`, the documented range.
+const OLE_MIN_DAYS: i64 = 657_435;
+const OLE_MAX_DAYS: i64 = 2_958_465;
+
+/// Length of a day, the unit of day counts such as OLE dates.
+pub(crate) const MILLIS_PER_DAY: i64 = 86_400_000;
+pub(crate) const NANOS_PER_DAY: i128 = 86_400_000_000_000;
+
+/// Decimal places written for the time of day; 10 resolve 8.64 µs, enough to come
+/// back to the same millisecond.
+const FRACTION_DIGITS: u32 = 10;
+
+/// Active Directory writes this for "never expires" (`accountExpires`).
+const AD_NEVER: u64 = i64::MAX as u64;
+
+/// One of the Windows time encodings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowsTime {
+    FileTime,
+    DotNetTicks,
+    ActiveDirectory,
+    OleDate,
+    DosDateTime,
+}
+
+/// A decoded value: an instant, or a wall-clock time still to be placed in a zone.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Decoded {
+    Instant(Timestamp),
+    Civil(DateTime),
+}
+
+impl WindowsTime {
+    pub const ALL: [WindowsTime; 5] = [
+        WindowsTime::FileTime,
+        WindowsTime::DotNetTicks,
+        WindowsTime::ActiveDirectory,
+        WindowsTime::OleDate,
+        WindowsTime::DosDateTime,
+    ];
+
+    /// The name used as an input prefix and output format name.
+    pub fn name(self) -> &'static str {
+        match self {
+            WindowsTime::FileTime => "fileTime",
+            WindowsTime::DotNetTicks => "dotnetTicks",
+            WindowsTime::ActiveDirectory => "activeDirectory",
+            WindowsTime::OleDate => "oleDate",
+            WindowsTime::DosDateTime => "dosDateTime",
+        }
+    }
+
+    /// Short input prefix, e.g. `ticks:638562308400000000`.
+    fn alias(self) -> &'static str {
+        match self {
+            WindowsTime::FileTime => "filetime",
+            WindowsTime::DotNetTicks => "ticks",
+            WindowsTime::ActiveDirectory => "ad",
+            WindowsTime::OleDate => "ole",
+            WindowsTime::DosDateTime => "dos",
+        }
+    }
+
+    /// Look up an input prefix, by name or alias, ignoring case.
+    pub fn from_prefix(prefix: &str) -> Option<Self> {
+        let prefix = prefix.trim();
+        WindowsTime::ALL.into_iter().find(|w| {
+            w.name().eq_ignore_ascii_case(prefix) || w.alias().eq_ignore_ascii_case(prefix)
+        })
+    }
+
+    /// Decode `value` (decimal, or `0x` hex for the integer encodings).
+    pub fn decode(self, value: &str) -> Result<Decoded, String> {
+        let value = value.trim();
+        match self {
+            WindowsTime::FileTime => {
+                from_intervals(FILETIME_EPOCH, parse_integer(value)?).map(Decoded::Instant)
+            }
+            WindowsTime::DotNetTicks => {
+                from_intervals(TICKS_EPOCH, parse_integer(value)?).map(Decoded::Instant)
+            }
+            WindowsTime::ActiveDirectory => match parse_integer(value)? {
+                0 | AD_NEVER => Err("the value means 'never' (not set or no expiry)".to_string()),
+                intervals => from_intervals(FILETIME_EPOCH, intervals).map(Decoded::Instant),
+            },
+            WindowsTime::OleDate => decode_ole(value).map(Decoded::Civil),
+            WindowsTime::DosDateTime => {
+                let packed = u32::try_from(parse_integer(value)?)
+                    .map_err(|_| "does not fit in 32 bits".to_string())?;
+                decode_dos(packed).map(Decoded::Civil)
+            }
+        }
+    }
+
+    /// Encode `ts`; OLE dates and DOS date-times use the wall clock of `tz`.
+    pub fn encode(self, ts: Timestamp, tz: &TimeZone) -> Result<String, String> {
+        match self {
+            WindowsTime::FileTime => Ok(format!(
+                "0x{:016X}",
+                to_intervals(FILETIME_EPOCH, ts, "1601-01-01")?
+            )),
+            WindowsTime::DotNetTicks => {
+                Ok(to_intervals(TICKS_EPOCH, ts, "0001-01-01")?.to_string())
+            }
+            WindowsTime::ActiveDirectory => {
+                Ok(to_intervals(FILETIME_EPOCH, ts, "1601-01-01")?.to_string())
+            }
+            WindowsTime::OleDate => encode_ole(ts.to_zoned(tz.clone()).datetime()),
+            WindowsTime::DosDateTime => {
+                encode_dos(ts.to_zoned(tz.clone()).datetime()).map(|p| format!("0x{:08X}", p))
+            }
+        }
+    }
+}
+
+/// Split `prefix:value` input such as `filetime:0x01DAD33A4B1F7000`; `None` when the
+/// input does not start with a known prefix.
+pub fn parse_input(input: &str) -> Option<Result<Decoded, String>> {
+    let (prefix, value) = input.trim().split_once(':')?;
+    let format = WindowsTime::from_prefix(prefix)?;
+    Some(
+        format
+            .decode(value)
+            .map_err(|e| format!("Invalid {} '{}': {}", format.name(), value.trim(), e)),
+    )
+}
+
+fn parse_integer(value: &str) -> Result<u64, String> {
+    let parsed = match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => value.parse(),
+    };
+    parsed.map_err(|_| "expected a non-negative integer (decimal or 0x hex)".to_string())
+}
+
+fn from_intervals(epoch: i64, intervals: u64) -> Result<Timestamp, String> {
+    let nanos = i128::from(epoch) * 1_000_000_000 + i128::from(intervals) * 100;
+    // Checked here rather than left to jiff, so a huge value is an error and never a panic
+    if nanos > Timestamp::MAX.as_nanosecond() {
+        return Err(format!("out of range: after {}", Timestamp::MAX));
+    }
+    Timestamp::from_nanosecond(nanos).map_err(|e| format!("out of range: {}", e))
+}
+
+fn to_intervals(epoch: i64, ts: Timestamp, epoch_name: &str) -> Result<u64, String> {
+    let intervals = (ts.as_nanosecond() - i128::from(epoch) * 1_000_000_000).div_euclid(100);
+    u64::try_from(intervals).map_err(|_| format!("{} is before {}", ts, epoch_name))
+}
+
+/// A day count as written: its sign, whole days and the fraction in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct DayCount {
+    pub negative: bool,
+    pub whole: i64,
+    pub millis: i64,
+}
+
+/// Parse a decimal day count such as `45484.5` or `-1.25` exactly, without `f64`.
+pub(crate) fn parse_day_count(text: &str) -> Result<DayCount, String> {
+    let text = text.trim();
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+    let all_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if (whole.is_empty() && fraction.is_empty()) || !all_digits(whole) || !all_digits(fraction) {
+        return Err(format!(
+            "expected a day count such as 45484.5, got '{}'",
+            text
+        ));
+    }
+    let mut whole: i64 = if whole.is_empty() {
+        0
+    } else {
+        whole
+            .parse()
+            .map_err(|_| format!("day count '{}' is out of range", text))?
+    };
+    // Digits past the 18th cannot move the result by a millisecond
+    let fraction = &fraction[..fraction.len().min(18)];
+    let mut millis = if fraction.is_empty() {
+        0
+    } else {
+        let numerator: i128 = fraction.parse().expect("checked digits");
+        let denominator = 10i128.pow(fraction.len() as u32);
+        let scaled = numerator * i128::from(MILLIS_PER_DAY);
+        ((scaled * 2 + denominator) / (denominator * 2)) as i64
+    };
+    if millis == MILLIS_PER_DAY {
+        whole += 1;
+        millis = 0;
+    }
+    Ok(DayCount {
+        negative,
+        whole,
+        millis,
+    })
+}
+
+/// Write whole days plus `nanos` of a day as a decimal day count.
+pub(crate) fn format_day_count(negative: bool, whole: i64, nanos: i128) -> String {
+    let unit = 10i128.pow(FRACTION_DIGITS);
+    let mut whole = whole;
+    let mut fraction = (nanos * unit * 2 + NANOS_PER_DAY) / (NANOS_PER_DAY * 2);
+    if fraction == unit {
+        whole += 1;
+        fraction = 0;
+    }
+    let sign = if negative && (whole != 0 || fraction != 0) {
+        "-"
+    } else {
+        ""
+    };
+    if fraction == 0 {
+        format!("{}{}", sign, whole)
+    } else {
+        let digits = format!("{:0width$}", fraction, width = FRACTION_DIGITS as usize);
+        format!("{}{}.{}", sign, whole, digits.trim_end_matches('0'))
+    }
+}
+
+/// Nanoseconds since midnight of the wall-clock time.
+pub(crate) fn nanos_of_day(dt: DateTime) -> i128 {
+    let time = dt.time();
+    i128::from(
+        i64::from(time.hour()) * 3_600_000_000_000
+            + i64::from(time.minute()) * 60_000_000_000
+            + i64::from(time.second()) * 1_000_000_000
+            + i64::from(time.subsec_nanosecond()),
+    )
+}
+
+fn decode_ole(value: &str) -> Result<DateTime, String> {
+    let count = parse_day_count(value)?;
+    let limit = if count.negative {
+        OLE_MIN_DAYS
+    } else {
+        OLE_MAX_DAYS
+    };
+    if count.whole > limit {
+        return Err("outside 0100-01-01 to 9999-12-31".to_string());
+    }
+    // Negative dates keep a positive time of day: -1.25 is 1899-12-29 06:00
+    let days = if count.negative {
+        -count.whole
+    } else {
+        count.whole
+    };
+    OLE_EPOCH
+        .to_datetime(civil::Time::midnight())
+        .checked_add(days.days())
+        .and_then(|dt| dt.checked_add(count.millis.milliseconds()))
+        .map_err(|e| format!("out of range: {}", e))
+}
+
+fn encode_ole(dt: DateTime) -> Result<String, String> {
+    let days = OLE_EPOCH
+        .until(dt.date())
+        .map_err(|e| format!("Date out of range: {}", e))?
+        .get_days();
+    Ok(format_day_count(
+        days < 0,
+        i64::from(days.abs()),
+        nanos_of_day(dt),
+    ))
+}
+
+fn decode_dos(packed: u32) -> Result<DateTime, String> {
+    let field = |shift: u32, bits: u32| ((packed >> shift) & ((1 << bits) - 1)) as i8;
+    let year = 1980 + (packed >> 25) as i16;
+    let (month, day) = (field(21, 4), field(16, 5));
+    let (hour, minute, second) = (field(11, 5), field(5, 6), field(0, 5) * 2);
+    civil::DateTime::new(year, month, day, hour, minute, second, 0).map_err(|_| {
+        format!(
+            "packs {}-{:02}-{:02} {:02}:{:02}:{:02}, which is not a valid date-time",
+            year, month, day, hour, minute, second
+        )
+    })
+}
+
+fn encode_dos(dt: DateTime) -> Result<u32, String> {
+    if !(1980..=2107).contains(&dt.year()) {
+        return Err(format!(
+            "DOS date-times cover 1980 to 2107, not {}",
+            dt.year()
+        ));
+    }
+    let field = |value: i8, shift: u32| (value as u32) << shift;
+    Ok((((dt.year() - 1980) as u32) << 25)
+        | field(dt.month(), 21)
+        | field(dt.day(), 16)
+        | field(dt.hour(), 11)
+        | field(dt.minute(), 5)
+        | field(dt.second() / 2, 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instant(format: WindowsTime, value: &str) -> String {
+        match format.decode(value).unwrap() {
+            Decoded::Instant(ts) => ts.to_string(),
+            Decoded::Civil(dt) => panic!("{} decoded to civil {}", format.name(), dt),
+        }
+    }
+
+    fn wall_clock(format: WindowsTime, value: &str) -> String {
+        match format.decode(value).unwrap() {
+            Decoded::Civil(dt) => dt.to_string(),
+            Decoded::Instant(ts) => panic!("{} decoded to instant {}", format.name(), ts),
+        }
+    }
+
+    #[test]
+    fn test_interval_epochs() {
+        assert_eq!(
+            instant(WindowsTime::FileTime, "116444736000000000"),
+            "1970-01-01T00:00:00Z"
+        );
+        assert_eq!(
+            instant(WindowsTime::FileTime, "0x019DB1DED53E8000"),
+            "1970-01-01T00:00:00Z"
+        );
+        assert_eq!(
+            instant(WindowsTime::DotNetTicks, "621355968000000000"),
+            "1970-01-01T00:00:00Z"
+        );
+        assert_eq!(
+            instant(WindowsTime::DotNetTicks, "0"),
+            "0001-01-01T00:00:00Z"
+        );
+        let ts: Timestamp = "2024-07-11T01:14:00.1234567Z".parse().unwrap();
+        for format in [
+            WindowsTime::FileTime,
+            WindowsTime::DotNetTicks,
+            WindowsTime::ActiveDirectory,
+        ] {
+            let encoded = format.encode(ts, &TimeZone::UTC).unwrap();
+            assert_eq!(format.decode(&encoded).unwrap(), Decoded::Instant(ts));
+        }
+    }
+
+    #[test]
+    fn test_active_directory_never() {
+        assert!(WindowsTime::ActiveDirectory
+            .decode("0")
+            .unwrap_err()
+            .contains("never"));
+        assert!(WindowsTime::ActiveDirectory
+            .decode("9223372036854775807")
+            .unwrap_err()
+            .contains("never"));
+    }
+
+    #[test]
+    fn test_day_count_text() {
+        let count = parse_day_count("45484.0513888889").unwrap();
+        assert_eq!((count.whole, count.millis), (45484, 4_440_000));
+        assert_eq!(parse_day_count(".5").unwrap().millis, 43_200_000);
+        assert_eq!(parse_day_count("1.99999999999").unwrap().whole, 2);
+        assert!(parse_day_count("1e5").is_err());
+        assert!(parse_day_count("-").is_err());
+        assert_eq!(
+            format_day_count(false, 45484, 4_440_000_000_000),
+            "45484.0513888889"
+        );
+        assert_eq!(format_day_count(true, 1, NANOS_PER_DAY / 4), "-1.25");
+        assert_eq!(format_day_count(true, 0, 0), "0");
+    }
+
+    #[test]
+    fn test_ole_dates() {
+        assert_eq!(wall_clock(WindowsTime::OleDate, "0"), "1899-12-30T00:00:00");
+        assert_eq!(
+            wall_clock(WindowsTime::OleDate, "-1.25"),
+            "1899-12-29T06:00:00"
+        );
+        assert_eq!(
+            wall_clock(WindowsTime::OleDate, "45484.5"),
+            "2024-07-11T12:00:00"
+        );
+        let dt: DateTime = "1899-12-29T06:00:00".parse().unwrap();
+        assert_eq!(encode_ole(dt).unwrap(), "-1.25");
+        assert!(WindowsTime::OleDate.decode("3000000").is_err());
+    }
+
+    #[test]
+    fn test_dos_date_times() {
+        let dt: DateTime = "2024-07-11T03:14:07".parse().unwrap();
+        let packed = encode_dos(dt).unwrap();
+        // Two-second resolution: :07 comes back as :06
+        assert_eq!(
+            decode_dos(packed).unwrap().to_string(),
+            "2024-07-11T03:14:06"
+        );
+        assert_eq!(
+            wall_clock(WindowsTime::DosDateTime, "0x00210000"),
+            "1980-01-01T00:00:00"
+        );
+        assert!(decode_dos(0).unwrap_err().contains("not a valid"));
+        assert!(encode_dos("1979-12-31T00:00:00".parse().unwrap()).is_err());
+    }
+}
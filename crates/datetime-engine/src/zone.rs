@@ -0,0 +1,734 @@
+use std::cell::RefCell;
+
+use jiff::civil::DateTime;
+use jiff::tz::{AmbiguousOffset, Offset, TimeZone};
+use jiff::{Timestamp, ToSpan, Zoned};
+
+use crate::abbreviation::format_offset;
+use crate::calc::{parse_start_date, parse_start_date_in, parse_window};
+use crate::json_utils::{extract_string_field, json_escape, parse_string_array};
+
+/// Resolve a time zone name from the Wasm boundary into a `TimeZone`.
+///
+/// Accepts:
+/// - `""`, `"UTC"` or `"Z"` — UTC
+/// - Fixed offsets (e.g., `"+05:30"`, `"-0800"`, `"+09"`), also as `"UTC+5:30"` or `"GMT-3"`
+/// - Zones registered with `register_zone` (e.g. a TZif file loaded by name)
+/// - IANA identifiers (e.g., `"America/New_York"`) — these need the time zone
+///   database, which is only bundled into the Wasm build with the `tzdb` feature
+/// - POSIX TZ strings (e.g., `"CET-1CEST,M3.5.0,M10.5.0/3"`)
+pub fn resolve_zone(name: &str) -> Result<TimeZone, String> {
+    let trimmed = name.trim();
+    if let Some(tz) = builtin_zone(trimmed) {
+        return Ok(tz);
+    }
+
+    if let Some(tz) = registered_zone(trimmed) {
+        return Ok(tz);
+    }
+
+    // `UTC+15` would otherwise read as a POSIX string for UTC-15
+    if trimmed.len() > 3 && crate::abbreviation::parse_offset_notation_unchecked(trimmed).is_some()
+    {
+        return Err(format!(
+            "Offset '{}' is out of range (zones use -12:00 to +14:00)",
+            trimmed
+        ));
+    }
+
+    jiff::tz::db().get(trimmed).or_else(|_| {
+        // `CET-1CEST,M3.5.0,M10.5.0/3`, `EST5EDT`; names without digits are never
+        // valid POSIX strings, so an unknown IANA name keeps its own error
+        if trimmed.bytes().any(|b| b.is_ascii_digit()) {
+            if let Ok(tz) = TimeZone::posix(trimmed) {
+                // Registered so `zone_name` can give the string back
+                register_zone(trimmed, tz.clone());
+                return Ok(tz);
+            }
+        }
+        Err(if jiff::tz::db().is_definitively_empty() {
+            format!(
+                "Unknown time zone '{}' (IANA zones require the `tzdb` feature; use a fixed offset like +05:30)",
+                trimmed
+            )
+        } else {
+            format!("Unknown time zone '{}'", trimmed)
+        })
+    })
+}
+
+/// UTC or the fixed offset a name denotes without any zone data: `""`, `UTC`, `Z`,
+/// `+05:30`, `UTC+5:30`. These names are resolved before registered or IANA zones.
+pub fn builtin_zone(name: &str) -> Option<TimeZone> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() || trimmed.eq_ignore_ascii_case("UTC") || trimmed == "Z" {
+        return Some(TimeZone::UTC);
+    }
+    if let Some(offset) = parse_fixed_offset(trimmed) {
+        return Some(TimeZone::fixed(offset));
+    }
+    // `UTC+5:30`, `GMT-3`; a bare `GMT` stays the IANA zone of that name
+    if trimmed.len() > 3 {
+        if let Some(offset) = crate::abbreviation::parse_offset_notation(trimmed) {
+            return Some(TimeZone::fixed(offset));
+        }
+    }
+    None
+}
+
+thread_local! {
+    /// Zones loaded at runtime (e.g. from a device's TZif file), by name.
+    static REGISTERED_ZONES: RefCell<Vec<(String, TimeZone)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Make `tz` available to `resolve_zone` under `name`, replacing any zone already
+/// registered under it. Registered zones shadow IANA zones of the same name.
+pub fn register_zone(name: &str, tz: TimeZone) {
+    REGISTERED_ZONES.with(|zones| {
+        let mut zones = zones.borrow_mut();
+        zones.retain(|(existing, _)| existing != name);
+        zones.push((name.to_string(), tz));
+    });
+}
+
+fn registered_name(tz: &TimeZone) -> Option<String> {
+    REGISTERED_ZONES.with(|zones| {
+        zones
+            .borrow()
+            .iter()
+            .find(|(_, existing)| existing == tz)
+            .map(|(name, _)| name.clone())
+    })
+}
+
+fn registered_zone(name: &str) -> Option<TimeZone> {
+    REGISTERED_ZONES.with(|zones| {
+        zones
+            .borrow()
+            .iter()
+            .find(|(existing, _)| existing == name)
+            .map(|(_, tz)| tz.clone())
+    })
+}
+
+/// Parse a fixed UTC offset in `±HH`, `±HHMM` or `±HH:MM` form.
+pub fn parse_fixed_offset(input: &str) -> Option<Offset> {
+    parse_offset_with_hour_digits(input, 2..=2)
+}
+
+/// Like `parse_fixed_offset`, but also with a one-digit hour (`+5`, `+5:30`), as
+/// written after `UTC` or `GMT`.
+pub(crate) fn parse_short_offset(input: &str) -> Option<Offset> {
+    parse_offset_with_hour_digits(input, 1..=2)
+}
+
+fn parse_offset_with_hour_digits(
+    input: &str,
+    hour_digits: std::ops::RangeInclusive<usize>,
+) -> Option<Offset> {
+    let (sign, rest) = match input.as_bytes().first()? {
+        b'+' => (1, &input[1..]),
+        b'-' => (-1, &input[1..]),
+        _ => return None,
+    };
+    if !rest.bytes().all(|b| b.is_ascii_digit() || b == b':') {
+        return None;
+    }
+
+    // `HH:MM` splits at the colon, `HHMM` before the last two digits
+    let (hours, minutes) = match rest.split_once(':') {
+        Some((hours, minutes)) if minutes.len() == 2 => (hours, minutes),
+        Some(_) => return None,
+        None if rest.len() == 4 => rest.split_at(2),
+        None => (rest, "00"),
+    };
+    if !hour_digits.contains(&hours.len()) || minutes.contains(':') {
+        return None;
+    }
+    let (hours, minutes) = (hours.parse::<i32>().ok()?, minutes.parse::<i32>().ok()?);
+    if hours > 25 || minutes > 59 {
+        return None;
+    }
+
+    Offset::from_seconds(sign * (hours * 3600 + minutes * 60)).ok()
+}
+
+/// A display name for a zone: its IANA identifier, its offset for fixed zones, or the
+/// name it was registered under (POSIX TZ strings from `resolve_zone` are registered
+/// under themselves).
+pub fn zone_name(tz: &TimeZone) -> String {
+    match tz.iana_name() {
+        Some(name) => name.to_string(),
+        None => tz.to_fixed_offset().map_or_else(
+            |_| registered_name(tz).unwrap_or_else(|| "UTC".to_string()),
+            |o| o.to_string(),
+        ),
+    }
+}
+
+/// How a wall-clock time that is skipped or repeated in a zone becomes an instant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Disambiguation {
+    /// Gap times move forward by the gap's length; fold times take the first pass
+    /// (RFC 5545 and Temporal behavior)
+    #[default]
+    Compatible,
+    /// The earlier of the two candidate instants
+    Earlier,
+    /// The later of the two candidate instants
+    Later,
+    /// Fail instead of guessing
+    Reject,
+}
+
+impl Disambiguation {
+    /// Parse a disambiguation name from the Wasm boundary; `""` selects `Compatible`.
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "" | "compatible" => Ok(Disambiguation::Compatible),
+            "earlier" => Ok(Disambiguation::Earlier),
+            "later" => Ok(Disambiguation::Later),
+            "reject" => Ok(Disambiguation::Reject),
+            other => Err(format!(
+                "Unknown disambiguation '{}' (expected compatible, earlier, later or reject)",
+                other
+            )),
+        }
+    }
+}
+
+/// Whether a wall-clock time exists once, never or twice in a zone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocalTimeKind {
+    Unique,
+    /// Skipped when clocks jump forward
+    Gap,
+    /// Repeated when clocks fall back
+    Fold,
+}
+
+impl LocalTimeKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            LocalTimeKind::Unique => "unique",
+            LocalTimeKind::Gap => "gap",
+            LocalTimeKind::Fold => "fold",
+        }
+    }
+}
+
+/// A wall-clock time placed in a zone, with both candidate instants.
+///
+/// `earlier` and `later` are equal for unique times. In a gap they are the instants
+/// reached by reading the time with the offset after and before the transition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalTime {
+    pub kind: LocalTimeKind,
+    pub earlier: Timestamp,
+    pub later: Timestamp,
+}
+
+/// Classify `civil` in `tz`.
+pub fn local_time(civil: DateTime, tz: &TimeZone) -> Result<LocalTime, String> {
+    let ambiguous = tz.to_ambiguous_zoned(civil);
+    let kind = match ambiguous.offset() {
+        AmbiguousOffset::Unambiguous { .. } => LocalTimeKind::Unique,
+        AmbiguousOffset::Gap { .. } => LocalTimeKind::Gap,
+        AmbiguousOffset::Fold { .. } => LocalTimeKind::Fold,
+    };
+    let out_of_range = |e: jiff::Error| format!("Local time {} is out of range: {}", civil, e);
+    Ok(LocalTime {
+        kind,
+        earlier: ambiguous
+            .clone()
+            .earlier()
+            .map_err(out_of_range)?
+            .timestamp(),
+        later: ambiguous.later().map_err(out_of_range)?.timestamp(),
+    })
+}
+
+/// Resolve `civil` in `tz` to one instant, following `disambiguation` when the time
+/// falls in a gap or fold.
+pub fn resolve_local(
+    civil: DateTime,
+    tz: &TimeZone,
+    disambiguation: Disambiguation,
+) -> Result<Timestamp, String> {
+    let local = local_time(civil, tz)?;
+    Ok(match (local.kind, disambiguation) {
+        (LocalTimeKind::Unique, _) => local.earlier,
+        (LocalTimeKind::Gap, Disambiguation::Reject) => {
+            return Err(format!(
+                "{} does not exist in {} (skipped by a DST gap)",
+                civil,
+                zone_name(tz)
+            ))
+        }
+        (LocalTimeKind::Fold, Disambiguation::Reject) => {
+            return Err(format!(
+                "{} is ambiguous in {} (repeated by a DST fold)",
+                civil,
+                zone_name(tz)
+            ))
+        }
+        // Moving forward by the gap's length is reading the time with the earlier offset
+        (LocalTimeKind::Gap, Disambiguation::Compatible | Disambiguation::Later) => local.later,
+        (LocalTimeKind::Fold, Disambiguation::Compatible | Disambiguation::Earlier) => {
+            local.earlier
+        }
+        (_, Disambiguation::Earlier) => local.earlier,
+        (_, Disambiguation::Later) => local.later,
+    })
+}
+
+/// How the wall clock moves at a transition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransitionKind {
+    /// Clocks jump forward; the skipped local times do not exist
+    Gap,
+    /// Clocks fall back; the repeated local times occur twice
+    Fold,
+    /// Only the abbreviation or DST flag changes
+    Unchanged,
+}
+
+impl TransitionKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            TransitionKind::Gap => "gap",
+            TransitionKind::Fold => "fold",
+            TransitionKind::Unchanged => "none",
+        }
+    }
+}
+
+/// One change of a zone's UTC offset or abbreviation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transition {
+    pub timestamp: Timestamp,
+    pub offset_before: Offset,
+    pub offset_after: Offset,
+    pub abbreviation_before: String,
+    pub abbreviation_after: String,
+    /// Whether the new offset is daylight saving time
+    pub dst: bool,
+}
+
+impl Transition {
+    fn at(tz: &TimeZone, timestamp: Timestamp) -> Self {
+        let before = tz.to_offset_info(timestamp.checked_sub(1.nanosecond()).unwrap_or(timestamp));
+        let after = tz.to_offset_info(timestamp);
+        Transition {
+            timestamp,
+            offset_before: before.offset(),
+            offset_after: after.offset(),
+            abbreviation_before: before.abbreviation().to_string(),
+            abbreviation_after: after.abbreviation().to_string(),
+            dst: after.dst().is_dst(),
+        }
+    }
+
+    /// Serialize one transition as a JSON object.
+    ///
+    /// `localBefore`/`localAfter` show the wall clock just as the transition happens on
+    /// each side, e.g. `02:00` jumping to `03:00` for a spring-forward gap.
+    pub fn to_json(&self) -> String {
+        format!(
+            r#"{{"iso":"{}","unixTimestamp":{},"kind":"{}","offsetBefore":"{}","offsetAfter":"{}","shiftSeconds":{},"abbreviationBefore":"{}","abbreviationAfter":"{}","dst":{},"localBefore":"{}","localAfter":"{}"}}"#,
+            self.timestamp,
+            self.timestamp.as_second(),
+            self.kind().name(),
+            format_offset(self.offset_before),
+            format_offset(self.offset_after),
+            self.offset_after.seconds() - self.offset_before.seconds(),
+            json_escape(&self.abbreviation_before),
+            json_escape(&self.abbreviation_after),
+            self.dst,
+            self.offset_before.to_datetime(self.timestamp),
+            self.offset_after.to_datetime(self.timestamp)
+        )
+    }
+
+    pub fn kind(&self) -> TransitionKind {
+        match self
+            .offset_after
+            .seconds()
+            .cmp(&self.offset_before.seconds())
+        {
+            std::cmp::Ordering::Greater => TransitionKind::Gap,
+            std::cmp::Ordering::Less => TransitionKind::Fold,
+            std::cmp::Ordering::Equal => TransitionKind::Unchanged,
+        }
+    }
+}
+
+/// Transitions of `tz` in `[start, end)`, oldest first. Fixed-offset zones have none.
+pub fn transitions_between(tz: &TimeZone, start: Timestamp, end: Timestamp) -> Vec<Transition> {
+    let from = start.checked_sub(1.nanosecond()).unwrap_or(start);
+    tz.following(from)
+        .map(|t| t.timestamp())
+        .skip_while(|ts| *ts < start)
+        .take_while(|ts| *ts < end)
+        .map(|ts| Transition::at(tz, ts))
+        .collect()
+}
+
+/// The first transition of `tz` strictly after `after`.
+pub fn next_transition(tz: &TimeZone, after: Timestamp) -> Option<Transition> {
+    tz.following(after)
+        .map(|t| t.timestamp())
+        .find(|ts| *ts > after)
+        .map(|ts| Transition::at(tz, ts))
+}
+
+/// A zone's transitions, serialized for the Wasm boundary.
+#[derive(Debug, PartialEq)]
+pub struct ZoneTransitions {
+    pub zone: TimeZone,
+    pub transitions: Vec<Transition>,
+}
+
+impl ZoneTransitions {
+    /// Serialize to JSON string for Wasm boundary (manual serialization).
+    pub fn to_json(&self) -> String {
+        let transitions: Vec<String> = self.transitions.iter().map(Transition::to_json).collect();
+        format!(
+            r#"{{"zone":"{}","transitions":[{}]}}"#,
+            json_escape(&zone_name(&self.zone)),
+            transitions.join(",")
+        )
+    }
+}
+
+/// One instant shown on the wall clocks of several zones.
+#[derive(Debug, PartialEq)]
+pub struct WorldClock {
+    pub timestamp: Timestamp,
+    /// Zone the day differences are counted from
+    pub reference: TimeZone,
+    /// Each requested zone name with the instant in that zone, in request order
+    pub clocks: Vec<(String, Zoned)>,
+}
+
+impl WorldClock {
+    /// Show `timestamp` in each of `zones` (names accepted by `resolve_zone`).
+    pub fn new(
+        timestamp: Timestamp,
+        zones: &[String],
+        reference: TimeZone,
+    ) -> Result<Self, String> {
+        let clocks = zones
+            .iter()
+            .map(|name| {
+                Ok((
+                    name.trim().to_string(),
+                    timestamp.to_zoned(resolve_zone(name)?),
+                ))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(WorldClock {
+            timestamp,
+            reference,
+            clocks,
+        })
+    }
+
+    /// Calendar days between the reference zone's date and `zoned`'s date, e.g. `1`
+    /// when it is already tomorrow there.
+    pub fn day_difference(&self, zoned: &Zoned) -> i32 {
+        let reference = self.timestamp.to_zoned(self.reference.clone()).date();
+        reference
+            .until(zoned.date())
+            .map(|span| span.get_days())
+            .unwrap_or(0)
+    }
+
+    /// Serialize to JSON string for Wasm boundary (manual serialization).
+    ///
+    /// `dayDifference` counts calendar days from the reference zone's date and
+    /// `dayMarker` is its display form (`"+1 day"`, `"-1 day"`, `""` on the same day).
+    pub fn to_json(&self) -> String {
+        let clocks: Vec<String> = self
+            .clocks
+            .iter()
+            .map(|(name, zoned)| {
+                let info = zoned.time_zone().to_offset_info(self.timestamp);
+                let days = self.day_difference(zoned);
+                let marker = match days {
+                    0 => String::new(),
+                    1 | -1 => format!("{:+} day", days),
+                    _ => format!("{:+} days", days),
+                };
+                format!(
+                    r#"{{"zone":"{}","local":"{}","date":"{}","time":"{}","weekday":"{}","offset":"{}","abbreviation":"{}","dst":{},"dayDifference":{},"dayMarker":"{}"}}"#,
+                    json_escape(name),
+                    zoned.datetime(),
+                    zoned.date(),
+                    zoned.time(),
+                    zoned.strftime("%a"),
+                    format_offset(zoned.offset()),
+                    json_escape(info.abbreviation()),
+                    info.dst().is_dst(),
+                    days,
+                    marker
+                )
+            })
+            .collect();
+        format!(
+            r#"{{"iso":"{}","unixTimestamp":{},"reference":"{}","clocks":[{}]}}"#,
+            self.timestamp,
+            self.timestamp.as_second(),
+            json_escape(&zone_name(&self.reference)),
+            clocks.join(",")
+        )
+    }
+}
+
+/// Offset transitions of IANA `zone` in `[start, end)`.
+///
+/// Either end may be a bare year (e.g., `"2024"`), which covers that whole calendar
+/// year on the zone's wall clock: `("2024", "")` is one year and `("2024", "2026")`
+/// three. Otherwise `end` defaults to one year after `start`.
+pub fn zone_transitions(zone: &str, start: &str, end: &str) -> Result<ZoneTransitions, String> {
+    let tz = resolve_zone(zone)?;
+    let (start, end) = match (year_window(start, &tz)?, year_window(end, &tz)?) {
+        (None, None) => parse_window(start, end, &tz)?,
+        (Some(year), None) if end.trim().is_empty() => year,
+        (start_year, end_year) => {
+            let start = match start_year {
+                Some((first, _)) => first,
+                None => parse_start_date(start)?,
+            };
+            let end = match end_year {
+                Some((_, next)) => next,
+                None => parse_start_date(end)?,
+            };
+            if end <= start {
+                return Err("Window end must be after its start".to_string());
+            }
+            (start, end)
+        }
+    };
+    Ok(ZoneTransitions {
+        transitions: transitions_between(&tz, start, end),
+        zone: tz,
+    })
+}
+
+/// The start of a bare four-digit `year` on the wall clock of `tz` and the start of the
+/// next one; `None` when `text` is not a bare year.
+fn year_window(
+    text: &str,
+    tz: &jiff::tz::TimeZone,
+) -> Result<Option<(Timestamp, Timestamp)>, String> {
+    let year = text.trim();
+    if year.len() != 4 || !year.bytes().all(|b| b.is_ascii_digit()) {
+        return Ok(None);
+    }
+    let year: i16 = year
+        .parse()
+        .map_err(|_| format!("Invalid year: {}", year))?;
+    let first = jiff::civil::date(year, 1, 1)
+        .to_zoned(tz.clone())
+        .map_err(|e| format!("Invalid year: {}", e))?;
+    let next = first
+        .checked_add(1.year())
+        .map_err(|e| format!("Window end out of range: {}", e))?;
+    Ok(Some((first.timestamp(), next.timestamp())))
+}
+
+/// The next offset transition of IANA `zone` strictly after `after` (now if empty).
+///
+/// The result holds at most one transition; it is empty when the zone has no
+/// further changes scheduled.
+pub fn next_zone_transition(zone: &str, after: &str) -> Result<ZoneTransitions, String> {
+    let tz = resolve_zone(zone)?;
+    let after = if after.trim().is_empty() {
+        Timestamp::now()
+    } else {
+        parse_start_date(after)?
+    };
+    Ok(ZoneTransitions {
+        transitions: next_transition(&tz, after).into_iter().collect(),
+        zone: tz,
+    })
+}
+
+/// Show one instant in every zone of `zones_json` (a JSON array of zone names).
+///
+/// `options_json` is an optional JSON object: `reference`, the zone a civil `instant`
+/// is read in and day differences are counted from (UTC by default), and
+/// `disambiguation` as for `calculate_in_zone`.
+pub fn world_clock(
+    instant: &str,
+    zones_json: &str,
+    options_json: &str,
+) -> Result<WorldClock, String> {
+    let zones = parse_string_array(zones_json)?;
+    if zones.is_empty() {
+        return Err("At least one zone is needed".to_string());
+    }
+    let reference = match extract_string_field(options_json, "reference") {
+        Some(zone) => resolve_zone(&zone)?,
+        None => jiff::tz::TimeZone::UTC,
+    };
+    let disambiguation = match extract_string_field(options_json, "disambiguation") {
+        Some(name) => Disambiguation::from_name(&name)?,
+        None => Disambiguation::default(),
+    };
+    let timestamp = parse_start_date_in(instant, &reference, disambiguation)?;
+    WorldClock::new(timestamp, &zones, reference)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_utc_aliases() {
+        for name in ["", "UTC", "utc", "Z"] {
+            assert_eq!(resolve_zone(name).unwrap(), TimeZone::UTC);
+        }
+    }
+
+    #[test]
+    fn test_resolve_fixed_offsets() {
+        let tz = resolve_zone("+05:30").unwrap();
+        assert_eq!(tz.to_fixed_offset().unwrap().seconds(), 19800);
+        let tz = resolve_zone("-0800").unwrap();
+        assert_eq!(tz.to_fixed_offset().unwrap().seconds(), -28800);
+        let tz = resolve_zone("+09").unwrap();
+        assert_eq!(tz.to_fixed_offset().unwrap().seconds(), 32400);
+    }
+
+    #[test]
+    fn test_resolve_iana_zone() {
+        let tz = resolve_zone("America/New_York").unwrap();
+        assert_eq!(zone_name(&tz), "America/New_York");
+    }
+
+    #[test]
+    fn test_unknown_zone() {
+        let err = resolve_zone("Mars/Olympus_Mons").unwrap_err();
+        assert!(err.contains("Unknown time zone"));
+    }
+
+    #[test]
+    fn test_out_of_range_offset_notation() {
+        assert!(resolve_zone("UTC+15").unwrap_err().contains("out of range"));
+        assert!(resolve_zone("GMT-25").unwrap_err().contains("out of range"));
+        let tz = resolve_zone("UTC+14").unwrap();
+        assert_eq!(tz.to_fixed_offset().unwrap().seconds(), 50_400);
+    }
+
+    #[test]
+    fn test_parse_fixed_offset_rejects_garbage() {
+        assert!(parse_fixed_offset("05:30").is_none());
+        assert!(parse_fixed_offset("+5:3x").is_none());
+        assert!(parse_fixed_offset("+12345").is_none());
+        assert!(parse_fixed_offset("+05:75").is_none());
+        assert!(parse_fixed_offset("+12:3").is_none());
+        assert!(parse_fixed_offset("+1:23").is_none());
+        assert!(parse_fixed_offset("+0:5:30").is_none());
+        assert!(parse_fixed_offset("+530").is_none());
+        assert!(parse_fixed_offset("+5").is_none());
+        assert_eq!(parse_short_offset("+5:30").unwrap().seconds(), 19800);
+        assert_eq!(parse_short_offset("-3").unwrap().seconds(), -10800);
+        assert!(parse_short_offset("+530").is_none());
+    }
+
+    #[test]
+    fn test_transitions_in_year() {
+        let tz = resolve_zone("America/New_York").unwrap();
+        let start: Timestamp = "2024-01-01T05:00:00Z".parse().unwrap();
+        let end: Timestamp = "2025-01-01T05:00:00Z".parse().unwrap();
+        let found = transitions_between(&tz, start, end);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].timestamp.to_string(), "2024-03-10T07:00:00Z");
+        assert_eq!(found[0].kind(), TransitionKind::Gap);
+        assert_eq!(
+            (
+                found[0].abbreviation_before.as_str(),
+                found[0].abbreviation_after.as_str()
+            ),
+            ("EST", "EDT")
+        );
+        assert!(found[0].dst);
+        assert_eq!(found[1].kind(), TransitionKind::Fold);
+    }
+
+    #[test]
+    fn test_transition_at_window_start_is_included() {
+        let tz = resolve_zone("Europe/Berlin").unwrap();
+        let at: Timestamp = "2024-03-31T01:00:00Z".parse().unwrap();
+        let found = transitions_between(&tz, at, at.checked_add(1.hour()).unwrap());
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            next_transition(&tz, at).unwrap().timestamp.to_string(),
+            "2024-10-27T01:00:00Z"
+        );
+    }
+
+    #[test]
+    fn test_fixed_offset_has_no_transitions() {
+        let tz = resolve_zone("+05:30").unwrap();
+        assert!(next_transition(&tz, Timestamp::UNIX_EPOCH).is_none());
+    }
+
+    #[test]
+    fn test_local_time_gap_and_fold() {
+        let tz = resolve_zone("America/New_York").unwrap();
+        let gap = local_time(jiff::civil::datetime(2024, 3, 10, 2, 30, 0, 0), &tz).unwrap();
+        assert_eq!(gap.kind, LocalTimeKind::Gap);
+        assert_eq!(gap.earlier.to_string(), "2024-03-10T06:30:00Z");
+        assert_eq!(gap.later.to_string(), "2024-03-10T07:30:00Z");
+        let fold = jiff::civil::datetime(2024, 11, 3, 1, 30, 0, 0);
+        assert_eq!(local_time(fold, &tz).unwrap().kind, LocalTimeKind::Fold);
+        assert_eq!(
+            resolve_local(fold, &tz, Disambiguation::Later)
+                .unwrap()
+                .to_string(),
+            "2024-11-03T06:30:00Z"
+        );
+        assert!(resolve_local(fold, &tz, Disambiguation::Reject)
+            .unwrap_err()
+            .contains("ambiguous"));
+    }
+
+    #[test]
+    fn test_compatible_matches_jiff() {
+        let tz = resolve_zone("Europe/Berlin").unwrap();
+        for civil in [
+            jiff::civil::datetime(2024, 3, 31, 2, 30, 0, 0),
+            jiff::civil::datetime(2024, 10, 27, 2, 30, 0, 0),
+            jiff::civil::datetime(2024, 7, 1, 12, 0, 0, 0),
+        ] {
+            assert_eq!(
+                resolve_local(civil, &tz, Disambiguation::Compatible).unwrap(),
+                civil.to_zoned(tz.clone()).unwrap().timestamp()
+            );
+        }
+        assert!(Disambiguation::from_name("latest").is_err());
+    }
+
+    #[test]
+    fn test_world_clock_day_difference() {
+        let ts: Timestamp = "2024-06-01T22:30:00Z".parse().unwrap();
+        let zones = ["Asia/Tokyo", "UTC", "America/Los_Angeles"].map(String::from);
+        let clock = WorldClock::new(ts, &zones, TimeZone::UTC).unwrap();
+        let days: Vec<i32> = clock
+            .clocks
+            .iter()
+            .map(|(_, z)| clock.day_difference(z))
+            .collect();
+        assert_eq!(days, vec![1, 0, 0]);
+        let clock = WorldClock::new(ts, &zones, resolve_zone("Asia/Tokyo").unwrap()).unwrap();
+        let days: Vec<i32> = clock
+            .clocks
+            .iter()
+            .map(|(_, z)| clock.day_difference(z))
+            .collect();
+        assert_eq!(days, vec![0, -1, -1]);
+    }
+}
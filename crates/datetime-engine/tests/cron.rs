@@ -0,0 +1,446 @@
+//! Cron schedule tests for the datetime-engine crate.
+//!
+//! Tests cover:
+//! - Next/previous run listing through `cron::cron_runs`
+//! - Agreement between the field-jumping search and a brute-force minute scan
+//! - Error reporting for malformed expressions
+//! - Wall-clock evaluation in IANA zones across DST transitions
+//! - Quartz, AWS EventBridge, Jenkins and systemd OnCalendar schedules
+//! - Plain-English explanations
+//! - Collision and interval analysis over a window
+
+use datetime_engine::analysis;
+use datetime_engine::cron::{self, CronSchedule, DstPolicy};
+use datetime_engine::explain;
+use datetime_engine::oncalendar;
+use jiff::civil::DateTime;
+use jiff::tz::TimeZone;
+use jiff::{Timestamp, ToSpan};
+
+// ============================================================================
+// Run Listing
+// ============================================================================
+
+#[test]
+fn test_next_runs_weekdays() {
+    let result = cron::cron_runs("0 9 * * MON-FRI", "2024-07-12T10:00:00Z", 3, false, "").unwrap();
+    let iso: Vec<String> = result
+        .runs
+        .iter()
+        .map(|run| run.timestamp.to_string())
+        .collect();
+    assert_eq!(
+        iso,
+        vec![
+            "2024-07-15T09:00:00Z",
+            "2024-07-16T09:00:00Z",
+            "2024-07-17T09:00:00Z"
+        ]
+    );
+}
+
+#[test]
+fn test_previous_runs_most_recent_first() {
+    let result = cron::cron_runs("@monthly", "2024-03-15", 3, true, "").unwrap();
+    let iso: Vec<String> = result
+        .runs
+        .iter()
+        .map(|run| run.timestamp.to_string())
+        .collect();
+    assert_eq!(
+        iso,
+        vec![
+            "2024-03-01T00:00:00Z",
+            "2024-02-01T00:00:00Z",
+            "2024-01-01T00:00:00Z"
+        ]
+    );
+}
+
+#[test]
+fn test_impossible_schedule_returns_no_runs() {
+    let result = cron::cron_runs("0 0 30 2 *", "2024-01-01", 5, false, "").unwrap();
+    assert!(result.runs.is_empty());
+}
+
+#[test]
+fn test_run_count_is_capped() {
+    let result = cron::cron_runs("* * * * *", "2024-01-01", 1_000_000, false, "").unwrap();
+    assert_eq!(result.runs.len(), 1000);
+}
+
+#[test]
+fn test_invalid_expression_errors() {
+    let err = cron::cron_runs("0 25 * * *", "2024-01-01", 1, false, "").unwrap_err();
+    assert!(err.contains("hour"));
+}
+
+// ============================================================================
+// Brute-force Agreement
+// ============================================================================
+
+/// Scan minute by minute, as the old TS debugger did.
+fn brute_force_next(schedule: &CronSchedule, after: DateTime) -> DateTime {
+    let mut dt = after.checked_add(1.minute()).unwrap();
+    loop {
+        if schedule.matches(dt) {
+            return dt;
+        }
+        dt = dt.checked_add(1.minute()).unwrap();
+    }
+}
+
+#[test]
+fn test_search_matches_brute_force() {
+    let expressions = [
+        "*/7 * * * *",
+        "15,45 9-17 * * 1-5",
+        "0 0 13 * FRI",
+        "30 4 1,15 * 5",
+        "0 22 * * SUN",
+        "5/20 */3 1 */2 *",
+    ];
+    for expression in expressions {
+        let schedule = CronSchedule::parse(expression).unwrap();
+        let mut forward: DateTime = "2024-02-27T23:50:00".parse().unwrap();
+        for _ in 0..50 {
+            let expected = brute_force_next(&schedule, forward);
+            assert_eq!(
+                schedule.next_civil(forward),
+                Some(expected),
+                "{}",
+                expression
+            );
+            assert_eq!(
+                schedule.previous_civil(expected.checked_add(1.second()).unwrap()),
+                Some(expected),
+                "{}",
+                expression
+            );
+            forward = expected;
+        }
+    }
+}
+
+// ============================================================================
+// Zones and DST
+// ============================================================================
+
+#[test]
+fn test_hourly_job_follows_real_time_across_dst() {
+    let zone = TimeZone::get("Europe/Berlin").unwrap();
+    let schedule = CronSchedule::parse("0 * * * *").unwrap();
+    let start: Timestamp = "2023-12-31T23:00:00Z".parse().unwrap();
+    let runs = schedule.next_runs(start, 8784, &zone, DstPolicy::Vixie);
+    assert_eq!(runs.len(), 8784);
+    for pair in runs.windows(2) {
+        assert_eq!(
+            pair[1].timestamp.as_second() - pair[0].timestamp.as_second(),
+            3600
+        );
+    }
+}
+
+#[test]
+fn test_fold_flags_in_json() {
+    let result = cron::cron_runs(
+        "30 1 * * *",
+        "2024-11-02T12:00:00Z",
+        3,
+        false,
+        r#"{"zone":"America/Chicago","dstPolicy":"kubernetes"}"#,
+    )
+    .unwrap();
+    let json = result.to_json();
+    assert!(
+        json.contains(r#""local":"2024-11-03T01:30:00-05:00[America/Chicago]","dst":"foldFirst""#)
+    );
+    assert!(
+        json.contains(r#""local":"2024-11-03T01:30:00-06:00[America/Chicago]","dst":"foldSecond""#)
+    );
+}
+
+#[test]
+fn test_unknown_dst_policy_errors() {
+    let err = cron::cron_runs(
+        "0 0 * * *",
+        "2024-01-01",
+        1,
+        false,
+        r#"{"dstPolicy":"cronie"}"#,
+    )
+    .unwrap_err();
+    assert!(err.contains("DST policy"));
+}
+
+// ============================================================================
+// Dialects
+// ============================================================================
+
+fn next_iso(expression: &str, start: &str, count: u32, options: &str) -> Vec<String> {
+    cron::cron_runs(expression, start, count, false, options)
+        .unwrap()
+        .runs
+        .iter()
+        .map(|run| run.timestamp.to_string())
+        .collect()
+}
+
+#[test]
+fn test_quartz_seconds_and_last_friday() {
+    assert_eq!(
+        next_iso("30 0 18 ? * 6L", "2024-07-01", 2, r#"{"dialect":"quartz"}"#),
+        vec!["2024-07-26T18:00:30Z", "2024-08-30T18:00:30Z"]
+    );
+}
+
+#[test]
+fn test_quartz_every_second_in_zone() {
+    assert_eq!(
+        next_iso(
+            "* * * * * ?",
+            "2024-07-01T12:00:00Z",
+            3,
+            r#"{"dialect":"quartz","zone":"America/New_York"}"#
+        ),
+        vec![
+            "2024-07-01T12:00:01Z",
+            "2024-07-01T12:00:02Z",
+            "2024-07-01T12:00:03Z"
+        ]
+    );
+}
+
+#[test]
+fn test_quartz_nearest_weekday_and_nth() {
+    // 2024-06-15 is a Saturday, so 15W is Friday the 14th.
+    assert_eq!(
+        next_iso("0 0 9 15W * ?", "2024-06-01", 1, r#"{"dialect":"quartz"}"#),
+        vec!["2024-06-14T09:00:00Z"]
+    );
+    // Thanksgiving: fourth Thursday of November
+    assert_eq!(
+        next_iso(
+            "0 0 12 ? NOV THU#4 2024-2025",
+            "2024-01-01",
+            3,
+            r#"{"dialect":"quartz"}"#
+        ),
+        vec!["2024-11-28T12:00:00Z", "2025-11-27T12:00:00Z"]
+    );
+}
+
+#[test]
+fn test_aws_cron_and_rate() {
+    assert_eq!(
+        next_iso(
+            "cron(0 18 L * ? *)",
+            "2024-02-01",
+            2,
+            r#"{"dialect":"aws"}"#
+        ),
+        vec!["2024-02-29T18:00:00Z", "2024-03-31T18:00:00Z"]
+    );
+    assert_eq!(
+        next_iso(
+            "rate(5 minutes)",
+            "2024-02-01T10:02:00Z",
+            2,
+            r#"{"dialect":"aws"}"#
+        ),
+        vec!["2024-02-01T10:07:00Z", "2024-02-01T10:12:00Z"]
+    );
+}
+
+#[test]
+fn test_jenkins_hash_is_stable_per_seed() {
+    let options = r#"{"dialect":"jenkins","seed":"nightly-build"}"#;
+    let first = next_iso("H H * * *", "2024-01-01", 3, options);
+    assert_eq!(first, next_iso("@daily", "2024-01-01", 3, options));
+    assert_eq!(first, next_iso("H H * * *", "2024-01-01", 3, options));
+    let other = next_iso(
+        "H H * * *",
+        "2024-01-01",
+        3,
+        r#"{"dialect":"jenkins","seed":"deploy"}"#,
+    );
+    assert_ne!(first, other);
+}
+
+#[test]
+fn test_rare_dates_within_search_horizon() {
+    // Jenkins requires both day fields; 2100 is not a leap year, so the next
+    // Monday, February 29 after 2072 is 40 years away.
+    let options = r#"{"dialect":"jenkins"}"#;
+    assert_eq!(
+        next_iso("0 0 29 2 1", "2072-03-01", 1, options),
+        vec!["2112-02-29T00:00:00Z"]
+    );
+    let previous = cron::cron_runs("0 0 29 2 1", "2112-02-01", 1, true, options).unwrap();
+    assert_eq!(
+        previous.runs[0].timestamp.to_string(),
+        "2072-02-29T00:00:00Z"
+    );
+}
+
+#[test]
+fn test_dialect_errors_name_the_construct() {
+    let err = |expression: &str, options: &str| {
+        cron::cron_runs(expression, "2024-01-01", 1, false, options).unwrap_err()
+    };
+    assert!(err("0 0 L * *", "").contains("only valid in Quartz and AWS"));
+    assert!(err("0 0 ? * *", "").contains("'?' is not valid in standard cron"));
+    assert!(err("H * * * *", "").contains("only valid in Jenkins"));
+    assert!(err("rate(5 minutes)", "").contains("only valid in AWS"));
+    assert!(err("0 0 12 * * MON", r#"{"dialect":"quartz"}"#).contains("exactly one"));
+    assert!(err("rate(1 minutes)", r#"{"dialect":"aws"}"#).contains("rate(1 minute)"));
+    assert!(err("@daily", r#"{"dialect":"aws"}"#).contains("not valid in AWS"));
+    assert!(err("0 12 * * ?", r#"{"dialect":"aws"}"#).contains("6 fields"));
+}
+
+// ============================================================================
+// systemd OnCalendar
+// ============================================================================
+
+#[test]
+fn test_oncalendar_uses_option_zone_as_local() {
+    let result = oncalendar::oncalendar_runs(
+        "Mon..Fri 09:00",
+        "2024-07-12T14:00:00Z",
+        1,
+        r#"{"zone":"America/New_York"}"#,
+    )
+    .unwrap();
+    assert_eq!(result.normalized, "Mon..Fri *-*-* 09:00:00");
+    assert_eq!(result.runs[0].timestamp.to_string(), "2024-07-15T13:00:00Z");
+    assert!(result
+        .to_json()
+        .contains(r#""display":"Mon 2024-07-15 09:00:00 EDT""#));
+}
+
+#[test]
+fn test_oncalendar_expression_zone_wins() {
+    let result = oncalendar::oncalendar_runs(
+        "quarterly Asia/Tokyo",
+        "2024-05-01",
+        1,
+        r#"{"zone":"America/New_York"}"#,
+    )
+    .unwrap();
+    assert_eq!(result.runs[0].timestamp.to_string(), "2024-06-30T15:00:00Z");
+}
+
+#[test]
+fn test_oncalendar_dst_follows_systemd() {
+    let elapses = |expression: &str, start: &str, count: u32| {
+        oncalendar::oncalendar_runs(expression, start, count, r#"{"zone":"America/New_York"}"#)
+            .unwrap()
+            .runs
+            .iter()
+            .map(|run| run.timestamp.to_string())
+            .collect::<Vec<_>>()
+    };
+    // 02:30 does not exist on 2024-03-10, so that day is skipped rather than shifted.
+    assert_eq!(
+        elapses("*-*-* 02:30:00", "2024-03-09T12:00:00Z", 2),
+        vec!["2024-03-11T06:30:00Z", "2024-03-12T06:30:00Z"]
+    );
+    // 01:30 happens twice on 2024-11-03 but elapses once.
+    assert_eq!(
+        elapses("*-*-* 01:30:00", "2024-11-02T12:00:00Z", 2),
+        vec!["2024-11-03T05:30:00Z", "2024-11-04T06:30:00Z"]
+    );
+    assert_eq!(
+        elapses("*:*:*", "2024-07-01T12:00:00Z", 3),
+        vec![
+            "2024-07-01T12:00:01Z",
+            "2024-07-01T12:00:02Z",
+            "2024-07-01T12:00:03Z"
+        ]
+    );
+}
+
+// ============================================================================
+// Explanations
+// ============================================================================
+
+#[test]
+fn test_explain_across_dialects() {
+    let summary = |expression: &str, options: &str| {
+        explain::explain_schedule(expression, options)
+            .unwrap()
+            .summary
+    };
+    assert_eq!(
+        summary("30 9 * 3 1-5", ""),
+        "At 09:30 on every weekday in March"
+    );
+    assert_eq!(
+        summary("0 30 9 ? MAR MON-FRI", r#"{"dialect":"quartz"}"#),
+        "At 09:30 on every weekday in March"
+    );
+    assert_eq!(
+        summary("Mon..Fri *-03-* 09:30", r#"{"dialect":"systemd"}"#),
+        "At 09:30 on every weekday in March"
+    );
+    assert!(explain::explain_schedule("0 0 L * *", "").is_err());
+}
+
+// ============================================================================
+// Analysis
+// ============================================================================
+
+#[test]
+fn test_longest_gap_over_next_year() {
+    let analysis = analysis::analyze_schedule("0 3 1 */3 *", "2024-01-15", "", "").unwrap();
+    assert_eq!(analysis.end.to_string(), "2025-01-15T00:00:00Z");
+    assert_eq!(analysis.runs.len(), 4);
+    let (from, to) = analysis.longest_gap.unwrap();
+    // Oct -> Jan spans 92 days, the longest quarter
+    assert_eq!(from.to_string(), "2024-07-01T03:00:00Z");
+    assert_eq!(to.to_string(), "2024-10-01T03:00:00Z");
+    assert_eq!(analysis.runs_per_day.len(), 366);
+}
+
+#[test]
+fn test_runs_per_day_follow_local_dst_day() {
+    // The spring-forward day in Berlin has only 23 hourly runs.
+    let analysis = analysis::analyze_schedule(
+        "0 * * * *",
+        "2024-03-30T00:00:00+01:00",
+        "2024-04-02T00:00:00+02:00",
+        r#"{"zone":"Europe/Berlin"}"#,
+    )
+    .unwrap();
+    let counts: Vec<usize> = analysis.runs_per_day.iter().map(|(_, n)| *n).collect();
+    assert_eq!(counts, vec![24, 23, 24]);
+}
+
+#[test]
+fn test_collisions_json() {
+    let report = analysis::schedule_collisions(
+        r#"["30 2 * * *","*/30 * * * 0"]"#,
+        "2024-07-01",
+        "2024-07-15",
+        "",
+    )
+    .unwrap();
+    assert_eq!(report.collision_count, 2);
+    let json = report.to_json();
+    assert!(json.contains(r#""collisionCount":2"#));
+    assert!(json.contains(r#"{"iso":"2024-07-07T02:30:00Z","unixTimestamp":1720319400,"local":"2024-07-07T02:30:00+00:00[UTC]","schedules":[0,1]}"#));
+}
+
+#[test]
+fn test_analysis_errors() {
+    assert!(
+        analysis::schedule_collisions(r#"["0 * * * *"]"#, "2024-01-01", "", "")
+            .unwrap_err()
+            .contains("At least two")
+    );
+    assert!(
+        analysis::analyze_schedule("0 * * * *", "2024-02-01", "2024-01-01", "")
+            .unwrap_err()
+            .contains("after its start")
+    );
+}
@@ -0,0 +1,168 @@
+//! Prefixed encoding tests for the datetime-engine crate: Windows times and
+//! spreadsheet serials.
+//!
+//! Tests cover:
+//! - Each encoding as an output format, in UTC and in another zone
+//! - Each encoding, under every prefix, as calculate and validate input
+//! - Wall-clock encodings falling in a DST gap
+//!
+//! Checks particular to one family live in `windows.rs` and `spreadsheet.rs`.
+
+use datetime_engine::calc;
+use datetime_engine::validate;
+
+/// The instant every encoding below stands for.
+const INSTANT: &str = "2024-07-11T01:14:00Z";
+
+struct Encoding {
+    /// Output format name
+    format: &'static str,
+    /// Input prefixes, the format name's spelling first
+    prefixes: &'static [&'static str],
+    /// `INSTANT` in UTC
+    utc: &'static str,
+    /// `INSTANT` in Europe/Berlin; differs from `utc` for wall-clock encodings
+    berlin: &'static str,
+}
+
+impl Encoding {
+    fn is_wall_clock(&self) -> bool {
+        self.utc != self.berlin
+    }
+}
+
+const ENCODINGS: &[Encoding] = &[
+    Encoding {
+        format: "fileTime",
+        prefixes: &["filetime", "fileTime"],
+        utc: "0x01DAD32F9C4BFC00",
+        berlin: "0x01DAD32F9C4BFC00",
+    },
+    Encoding {
+        format: "dotnetTicks",
+        prefixes: &["ticks"],
+        utc: "638562572400000000",
+        berlin: "638562572400000000",
+    },
+    Encoding {
+        format: "activeDirectory",
+        prefixes: &["ad", "AD"],
+        utc: "133651340400000000",
+        berlin: "133651340400000000",
+    },
+    Encoding {
+        format: "oleDate",
+        prefixes: &["ole"],
+        utc: "45484.0513888889",
+        berlin: "45484.1347222222",
+    },
+    Encoding {
+        format: "dosDateTime",
+        prefixes: &["dos"],
+        utc: "0x58EB09C0",
+        berlin: "0x58EB19C0",
+    },
+    Encoding {
+        format: "excel1900",
+        prefixes: &["excel1900", "Excel1900", "excel", "lotus"],
+        utc: "45484.0513888889",
+        berlin: "45484.1347222222",
+    },
+    Encoding {
+        format: "excel1904",
+        prefixes: &["excel1904"],
+        utc: "44022.0513888889",
+        berlin: "44022.1347222222",
+    },
+    Encoding {
+        format: "googleSheets",
+        prefixes: &["googleSheets", "sheets"],
+        utc: "45484.0513888889",
+        berlin: "45484.1347222222",
+    },
+];
+
+// ============================================================================
+// Output Formats
+// ============================================================================
+
+#[test]
+fn test_output_formats() {
+    for encoding in ENCODINGS {
+        let formats = format!(r#"["{}"]"#, encoding.format);
+        for (zone, expected) in [("UTC", encoding.utc), ("Europe/Berlin", encoding.berlin)] {
+            let result = calc::calculate_with_formats(INSTANT, "[]", &formats, zone).unwrap();
+            assert_eq!(
+                result.formats,
+                vec![(encoding.format.to_string(), Ok(expected.to_string()))],
+                "{} in {}",
+                encoding.format,
+                zone
+            );
+            assert!(result.to_json().contains(&format!(
+                r#""formats":{{"{}":"{}"}}"#,
+                encoding.format, expected
+            )));
+        }
+    }
+}
+
+// ============================================================================
+// Input Formats
+// ============================================================================
+
+#[test]
+fn test_prefixed_inputs() {
+    for encoding in ENCODINGS {
+        for prefix in encoding.prefixes {
+            let input = format!("{}:{}", prefix, encoding.utc);
+            assert_eq!(
+                calc::calculate(&input, "[]").unwrap().iso_8601,
+                INSTANT,
+                "{}",
+                input
+            );
+
+            let input = format!("{}:{}", prefix, encoding.berlin);
+            let result =
+                calc::calculate_in_zone(&input, "[]", r#"{"zone":"Europe/Berlin"}"#).unwrap();
+            assert_eq!(result.iso_8601, INSTANT, "{} in Europe/Berlin", input);
+        }
+    }
+}
+
+#[test]
+fn test_validate_prefixed_inputs() {
+    for encoding in ENCODINGS {
+        // Wall-clock encodings normalize to a civil datetime, the others to the instant
+        let normalized = if encoding.is_wall_clock() {
+            "2024-07-11T01:14:00"
+        } else {
+            INSTANT
+        };
+        let input = format!("{}:{}", encoding.prefixes[0], encoding.utc);
+        assert_eq!(
+            validate::validate(&input).to_json(),
+            format!(r#"{{"valid":true,"normalized":"{}"}}"#, normalized),
+            "{}",
+            input
+        );
+    }
+}
+
+#[test]
+fn test_validate_in_zone_sees_wall_clock_gap() {
+    // 2024-03-10 02:30 (serial 45361.1041666667) does not exist in New York
+    for input in [
+        "ole:45361.1041666667",
+        "excel:45361.1041666667",
+        "sheets:45361.1041666667",
+    ] {
+        assert_eq!(
+            validate::validate_in_zone(input, "America/New_York").to_json(),
+            r#"{"valid":true,"normalized":"2024-03-10T02:30:00","localTime":{"kind":"gap","earlier":"2024-03-10T06:30:00Z","later":"2024-03-10T07:30:00Z"}}"#,
+            "{}",
+            input
+        );
+    }
+}
@@ -0,0 +1,193 @@
+//! ID decoding tests for the datetime-engine crate.
+//!
+//! Tests cover:
+//! - Snowflake IDs with preset and custom layouts
+//! - Smallest Snowflake for an instant (range query bounds)
+//! - UUID v1/v6/v7, ULID, KSUID and ObjectId detection and decoding
+//! - Boundary IDs for an instant
+//! - JSON output and errors
+
+use datetime_engine::id;
+
+// ============================================================================
+// Snowflake IDs
+// ============================================================================
+
+#[test]
+fn test_twitter_id_with_alternatives() {
+    // Without a layout the ID is read as Twitter's, with the other presets alongside
+    let json = id::decode_snowflake("1212092628029698048", "")
+        .unwrap()
+        .to_json();
+    assert_eq!(
+        json,
+        r#"{"id":"1212092628029698048","layout":"twitter","iso":"2019-12-31T19:26:16.771Z","unixTimestamp":1577820376,"unixTimestampMs":1577820376771,"fields":{"datacenter":10,"worker":7,"sequence":0},"alternatives":[{"layout":"discord","iso":"2024-02-27T17:43:22.114Z","unixTimestampMs":1709055802114},{"layout":"instagram","iso":"2016-03-23T05:58:42.778Z","unixTimestampMs":1458712722778},{"layout":"sonyflake","iso":"2037-07-23T20:18:25.28Z","unixTimestampMs":2131993105280}]}"#
+    );
+}
+
+#[test]
+fn test_discord_id() {
+    let json = id::decode_snowflake("175928847299117063", r#"{"layout":"Discord"}"#)
+        .unwrap()
+        .to_json();
+    assert_eq!(
+        json,
+        r#"{"id":"175928847299117063","layout":"discord","iso":"2016-04-30T11:18:25.796Z","unixTimestamp":1462015105,"unixTimestampMs":1462015105796,"fields":{"worker":1,"process":0,"increment":7},"alternatives":[]}"#
+    );
+}
+
+#[test]
+fn test_custom_layout() {
+    // 10 ms ticks since 2020 over 40 bits, then an 8-bit worker and a 15-bit sequence
+    let options = r#"{"layout":"custom","epoch":"2020-01-01T00:00:00Z","timestampBits":40,"workerBits":8,"sequenceBits":15,"unitMs":10}"#;
+    let bound = id::snowflake_for_instant("2024-05-01T00:00:00Z", options).unwrap();
+    let json = id::decode_snowflake(&(bound.id + (3 << 15) + 9).to_string(), options)
+        .unwrap()
+        .to_json();
+    assert_eq!(
+        json,
+        r#"{"id":"114659520675938313","layout":"custom","iso":"2024-05-01T00:00:00Z","unixTimestamp":1714521600,"unixTimestampMs":1714521600000,"fields":{"worker":3,"sequence":9},"alternatives":[]}"#
+    );
+}
+
+#[test]
+fn test_range_query_bounds() {
+    let start = id::snowflake_for_instant("2024-01-01T00:00:00Z", r#"{"layout":"discord"}"#)
+        .unwrap()
+        .to_json();
+    assert_eq!(
+        start,
+        r#"{"layout":"discord","instant":"2024-01-01T00:00:00Z","id":"1191168914227200000"}"#
+    );
+}
+
+#[test]
+fn test_snowflake_errors() {
+    assert!(id::decode_snowflake("12ab", "")
+        .unwrap_err()
+        .contains("Invalid Snowflake ID"));
+    assert!(id::decode_snowflake("1", r#"{"layout":"flickr"}"#)
+        .unwrap_err()
+        .contains("Unknown Snowflake layout"));
+    assert!(id::decode_snowflake("1", r#"{"layout":"custom"}"#)
+        .unwrap_err()
+        .contains("epoch"));
+    assert!(
+        id::decode_snowflake("1", r#"{"layout":"custom","epoch":0,"workerBits":-1}"#)
+            .unwrap_err()
+            .contains("negative")
+    );
+    assert!(id::snowflake_for_instant("2009-01-01", "")
+        .unwrap_err()
+        .contains("before the twitter epoch"));
+}
+
+// ============================================================================
+// UUID, ULID, KSUID and ObjectId
+// ============================================================================
+
+#[test]
+fn test_uuid_v1_json() {
+    let json = id::decode_id("C232AB00-9414-11EC-B3C8-9F6BDECED846", "")
+        .unwrap()
+        .to_json();
+    assert_eq!(
+        json,
+        r#"{"kind":"uuid-v1","id":"c232ab00-9414-11ec-b3c8-9f6bdeced846","unixTimestampMs":1645557742000,"result":{"unixTimestamp":1645557742,"iso8601":"2022-02-22T19:22:22Z","rfc2822":"Tue, 22 Feb 2022 19:22:22 +0000","localHuman":"February 22, 2022 07:22:22 PM UTC"},"components":{"version":1,"clockSequence":13256,"node":"9f:6b:de:ce:d8:46","randomNode":true}}"#
+    );
+}
+
+#[test]
+fn test_uuid_v7_json() {
+    let json = id::decode_id("017f22e2-79b0-7cc3-98c4-dc0c0c07398f", "")
+        .unwrap()
+        .to_json();
+    assert_eq!(
+        json,
+        r#"{"kind":"uuid-v7","id":"017f22e2-79b0-7cc3-98c4-dc0c0c07398f","unixTimestampMs":1645557742000,"result":{"unixTimestamp":1645557742,"iso8601":"2022-02-22T19:22:22Z","rfc2822":"Tue, 22 Feb 2022 19:22:22 +0000","localHuman":"February 22, 2022 07:22:22 PM UTC"},"components":{"version":7,"randA":3267,"randB":"18c4dc0c0c07398f"}}"#
+    );
+}
+
+#[test]
+fn test_ulid_ksuid_and_object_id() {
+    let ulid = id::decode_id("01arz3ndektsv4rrffq69g5fav", "")
+        .unwrap()
+        .to_json();
+    assert_eq!(
+        ulid,
+        r#"{"kind":"ulid","id":"01ARZ3NDEKTSV4RRFFQ69G5FAV","unixTimestampMs":1469922850259,"result":{"unixTimestamp":1469922850,"iso8601":"2016-07-30T23:54:10.259Z","rfc2822":"Sat, 30 Jul 2016 23:54:10 +0000","localHuman":"July 30, 2016 11:54:10 PM UTC"},"components":{"randomness":"d6764c61efb99302bd5b"}}"#
+    );
+    let ksuid = id::decode_id("0ujtsYcgvSTl8PAuAdqWYSMnLOv", "")
+        .unwrap()
+        .to_json();
+    assert_eq!(
+        ksuid,
+        r#"{"kind":"ksuid","id":"0ujtsYcgvSTl8PAuAdqWYSMnLOv","unixTimestampMs":1507608047000,"result":{"unixTimestamp":1507608047,"iso8601":"2017-10-10T04:00:47Z","rfc2822":"Tue, 10 Oct 2017 04:00:47 +0000","localHuman":"October 10, 2017 04:00:47 AM UTC"},"components":{"payload":"b5a1cd34b5f99d1154fb6853345c9735"}}"#
+    );
+    let object_id = id::decode_id("507f1f77bcf86cd799439011", "")
+        .unwrap()
+        .to_json();
+    assert_eq!(
+        object_id,
+        r#"{"kind":"objectid","id":"507f1f77bcf86cd799439011","unixTimestampMs":1350508407000,"result":{"unixTimestamp":1350508407,"iso8601":"2012-10-17T21:13:27Z","rfc2822":"Wed, 17 Oct 2012 21:13:27 +0000","localHuman":"October 17, 2012 09:13:27 PM UTC"},"components":{"random":"bcf86cd799","counter":4427793}}"#
+    );
+}
+
+#[test]
+fn test_decimal_input_uses_snowflake_layout() {
+    let json = id::decode_id("175928847299117063", r#"{"layout":"discord"}"#)
+        .unwrap()
+        .to_json();
+    assert_eq!(
+        json,
+        r#"{"kind":"snowflake","id":"175928847299117063","unixTimestampMs":1462015105796,"result":{"unixTimestamp":1462015105,"iso8601":"2016-04-30T11:18:25.796Z","rfc2822":"Sat, 30 Apr 2016 11:18:25 +0000","localHuman":"April 30, 2016 11:18:25 AM UTC"},"components":{"layout":"discord","worker":1,"process":0,"increment":7}}"#
+    );
+}
+
+#[test]
+fn test_id_bounds() {
+    let json = id::id_bounds("2024-05-01T12:00:00Z", "").unwrap().to_json();
+    assert_eq!(
+        json,
+        r#"{"instant":"2024-05-01T12:00:00Z","bounds":[{"kind":"uuid-v1","min":"55fd2000-07b2-11ef-8000-000000000000","max":"55fd2000-07b2-11ef-bfff-ffffffffffff"},{"kind":"uuid-v6","min":"1ef07b25-5fd2-6000-8000-000000000000","max":"1ef07b25-5fd2-6000-bfff-ffffffffffff"},{"kind":"uuid-v7","min":"018f3406-9e00-7000-8000-000000000000","max":"018f3406-9e00-7fff-bfff-ffffffffffff"},{"kind":"ulid","min":"01HWT0D7G00000000000000000","max":"01HWT0D7G0ZZZZZZZZZZZZZZZZ"},{"kind":"ksuid","min":"2frgqLLEiIqu96n0T7ajGnNuM2S","max":"2frgqT8IkW7GEaSmDHNs3gVA3eZ"},{"kind":"objectid","min":"66322ec00000000000000000","max":"66322ec0ffffffffffffffff"},{"kind":"snowflake","min":"1785640309355446272","max":"1785640309359640575"}]}"#
+    );
+    let json = id::id_bounds("2012-01-01T00:00:00Z", r#"{"kind":"ulid"}"#)
+        .unwrap()
+        .to_json();
+    assert_eq!(
+        json,
+        r#"{"instant":"2012-01-01T00:00:00Z","bounds":[{"kind":"ulid","min":"016JB91M000000000000000000","max":"016JB91M00ZZZZZZZZZZZZZZZZ"}]}"#
+    );
+}
+
+#[test]
+fn test_id_errors() {
+    assert!(id::decode_id("f47ac10b-58cc-4372-a567-0e02b2c3d479", "")
+        .unwrap_err()
+        .contains("no timestamp"));
+    assert!(id::decode_id("c232ab00-9414-11ec-c3c8-9f6bdeced846", "")
+        .unwrap_err()
+        .contains("variant"));
+    assert!(id::decode_id("81ARZ3NDEKTSV4RRFFQ69G5FAV", "")
+        .unwrap_err()
+        .contains("128 bits"));
+    assert!(id::decode_id("zzzzzzzzzzzzzzzzzzzzzzzzzzz", "")
+        .unwrap_err()
+        .contains("160 bits"));
+    // Errors name the character typed, not one of its UTF-8 bytes
+    assert!(id::decode_id("01ARZ3NDEKTSV4RRFFQ69G5Fé", "")
+        .unwrap_err()
+        .contains("Invalid ULID character 'é'"));
+    assert!(id::decode_id("0ujtsYcgvSTl8PAuAdqWYSMnLé", "")
+        .unwrap_err()
+        .contains("Invalid KSUID character 'é'"));
+    assert!(id::decode_id("not-an-id", "")
+        .unwrap_err()
+        .contains("Unrecognized"));
+    assert!(id::id_bounds("2010-01-01", r#"{"kind":"ksuid"}"#)
+        .unwrap_err()
+        .contains("before the ksuid epoch"));
+    assert!(id::id_bounds("2024-01-01", r#"{"kind":"uuid4"}"#)
+        .unwrap_err()
+        .contains("Unknown ID kind"));
+}
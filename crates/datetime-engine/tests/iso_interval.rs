@@ -0,0 +1,175 @@
+//! ISO 8601 interval and interval algebra tests for the datetime-engine crate.
+//!
+//! Tests cover:
+//! - `start/end`, `start/duration` and `duration/end` bounds, including abbreviated ends
+//! - Repeating `Rn/...` and unbounded `R/...` expansion
+//! - Month-end clamping that matches `calc::calculate`
+//! - JSON output and errors
+//! - Union, intersection, subtraction, gaps and overlaps through `interval::interval_algebra`
+
+use datetime_engine::calc;
+use datetime_engine::interval;
+use datetime_engine::iso_interval;
+
+fn bounds(expression: &str, count: u32) -> Vec<(String, String)> {
+    iso_interval::iso_interval(expression, count)
+        .unwrap()
+        .intervals
+        .iter()
+        .map(|(start, end)| (start.to_string(), end.to_string()))
+        .collect()
+}
+
+fn pair(start: &str, end: &str) -> (String, String) {
+    (start.to_string(), end.to_string())
+}
+
+// ============================================================================
+// Plain Intervals
+// ============================================================================
+
+#[test]
+fn test_three_plain_forms_agree() {
+    let expected = vec![pair("2024-03-01T09:00:00Z", "2024-03-01T17:30:00Z")];
+    assert_eq!(bounds("2024-03-01T09:00Z/2024-03-01T17:30Z", 1), expected);
+    assert_eq!(bounds("2024-03-01T09:00Z/PT8H30M", 1), expected);
+    assert_eq!(bounds("PT8H30M/2024-03-01T17:30Z", 1), expected);
+    assert_eq!(bounds("2024-03-01T09:00Z/17:30", 1), expected);
+    assert_eq!(bounds("2024-03-01T09:00Z--2024-03-01T17:30Z", 1), expected);
+}
+
+#[test]
+fn test_abbreviated_date_end_keeps_offset() {
+    assert_eq!(
+        bounds("2024-02-10/15", 1),
+        vec![pair("2024-02-10T00:00:00Z", "2024-02-15T00:00:00Z")]
+    );
+    assert_eq!(
+        bounds("2024-02-10T09:00+01:00/17:00", 1),
+        vec![pair("2024-02-10T08:00:00Z", "2024-02-10T16:00:00Z")]
+    );
+}
+
+// ============================================================================
+// Repeating Intervals
+// ============================================================================
+
+#[test]
+fn test_monthly_repeat_clamps_like_calculate() {
+    let intervals = bounds("R4/2024-01-31T00:00Z/P1M", 100);
+    let starts: Vec<&str> = intervals.iter().map(|(s, _)| s.as_str()).collect();
+    assert_eq!(
+        starts,
+        vec![
+            "2024-01-31T00:00:00Z",
+            "2024-02-29T00:00:00Z",
+            "2024-03-31T00:00:00Z",
+            "2024-04-30T00:00:00Z"
+        ]
+    );
+    let calculated = calc::calculate(
+        "2024-01-31",
+        r#"[{"type":"add","unit":"months","value":3}]"#,
+    )
+    .unwrap();
+    assert_eq!(calculated.iso_8601, "2024-04-30T00:00:00Z");
+}
+
+#[test]
+fn test_repeat_backwards_from_end() {
+    assert_eq!(
+        bounds("R3/P1W/2024-03-04", 10),
+        vec![
+            pair("2024-02-12T00:00:00Z", "2024-02-19T00:00:00Z"),
+            pair("2024-02-19T00:00:00Z", "2024-02-26T00:00:00Z"),
+            pair("2024-02-26T00:00:00Z", "2024-03-04T00:00:00Z")
+        ]
+    );
+}
+
+#[test]
+fn test_unbounded_and_start_end_repeats() {
+    assert_eq!(bounds("R/2024-01-01/P1D", 7).len(), 7);
+    assert_eq!(
+        bounds("R2/2024-01-01T00:00Z/2024-01-01T01:30Z", 10),
+        vec![
+            pair("2024-01-01T00:00:00Z", "2024-01-01T01:30:00Z"),
+            pair("2024-01-01T01:30:00Z", "2024-01-01T03:00:00Z")
+        ]
+    );
+}
+
+#[test]
+fn test_json_and_errors() {
+    assert_eq!(
+        iso_interval::iso_interval("R5/2024-01-01T00:00Z/P1M", 1)
+            .unwrap()
+            .to_json(),
+        r#"{"expression":"R5/2024-01-01T00:00Z/P1M","repeating":true,"repetitions":5,"duration":"P1M","intervals":[{"start":"2024-01-01T00:00:00Z","end":"2024-02-01T00:00:00Z","seconds":2678400}]}"#
+    );
+    let err = |expression: &str| iso_interval::iso_interval(expression, 5).unwrap_err();
+    assert!(err("2024-01-01").contains("ISO 8601 interval"));
+    assert!(err("P1D/P2D").contains("two durations"));
+    assert!(err("2024-02-01/2024-01-01").contains("before its start"));
+    assert!(err("R/2024-01-01/PT0S").contains("non-zero"));
+    assert!(err("2024-01-01/P1Q").contains("unexpected 'Q'"));
+    assert!(err("2024-01-01T00:00Z/P99999999999D").contains("out of range"));
+    assert!(err("R5/2024-01-01T00:00Z/P999999999M").contains("out of range"));
+    assert!(
+        interval::interval_algebra("union", r#"["2024-01-01T00:00Z/P99999999999D"]"#, "")
+            .unwrap_err()
+            .contains("out of range")
+    );
+}
+
+// ============================================================================
+// Interval Algebra
+// ============================================================================
+
+#[test]
+fn test_maintenance_window_free_time() {
+    let maintenance = r#"["2024-06-01T01:00Z/PT2H","2024-06-01T02:30Z/2024-06-01T04:00Z"]"#;
+    let report =
+        interval::interval_algebra("gaps", maintenance, r#"["2024-06-01T00:00Z/P1D"]"#).unwrap();
+    let free: Vec<String> = report
+        .intervals
+        .iter()
+        .map(|i| format!("{}/{}", i.start, i.end))
+        .collect();
+    assert_eq!(
+        free,
+        vec![
+            "2024-06-01T00:00:00Z/2024-06-01T01:00:00Z",
+            "2024-06-01T04:00:00Z/2024-06-02T00:00:00Z"
+        ]
+    );
+}
+
+#[test]
+fn test_on_call_overlap_json() {
+    let rota = r#"["2024-06-03T00:00Z/PT12H","2024-06-03T11:00Z/PT12H","2024-06-04T00:00Z/PT12H"]"#;
+    assert_eq!(
+        interval::interval_algebra("overlaps", rota, "")
+            .unwrap()
+            .to_json(),
+        r#"{"operation":"overlaps","intervals":[{"start":"2024-06-03T11:00:00Z","end":"2024-06-03T12:00:00Z","seconds":3600}],"totalSeconds":3600,"overlaps":[{"left":0,"right":1,"relation":"overlaps","start":"2024-06-03T11:00:00Z","end":"2024-06-03T12:00:00Z"}]}"#
+    );
+}
+
+#[test]
+fn test_subtract_and_union_totals() {
+    let day = r#"["2024-06-03T09:00Z/17:00"]"#;
+    let meetings =
+        r#"["2024-06-03T10:00Z/PT1H","2024-06-03T10:30Z/PT1H","2024-06-03T16:00Z/PT2H"]"#;
+    let free = interval::interval_algebra("subtract", day, meetings).unwrap();
+    assert_eq!(
+        free.intervals.iter().map(|i| i.seconds()).sum::<i64>(),
+        5 * 3600 + 1800
+    );
+    let busy = interval::interval_algebra("union", meetings, "").unwrap();
+    assert_eq!(busy.intervals.len(), 2);
+    let err = interval::interval_algebra("xor", day, "").unwrap_err();
+    assert!(err.contains("Unknown interval operation"));
+    let err = interval::interval_algebra("union", r#"["R2/2024-01-01/P1D"]"#, "").unwrap_err();
+    assert!(err.starts_with("Interval 0:") && err.contains("repeats"));
+}
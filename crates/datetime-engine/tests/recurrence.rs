@@ -0,0 +1,313 @@
+//! Recurrence rule tests for the datetime-engine crate.
+//!
+//! Tests cover:
+//! - The RFC 5545 section 3.8.5.3 examples (America/New_York, starting September 1997)
+//! - EXDATE/RDATE handling and paging with `after`
+//! - DST gaps and folds in the rule's zone
+//! - JSON output and error reporting through `rrule::rrule_occurrences`
+
+use datetime_engine::rrule;
+
+/// Expand `rrule` from 1997-09-02 09:00 New York (a Tuesday), as most RFC examples do,
+/// returning local date-times.
+fn rfc(rrule: &str, count: u32) -> Vec<String> {
+    rfc_from("19970902T090000", rrule, count)
+}
+
+fn rfc_from(dtstart: &str, rrule: &str, count: u32) -> Vec<String> {
+    let text = format!("DTSTART;TZID=America/New_York:{}\nRRULE:{}", dtstart, rrule);
+    let recurrence = rrule::rrule_occurrences(&text, "", count, "").unwrap();
+    recurrence
+        .occurrences
+        .iter()
+        .map(|o| {
+            o.timestamp
+                .to_zoned(recurrence.zone.clone())
+                .datetime()
+                .to_string()
+        })
+        .collect()
+}
+
+fn days(month: &str, days: &[u8]) -> Vec<String> {
+    days.iter()
+        .map(|d| format!("{}-{:02}T09:00:00", month, d))
+        .collect()
+}
+
+// ============================================================================
+// RFC 5545 Examples
+// ============================================================================
+
+#[test]
+fn test_daily_for_ten_occurrences() {
+    assert_eq!(
+        rfc("FREQ=DAILY;COUNT=10", 100),
+        days("1997-09", &[2, 3, 4, 5, 6, 7, 8, 9, 10, 11])
+    );
+}
+
+#[test]
+fn test_daily_until_crosses_dst() {
+    let runs = rfc("FREQ=DAILY;UNTIL=19971224T000000Z", 1000);
+    assert_eq!(runs.len(), 113);
+    assert_eq!(runs.last().unwrap(), "1997-12-23T09:00:00");
+    // Still 09:00 local after the October fall-back
+    assert!(runs.contains(&"1997-10-27T09:00:00".to_string()));
+}
+
+#[test]
+fn test_every_other_day() {
+    assert_eq!(
+        rfc("FREQ=DAILY;INTERVAL=2", 4),
+        days("1997-09", &[2, 4, 6, 8])
+    );
+}
+
+#[test]
+fn test_weekly_wkst_changes_result() {
+    let dtstart = "19970805T090000";
+    assert_eq!(
+        rfc_from(
+            dtstart,
+            "FREQ=WEEKLY;INTERVAL=2;COUNT=4;BYDAY=TU,SU;WKST=MO",
+            10
+        ),
+        days("1997-08", &[5, 10, 19, 24])
+    );
+    assert_eq!(
+        rfc_from(
+            dtstart,
+            "FREQ=WEEKLY;INTERVAL=2;COUNT=4;BYDAY=TU,SU;WKST=SU",
+            10
+        ),
+        days("1997-08", &[5, 17, 19, 31])
+    );
+}
+
+#[test]
+fn test_monthly_first_friday() {
+    let runs = rfc_from("19970905T090000", "FREQ=MONTHLY;COUNT=10;BYDAY=1FR", 100);
+    assert_eq!(
+        runs,
+        vec![
+            "1997-09-05T09:00:00",
+            "1997-10-03T09:00:00",
+            "1997-11-07T09:00:00",
+            "1997-12-05T09:00:00",
+            "1998-01-02T09:00:00",
+            "1998-02-06T09:00:00",
+            "1998-03-06T09:00:00",
+            "1998-04-03T09:00:00",
+            "1998-05-01T09:00:00",
+            "1998-06-05T09:00:00"
+        ]
+    );
+}
+
+#[test]
+fn test_monthly_second_to_last_monday() {
+    assert_eq!(
+        rfc_from("19970922T090000", "FREQ=MONTHLY;COUNT=6;BYDAY=-2MO", 10),
+        vec![
+            "1997-09-22T09:00:00",
+            "1997-10-20T09:00:00",
+            "1997-11-17T09:00:00",
+            "1997-12-22T09:00:00",
+            "1998-01-19T09:00:00",
+            "1998-02-16T09:00:00"
+        ]
+    );
+}
+
+#[test]
+fn test_monthly_on_invalid_days_is_skipped() {
+    assert_eq!(
+        rfc_from(
+            "20070115T090000",
+            "FREQ=MONTHLY;BYMONTHDAY=15,30;COUNT=5",
+            10
+        ),
+        vec![
+            "2007-01-15T09:00:00",
+            "2007-01-30T09:00:00",
+            "2007-02-15T09:00:00",
+            "2007-03-15T09:00:00",
+            "2007-03-30T09:00:00"
+        ]
+    );
+}
+
+#[test]
+fn test_yearly_twentieth_monday() {
+    assert_eq!(
+        rfc_from("19970519T090000", "FREQ=YEARLY;BYDAY=20MO", 3),
+        vec![
+            "1997-05-19T09:00:00",
+            "1998-05-18T09:00:00",
+            "1999-05-17T09:00:00"
+        ]
+    );
+}
+
+#[test]
+fn test_yearly_by_week_number() {
+    assert_eq!(
+        rfc_from("19970512T090000", "FREQ=YEARLY;BYWEEKNO=20;BYDAY=MO", 3),
+        vec![
+            "1997-05-12T09:00:00",
+            "1998-05-11T09:00:00",
+            "1999-05-17T09:00:00"
+        ]
+    );
+}
+
+#[test]
+fn test_friday_thirteenth_with_exdate() {
+    let text = "DTSTART;TZID=America/New_York:19970902T090000\n\
+                EXDATE;TZID=America/New_York:19970902T090000\n\
+                RRULE:FREQ=MONTHLY;BYDAY=FR;BYMONTHDAY=13";
+    let recurrence = rrule::rrule_occurrences(text, "", 5, "").unwrap();
+    let iso: Vec<String> = recurrence
+        .occurrences
+        .iter()
+        .map(|o| o.civil.date().to_string())
+        .collect();
+    assert_eq!(
+        iso,
+        vec![
+            "1998-02-13",
+            "1998-03-13",
+            "1998-11-13",
+            "1999-08-13",
+            "2000-10-13"
+        ]
+    );
+}
+
+#[test]
+fn test_set_positions() {
+    // The third instance of a Tuesday, Wednesday or Thursday each month
+    assert_eq!(
+        rfc_from(
+            "19970904T090000",
+            "FREQ=MONTHLY;COUNT=3;BYDAY=TU,WE,TH;BYSETPOS=3",
+            10
+        ),
+        vec![
+            "1997-09-04T09:00:00",
+            "1997-10-07T09:00:00",
+            "1997-11-06T09:00:00"
+        ]
+    );
+    // The second-to-last weekday of the month
+    assert_eq!(
+        rfc_from(
+            "19970929T090000",
+            "FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-2",
+            4
+        ),
+        vec![
+            "1997-09-29T09:00:00",
+            "1997-10-30T09:00:00",
+            "1997-11-27T09:00:00",
+            "1997-12-30T09:00:00"
+        ]
+    );
+}
+
+#[test]
+fn test_sub_daily_frequencies() {
+    assert_eq!(
+        rfc("FREQ=HOURLY;INTERVAL=3;UNTIL=19970902T210000Z", 10),
+        vec![
+            "1997-09-02T09:00:00",
+            "1997-09-02T12:00:00",
+            "1997-09-02T15:00:00"
+        ]
+    );
+    assert_eq!(
+        rfc("FREQ=MINUTELY;INTERVAL=90;COUNT=4", 10),
+        vec![
+            "1997-09-02T09:00:00",
+            "1997-09-02T10:30:00",
+            "1997-09-02T12:00:00",
+            "1997-09-02T13:30:00"
+        ]
+    );
+    let every_20 = rfc(
+        "FREQ=DAILY;BYHOUR=9,10,11,12,13,14,15,16;BYMINUTE=0,20,40",
+        30,
+    );
+    assert_eq!(every_20[23], "1997-09-02T16:40:00");
+    assert_eq!(every_20[24], "1997-09-03T09:00:00");
+}
+
+// ============================================================================
+// Sets, Paging and Zones
+// ============================================================================
+
+#[test]
+fn test_after_pages_through_occurrences() {
+    let result = rrule::rrule_occurrences(
+        "FREQ=WEEKLY;BYDAY=MO,WE",
+        "2024-01-01T09:00",
+        3,
+        r#"{"zone":"Europe/London","after":"2030-06-01"}"#,
+    )
+    .unwrap();
+    let local: Vec<String> = result
+        .occurrences
+        .iter()
+        .map(|o| o.civil.to_string())
+        .collect();
+    assert_eq!(
+        local,
+        vec![
+            "2030-06-03T09:00:00",
+            "2030-06-05T09:00:00",
+            "2030-06-10T09:00:00"
+        ]
+    );
+}
+
+#[test]
+fn test_gap_time_uses_offset_before_gap() {
+    let result = rrule::rrule_occurrences(
+        "RRULE:FREQ=DAILY;COUNT=3",
+        "2024-03-09T02:30[America/New_York]",
+        3,
+        "",
+    )
+    .unwrap();
+    let json = result.to_json();
+    assert!(json.contains(r#""local":"2024-03-10T03:30:00-04:00[America/New_York]""#));
+    assert!(json.contains(r#""local":"2024-03-11T02:30:00-04:00[America/New_York]""#));
+}
+
+#[test]
+fn test_all_day_json() {
+    let json = rrule::rrule_occurrences("FREQ=YEARLY;COUNT=2", "2024-02-29", 5, "")
+        .unwrap()
+        .to_json();
+    assert_eq!(
+        json,
+        r#"{"dtstart":"2024-02-29T00:00:00","zone":"UTC","allDay":true,"occurrences":[{"iso":"2024-02-29T00:00:00Z","unixTimestamp":1709164800,"local":"2024-02-29T00:00:00+00:00[UTC]","date":"2024-02-29"},{"iso":"2028-02-29T00:00:00Z","unixTimestamp":1835395200,"local":"2028-02-29T00:00:00+00:00[UTC]","date":"2028-02-29"}]}"#
+    );
+}
+
+#[test]
+fn test_errors() {
+    let err =
+        |rule: &str, dtstart: &str| rrule::rrule_occurrences(rule, dtstart, 5, "").unwrap_err();
+    assert!(err("FREQ=DAILY", "").contains("DTSTART is required"));
+    assert!(err("DTSTART:20240101\nRRULE:FREQ=DAILY", "2024-01-01").contains("both"));
+    assert!(err("FREQ=DAILY", "2024-01-01T09:00:00-05:00").contains("no zone"));
+    assert!(err("FREQ=HOURLY", "2024-01-01").contains("all-day"));
+    assert!(err("EXRULE:FREQ=DAILY", "2024-01-01").contains("EXDATE"));
+    assert!(err(
+        "RDATE;VALUE=PERIOD:19960403T020000Z/19960403T040000Z",
+        "2024-01-01"
+    )
+    .contains("PERIOD"));
+}
@@ -0,0 +1,118 @@
+//! Relative time tests for the datetime-engine crate.
+//!
+//! Tests cover:
+//! - Phrases with rounding, several units and the short style
+//! - Calendar phrases ("yesterday at 14:00") in a zone
+//! - Phrases in every compiled-in locale
+//! - Countdown breakdown in the JSON output
+//! - Option errors
+
+use datetime_engine::relative;
+
+fn phrase(reference: &str, target: &str, options: &str) -> String {
+    relative::relative_time(reference, target, options)
+        .unwrap()
+        .phrase
+}
+
+// ============================================================================
+// English Phrases
+// ============================================================================
+
+#[test]
+fn test_english_phrases() {
+    assert_eq!(
+        phrase("2024-07-11T00:00:00Z", "2024-04-02T00:00:00Z", ""),
+        "about 3 months ago"
+    );
+    assert_eq!(
+        phrase(
+            "2024-07-11T10:00:00Z",
+            "2024-07-11T12:05:20Z",
+            r#"{"units":2,"style":"short"}"#
+        ),
+        "in 2h 5m"
+    );
+    assert_eq!(
+        phrase(
+            "2024-07-11T08:00:00Z",
+            "2024-07-10T12:00:00Z",
+            r#"{"calendar":true,"zone":"Europe/Berlin"}"#
+        ),
+        "yesterday at 14:00"
+    );
+}
+
+#[test]
+fn test_json_breakdown() {
+    let json = relative::relative_time("2024-01-31T10:00:00Z", "2025-03-01T12:30:15Z", "")
+        .unwrap()
+        .to_json();
+    assert_eq!(
+        json,
+        r#"{"phrase":"in about 1 year","direction":"future","breakdown":{"sign":1,"years":1,"months":1,"days":1,"hours":2,"minutes":30,"seconds":15,"totalSeconds":34137015}}"#
+    );
+}
+
+// ============================================================================
+// Locales
+// ============================================================================
+
+#[test]
+fn test_every_locale_has_phrases() {
+    let cases = [
+        ("de", "vor etwa 3 Monaten", "morgen um 09:30"),
+        ("fr", "il y a environ 3 mois", "demain à 09:30"),
+        ("es", "hace aproximadamente 3 meses", "mañana a las 09:30"),
+        ("it", "circa 3 mesi fa", "domani alle 09:30"),
+        ("pt-BR", "há cerca de 3 meses", "amanhã às 09:30"),
+        ("nl", "ongeveer 3 maanden geleden", "morgen om 09:30"),
+        ("ja", "約3か月前", "明日 09:30"),
+        ("zh", "大约3个月前", "明天 09:30"),
+    ];
+    for (tag, past, tomorrow) in cases {
+        let options = format!(r#"{{"locale":"{}"}}"#, tag);
+        assert_eq!(
+            phrase("2024-07-11T00:00:00Z", "2024-04-02T00:00:00Z", &options),
+            past
+        );
+        let options = format!(r#"{{"locale":"{}","calendar":true}}"#, tag);
+        assert_eq!(
+            phrase("2024-07-11T08:00:00Z", "2024-07-12T09:30:00Z", &options),
+            tomorrow
+        );
+    }
+}
+
+#[test]
+fn test_locale_fallback_and_multiple_units() {
+    assert_eq!(
+        phrase(
+            "2024-07-11T10:00:00Z",
+            "2024-07-10T07:58:59Z",
+            r#"{"locale":"de-CH","units":3,"rounding":"floor"}"#
+        ),
+        "vor 1 Tag, 2 Stunden und 1 Minute"
+    );
+    assert_eq!(
+        phrase(
+            "2024-07-11T10:00:00Z",
+            "2024-07-11T12:05:20Z",
+            r#"{"locale":"fr","units":2,"style":"short"}"#
+        ),
+        "dans 2 h 5 min"
+    );
+}
+
+// ============================================================================
+// Errors
+// ============================================================================
+
+#[test]
+fn test_option_errors() {
+    let err =
+        |options: &str| relative::relative_time("2024-07-11", "2024-07-12", options).unwrap_err();
+    assert_eq!(err(r#"{"locale":"tlh"}"#), "Unsupported locale: 'tlh'");
+    assert!(err(r#"{"units":0}"#).contains("between 1 and 6"));
+    assert!(err(r#"{"style":"poetic"}"#).contains("Unknown style"));
+}
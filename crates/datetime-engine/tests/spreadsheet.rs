@@ -0,0 +1,170 @@
+//! Spreadsheet serial date tests for the datetime-engine crate.
+//!
+//! Tests cover:
+//! - Excel 1900's fictional 1900-02-29 (serial 60) as output and input
+//! - The 1904 system against the 1900 one
+//! - Negative Google Sheets serials, and negative and out-of-range serials elsewhere
+//! - Exact round-tripping of fractional-day times
+//!
+//! Output and input of every system are covered in `encodings.rs`.
+
+use datetime_engine::calc;
+use datetime_engine::validate;
+
+// ============================================================================
+// Excel 1900 Leap Bug
+// ============================================================================
+
+#[test]
+fn test_systems_diverge_before_march_1900() {
+    // Excel counts the fictional 1900-02-29, so its serials are one lower before it
+    let result = calc::calculate_with_formats(
+        "1900-02-28T18:00:00Z",
+        "[]",
+        r#"["excel1900","googleSheets"]"#,
+        "UTC",
+    )
+    .unwrap();
+    assert_eq!(
+        result.formats,
+        vec![
+            ("excel1900".to_string(), Ok("59.75".to_string())),
+            ("googleSheets".to_string(), Ok("60.75".to_string())),
+        ]
+    );
+    assert_eq!(
+        validate::validate("excel:0.75").to_json(),
+        r#"{"valid":true,"normalized":"1899-12-31T18:00:00"}"#
+    );
+}
+
+#[test]
+fn test_serial_60_reads_as_february_28() {
+    assert_eq!(
+        validate::validate("excel:60").to_json(),
+        r#"{"valid":true,"normalized":"1900-02-28T00:00:00","warning":"serial 60 is 1900-02-29, a day that never existed (Excel keeps it for Lotus 1-2-3 compatibility); read as 1900-02-28"}"#
+    );
+    let result = calc::calculate("excel:60.5", "[]").unwrap();
+    assert_eq!(result.iso_8601, "1900-02-28T12:00:00Z");
+    // Only Excel 1900 has the fictional day
+    assert_eq!(
+        validate::validate("sheets:60").to_json(),
+        r#"{"valid":true,"normalized":"1900-02-28T00:00:00"}"#
+    );
+}
+
+#[test]
+fn test_excel_1900_starts_on_january_1() {
+    let result = calc::calculate_with_formats(
+        "1899-12-30T00:00:00Z",
+        "[]",
+        r#"["excel1900","googleSheets"]"#,
+        "UTC",
+    )
+    .unwrap();
+    assert!(result.formats[0]
+        .1
+        .as_ref()
+        .unwrap_err()
+        .contains("start of the Excel 1900 date system"));
+    assert_eq!(result.formats[1].1.as_deref(), Ok("0"));
+}
+
+// ============================================================================
+// 1904 vs 1900
+// ============================================================================
+
+#[test]
+fn test_1904_system_is_1462_days_behind() {
+    let result = calc::calculate_with_formats(
+        "2024-07-11T12:00:00Z",
+        "[]",
+        r#"["excel1900","excel1904"]"#,
+        "UTC",
+    )
+    .unwrap();
+    assert_eq!(
+        result.formats,
+        vec![
+            ("excel1900".to_string(), Ok("45484.5".to_string())),
+            ("excel1904".to_string(), Ok("44022.5".to_string())),
+        ]
+    );
+}
+
+#[test]
+fn test_1904_system_cannot_show_earlier_dates() {
+    // excel:1 is 1900-01-01, which the 1904 system cannot show
+    let json = calc::calculate_with_formats(
+        "excel:1",
+        "[]",
+        r#"["excel1900","excel1904","googleSheets"]"#,
+        "UTC",
+    )
+    .unwrap()
+    .to_json();
+    assert!(json.contains(r#""formats":{"excel1900":"1","excel1904":null,"googleSheets":"2"},"formatErrors":{"excel1904":""#));
+    assert!(json.contains("start of the Excel 1904 date system"));
+}
+
+// ============================================================================
+// Negative and Out-of-Range Serials
+// ============================================================================
+
+#[test]
+fn test_sheets_serials_go_negative() {
+    assert_eq!(
+        validate::validate("sheets:-1.25").to_json(),
+        r#"{"valid":true,"normalized":"1899-12-28T18:00:00"}"#
+    );
+    let result =
+        calc::calculate_with_formats("1899-12-28T18:00:00Z", "[]", r#"["googleSheets"]"#, "UTC")
+            .unwrap();
+    assert_eq!(result.formats[0].1.as_deref(), Ok("-1.25"));
+    assert!(validate::validate("excel1904:-2")
+        .error
+        .unwrap()
+        .contains("cannot be negative"));
+}
+
+#[test]
+fn test_validate_rejects_malformed_and_out_of_range_serials() {
+    assert!(validate::validate("sheets:4.5e4")
+        .error
+        .unwrap()
+        .contains("expected a day count"));
+    // Far past Excel's last day (9999-12-31 is 2958465): an error, not a panic
+    for input in ["excel:8000000", "excel1904:8000000", "sheets:-8000000"] {
+        assert!(
+            validate::validate(input)
+                .error
+                .unwrap()
+                .contains("out of range"),
+            "{}",
+            input
+        );
+    }
+}
+
+// ============================================================================
+// Round-Tripping
+// ============================================================================
+
+#[test]
+fn test_fractional_days_round_trip_exactly() {
+    for start in [
+        "2024-07-11T01:14:00Z",
+        "2024-07-11T23:59:59.999Z",
+        "1900-01-01T00:00:00.001Z",
+        "1955-11-05T06:15:00.5Z",
+    ] {
+        let result =
+            calc::calculate_with_formats(start, "[]", r#"["excel1900","googleSheets"]"#, "UTC")
+                .unwrap();
+        for (name, serial) in result.formats {
+            let serial = serial.unwrap();
+            let back = calc::calculate(&format!("{}:{}", name, serial), "[]").unwrap();
+            assert_eq!(back.iso_8601, start, "{} {}", name, serial);
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! Windows time encoding tests for the datetime-engine crate.
+//!
+//! Tests cover:
+//! - Output range errors for FILETIME and DOS date-times, reported per format
+//! - FILETIME as decimal input and DOS date-times under operations
+//! - Validation of negative OLE dates, AD's "never", invalid DOS values and
+//!   out-of-range values
+//!
+//! Output and input of every encoding are covered in `encodings.rs`.
+
+use datetime_engine::calc;
+use datetime_engine::validate;
+
+// ============================================================================
+// Output Formats
+// ============================================================================
+
+#[test]
+fn test_output_format_range_errors() {
+    let error = |start: &str, format: &str| {
+        let formats = format!(r#"["{}"]"#, format);
+        calc::calculate_with_formats(start, "[]", &formats, "UTC")
+            .unwrap()
+            .formats[0]
+            .1
+            .clone()
+            .unwrap_err()
+    };
+    assert!(error("1975-01-01T00:00:00Z", "dosDateTime").contains("1980 to 2107"));
+    assert!(error("1600-01-01T00:00:00Z", "fileTime").contains("before 1601-01-01"));
+}
+
+#[test]
+fn test_failing_format_does_not_fail_the_others() {
+    let json = calc::calculate_with_formats(
+        "1600-01-01T00:00:00Z",
+        "[]",
+        r#"["fileTime","basicIso"]"#,
+        "UTC",
+    )
+    .unwrap()
+    .to_json();
+    assert!(json.contains(
+        r#""formats":{"fileTime":null,"basicIso":"16000101T000000Z"},"formatErrors":{"fileTime":""#
+    ));
+}
+
+// ============================================================================
+// Input Formats
+// ============================================================================
+
+#[test]
+fn test_filetime_accepts_decimal() {
+    let result = calc::calculate("fileTime:133651340400000000", "[]").unwrap();
+    assert_eq!(result.iso_8601, "2024-07-11T01:14:00Z");
+}
+
+#[test]
+fn test_dos_input_takes_operations() {
+    let result = calc::calculate(
+        "dos:0x58EB19C0",
+        r#"[{"type":"add","value":1,"unit":"days"}]"#,
+    )
+    .unwrap();
+    assert_eq!(result.iso_8601, "2024-07-12T03:14:00Z");
+}
+
+#[test]
+fn test_validate_edge_cases() {
+    // Before 1899-12-30 an OLE date's fraction still counts forward from its day
+    assert_eq!(
+        validate::validate("ole:-1.25").to_json(),
+        r#"{"valid":true,"normalized":"1899-12-29T06:00:00"}"#
+    );
+    assert_eq!(
+        validate::validate("ad:0").to_json(),
+        r#"{"valid":false,"error":"Invalid activeDirectory '0': the value means 'never' (not set or no expiry)"}"#
+    );
+    assert!(validate::validate("dos:0")
+        .error
+        .unwrap()
+        .contains("1980-00-00 00:00:00, which is not a valid date-time"));
+    assert!(validate::validate("filetime:-5")
+        .error
+        .unwrap()
+        .contains("non-negative integer"));
+    // Past the end of the representable range, not a panic
+    for input in [
+        "filetime:0xFFFFFFFFFFFFFFFF",
+        "ticks:18446744073709551615",
+        "ad:9000000000000000000",
+    ] {
+        assert!(
+            validate::validate(input)
+                .error
+                .unwrap()
+                .contains("out of range"),
+            "{}",
+            input
+        );
+        assert!(calc::calculate(input, "[]").is_err(), "{}", input);
+    }
+}
@@ -0,0 +1,589 @@
+//! Time zone tests for the datetime-engine crate.
+//!
+//! Tests cover:
+//! - DST transitions within a window, classified as gaps or folds
+//! - Finding the next transition after an instant
+//! - Disambiguating civil times that fall in a gap or fold
+//! - Showing one instant across several zones (world clock)
+//! - Meeting slots across zones whose DST changes fall on different dates
+//! - Zone abbreviations and offset notations in pasted input
+//! - POSIX TZ strings and TZif files as zones, and their inspection
+//! - Offset history eras and historical conversions against a fixed offset
+//! - JSON output and errors
+
+use datetime_engine::calc;
+use datetime_engine::history;
+use datetime_engine::meeting;
+use datetime_engine::tzfile;
+use datetime_engine::zone;
+
+// ============================================================================
+// Transitions
+// ============================================================================
+
+#[test]
+fn test_new_york_year() {
+    let json = zone::zone_transitions("America/New_York", "2024", "")
+        .unwrap()
+        .to_json();
+    assert_eq!(
+        json,
+        r#"{"zone":"America/New_York","transitions":[{"iso":"2024-03-10T07:00:00Z","unixTimestamp":1710054000,"kind":"gap","offsetBefore":"-05:00","offsetAfter":"-04:00","shiftSeconds":3600,"abbreviationBefore":"EST","abbreviationAfter":"EDT","dst":true,"localBefore":"2024-03-10T02:00:00","localAfter":"2024-03-10T03:00:00"},{"iso":"2024-11-03T06:00:00Z","unixTimestamp":1730613600,"kind":"fold","offsetBefore":"-04:00","offsetAfter":"-05:00","shiftSeconds":-3600,"abbreviationBefore":"EDT","abbreviationAfter":"EST","dst":false,"localBefore":"2024-11-03T02:00:00","localAfter":"2024-11-03T01:00:00"}]}"#
+    );
+}
+
+#[test]
+fn test_southern_hemisphere_and_half_hour_shift() {
+    let sydney = zone::zone_transitions("Australia/Sydney", "2024", "").unwrap();
+    let kinds: Vec<&str> = sydney.transitions.iter().map(|t| t.kind().name()).collect();
+    assert_eq!(kinds, vec!["fold", "gap"]);
+
+    let lord_howe = zone::zone_transitions("Australia/Lord_Howe", "2024", "").unwrap();
+    assert_eq!(
+        lord_howe.transitions[0].offset_after.seconds()
+            - lord_howe.transitions[0].offset_before.seconds(),
+        -1800
+    );
+}
+
+#[test]
+fn test_explicit_window_and_no_dst_zone() {
+    let window = zone::zone_transitions(
+        "Europe/London",
+        "2024-03-31T01:00:00Z",
+        "2024-10-27T01:00:00Z",
+    )
+    .unwrap();
+    // The start is inclusive, the end exclusive
+    assert_eq!(window.transitions.len(), 1);
+    assert_eq!(window.transitions[0].abbreviation_after, "BST");
+
+    // A bare year at the end includes that whole year
+    let years = zone::zone_transitions("Europe/London", "2024", "2026").unwrap();
+    assert_eq!(years.transitions.len(), 6);
+    assert_eq!(
+        years.transitions[5].timestamp.to_string(),
+        "2026-10-25T01:00:00Z"
+    );
+    let to_year = zone::zone_transitions("Europe/London", "2024-07-01", "2024").unwrap();
+    assert_eq!(to_year.transitions.len(), 1);
+    assert!(zone::zone_transitions("Europe/London", "2026", "2024").is_err());
+
+    let tokyo = zone::zone_transitions("Asia/Tokyo", "2024", "").unwrap();
+    assert!(tokyo.transitions.is_empty());
+}
+
+// ============================================================================
+// Next Transition
+// ============================================================================
+
+#[test]
+fn test_next_transition() {
+    let next = zone::next_zone_transition("Europe/Berlin", "2024-04-01T00:00:00Z").unwrap();
+    assert_eq!(next.transitions.len(), 1);
+    assert_eq!(
+        next.transitions[0].timestamp.to_string(),
+        "2024-10-27T01:00:00Z"
+    );
+
+    // Exactly at a transition, the following one is returned
+    let next = zone::next_zone_transition("Europe/Berlin", "2024-10-27T01:00:00Z").unwrap();
+    assert_eq!(
+        next.transitions[0].timestamp.to_string(),
+        "2025-03-30T01:00:00Z"
+    );
+
+    let none = zone::next_zone_transition("UTC", "2024-01-01").unwrap();
+    assert_eq!(none.to_json(), r#"{"zone":"UTC","transitions":[]}"#);
+}
+
+#[test]
+fn test_errors() {
+    assert!(zone::zone_transitions("Mars/Olympus", "2024", "").is_err());
+    let err = zone::zone_transitions("Europe/Paris", "2024-06-01", "2024-01-01").unwrap_err();
+    assert!(err.contains("after its start"));
+}
+
+// ============================================================================
+// Disambiguation
+// ============================================================================
+
+fn in_new_york(start: &str, operations: &str, disambiguation: &str) -> Result<String, String> {
+    let options = format!(
+        r#"{{"zone":"America/New_York","disambiguation":"{}"}}"#,
+        disambiguation
+    );
+    calc::calculate_in_zone(start, operations, &options).map(|r| r.iso_8601)
+}
+
+#[test]
+fn test_start_in_gap() {
+    let start = "2024-03-10T02:30";
+    assert_eq!(
+        in_new_york(start, "", "compatible").unwrap(),
+        "2024-03-10T07:30:00Z"
+    );
+    assert_eq!(
+        in_new_york(start, "", "earlier").unwrap(),
+        "2024-03-10T06:30:00Z"
+    );
+    assert_eq!(
+        in_new_york(start, "", "later").unwrap(),
+        "2024-03-10T07:30:00Z"
+    );
+    assert!(in_new_york(start, "", "reject")
+        .unwrap_err()
+        .contains("does not exist"));
+}
+
+#[test]
+fn test_start_in_fold() {
+    let start = "2024-11-03T01:30:00";
+    assert_eq!(in_new_york(start, "", "").unwrap(), "2024-11-03T05:30:00Z");
+    assert_eq!(
+        in_new_york(start, "", "later").unwrap(),
+        "2024-11-03T06:30:00Z"
+    );
+    assert!(in_new_york(start, "", "reject")
+        .unwrap_err()
+        .contains("ambiguous"));
+    // Absolute inputs are never ambiguous
+    assert_eq!(
+        in_new_york("2024-11-03T06:30:00Z", "", "reject").unwrap(),
+        "2024-11-03T06:30:00Z"
+    );
+}
+
+#[test]
+fn test_operations_landing_in_gap() {
+    let set_hour = r#"[{"type":"set","unit":"hours","value":2}]"#;
+    assert_eq!(
+        in_new_york("2024-03-10T09:15", set_hour, "earlier").unwrap(),
+        "2024-03-10T06:15:00Z"
+    );
+    assert!(in_new_york("2024-03-10T09:15", set_hour, "reject").is_err());
+    let next_day = r#"[{"type":"add","unit":"days","value":1}]"#;
+    assert_eq!(
+        in_new_york("2024-03-09T02:30", next_day, "").unwrap(),
+        "2024-03-10T07:30:00Z"
+    );
+    // Exact units never need disambiguation
+    let hours = r#"[{"type":"add","unit":"hours","value":24}]"#;
+    assert_eq!(
+        in_new_york("2024-03-09T02:30", hours, "reject").unwrap(),
+        "2024-03-10T07:30:00Z"
+    );
+}
+
+#[test]
+fn test_set_fields_and_option_errors() {
+    // Setting the month or year clamps the day, as adding months does
+    let set = |start: &str, unit: &str, value: i64| {
+        let ops = format!(r#"[{{"type":"set","unit":"{}","value":{}}}]"#, unit, value);
+        calc::calculate(start, &ops).map(|r| r.iso_8601)
+    };
+    assert_eq!(
+        set("2024-01-31T10:00:00Z", "months", 2).unwrap(),
+        "2024-02-29T10:00:00Z"
+    );
+    assert_eq!(
+        set("2024-01-31T10:00:00Z", "months", 4).unwrap(),
+        "2024-04-30T10:00:00Z"
+    );
+    assert_eq!(
+        set("2024-02-29T10:00:00Z", "years", 2023).unwrap(),
+        "2023-02-28T10:00:00Z"
+    );
+    assert_eq!(
+        set("2024-02-10T10:00:00Z", "days", 31).unwrap_err(),
+        "Set operation failed: February 2024 has no day 31"
+    );
+    assert!(set("2024-01-31T10:00:00Z", "months", 13)
+        .unwrap_err()
+        .contains("13 is out of range for months"));
+    let result = calc::calculate(
+        "2024-01-31T10:45:00Z",
+        r#"[{"type":"set","unit":"days","value":1},{"type":"set","unit":"minutes","value":0}]"#,
+    )
+    .unwrap();
+    assert_eq!(result.iso_8601, "2024-01-01T10:00:00Z");
+    assert!(
+        calc::calculate_in_zone("2024-01-01", "", r#"{"disambiguation":"latest"}"#)
+            .unwrap_err()
+            .contains("Unknown disambiguation")
+    );
+}
+
+// ============================================================================
+// World Clock
+// ============================================================================
+
+#[test]
+fn test_incident_across_team_zones() {
+    let zones = r#"["America/Los_Angeles","America/New_York","Europe/London","Asia/Kolkata","Australia/Sydney"]"#;
+    let clock = zone::world_clock("2024-06-01T23:30:00Z", zones, "").unwrap();
+    let json = clock.to_json();
+    assert!(json.starts_with(
+        r#"{"iso":"2024-06-01T23:30:00Z","unixTimestamp":1717284600,"reference":"UTC","clocks":["#
+    ));
+    assert!(json.contains(r#"{"zone":"America/Los_Angeles","local":"2024-06-01T16:30:00","date":"2024-06-01","time":"16:30:00","weekday":"Sat","offset":"-07:00","abbreviation":"PDT","dst":true,"dayDifference":0,"dayMarker":""}"#));
+    assert!(json.contains(r#"{"zone":"Asia/Kolkata","local":"2024-06-02T05:00:00","date":"2024-06-02","time":"05:00:00","weekday":"Sun","offset":"+05:30","abbreviation":"IST","dst":false,"dayDifference":1,"dayMarker":"+1 day"}"#));
+    assert!(json.contains(r#""zone":"Australia/Sydney","local":"2024-06-02T09:30:00""#));
+    assert!(json.contains(r#""abbreviation":"AEST","dst":false"#));
+}
+
+#[test]
+fn test_civil_instant_in_reference_zone() {
+    let clock = zone::world_clock(
+        "2024-01-15T09:00",
+        r#"["Pacific/Auckland","Pacific/Honolulu","-03:00"]"#,
+        r#"{"reference":"Europe/Berlin"}"#,
+    )
+    .unwrap();
+    assert_eq!(clock.timestamp.to_string(), "2024-01-15T08:00:00Z");
+    let markers: Vec<i32> = clock
+        .clocks
+        .iter()
+        .map(|(_, zoned)| clock.day_difference(zoned))
+        .collect();
+    assert_eq!(markers, vec![0, -1, 0]);
+    assert!(clock
+        .to_json()
+        .contains(r#""zone":"-03:00","local":"2024-01-15T05:00:00""#));
+}
+
+#[test]
+fn test_world_clock_errors() {
+    assert!(zone::world_clock("2024-01-01", "[]", "")
+        .unwrap_err()
+        .contains("At least one zone"));
+    assert!(zone::world_clock("2024-01-01", r#"["UTC","Mars/Olympus"]"#, "").is_err());
+}
+
+// ============================================================================
+// Meeting Planner
+// ============================================================================
+
+fn daily_slots(participants: &str, start: &str, end: &str) -> Vec<(String, i64)> {
+    meeting::meeting_slots(participants, start, end, "")
+        .unwrap()
+        .slots
+        .iter()
+        .map(|slot| (slot.start.to_string(), slot.seconds() / 3600))
+        .collect()
+}
+
+#[test]
+fn test_us_and_uk_shift_on_different_dates() {
+    let team =
+        r#"[{"name":"NYC","zone":"America/New_York"},{"name":"LDN","zone":"Europe/London"}]"#;
+    // Before the US change: 14:00-17:00Z; between the two changes: 13:00-17:00Z;
+    // after the UK change: 13:00-16:00Z
+    assert_eq!(
+        daily_slots(team, "2024-03-08", "2024-03-09"),
+        vec![("2024-03-08T14:00:00Z".to_string(), 3)]
+    );
+    assert_eq!(
+        daily_slots(team, "2024-03-11", "2024-03-12"),
+        vec![("2024-03-11T13:00:00Z".to_string(), 4)]
+    );
+    assert_eq!(
+        daily_slots(team, "2024-04-01", "2024-04-02"),
+        vec![("2024-04-01T13:00:00Z".to_string(), 3)]
+    );
+}
+
+#[test]
+fn test_southern_hemisphere_and_weekend_boundary() {
+    // Sydney's Monday morning is Sunday evening in Los Angeles
+    let team = r#"[
+        {"name":"SYD","zone":"Australia/Sydney","start":"08:00","end":"18:00"},
+        {"name":"LAX","zone":"America/Los_Angeles","start":"13:00","end":"20:00","days":"Mon..Fri"}
+    ]"#;
+    let week = meeting::meeting_slots(team, "2024-10-07", "", "").unwrap();
+    // Five LA weekdays, each overlapping Sydney's next morning; Sydney's Saturday
+    // leaves Friday's LA afternoon without a partner
+    assert_eq!(week.slots.len(), 4);
+    let json = week.to_json();
+    assert!(json.contains(
+        r#"{"name":"SYD","start":"2024-10-08T08:00:00+11:00","end":"2024-10-08T14:00:00+11:00"}"#
+    ));
+    assert!(json.contains(
+        r#"{"name":"LAX","start":"2024-10-07T14:00:00-07:00","end":"2024-10-07T20:00:00-07:00"}"#
+    ));
+}
+
+#[test]
+fn test_holidays_and_min_minutes() {
+    let team = r#"[{"zone":"Europe/Berlin","holidays":["2024-10-03"]},{"zone":"Europe/Lisbon","start":"08:30","end":"09:15"}]"#;
+    let plan = meeting::meeting_slots(
+        team,
+        "2024-09-30",
+        "2024-10-05",
+        r#"{"zone":"Europe/Berlin","minMinutes":15}"#,
+    )
+    .unwrap();
+    // Lisbon 08:30-09:15 is 09:30-10:15 in Berlin; no slot on the German holiday
+    assert_eq!(plan.slots.len(), 4);
+    assert!(plan
+        .slots
+        .iter()
+        .all(|slot| !slot.start.to_string().starts_with("2024-10-03")));
+    let strict =
+        meeting::meeting_slots(team, "2024-09-30", "2024-10-05", r#"{"minMinutes":60}"#).unwrap();
+    assert!(strict
+        .to_json()
+        .contains(r#""slotCount":0,"totalSeconds":0,"slots":[]"#));
+}
+
+#[test]
+fn test_meeting_errors() {
+    let err = |participants: &str| {
+        meeting::meeting_slots(participants, "2024-01-01", "", "").unwrap_err()
+    };
+    assert!(err("[]").contains("At least one participant"));
+    assert!(err(r#"[{"zone":"UTC"},{"name":"B"}]"#).starts_with("Participant 2: Missing 'zone'"));
+    assert!(err(r#"[{"zone":"UTC","days":"Funday"}]"#).contains("Invalid weekday"));
+    assert!(err(r#"[{"zone":"UTC","holidays":["soon"]}]"#).contains("Invalid holiday"));
+}
+
+// ============================================================================
+// Abbreviations and Offset Notations
+// ============================================================================
+
+#[test]
+fn test_pasted_times_validate_with_warnings() {
+    use datetime_engine::validate::validate;
+
+    let ist = validate("14:00 IST");
+    assert!(ist.valid);
+    assert_eq!(ist.normalized.as_deref(), Some("14:00:00+05:30"));
+    let json = ist.to_json();
+    assert!(json.contains(r#""warning":"'IST' is ambiguous: India Standard Time (+05:30), Irish Standard Time (+01:00), Israel Standard Time (+02:00); assumed India Standard Time""#));
+    assert!(
+        json.contains(r#"{"offset":"+01:00","zone":"Europe/Dublin","name":"Irish Standard Time"}"#)
+    );
+
+    let gmt = validate("2024-03-01T09:30 GMT-3");
+    assert_eq!(gmt.normalized.as_deref(), Some("2024-03-01T09:30:00-03:00"));
+    assert!(!gmt.to_json().contains("warning"));
+
+    let jst = validate("2024-03-01 09:30 jst");
+    assert_eq!(jst.normalized.as_deref(), Some("2024-03-01T09:30:00+09:00"));
+}
+
+#[test]
+fn test_abbreviated_start_dates_and_zones() {
+    let result = calc::calculate("2024-01-15 10:00 PST", "").unwrap();
+    assert_eq!(result.iso_8601, "2024-01-15T18:00:00Z");
+    let result = calc::calculate("2024-01-15 10:00 UTC+5:30", "").unwrap();
+    assert_eq!(result.iso_8601, "2024-01-15T04:30:00Z");
+    // Offset notations also work wherever a zone name is accepted
+    let clock = zone::world_clock("2024-01-15T12:00:00Z", r#"["GMT-3","UTC+05:45"]"#, "").unwrap();
+    assert!(clock
+        .to_json()
+        .contains(r#""zone":"GMT-3","local":"2024-01-15T09:00:00""#));
+    assert!(clock
+        .to_json()
+        .contains(r#""zone":"UTC+05:45","local":"2024-01-15T17:45:00""#));
+    // A bare time is today's time at that offset, as `validate` accepts it
+    let today = jiff::Timestamp::now()
+        .to_zoned(jiff::tz::TimeZone::fixed(jiff::tz::offset(-8)))
+        .date();
+    let result = calc::calculate("10:00 PST", "").unwrap();
+    assert_eq!(result.iso_8601, format!("{}T18:00:00Z", today));
+}
+
+// ============================================================================
+// POSIX TZ Strings and TZif Files
+// ============================================================================
+
+/// Encode a version 2 TZif file with the given types, transitions and footer (the
+/// version 1 block holds a single UTC type, as `zic -b slim` writes it).
+fn tzif_v2(types: &[(i32, bool, &str)], transitions: &[(i64, u8)], footer: &str) -> Vec<u8> {
+    let header = |timecnt: usize, typecnt: usize, charcnt: usize| {
+        let mut h = b"TZif2".to_vec();
+        h.extend([0; 15]);
+        for count in [0, 0, 0, timecnt, typecnt, charcnt] {
+            h.extend((count as u32).to_be_bytes());
+        }
+        h
+    };
+    let mut chars = Vec::new();
+    let mut indices = Vec::new();
+    for (_, _, abbreviation) in types {
+        indices.push(chars.len() as u8);
+        chars.extend(abbreviation.bytes());
+        chars.push(0);
+    }
+
+    let mut data = header(0, 1, 4);
+    data.extend(0i32.to_be_bytes());
+    data.extend([0, 0]);
+    data.extend(b"UTC\0");
+    data.extend(header(transitions.len(), types.len(), chars.len()));
+    for (time, _) in transitions {
+        data.extend(time.to_be_bytes());
+    }
+    data.extend(transitions.iter().map(|(_, index)| *index));
+    for ((offset, dst, _), index) in types.iter().zip(&indices) {
+        data.extend(offset.to_be_bytes());
+        data.extend([*dst as u8, *index]);
+    }
+    data.extend(&chars);
+    data.push(b'\n');
+    data.extend(footer.bytes());
+    data.push(b'\n');
+    data
+}
+
+#[test]
+fn test_posix_string_as_zone() {
+    let posix = "CET-1CEST,M3.5.0,M10.5.0/3";
+    let transitions = zone::zone_transitions(posix, "2024", "").unwrap();
+    let json = transitions.to_json();
+    assert!(json.starts_with(
+        r#"{"zone":"CET-1CEST,M3.5.0,M10.5.0/3","transitions":[{"iso":"2024-03-31T01:00:00Z""#
+    ));
+    assert_eq!(transitions.transitions.len(), 2);
+    let result = calc::calculate_in_zone(
+        "2024-07-01T12:00",
+        "",
+        &format!(r#"{{"zone":"{}"}}"#, posix),
+    )
+    .unwrap();
+    assert_eq!(result.iso_8601, "2024-07-01T10:00:00Z");
+    assert_eq!(result.local_human, "July 01, 2024 12:00:00 PM CEST");
+}
+
+#[test]
+fn test_inspect_posix_tz() {
+    let json = tzfile::inspect_posix_tz("AEST-10AEDT,M10.1.0,M4.1.0/3", 2024)
+        .unwrap()
+        .to_json();
+    assert!(json.starts_with(r#"{"name":"AEST-10AEDT,M10.1.0,M4.1.0/3","source":"posix","rule":{"text":"AEST-10AEDT,M10.1.0,M4.1.0/3","std":{"abbreviation":"AEST","offset":"+10:00"},"dst":{"abbreviation":"AEDT","offset":"+11:00","start":{"rule":"M10.1.0/02:00:00","description":"first Sunday of October at 02:00:00"},"end":{"rule":"M4.1.0/03:00:00","description":"first Sunday of April at 03:00:00"}}},"transitions":["#));
+    assert!(
+        json.contains(r#""iso":"2024-04-06T16:00:00Z","unixTimestamp":1712419200,"kind":"fold""#)
+    );
+    assert!(tzfile::inspect_posix_tz("CET-1CEST,M3.5.0", 2024).is_err());
+    assert!(tzfile::inspect_posix_tz("UTC0", 40_000)
+        .unwrap_err()
+        .contains("out of range"));
+}
+
+#[test]
+fn test_stale_device_tzif_against_upstream() {
+    // A New York file from before the 2007 US rule change: DST from the first Sunday
+    // of April to the last Sunday of October
+    let data = tzif_v2(
+        &[(-18_000, false, "EST"), (-14_400, true, "EDT")],
+        &[(1_143_961_200, 1), (1_162_101_600, 0)],
+        "EST5EDT,M4.1.0,M10.5.0",
+    );
+    let inspection = tzfile::load_tzif("device/New_York", &data).unwrap();
+    let json = inspection.to_json();
+    assert!(json.starts_with(r#"{"name":"device/New_York","source":"tzif","version":2,"types":[{"offset":"-05:00","dst":false,"abbreviation":"EST"},{"offset":"-04:00","dst":true,"abbreviation":"EDT"}],"transitionCount":2,"leapSeconds":0,"footer":"EST5EDT,M4.1.0,M10.5.0","rule":{"text":"EST5EDT,M4.1.0,M10.5.0""#));
+    assert!(json.contains(r#"{"iso":"2006-04-02T07:00:00Z","unixTimestamp":1143961200,"kind":"gap","offsetBefore":"-05:00","offsetAfter":"-04:00""#));
+
+    // The loaded zone is usable by name; on 2024-03-20 the device still shows EST
+    let clock = zone::world_clock(
+        "2024-03-20T12:00:00Z",
+        r#"["device/New_York","America/New_York"]"#,
+        "",
+    )
+    .unwrap()
+    .to_json();
+    assert!(clock.contains(r#""zone":"device/New_York","local":"2024-03-20T07:00:00","date":"2024-03-20","time":"07:00:00","weekday":"Wed","offset":"-05:00","abbreviation":"EST""#));
+    assert!(clock.contains(r#""zone":"America/New_York","local":"2024-03-20T08:00:00""#));
+}
+
+#[test]
+fn test_tzif_errors() {
+    assert!(tzfile::load_tzif("x", b"\x7fELF")
+        .unwrap_err()
+        .contains("magic"));
+    assert!(tzfile::load_tzif("x", b"TZif2")
+        .unwrap_err()
+        .contains("truncated"));
+    let mut truncated = tzif_v2(&[(0, false, "UTC")], &[(0, 0)], "UTC0");
+    truncated.truncate(70);
+    assert!(tzfile::load_tzif("x", &truncated)
+        .unwrap_err()
+        .contains("truncated"));
+    assert!(tzfile::load_tzif(" ", &tzif_v2(&[(0, false, "UTC")], &[], "UTC0")).is_err());
+    // Names that always mean UTC or a fixed offset cannot be shadowed
+    let berlin = tzif_v2(&[(3_600, false, "CET")], &[], "CET-1");
+    for name in ["UTC", "utc", "Z", "+05:30", "GMT-3"] {
+        assert!(tzfile::load_tzif(name, &berlin)
+            .unwrap_err()
+            .contains("another name"));
+    }
+}
+
+#[test]
+fn test_tzif_local_mean_time_keeps_seconds() {
+    let data = tzif_v2(
+        &[(3_208, false, "LMT"), (3_600, false, "CET")],
+        &[(-2_422_054_408, 1)],
+        "CET-1",
+    );
+    let json = tzfile::load_tzif("device/Berlin", &data).unwrap().to_json();
+    assert!(json.contains(r#"{"offset":"+00:53:28","dst":false,"abbreviation":"LMT"}"#));
+}
+
+// ============================================================================
+// Offset History
+// ============================================================================
+
+#[test]
+fn test_moscow_history_json() {
+    let json = history::zone_history("Europe/Moscow", r#"{"from":2009,"to":2015}"#)
+        .unwrap()
+        .to_json();
+    assert_eq!(
+        json,
+        r#"{"zone":"Europe/Moscow","from":2009,"to":2015,"eras":[{"from":2009,"to":2010,"standard":{"offset":"+03:00","abbreviation":"MSK"},"dst":{"offset":"+04:00","abbreviation":"MSD"},"transitions":4},{"from":2011,"to":2013,"standard":{"offset":"+04:00","abbreviation":"MSK"},"dst":null,"transitions":1,"change":"DST abolished; standard offset changed from +03:00 to +04:00"},{"from":2014,"to":2015,"standard":{"offset":"+03:00","abbreviation":"MSK"},"dst":null,"transitions":1,"change":"standard offset changed from +04:00 to +03:00"}]}"#
+    );
+}
+
+#[test]
+fn test_turkey_permanent_summer_time() {
+    let json = history::zone_history("Europe/Istanbul", r#"{"from":2010,"to":2020}"#)
+        .unwrap()
+        .to_json();
+    // 2016 is a year of its own: summer time began in March, then became the
+    // standard offset in September
+    assert_eq!(
+        json,
+        r#"{"zone":"Europe/Istanbul","from":2010,"to":2020,"eras":[{"from":2010,"to":2015,"standard":{"offset":"+02:00","abbreviation":"EET"},"dst":{"offset":"+03:00","abbreviation":"EEST"},"transitions":12},{"from":2016,"to":2016,"standard":{"offset":"+03:00","abbreviation":"+03"},"dst":{"offset":"+03:00","abbreviation":"EEST"},"transitions":2,"change":"standard offset changed from +02:00 to +03:00; standard abbreviation changed from EET to +03"},{"from":2017,"to":2020,"standard":{"offset":"+03:00","abbreviation":"+03"},"dst":null,"transitions":0,"change":"DST abolished"}]}"#
+    );
+}
+
+#[test]
+fn test_historical_conversion_against_fixed_offset() {
+    let json = history::compare_historical_offset("Europe/Moscow", "2012-06-01 12:00", "+03:00")
+        .unwrap()
+        .to_json();
+    // Moscow was on +04 all year from 2011 to 2014
+    assert_eq!(
+        json,
+        r#"{"input":"2012-06-01 12:00","zone":"Europe/Moscow","absolute":false,"historical":{"utc":"2012-06-01T08:00:00Z","local":"2012-06-01T12:00:00","offset":"+04:00","abbreviation":"MSK","dst":false},"naive":{"utc":"2012-06-01T09:00:00Z","local":"2012-06-01T12:00:00","offset":"+03:00"},"differenceSeconds":3600}"#
+    );
+    let json = history::compare_historical_offset("Europe/Moscow", "2012-06-01T12:00:00Z", "UTC+3")
+        .unwrap()
+        .to_json();
+    assert_eq!(
+        json,
+        r#"{"input":"2012-06-01T12:00:00Z","zone":"Europe/Moscow","absolute":true,"historical":{"utc":"2012-06-01T12:00:00Z","local":"2012-06-01T16:00:00","offset":"+04:00","abbreviation":"MSK","dst":false},"naive":{"utc":"2012-06-01T12:00:00Z","local":"2012-06-01T15:00:00","offset":"+03:00"},"differenceSeconds":3600}"#
+    );
+}
+
+#[test]
+fn test_history_errors() {
+    assert!(history::zone_history("Europe/Moscow", r#"{"from":2020,"to":2010}"#).is_err());
+    assert!(history::zone_history("Europe/Moscow", r#"{"from":70000}"#)
+        .unwrap_err()
+        .contains("out of range"));
+    assert!(history::compare_historical_offset("Europe/Moscow", "2012-06-01", "+3h").is_err());
+    assert!(history::compare_historical_offset("Europe/Moscow", "sometime", "").is_err());
+}
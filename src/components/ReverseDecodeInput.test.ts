@@ -18,4 +18,27 @@ describe("ReverseDecodeInput", () => {
     expect(onInput).toHaveBeenCalledWith("1739634600");
     expect(screen.getByText("Invalid reverse decode input.")).toBeTruthy();
   });
+
+  it("shows the reading used and switches to another", async () => {
+    const onLayoutChange = vi.fn();
+    render(ReverseDecodeInput, {
+      props: {
+        value: "1212092628029698048",
+        error: null,
+        onInput: vi.fn(),
+        snowflake: {
+          layout: "unix-nanoseconds",
+          alternatives: [
+            { layout: "twitter", iso: "2019-12-31T19:26:16.771Z", unixTimestampMs: 1577820376771 },
+            { layout: "discord", iso: "2024-02-27T17:43:22.114Z", unixTimestampMs: 1709055802114 },
+          ],
+        },
+        onLayoutChange,
+      },
+    });
+
+    expect(screen.getByText("Unix timestamp in nanoseconds")).toBeTruthy();
+    await fireEvent.click(screen.getByText("discord Snowflake ID: 2024-02-27T17:43:22.114Z"));
+    expect(onLayoutChange).toHaveBeenCalledWith("discord");
+  });
 });
@@ -1,29 +1,68 @@
-import { describe, expect, it } from "vitest";
-import { CronParseError, getNextCronRuns, parseCronExpression } from "./cronDebugger";
+import { describe, expect, it, vi } from "vitest";
+import { CronParseError, getNextCronRuns, getPreviousCronRuns } from "./cronDebugger";
+
+const { cronNextRunsMock, cronPreviousRunsMock } = vi.hoisted(() => ({
+  cronNextRunsMock: vi.fn(),
+  cronPreviousRunsMock: vi.fn(),
+}));
+
+vi.mock("./wasmBridge", () => ({
+  cronNextRuns: cronNextRunsMock,
+  cronPreviousRuns: cronPreviousRunsMock,
+}));
+
+// Engine output of cron_next_runs("0 * * * *", "2026-02-16T10:10:00Z", 2, "").
+const hourlyRuns = {
+  expression: "0 * * * *",
+  dialect: "standard",
+  zone: "UTC",
+  runs: [
+    {
+      iso: "2026-02-16T11:00:00Z",
+      unixTimestamp: 1771239600,
+      local: "2026-02-16T11:00:00+00:00[UTC]",
+    },
+    {
+      iso: "2026-02-16T12:00:00Z",
+      unixTimestamp: 1771243200,
+      local: "2026-02-16T12:00:00+00:00[UTC]",
+    },
+  ],
+};
 
 describe("cronDebugger", () => {
-  it("parses supported cron format", () => {
-    expect(parseCronExpression("*/15 * * * *")).toEqual({
-      minute: "*/15",
-      hour: "*",
-      dayOfMonth: "*",
-      month: "*",
-      dayOfWeek: "*",
+  it("returns the engine's upcoming runs", () => {
+    cronNextRunsMock.mockReturnValueOnce(hourlyRuns);
+    const runs = getNextCronRuns("0 * * * *", "2026-02-16T10:10:00Z", 2);
+    expect(cronNextRunsMock).toHaveBeenCalledWith("0 * * * *", "2026-02-16T10:10:00Z", 2, {
+      zone: "UTC",
     });
+    expect(runs.length).toBe(2);
+    expect(runs[0]?.iso).toBe("2026-02-16T11:00:00Z");
   });
 
-  it("throws for invalid cron field count", () => {
-    expect(() => parseCronExpression("* * *")).toThrow(CronParseError);
+  it("evaluates local mode in the browser's zone", () => {
+    cronNextRunsMock.mockReturnValueOnce({ ...hourlyRuns, runs: hourlyRuns.runs.slice(0, 1) });
+    getNextCronRuns("*/30 * * * *", "2026-02-16T10:10:00Z", 1, "LOCAL");
+    expect(cronNextRunsMock).toHaveBeenLastCalledWith("*/30 * * * *", "2026-02-16T10:10:00Z", 1, {
+      zone: Intl.DateTimeFormat().resolvedOptions().timeZone,
+    });
   });
 
-  it("returns deterministic upcoming runs", () => {
-    const runs = getNextCronRuns("0 * * * *", "2026-02-16T10:10:00Z", 2);
-    expect(runs.length).toBe(2);
-    expect(runs[0]?.iso).toBe("2026-02-16T11:00:00.000Z");
+  it("lists previous runs through the engine", () => {
+    cronPreviousRunsMock.mockReturnValueOnce({ ...hourlyRuns, runs: [] });
+    expect(getPreviousCronRuns("0 * * * *", "2026-02-16T10:10:00Z")).toEqual([]);
+    expect(cronPreviousRunsMock).toHaveBeenCalledWith("0 * * * *", "2026-02-16T10:10:00Z", 5, {
+      zone: "UTC",
+    });
   });
 
-  it("supports local-time evaluation mode", () => {
-    const runs = getNextCronRuns("*/30 * * * *", "2026-02-16T10:10:00Z", 1, "LOCAL");
-    expect(runs.length).toBe(1);
+  it("reports engine errors as CronParseError", () => {
+    cronNextRunsMock.mockImplementationOnce(() => {
+      throw new Error(
+        "Standard cron expressions must have 5 fields (minute hour day-of-month month day-of-week), got 3",
+      );
+    });
+    expect(() => getNextCronRuns("* * *", "2026-02-16T10:10:00Z")).toThrow(CronParseError);
   });
 });
@@ -1,5 +1,18 @@
-import { describe, it, expect } from "vitest";
-import { decodeDatetimeInput, ReverseDecodeError } from "./reverseDecode";
+import { describe, it, expect, vi } from "vitest";
+import {
+  decodeDatetimeInput,
+  ReverseDecodeError,
+  UNIX_MICROSECONDS,
+  UNIX_NANOSECONDS,
+} from "./reverseDecode";
+
+const { decodeSnowflakeMock } = vi.hoisted(() => ({
+  decodeSnowflakeMock: vi.fn(),
+}));
+
+vi.mock("./wasmBridge", () => ({
+  decodeSnowflake: decodeSnowflakeMock,
+}));
 
 describe("reverseDecode", () => {
   it("decodes unix timestamps", () => {
@@ -22,6 +35,97 @@ describe("reverseDecode", () => {
     expect(result.rfc2822).toBe("Sun, 15 Feb 2026 14:30:00 +0000");
   });
 
+  // Engine output of decode_snowflake("1212092628029698048", "").
+  const twitterDecoding = {
+    id: "1212092628029698048",
+    layout: "twitter",
+    iso: "2019-12-31T19:26:16.771Z",
+    unixTimestamp: 1577820376,
+    unixTimestampMs: 1577820376771,
+    fields: { datacenter: 10, worker: 7, sequence: 0 },
+    alternatives: [
+      { layout: "discord", iso: "2024-02-27T17:43:22.114Z", unixTimestampMs: 1709055802114 },
+      { layout: "instagram", iso: "2016-03-23T05:58:42.778Z", unixTimestampMs: 1458712722778 },
+      { layout: "sonyflake", iso: "2037-07-23T20:18:25.28Z", unixTimestampMs: 2131993105280 },
+    ],
+  };
+
+  // Engine output of decode_snowflake("1739634600123456", "").
+  const microsecondDecoding = {
+    id: "1739634600123456",
+    layout: "twitter",
+    iso: "2010-11-08T20:55:35.866Z",
+    unixTimestamp: 1289249735,
+    unixTimestampMs: 1289249735866,
+    fields: { datacenter: 16, worker: 17, sequence: 3136 },
+    alternatives: [
+      { layout: "discord", iso: "2015-01-05T19:12:41.209Z", unixTimestampMs: 1420485161209 },
+      { layout: "instagram", iso: "2011-08-27T06:43:22.325Z", unixTimestampMs: 1314427402325 },
+      { layout: "sonyflake", iso: "2014-09-13T00:01:43.02Z", unixTimestampMs: 1410566503020 },
+    ],
+  };
+
+  it("reads 16-digit inputs as Unix microseconds and keeps the Snowflake readings", () => {
+    decodeSnowflakeMock.mockReturnValueOnce(microsecondDecoding);
+    const result = decodeDatetimeInput("1739634600123456");
+    expect(result.unixTimestampMs).toBe(1739634600123);
+    expect(result.iso8601).toBe("2025-02-15T15:50:00.123Z");
+    expect(result.snowflake?.layout).toBe(UNIX_MICROSECONDS);
+    expect(result.snowflake?.alternatives.map((reading) => reading.layout)).toEqual([
+      "twitter",
+      "discord",
+      "instagram",
+      "sonyflake",
+    ]);
+  });
+
+  it("reads 19-digit inputs as Unix nanoseconds and keeps the Snowflake readings", () => {
+    decodeSnowflakeMock.mockReturnValueOnce(twitterDecoding);
+    const result = decodeDatetimeInput("1212092628029698048");
+    expect(decodeSnowflakeMock).toHaveBeenCalledWith("1212092628029698048");
+    expect(result.unixTimestampMs).toBe(1212092628029);
+    expect(result.snowflake?.layout).toBe(UNIX_NANOSECONDS);
+    expect(result.snowflake?.alternatives[0]).toEqual({
+      layout: "twitter",
+      iso: "2019-12-31T19:26:16.771Z",
+      unixTimestampMs: 1577820376771,
+    });
+  });
+
+  it("reads Snowflake IDs with the chosen layout", () => {
+    decodeSnowflakeMock.mockReturnValueOnce(twitterDecoding);
+    const result = decodeDatetimeInput("1212092628029698048", "discord");
+    expect(result.unixTimestampMs).toBe(1709055802114);
+    expect(result.iso8601).toBe("2024-02-27T17:43:22.114Z");
+    expect(result.snowflake?.layout).toBe("discord");
+    expect(result.snowflake?.alternatives.map((reading) => reading.layout)).toEqual([
+      UNIX_NANOSECONDS,
+      "twitter",
+      "instagram",
+      "sonyflake",
+    ]);
+  });
+
+  it("falls back to the Unix reading when the engine rejects the ID", () => {
+    decodeSnowflakeMock.mockImplementationOnce(() => {
+      throw new Error("invalid Snowflake ID");
+    });
+    const result = decodeDatetimeInput("1739634600123456789");
+    expect(result.unixTimestampMs).toBe(1739634600123);
+    expect(result.snowflake).toEqual({ layout: UNIX_NANOSECONDS, alternatives: [] });
+  });
+
+  it("does not attach a Snowflake reading to timestamps", () => {
+    expect(decodeDatetimeInput("1739634600").snowflake).toBeUndefined();
+  });
+
+  it("reports engine errors for IDs as typed errors", () => {
+    decodeSnowflakeMock.mockImplementationOnce(() => {
+      throw new Error("ID 18446744073709551615 does not fit the twitter layout (41 timestamp bits)");
+    });
+    expect(() => decodeDatetimeInput("18446744073709551615")).toThrow(ReverseDecodeError);
+  });
+
   it("throws typed error for invalid values", () => {
     expect(() => decodeDatetimeInput("not-a-date")).toThrow(ReverseDecodeError);
   });
@@ -1,7 +1,46 @@
-import type { FormattedResult } from "./types";
+import type { FormattedResult, SnowflakeDecoding } from "./types";
+import { decodeSnowflake } from "./wasmBridge";
 
 export class ReverseDecodeError extends Error {}
 
+/** Reading of a 16-digit input as Unix microseconds. */
+export const UNIX_MICROSECONDS = "unix-microseconds";
+/** Reading of a 19-digit input as Unix nanoseconds. */
+export const UNIX_NANOSECONDS = "unix-nanoseconds";
+
+/**
+ * How a long numeric input was read: the layout used (a Snowflake layout, or
+ * `UNIX_MICROSECONDS` / `UNIX_NANOSECONDS`) and the other readings.
+ */
+export interface SnowflakeReading {
+  layout: string;
+  alternatives: SnowflakeDecoding["alternatives"];
+}
+
+export interface ReverseDecodeResult extends FormattedResult {
+  /** Set when the input was a 15–20 digit number: the reading used and the others */
+  snowflake?: SnowflakeReading;
+}
+
+/** A reading's name as shown to the user, e.g. "discord Snowflake ID". */
+export function readingLabel(layout: string): string {
+  if (layout === UNIX_MICROSECONDS) return "Unix timestamp in microseconds";
+  if (layout === UNIX_NANOSECONDS) return "Unix timestamp in nanoseconds";
+  return `${layout} Snowflake ID`;
+}
+
+/** The Unix reading of a 16-digit (µs) or 19-digit (ns) input, exact to the millisecond. */
+function unixReading(digits: string): SnowflakeDecoding["alternatives"][number] | null {
+  const divisor = digits.length === 16 ? 1_000n : digits.length === 19 ? 1_000_000n : null;
+  if (divisor === null) return null;
+  const unixTimestampMs = Number(BigInt(digits) / divisor);
+  return {
+    layout: digits.length === 16 ? UNIX_MICROSECONDS : UNIX_NANOSECONDS,
+    iso: new Date(unixTimestampMs).toISOString(),
+    unixTimestampMs,
+  };
+}
+
 function formatLocalHuman(date: Date, timeZone?: string): string {
   return new Intl.DateTimeFormat("en-US", {
     timeZone,
@@ -15,15 +54,50 @@ function toRfc2822(date: Date): string {
   return date.toUTCString().replace("GMT", "+0000");
 }
 
-export function decodeDatetimeInput(input: string): FormattedResult {
+/**
+ * Decode a Unix timestamp, ISO 8601 value or Snowflake ID.
+ *
+ * A 15–20 digit number is read with `snowflakeLayout` when it can be read that way. By
+ * default 16 and 19 digits are Unix microseconds and nanoseconds, and other lengths a
+ * Twitter ID; the result names the reading used and lists the others.
+ */
+export function decodeDatetimeInput(input: string, snowflakeLayout?: string): ReverseDecodeResult {
   const trimmed = input.trim();
-  if (!trimmed) throw new ReverseDecodeError("Enter a Unix timestamp or ISO 8601 value.");
+  if (!trimmed) {
+    throw new ReverseDecodeError("Enter a Unix timestamp, ISO 8601 value or Snowflake ID.");
+  }
 
   let date: Date;
+  let snowflake: SnowflakeReading | undefined;
   if (/^-?\d{10,13}$/.test(trimmed)) {
     const asNumber = Number.parseInt(trimmed, 10);
     const ms = trimmed.length === 13 ? asNumber : asNumber * 1000;
     date = new Date(ms);
+  } else if (/^\d{15,20}$/.test(trimmed)) {
+    // Unix microseconds or nanoseconds, or a Snowflake ID decoded by the engine. Neither
+    // can be told from the digits alone, so every reading is kept for the user to choose from.
+    const readings: SnowflakeDecoding["alternatives"] = [];
+    const unix = unixReading(trimmed);
+    if (unix) readings.push(unix);
+    try {
+      const decoded = decodeSnowflake(trimmed);
+      readings.push(
+        { layout: decoded.layout, iso: decoded.iso, unixTimestampMs: decoded.unixTimestampMs },
+        ...decoded.alternatives,
+      );
+    } catch (error) {
+      if (readings.length === 0) {
+        throw new ReverseDecodeError(
+          error instanceof Error ? error.message : "Invalid Snowflake ID.",
+        );
+      }
+    }
+    const chosen = readings.find((reading) => reading.layout === snowflakeLayout) ?? readings[0];
+    date = new Date(chosen.unixTimestampMs);
+    snowflake = {
+      layout: chosen.layout,
+      alternatives: readings.filter((reading) => reading !== chosen),
+    };
   } else {
     date = new Date(trimmed);
   }
@@ -38,5 +112,6 @@ export function decodeDatetimeInput(input: string): FormattedResult {
     iso8601: date.toISOString(),
     rfc2822: toRfc2822(date),
     localHuman: formatLocalHuman(date),
+    ...(snowflake ? { snowflake } : {}),
   };
 }
@@ -27,6 +27,61 @@ export interface ValidationResult {
   normalized?: string;
 }
 
+/**
+ * A Snowflake ID decoded by the Wasm engine.
+ */
+export interface SnowflakeDecoding {
+  /** The ID as a decimal string (Snowflakes exceed the safe integer range) */
+  id: string;
+  /** Layout used: "twitter", "discord", "instagram", "sonyflake" or "custom" */
+  layout: string;
+  /** Creation time, ISO 8601 */
+  iso: string;
+  /** Creation time in Unix seconds */
+  unixTimestamp: number;
+  /** Creation time in Unix milliseconds */
+  unixTimestampMs: number;
+  /** Generator fields below the timestamp, e.g. worker and sequence */
+  fields: Record<string, number>;
+  /** Readings under the other layouts when none was chosen */
+  alternatives: { layout: string; iso: string; unixTimestampMs: number }[];
+}
+
+/**
+ * Options for listing cron runs in the Wasm engine.
+ */
+export interface CronOptions {
+  /** "standard" (default), "quartz", "aws" or "jenkins" */
+  dialect?: string;
+  /** Jenkins job name used to place `H` fields */
+  seed?: string;
+  /** "UTC" (default), a fixed offset or an IANA zone name */
+  zone?: string;
+  /** "vixie" (default) or "kubernetes" */
+  dstPolicy?: string;
+}
+
+/**
+ * Runs of a cron expression listed by the Wasm engine.
+ */
+export interface CronRuns {
+  expression: string;
+  /** Dialect the expression was read in */
+  dialect: string;
+  /** Zone the schedule was evaluated in */
+  zone: string;
+  runs: {
+    /** Run time, ISO 8601 in UTC */
+    iso: string;
+    /** Run time in Unix seconds */
+    unixTimestamp: number;
+    /** Run time in the schedule's zone, RFC 9557 */
+    local: string;
+    /** Set for runs moved or doubled by a DST transition */
+    dst?: "gapShifted" | "foldFirst" | "foldSecond";
+  }[];
+}
+
 /**
  * A single arithmetic operation to apply to a datetime.
  */
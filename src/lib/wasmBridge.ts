@@ -6,7 +6,14 @@
  * the JavaScript Date API is never used for datetime calculations.
  */
 
-import type { FormattedResult, Operation, ValidationResult } from "./types";
+import type {
+  CronOptions,
+  CronRuns,
+  FormattedResult,
+  Operation,
+  SnowflakeDecoding,
+  ValidationResult,
+} from "./types";
 
 /** Wasm module instance (loaded lazily) */
 let wasmModule: {
@@ -14,6 +21,9 @@ let wasmModule: {
   calculate: (startDate: string, operationsJson: string) => string;
   validate_date: (input: string) => string;
   now_unix: () => number;
+  decode_snowflake: (id: string, optionsJson: string) => string;
+  cron_next_runs: (expression: string, start: string, count: number, optionsJson: string) => string;
+  cron_previous_runs: (expression: string, start: string, count: number, optionsJson: string) => string;
 } | null = null;
 
 /** Whether the Wasm module has been initialized */
@@ -120,3 +130,81 @@ export function nowUnix(): number {
     );
   }
 }
+
+/**
+ * Decode the creation time of a Snowflake ID.
+ *
+ * @param id - Decimal Snowflake ID (e.g., "175928847299117063")
+ * @param layout - "twitter", "discord", "instagram" or "sonyflake"; omitted reads the ID
+ *                 as a Twitter ID and lists the other readings as alternatives
+ * @returns SnowflakeDecoding with the creation time and generator fields
+ * @throws Error if Wasm is not initialized or the ID does not decode
+ */
+export function decodeSnowflake(id: string, layout?: string): SnowflakeDecoding {
+  if (!wasmModule) {
+    throw new Error("Wasm module not initialized. Call init() first.");
+  }
+
+  const optionsJson = layout ? JSON.stringify({ layout }) : "";
+  const parsed: SnowflakeDecoding | { error: string } = JSON.parse(
+    wasmModule.decode_snowflake(id, optionsJson),
+  );
+  if ("error" in parsed) {
+    throw new Error(parsed.error);
+  }
+  return parsed;
+}
+
+/**
+ * List upcoming runs of a cron expression.
+ *
+ * @param expression - Cron expression, e.g. "0 9 * * MON-FRI", "@daily" or Quartz "0 0 12 ? * MON-FRI"
+ * @param start - Date input the search starts from (exclusive)
+ * @param count - Number of runs to return (the engine caps it at 1000)
+ * @param options - Dialect, Jenkins seed, zone and DST policy; omitted fields use the defaults
+ * @returns CronRuns with the runs in ascending order
+ * @throws Error if Wasm is not initialized or the expression does not parse
+ */
+export function cronNextRuns(
+  expression: string,
+  start: string,
+  count: number,
+  options: CronOptions = {},
+): CronRuns {
+  return cronRuns("cron_next_runs", expression, start, count, options);
+}
+
+/**
+ * List past runs of a cron expression before `start`, most recent first.
+ *
+ * Parameters and errors are the same as {@link cronNextRuns}.
+ */
+export function cronPreviousRuns(
+  expression: string,
+  start: string,
+  count: number,
+  options: CronOptions = {},
+): CronRuns {
+  return cronRuns("cron_previous_runs", expression, start, count, options);
+}
+
+function cronRuns(
+  entry: "cron_next_runs" | "cron_previous_runs",
+  expression: string,
+  start: string,
+  count: number,
+  options: CronOptions,
+): CronRuns {
+  if (!wasmModule) {
+    throw new Error("Wasm module not initialized. Call init() first.");
+  }
+
+  const optionsJson = Object.keys(options).length > 0 ? JSON.stringify(options) : "";
+  const parsed: CronRuns | { error: string } = JSON.parse(
+    wasmModule[entry](expression, start, count, optionsJson),
+  );
+  if ("error" in parsed) {
+    throw new Error(parsed.error);
+  }
+  return parsed;
+}